@@ -4,48 +4,39 @@
 //! 1. Create a vault transaction
 //! 2. Get approvals from members (meeting threshold)
 //! 3. Execute the transaction
+//!
+//! Requires `SQUADS_KEYPAIRS` (three member keypairs) and `SQUADS_MULTISIG`,
+//! see `squads-examples.env.example`.
 
 use squads_v4_client_v3::{
     instructions::{
         self, ProposalCreateArgs, ProposalVoteArgs, VaultTransactionCreateArgs,
     },
     message::TransactionMessage,
+    examples_config::{send_instructions, ExampleConfig},
     pda,
 };
-use solana_client::rpc_client::RpcClient;
-use solana_sdk::{
-    commitment_config::CommitmentConfig,
-    instruction::AccountMeta,
-    pubkey::Pubkey,
-    signature::{read_keypair_file, Signer},
-    system_instruction,
-    transaction::Transaction,
-};
-use std::str::FromStr;
-
-const SQUADS_PROGRAM_ID: &str = "SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf";
-const RPC_URL: &str = "https://mainnet.helius-rpc.com/?api-key=93812d12-f56f-4624-97c9-9a4d242db974";
-
-// Existing multisig from previous test
-const EXISTING_MULTISIG: &str = "jr7P3dmfnR8XBUSAPPJWNNhyaA4eyUvnpHbgBDfwx83";
+use solana_sdk::{instruction::AccountMeta, signature::Signer};
+use solana_system_interface::instruction as system_instruction;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\n=== Complete Multisig Transaction Flow ===\n");
 
     // Load wallets
     println!("Loading wallets...");
-    let principal = read_keypair_file("/Users/hogyzen12/.config/solana/RnGrVx38FRDJUyH6pS6QHFHikbTrs9m1csNiJPWHaZA.json")?;
-    let member2 = read_keypair_file("/Users/hogyzen12/.config/solana/6tBou5MHL5aWpDy6cgf3wiwGGK2mR8qs68ujtpaoWrf2.json")?;
-    let member3 = read_keypair_file("/Users/hogyzen12/.config/solana/worKFoQQH5KzuBnmS3jKKYsJuUi5toCoEp7n4mwRtwa.json")?;
+    let config = ExampleConfig::load()?;
+    let principal = config.keypair(0)?;
+    let member2 = config.keypair(1)?;
+    let member3 = config.keypair(2)?;
 
     println!("  Principal (Member 1): {}", principal.pubkey());
     println!("  Member 2: {}", member2.pubkey());
     println!("  Member 3: {}", member3.pubkey());
 
-    let rpc_client = RpcClient::new_with_commitment(RPC_URL.to_string(), CommitmentConfig::confirmed());
-    
-    let program_id = Pubkey::from_str(SQUADS_PROGRAM_ID)?;
-    let multisig_pda = Pubkey::from_str(EXISTING_MULTISIG)?;
+    let rpc_client = config.rpc_client();
+
+    let program_id = config.program_id;
+    let multisig_pda = config.require_multisig()?;
 
     println!("\nUsing existing multisig: {}", multisig_pda);
 
@@ -95,11 +86,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         Some(program_id),
     );
 
-    let mut transaction = Transaction::new_with_payer(&[vault_tx_create_ix], Some(&principal.pubkey()));
-    let recent_blockhash = rpc_client.get_latest_blockhash()?;
-    transaction.sign(&[&principal], recent_blockhash);
-
-    let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+    let signature = send_instructions(&rpc_client, &[vault_tx_create_ix], &[&principal])?;
     println!("✓ Vault transaction created! Signature: {}", signature);
 
     // Step 2: Create proposal for the transaction
@@ -122,11 +109,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         Some(program_id),
     );
 
-    let mut transaction = Transaction::new_with_payer(&[proposal_create_ix], Some(&principal.pubkey()));
-    let recent_blockhash = rpc_client.get_latest_blockhash()?;
-    transaction.sign(&[&principal], recent_blockhash);
-
-    let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+    let signature = send_instructions(&rpc_client, &[proposal_create_ix], &[&principal])?;
     println!("✓ Proposal created! Signature: {}", signature);
 
     // Step 3: Approve with principal (1/2)
@@ -142,11 +125,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         Some(program_id),
     );
 
-    let mut transaction = Transaction::new_with_payer(&[proposal_approve_ix], Some(&principal.pubkey()));
-    let recent_blockhash = rpc_client.get_latest_blockhash()?;
-    transaction.sign(&[&principal], recent_blockhash);
-
-    let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+    let signature = send_instructions(&rpc_client, &[proposal_approve_ix], &[&principal])?;
     println!("✓ Principal approved! Signature: {} (1/2 approvals)", signature);
 
     // Step 4: Approve with member3 (2/2 - threshold met!)
@@ -162,11 +141,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         Some(program_id),
     );
 
-    let mut transaction = Transaction::new_with_payer(&[proposal_approve_ix], Some(&member3.pubkey()));
-    let recent_blockhash = rpc_client.get_latest_blockhash()?;
-    transaction.sign(&[&member3], recent_blockhash);
-
-    let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+    let signature = send_instructions(&rpc_client, &[proposal_approve_ix], &[&member3])?;
     println!("✓ Member 3 approved! Signature: {} (2/2 approvals - THRESHOLD MET!)", signature);
 
     // Step 5: Execute the transaction
@@ -176,7 +151,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let remaining_accounts = vec![
         AccountMeta::new(vault_pda, false),
         AccountMeta::new(member2.pubkey(), false),
-        AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
+        AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
     ];
     
     let vault_transaction_execute_ix = instructions::vault_transaction_execute(
@@ -188,11 +163,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         Some(program_id),
     );
 
-    let mut transaction = Transaction::new_with_payer(&[vault_transaction_execute_ix], Some(&principal.pubkey()));
-    let recent_blockhash = rpc_client.get_latest_blockhash()?;
-    transaction.sign(&[&principal], recent_blockhash);
-
-    let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+    let signature = send_instructions(&rpc_client, &[vault_transaction_execute_ix], &[&principal])?;
     println!("✓ Transaction executed! Signature: {}", signature);
 
     // Verify the results
@@ -8,14 +8,13 @@
 //! ```
 
 use solana_sdk::{
-    pubkey::Pubkey,
     signature::Keypair,
     signer::Signer,
 };
 use squads_v4_client_v3::{
     client::SquadsClient,
     pda,
-    types::{Member, Permission, Permissions},
+    types::Member,
 };
 
 #[tokio::main]
@@ -24,7 +23,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Initialize client (using devnet)
     let client = SquadsClient::new("https://api.devnet.solana.com".to_string());
-    println!("Connected to Solana devnet");
+    println!("Connected to Solana devnet (program: {})", client.program_id);
 
     // Generate keypairs
     let create_key = Keypair::new();
@@ -43,7 +42,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\nMultisig PDA: {} (bump: {})", multisig_pda, bump);
 
     // Define multisig members
-    let members = vec![
+    let members = [
         Member::new(creator.pubkey()),
         Member::new(member1.pubkey()),
         Member::new(member2.pubkey()),
@@ -53,7 +52,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("  Threshold: 2 of 3");
     println!("  Time lock: 0 seconds");
     println!("  Config authority: None (autonomous)");
-    println!("  Members: 3");
+    println!("  Members: {}", members.len());
 
     // Note: In a real scenario, you would need to:
     // 1. Fund the creator account with SOL
@@ -1,43 +1,35 @@
 //! Create a pending transaction for testing the app
 //! This creates a vault transaction and proposal but doesn't approve it
+//!
+//! Requires `SQUADS_KEYPAIRS` (two member keypairs) and `SQUADS_MULTISIG`,
+//! see `squads-examples.env.example`.
 
 use squads_v4_client_v3::{
     instructions::{
         self, ProposalCreateArgs, VaultTransactionCreateArgs,
     },
+    examples_config::{send_instructions, ExampleConfig},
     message::TransactionMessage,
     pda,
 };
-use solana_client::rpc_client::RpcClient;
-use solana_sdk::{
-    commitment_config::CommitmentConfig,
-    pubkey::Pubkey,
-    signature::{read_keypair_file, Signer},
-    system_instruction,
-    transaction::Transaction,
-};
-use std::str::FromStr;
-
-const SQUADS_PROGRAM_ID: &str = "SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf";
-const RPC_URL: &str = "https://mainnet.helius-rpc.com/?api-key=93812d12-f56f-4624-97c9-9a4d242db974";
-
-// Existing multisig with transaction index 2
-const EXISTING_MULTISIG: &str = "jr7P3dmfnR8XBUSAPPJWNNhyaA4eyUvnpHbgBDfwx83";
+use solana_sdk::signature::Signer;
+use solana_system_interface::instruction as system_instruction;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\n=== Creating Pending Transaction for Testing ===\n");
 
     // Load wallet
-    let principal = read_keypair_file("/Users/hogyzen12/.config/solana/RnGrVx38FRDJUyH6pS6QHFHikbTrs9m1csNiJPWHaZA.json")?;
-    let member2 = read_keypair_file("/Users/hogyzen12/.config/solana/6tBou5MHL5aWpDy6cgf3wiwGGK2mR8qs68ujtpaoWrf2.json")?;
+    let config = ExampleConfig::load()?;
+    let principal = config.keypair(0)?;
+    let member2 = config.keypair(1)?;
 
     println!("Principal: {}", principal.pubkey());
     println!("Member 2: {}", member2.pubkey());
 
-    let rpc_client = RpcClient::new_with_commitment(RPC_URL.to_string(), CommitmentConfig::confirmed());
-    
-    let program_id = Pubkey::from_str(SQUADS_PROGRAM_ID)?;
-    let multisig_pda = Pubkey::from_str(EXISTING_MULTISIG)?;
+    let rpc_client = config.rpc_client();
+
+    let program_id = config.program_id;
+    let multisig_pda = config.require_multisig()?;
 
     println!("\nUsing multisig: {}", multisig_pda);
 
@@ -54,11 +46,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let fund_amount = 20_000_000u64; // 0.02 SOL
         let fund_ix = system_instruction::transfer(&principal.pubkey(), &vault_pda, fund_amount);
         
-        let mut transaction = Transaction::new_with_payer(&[fund_ix], Some(&principal.pubkey()));
-        let recent_blockhash = rpc_client.get_latest_blockhash()?;
-        transaction.sign(&[&principal], recent_blockhash);
-        
-        let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+        let signature = send_instructions(&rpc_client, &[fund_ix], &[&principal])?;
         println!("✓ Vault funded! Signature: {}", signature);
         
         let new_balance = rpc_client.get_balance(&vault_pda)?;
@@ -98,11 +86,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         Some(program_id),
     );
 
-    let mut transaction = Transaction::new_with_payer(&[vault_tx_create_ix], Some(&principal.pubkey()));
-    let recent_blockhash = rpc_client.get_latest_blockhash()?;
-    transaction.sign(&[&principal], recent_blockhash);
-
-    let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+    let signature = send_instructions(&rpc_client, &[vault_tx_create_ix], &[&principal])?;
     println!("✓ Vault transaction created! Signature: {}", signature);
 
     // Step 2: Create proposal for the transaction
@@ -125,11 +109,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         Some(program_id),
     );
 
-    let mut transaction = Transaction::new_with_payer(&[proposal_create_ix], Some(&principal.pubkey()));
-    let recent_blockhash = rpc_client.get_latest_blockhash()?;
-    transaction.sign(&[&principal], recent_blockhash);
-
-    let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+    let signature = send_instructions(&rpc_client, &[proposal_create_ix], &[&principal])?;
     println!("✓ Proposal created! Signature: {}", signature);
 
     println!("\n=== SUCCESS! ===");
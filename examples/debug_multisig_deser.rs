@@ -1,25 +1,16 @@
 //! Debug multisig deserialization to find the exact issue
+//!
+//! Requires `SQUADS_MULTISIG`, see `squads-examples.env.example`.
 
-use squads_v4_client_v3::accounts::Multisig;
-use solana_client::rpc_client::RpcClient;
-use solana_sdk::{
-    commitment_config::CommitmentConfig,
-    pubkey::Pubkey,
-};
-use std::str::FromStr;
-
-const RPC_URL: &str = "https://mainnet.helius-rpc.com/?api-key=93812d12-f56f-4624-97c9-9a4d242db974";
-const MULTISIG_ADDRESS: &str = "jr7P3dmfnR8XBUSAPPJWNNhyaA4eyUvnpHbgBDfwx83";
+use squads_v4_client_v3::{accounts::Multisig, examples_config::ExampleConfig};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\n=== Debug Multisig Deserialization ===\n");
 
-    let rpc_client = RpcClient::new_with_commitment(
-        RPC_URL.to_string(),
-        CommitmentConfig::confirmed()
-    );
-    
-    let multisig_address = Pubkey::from_str(MULTISIG_ADDRESS)?;
+    let config = ExampleConfig::load()?;
+    let rpc_client = config.rpc_client();
+
+    let multisig_address = config.require_multisig()?;
     println!("Fetching account: {}", multisig_address);
 
     let account = rpc_client.get_account(&multisig_address)?;
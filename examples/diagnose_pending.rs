@@ -1,38 +1,24 @@
 //! Diagnostic script to fetch pending transactions for a specific multisig
 //! This helps debug why the app isn't showing pending transactions
+//!
+//! Requires `SQUADS_MULTISIG` and `SQUADS_WALLET`, see `squads-examples.env.example`.
 
 use squads_v4_client_v3::{
     accounts::{Multisig, Proposal},
     pda,
+    examples_config::ExampleConfig,
     types::ProposalStatus,
 };
-use solana_client::rpc_client::RpcClient;
-use solana_sdk::{
-    commitment_config::CommitmentConfig,
-    pubkey::Pubkey,
-};
-use std::str::FromStr;
-
-const RPC_URL: &str = "https://mainnet.helius-rpc.com/?api-key=93812d12-f56f-4624-97c9-9a4d242db974";
-const SQUADS_PROGRAM_ID: &str = "SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf";
-
-// User's multisig with pending transaction
-const MULTISIG_ADDRESS: &str = "jr7P3dmfnR8XBUSAPPJWNNhyaA4eyUvnpHbgBDfwx83";
-
-// User's wallet address (one of the signers)
-const WALLET_ADDRESS: &str = "RnGrVx38FRDJUyH6pS6QHFHikbTrs9m1csNiJPWHaZA"; // Replace with actual wallet address
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\n=== Diagnosing Pending Transactions ===\n");
 
-    let rpc_client = RpcClient::new_with_commitment(
-        RPC_URL.to_string(),
-        CommitmentConfig::confirmed()
-    );
-    
-    let program_id = Pubkey::from_str(SQUADS_PROGRAM_ID)?;
-    let multisig_address = Pubkey::from_str(MULTISIG_ADDRESS)?;
-    let wallet_pubkey = Pubkey::from_str(WALLET_ADDRESS)?;
+    let config = ExampleConfig::load()?;
+    let rpc_client = config.rpc_client();
+
+    let program_id = config.program_id;
+    let multisig_address = config.require_multisig()?;
+    let wallet_pubkey = config.require_wallet()?;
 
     println!("Multisig: {}", multisig_address);
     println!("Wallet: {}", wallet_pubkey);
@@ -50,7 +36,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("  Stale Transaction Index: {}", multisig.stale_transaction_index);
     
     // Check if wallet is a member
-    let is_member = multisig.members.iter().any(|m| &m.key == &wallet_pubkey);
+    let is_member = multisig.members.iter().any(|m| m.key == wallet_pubkey);
     println!("  Wallet is member: {}", is_member);
     
     if !is_member {
@@ -116,6 +102,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                                 println!("    - {}", member);
                             }
                         }
+
+                        // Show members who still need to vote
+                        if is_active {
+                            let outstanding = proposal.outstanding_voters(&multisig);
+                            println!("  Awaiting votes from {} member(s):", outstanding.len());
+                            for member in &outstanding {
+                                println!("    - {}", member);
+                            }
+                        }
                     }
                     Err(e) => {
                         println!("  ❌ Failed to deserialize proposal: {}", e);
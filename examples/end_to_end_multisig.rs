@@ -9,36 +9,20 @@
 //! 6. Execute the transaction
 //!
 //! Run with: cargo run --example end_to_end_multisig
+//!
+//! Requires `SQUADS_RPC_URL` and `SQUADS_KEYPAIRS` (three member keypairs),
+//! see `squads-examples.env.example`.
 
-use solana_client::rpc_client::RpcClient;
-use solana_sdk::{
-    commitment_config::CommitmentConfig,
-    pubkey::Pubkey,
-    signature::{Keypair, Signer},
-    system_instruction,
-    transaction::Transaction,
-};
+use solana_sdk::signature::{Keypair, Signer};
+use solana_system_interface::instruction as system_instruction;
 use squads_v4_client_v3::{
+    examples_config::{fetch_treasury, send_instructions, ExampleConfig},
     instructions::{self, MultisigCreateArgsV2, ProposalCreateArgs, ProposalVoteArgs, VaultTransactionCreateArgs},
     message::TransactionMessage,
     pda,
     types::Member,
 };
-use std::{error::Error, str::FromStr, thread, time::Duration};
-
-const SQUADS_PROGRAM_ID: &str = "SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf";
-const RPC_URL: &str = "https://mainnet.helius-rpc.com/?api-key=93812d12-f56f-4624-97c9-9a4d242db974";
-
-// Wallet paths
-const PRINCIPAL_WALLET: &str = "/Users/hogyzen12/.config/solana/RnGrVx38FRDJUyH6pS6QHFHikbTrs9m1csNiJPWHaZA.json";
-const MEMBER2_WALLET: &str = "/Users/hogyzen12/.config/solana/6tBou5MHL5aWpDy6cgf3wiwGGK2mR8qs68ujtpaoWrf2.json";
-const MEMBER3_WALLET: &str = "/Users/hogyzen12/.config/solana/worKFoQQH5KzuBnmS3jKKYsJuUi5toCoEp7n4mwRtwa.json";
-
-fn load_keypair(path: &str) -> Result<Keypair, Box<dyn Error>> {
-    let wallet_data = std::fs::read_to_string(path)?;
-    let wallet_bytes: Vec<u8> = serde_json::from_str(&wallet_data)?;
-    Ok(Keypair::try_from(&wallet_bytes[..])?)
-}
+use std::{error::Error, thread, time::Duration};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
@@ -46,16 +30,17 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     // Load all three wallets
     println!("Loading wallets...");
-    let principal = load_keypair(PRINCIPAL_WALLET)?;
-    let member2 = load_keypair(MEMBER2_WALLET)?;
-    let member3 = load_keypair(MEMBER3_WALLET)?;
+    let config = ExampleConfig::load()?;
+    let principal = config.keypair(0)?;
+    let member2 = config.keypair(1)?;
+    let member3 = config.keypair(2)?;
     
     println!("  Principal (Member 1): {}", principal.pubkey());
     println!("  Member 2: {}", member2.pubkey());
     println!("  Member 3: {}", member3.pubkey());
 
     // Setup RPC client
-    let rpc_client = RpcClient::new_with_commitment(RPC_URL.to_string(), CommitmentConfig::confirmed());
+    let rpc_client = config.rpc_client();
     
     // Check principal wallet balance
     let balance = rpc_client.get_balance(&principal.pubkey())?;
@@ -65,13 +50,12 @@ async fn main() -> Result<(), Box<dyn Error>> {
         return Err("Insufficient balance in principal wallet. Need at least 0.04 SOL.".into());
     }
 
-    let program_id = Pubkey::from_str(SQUADS_PROGRAM_ID)?;
+    let program_id = config.program_id;
 
     // Get program config and treasury
     println!("\n=== Step 1: Getting Program Config ===");
     let (program_config_pda, _) = pda::get_program_config_pda(Some(&program_id));
-    let program_config_account = rpc_client.get_account(&program_config_pda)?;
-    let treasury = Pubkey::try_from(&program_config_account.data[48..80])?;
+    let treasury = fetch_treasury(&rpc_client, &program_id)?;
     println!("Treasury: {}", treasury);
 
     // Create a 2-of-3 multisig
@@ -104,17 +88,9 @@ async fn main() -> Result<(), Box<dyn Error>> {
         Some(program_id),
     );
 
-    let mut transaction = Transaction::new_with_payer(
-        &[create_multisig_ix],
-        Some(&principal.pubkey()),
-    );
-    
-    let recent_blockhash = rpc_client.get_latest_blockhash()?;
-    transaction.sign(&[&principal, &create_key], recent_blockhash);
-    
     println!("Multisig PDA: {}", multisig_pda);
     println!("Sending create multisig transaction...");
-    let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+    let signature = send_instructions(&rpc_client, &[create_multisig_ix], &[&principal, &create_key])?;
     println!("✓ Multisig created! Signature: {}\n", signature);
 
     // Get vault address
@@ -126,11 +102,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let fund_amount = 20_000_000u64; // 0.02 SOL
     let fund_ix = system_instruction::transfer(&principal.pubkey(), &vault_pda, fund_amount);
     
-    let mut transaction = Transaction::new_with_payer(&[fund_ix], Some(&principal.pubkey()));
-    let recent_blockhash = rpc_client.get_latest_blockhash()?;
-    transaction.sign(&[&principal], recent_blockhash);
-    
-    let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+    let signature = send_instructions(&rpc_client, &[fund_ix], &[&principal])?;
     println!("✓ Vault funded! Signature: {}", signature);
     
     // Verify vault balance
@@ -166,15 +138,8 @@ async fn main() -> Result<(), Box<dyn Error>> {
         Some(program_id),
     );
 
-    let mut transaction = Transaction::new_with_payer(
-        &[vault_tx_create_ix],
-        Some(&principal.pubkey()),
-    );
-    let recent_blockhash = rpc_client.get_latest_blockhash()?;
-    transaction.sign(&[&principal], recent_blockhash);
-    
     println!("Transaction PDA: {}", transaction_pda);
-    let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+    let signature = send_instructions(&rpc_client, &[vault_tx_create_ix], &[&principal])?;
     println!("✓ Vault transaction created! Signature: {}\n", signature);
 
     // Create proposal
@@ -195,15 +160,8 @@ async fn main() -> Result<(), Box<dyn Error>> {
         Some(program_id),
     );
 
-    let mut transaction = Transaction::new_with_payer(
-        &[proposal_create_ix],
-        Some(&principal.pubkey()),
-    );
-    let recent_blockhash = rpc_client.get_latest_blockhash()?;
-    transaction.sign(&[&principal], recent_blockhash);
-    
     println!("Proposal PDA: {}", proposal_pda);
-    let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+    let signature = send_instructions(&rpc_client, &[proposal_create_ix], &[&principal])?;
     println!("✓ Proposal created! Signature: {}\n", signature);
 
     // Approve with principal (1/2)
@@ -218,11 +176,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
         Some(program_id),
     );
 
-    let mut transaction = Transaction::new_with_payer(&[approve_ix], Some(&principal.pubkey()));
-    let recent_blockhash = rpc_client.get_latest_blockhash()?;
-    transaction.sign(&[&principal], recent_blockhash);
-    
-    let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+    let signature = send_instructions(&rpc_client, &[approve_ix], &[&principal])?;
     println!("✓ Principal approved! Signature: {}", signature);
 
     // Approve with member3 (2/2 - threshold met!)
@@ -236,11 +190,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
         Some(program_id),
     );
 
-    let mut transaction = Transaction::new_with_payer(&[approve_ix], Some(&member3.pubkey()));
-    let recent_blockhash = rpc_client.get_latest_blockhash()?;
-    transaction.sign(&[&member3], recent_blockhash);
-    
-    let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+    let signature = send_instructions(&rpc_client, &[approve_ix], &[&member3])?;
     println!("✓ Member 3 approved! Signature: {}", signature);
     println!("✓ Threshold reached (2/2)!\n");
 
@@ -251,7 +201,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let remaining_accounts = vec![
         solana_sdk::instruction::AccountMeta::new(vault_pda, true), // Vault as signer
         solana_sdk::instruction::AccountMeta::new(member2.pubkey(), false), // Destination
-        solana_sdk::instruction::AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
+        solana_sdk::instruction::AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
     ];
 
     let execute_ix = instructions::vault_transaction_execute(
@@ -263,11 +213,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
         Some(program_id),
     );
 
-    let mut transaction = Transaction::new_with_payer(&[execute_ix], Some(&principal.pubkey()));
-    let recent_blockhash = rpc_client.get_latest_blockhash()?;
-    transaction.sign(&[&principal], recent_blockhash);
-    
-    let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+    let signature = send_instructions(&rpc_client, &[execute_ix], &[&principal])?;
     println!("✓ Transaction executed! Signature: {}\n", signature);
 
     // Verify final balances
@@ -1,26 +1,17 @@
 //! Examine raw account bytes to understand the on-chain structure
+//!
+//! Requires `SQUADS_MULTISIG`, see `squads-examples.env.example`.
 
-use solana_client::rpc_client::RpcClient;
-use solana_sdk::{
-    commitment_config::CommitmentConfig,
-    pubkey::Pubkey,
-};
-use std::str::FromStr;
-
-const RPC_URL: &str = "https://mainnet.helius-rpc.com/?api-key=93812d12-f56f-4624-97c9-9a4d242db974";
-
-// User's multisig
-const MULTISIG_ADDRESS: &str = "jr7P3dmfnR8XBUSAPPJWNNhyaA4eyUvnpHbgBDfwx83";
+use solana_sdk::pubkey::Pubkey;
+use squads_v4_client_v3::examples_config::ExampleConfig;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\n=== Examining Raw Account Data ===\n");
 
-    let rpc_client = RpcClient::new_with_commitment(
-        RPC_URL.to_string(),
-        CommitmentConfig::confirmed()
-    );
-    
-    let multisig_address = Pubkey::from_str(MULTISIG_ADDRESS)?;
+    let config = ExampleConfig::load()?;
+    let rpc_client = config.rpc_client();
+
+    let multisig_address = config.require_multisig()?;
     println!("Multisig: {}", multisig_address);
 
     // Fetch the account
@@ -100,7 +91,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
     
     // rent_collector (Option<Pubkey> = 1 byte + potentially 32 bytes)
-    if data.len() >= offset + 1 {
+    if data.len() > offset {
         let has_rent_collector = data[offset];
         println!("has_rent_collector: {}", has_rent_collector);
         offset += 1;
@@ -113,7 +104,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
     
     // bump (u8 = 1 byte)
-    if data.len() >= offset + 1 {
+    if data.len() > offset {
         let bump = data[offset];
         println!("bump: {}", bump);
         offset += 1;
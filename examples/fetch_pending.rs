@@ -1,25 +1,17 @@
 //! Test fetching the pending transaction we created
 //! This will help debug the "Not all bytes read" error
+//!
+//! Requires `SQUADS_PROPOSAL`, see `squads-examples.env.example`.
 
-use squads_v4_client_v3::accounts::Proposal;
-use solana_client::rpc_client::RpcClient;
-use solana_sdk::{
-    commitment_config::CommitmentConfig,
-    pubkey::Pubkey,
-};
-use std::str::FromStr;
-
-const RPC_URL: &str = "https://mainnet.helius-rpc.com/?api-key=93812d12-f56f-4624-97c9-9a4d242db974";
-
-// The proposal we created
-const PROPOSAL_PDA: &str = "D5AsLKNt1jaYnSCQJxidgHFYzrEM6H8ToFGiJA5AWBG1";
+use squads_v4_client_v3::{accounts::Proposal, examples_config::ExampleConfig};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\n=== Testing Proposal Fetch ===\n");
 
-    let rpc_client = RpcClient::new_with_commitment(RPC_URL.to_string(), CommitmentConfig::confirmed());
-    
-    let proposal_pda = Pubkey::from_str(PROPOSAL_PDA)?;
+    let config = ExampleConfig::load()?;
+    let rpc_client = config.rpc_client();
+
+    let proposal_pda = config.require_proposal()?;
     println!("Fetching proposal: {}", proposal_pda);
 
     // Fetch the account
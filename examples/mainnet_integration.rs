@@ -3,38 +3,29 @@
 //! This example demonstrates creating a multisig on mainnet and deriving PDAs.
 //!
 //! Run with: cargo run --example mainnet_integration
+//!
+//! Requires `SQUADS_RPC_URL` and `SQUADS_KEYPAIRS`, see `squads-examples.env.example`.
 
-use solana_client::rpc_client::RpcClient;
-use solana_sdk::{
-    commitment_config::CommitmentConfig,
-    pubkey::Pubkey,
-    signature::{Keypair, Signer},
-    transaction::Transaction,
-};
+use solana_sdk::signature::{Keypair, Signer};
 use squads_v4_client_v3::{
+    examples_config::{fetch_treasury, send_instructions, ExampleConfig},
     instructions::{self, MultisigCreateArgsV2},
     pda,
     types::Member,
 };
-use std::{error::Error, str::FromStr};
-
-const SQUADS_PROGRAM_ID: &str = "SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf";
-const RPC_URL: &str = "https://mainnet.helius-rpc.com/?api-key=93812d12-f56f-4624-97c9-9a4d242db974";
-const WALLET_PATH: &str = "/Users/hogyzen12/.config/solana/RnGrVx38FRDJUyH6pS6QHFHikbTrs9m1csNiJPWHaZA.json";
+use std::error::Error;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
     println!("\n=== Squads v4 Mainnet Integration Test ===\n");
 
     // Load wallet
-    println!("Loading wallet from: {}", WALLET_PATH);
-    let wallet_data = std::fs::read_to_string(WALLET_PATH)?;
-    let wallet_bytes: Vec<u8> = serde_json::from_str(&wallet_data)?;
-    let wallet = Keypair::try_from(&wallet_bytes[..])?;
+    let config = ExampleConfig::load()?;
+    let wallet = config.payer()?;
     println!("Wallet pubkey: {}", wallet.pubkey());
 
     // Setup RPC client
-    let rpc_client = RpcClient::new_with_commitment(RPC_URL.to_string(), CommitmentConfig::confirmed());
+    let rpc_client = config.rpc_client();
     
     // Check wallet balance
     let balance = rpc_client.get_balance(&wallet.pubkey())?;
@@ -44,7 +35,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
         return Err("Insufficient balance. Need at least 0.01 SOL for transaction fees.".into());
     }
 
-    let program_id = Pubkey::from_str(SQUADS_PROGRAM_ID)?;
+    let program_id = config.program_id;
 
     // Step 1: Get program config PDA (required for creating multisigs)
     println!("Step 1: Deriving Program Config PDA");
@@ -52,17 +43,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
     println!("Program Config PDA: {}", program_config_pda);
     
     // Fetch program config to get treasury
-    let program_config_account = rpc_client.get_account(&program_config_pda)?;
-    println!("Program config found, size: {} bytes\n", program_config_account.data.len());
-
-    // Deserialize program config to get treasury
-    // ProgramConfig layout: 8 bytes discriminator + 32 bytes authority + 8 bytes fee + 32 bytes treasury
-    // Treasury is at offset 48
-    let treasury = if program_config_account.data.len() >= 80 {
-        Pubkey::try_from(&program_config_account.data[48..80])?
-    } else {
-        return Err("Program config account data too small".into());
-    };
+    let treasury = fetch_treasury(&rpc_client, &program_id)?;
     println!("Treasury from config: {}", treasury);
 
     // Step 2: Create a new multisig
@@ -96,16 +77,8 @@ async fn main() -> Result<(), Box<dyn Error>> {
     );
 
     // Send create multisig transaction
-    let mut transaction = Transaction::new_with_payer(
-        &[create_multisig_ix],
-        Some(&wallet.pubkey()),
-    );
-    
-    let recent_blockhash = rpc_client.get_latest_blockhash()?;
-    transaction.sign(&[&wallet, &create_key], recent_blockhash);
-    
     println!("Sending create multisig transaction...");
-    let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+    let signature = send_instructions(&rpc_client, &[create_multisig_ix], &[&wallet, &create_key])?;
     println!("✓ Multisig created! Signature: {}\n", signature);
 
     // Step 3: Verify multisig and show all derived PDAs
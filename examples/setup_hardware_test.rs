@@ -2,50 +2,32 @@
 //! 
 //! Creates a new multisig with 2 hardware wallets + 1 software wallet,
 //! funds it, and creates a pending transaction for testing hardware signing.
+//!
+//! Requires `SQUADS_KEYPAIRS` (the paying hot wallet) and `SQUADS_MEMBERS`
+//! (hardware wallet 1, hardware wallet 2, software wallet), see `squads-examples.env.example`.
 
-use solana_client::rpc_client::RpcClient;
-use solana_sdk::{
-    commitment_config::CommitmentConfig,
-    pubkey::Pubkey,
-    signature::{Keypair, Signer},
-    system_instruction,
-    transaction::Transaction,
-};
+use solana_sdk::signature::{Keypair, Signer};
+use solana_system_interface::instruction as system_instruction;
 use squads_v4_client_v3::{
+    examples_config::{fetch_treasury, send_instructions, ExampleConfig},
     instructions::{self, MultisigCreateArgsV2, ProposalCreateArgs, VaultTransactionCreateArgs},
     message::TransactionMessage,
     pda,
     types::Member,
 };
-use std::{error::Error, str::FromStr, thread, time::Duration};
-
-const SQUADS_PROGRAM_ID: &str = "SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf";
-const RPC_URL: &str = "https://johna-k3cr1v-fast-mainnet.helius-rpc.com";
-
-// Hot wallet that will create and fund the multisig
-const HOT_WALLET_PATH: &str = "/Users/hogyzen12/.config/solana/RnGrVx38FRDJUyH6pS6QHFHikbTrs9m1csNiJPWHaZA.json";
-
-// Multisig members
-const HARDWARE_WALLET_1: &str = "D7uvPcmK82AnexJXDBw1tnp9s2BBXf1UX5Edjsx8ptUt";
-const HARDWARE_WALLET_2: &str = "5LcChTmwCz8Q1eB8aqNhysyfo4jmmU1zTCeSbvPEj8Bm";
-const SOFTWARE_WALLET: &str = "RnGrVx38FRDJUyH6pS6QHFHikbTrs9m1csNiJPWHaZA";
-
-fn load_keypair(path: &str) -> Result<Keypair, Box<dyn Error>> {
-    let wallet_data = std::fs::read_to_string(path)?;
-    let wallet_bytes: Vec<u8> = serde_json::from_str(&wallet_data)?;
-    Ok(Keypair::try_from(&wallet_bytes[..])?)
-}
+use std::{error::Error, thread, time::Duration};
 
 fn main() -> Result<(), Box<dyn Error>> {
     println!("\n=== Hardware Wallet Test Setup ===\n");
 
     // Load the hot wallet (will pay for setup)
     println!("Loading hot wallet...");
-    let creator = load_keypair(HOT_WALLET_PATH)?;
+    let config = ExampleConfig::load()?;
+    let creator = config.payer()?;
     println!("Creator: {}", creator.pubkey());
 
     // Setup RPC client
-    let rpc_client = RpcClient::new_with_commitment(RPC_URL.to_string(), CommitmentConfig::confirmed());
+    let rpc_client = config.rpc_client();
     
     // Check creator balance
     let balance = rpc_client.get_balance(&creator.pubkey())?;
@@ -55,19 +37,18 @@ fn main() -> Result<(), Box<dyn Error>> {
         return Err("Insufficient balance. Need at least 0.05 SOL.".into());
     }
 
-    let program_id = Pubkey::from_str(SQUADS_PROGRAM_ID)?;
+    let program_id = config.program_id;
 
     // Get program config and treasury
     println!("\n=== Getting Program Config ===");
     let (program_config_pda, _) = pda::get_program_config_pda(Some(&program_id));
-    let program_config_account = rpc_client.get_account(&program_config_pda)?;
-    let treasury = Pubkey::try_from(&program_config_account.data[48..80])?;
+    let treasury = fetch_treasury(&rpc_client, &program_id)?;
     println!("Treasury: {}", treasury);
 
     // Parse member pubkeys
-    let hw1 = Pubkey::from_str(HARDWARE_WALLET_1)?;
-    let hw2 = Pubkey::from_str(HARDWARE_WALLET_2)?;
-    let sw = Pubkey::from_str(SOFTWARE_WALLET)?;
+    let [hw1, hw2, sw] = config.members[..] else {
+        return Err("SQUADS_MEMBERS must list two hardware wallets and one software wallet".into());
+    };
 
     println!("\n=== Multisig Members ===");
     println!("Hardware Wallet 1: {}", hw1);
@@ -104,17 +85,9 @@ fn main() -> Result<(), Box<dyn Error>> {
         Some(program_id),
     );
 
-    let mut transaction = Transaction::new_with_payer(
-        &[create_multisig_ix],
-        Some(&creator.pubkey()),
-    );
-    
-    let recent_blockhash = rpc_client.get_latest_blockhash()?;
-    transaction.sign(&[&creator, &create_key], recent_blockhash);
-    
     println!("Multisig PDA: {}", multisig_pda);
     println!("Sending create multisig transaction...");
-    let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+    let signature = send_instructions(&rpc_client, &[create_multisig_ix], &[&creator, &create_key])?;
     println!("✓ Multisig created! Signature: {}\n", signature);
 
     // Get vault address
@@ -126,11 +99,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     let fund_amount = 42_000_000u64; // 0.042 SOL
     let fund_ix = system_instruction::transfer(&creator.pubkey(), &vault_pda, fund_amount);
     
-    let mut transaction = Transaction::new_with_payer(&[fund_ix], Some(&creator.pubkey()));
-    let recent_blockhash = rpc_client.get_latest_blockhash()?;
-    transaction.sign(&[&creator], recent_blockhash);
-    
-    let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+    let signature = send_instructions(&rpc_client, &[fund_ix], &[&creator])?;
     println!("✓ Vault funded! Signature: {}", signature);
     
     // Verify vault balance
@@ -166,15 +135,8 @@ fn main() -> Result<(), Box<dyn Error>> {
         Some(program_id),
     );
 
-    let mut transaction = Transaction::new_with_payer(
-        &[vault_tx_create_ix],
-        Some(&creator.pubkey()),
-    );
-    let recent_blockhash = rpc_client.get_latest_blockhash()?;
-    transaction.sign(&[&creator], recent_blockhash);
-    
     println!("Transaction PDA: {}", transaction_pda);
-    let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+    let signature = send_instructions(&rpc_client, &[vault_tx_create_ix], &[&creator])?;
     println!("✓ Vault transaction created! Signature: {}\n", signature);
 
     // Create proposal (but don't approve - leave it for hardware wallet testing)
@@ -195,15 +157,8 @@ fn main() -> Result<(), Box<dyn Error>> {
         Some(program_id),
     );
 
-    let mut transaction = Transaction::new_with_payer(
-        &[proposal_create_ix],
-        Some(&creator.pubkey()),
-    );
-    let recent_blockhash = rpc_client.get_latest_blockhash()?;
-    transaction.sign(&[&creator], recent_blockhash);
-    
     println!("Proposal PDA: {}", proposal_pda);
-    let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+    let signature = send_instructions(&rpc_client, &[proposal_create_ix], &[&creator])?;
     println!("✓ Proposal created! Signature: {}\n", signature);
 
     println!("\n=== Setup Complete! ===");
@@ -4,19 +4,160 @@
 //! These structures can be deserialized from account data fetched from the blockchain.
 
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_sdk::pubkey::Pubkey;
-use std::io::Read;
+use solana_message::AddressLookupTableAccount;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
 
-use crate::types::{ConfigAction, Member, Period, ProposalStatus};
+use crate::error::{DiscriminatorMismatch, SquadsError, SquadsResult};
+use crate::types::{
+    ConfigAction, Member, Period, PeriodWindow, ProposalStatus, TransactionKind, TransitionContext,
+};
+
+/// Compute the Anchor account discriminator for an account type
+/// Discriminator is the first 8 bytes of SHA256("account:AccountName")
+pub fn account_discriminator(name: &str) -> [u8; 8] {
+    use solana_program::hash::hash;
+    let preimage = format!("account:{}", name);
+    let hash_result = hash(preimage.as_bytes());
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash_result.to_bytes()[..8]);
+    discriminator
+}
+
+/// Check whether raw account data starts with the discriminator of the given account type
+pub fn has_discriminator(data: &[u8], name: &str) -> bool {
+    data.len() >= 8 && data[..8] == account_discriminator(name)
+}
+
+/// A Squads account type with a known Anchor discriminator
+pub trait AnchorAccount {
+    /// Account struct name as declared in the program
+    const NAME: &'static str;
+
+    /// First 8 bytes of `SHA256("account:<NAME>")`, as computed by [`account_discriminator`]
+    const DISCRIMINATOR: [u8; 8];
+}
+
+macro_rules! anchor_account {
+    ($($account:ident => [$($byte:literal),*],)*) => {
+        $(
+            impl AnchorAccount for $account {
+                const NAME: &'static str = stringify!($account);
+                const DISCRIMINATOR: [u8; 8] = [$($byte),*];
+            }
+        )*
+    };
+}
+
+anchor_account! {
+    Multisig => [224, 116, 121, 186, 68, 161, 79, 236],
+    Proposal => [26, 94, 189, 187, 116, 136, 53, 33],
+    VaultTransaction => [168, 250, 162, 100, 81, 14, 162, 207],
+    ConfigTransaction => [94, 8, 4, 35, 113, 139, 139, 112],
+    SpendingLimit => [10, 201, 27, 160, 218, 195, 222, 152],
+    ProgramConfig => [196, 210, 90, 231, 144, 149, 140, 63],
+    Batch => [156, 194, 70, 44, 22, 88, 137, 44],
+    VaultBatchTransaction => [196, 121, 46, 36, 12, 19, 252, 7],
+    TransactionBuffer => [90, 36, 35, 219, 93, 225, 110, 96],
+}
+
+/// Check that account data starts with the discriminator of `T`
+///
+/// A mismatch fails with an error wrapping [`DiscriminatorMismatch`], which converts to
+/// [`SquadsError::DiscriminatorMismatch`].
+fn check_discriminator<T: AnchorAccount>(data: &[u8]) -> Result<(), std::io::Error> {
+    let Some(found) = data.get(..8) else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "Account data too short",
+        ));
+    };
+    if found != T::DISCRIMINATOR {
+        let mut discriminator = [0u8; 8];
+        discriminator.copy_from_slice(found);
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            DiscriminatorMismatch {
+                account: T::NAME,
+                expected: T::DISCRIMINATOR,
+                found: discriminator,
+            },
+        ));
+    }
+    Ok(())
+}
+
+/// Check that an account is owned by the Squads program before decoding it
+///
+/// # Arguments
+/// * `owner` - Owner of the fetched account
+/// * `program_id` - Program the account should belong to
+pub fn check_owner(owner: &Pubkey, program_id: &Pubkey) -> SquadsResult<()> {
+    if owner != program_id {
+        return Err(SquadsError::WrongOwner {
+            expected: *program_id,
+            found: *owner,
+        });
+    }
+    Ok(())
+}
+
+/// Deserialize an Anchor account, returning the value and any bytes following its known fields
+///
+/// Fails if the data does not start with the discriminator of `T`.
+/// Program upgrades may append fields to existing accounts; those bytes are returned
+/// rather than rejected so older clients keep working.
+fn deserialize_account<T: BorshDeserialize + AnchorAccount>(
+    data: &[u8],
+) -> Result<(T, Vec<u8>), std::io::Error> {
+    check_discriminator::<T>(data)?;
+    let mut buf = &data[8..];
+    let value = T::deserialize(&mut buf)?;
+    Ok((value, buf.to_vec()))
+}
+
+/// Options controlling how account data is parsed
+///
+/// The default is lenient: bytes after the known fields are kept in `unknown_tail`.
+/// Strict parsing rejects bytes the program's allocation does not account for, so
+/// indexers notice layout changes after a program upgrade rather than silently
+/// mis-parsing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    strict: bool,
+}
+
+impl ParseOptions {
+    /// Lenient parsing options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable or disable strict trailing byte checks
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Whether strict trailing byte checks are enabled
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+}
 
 /// The main multisig account that stores configuration and state
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Multisig {
     /// Key that is used to seed the multisig PDA
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::base58"))]
     pub create_key: Pubkey,
     /// The authority that can change the multisig config.
     /// If set to Pubkey::default(), the multisig is autonomous and changes go through voting.
     /// Otherwise, this authority can make config changes directly (controlled multisig).
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::base58"))]
     pub config_authority: Pubkey,
     /// Threshold for approval (number of votes required)
     pub threshold: u16,
@@ -27,104 +168,109 @@ pub struct Multisig {
     /// All transactions up to this index are stale (updated when config changes)
     pub stale_transaction_index: u64,
     /// Address where rent can be reclaimed for closed accounts (None disables rent reclamation)
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::option_base58"))]
     pub rent_collector: Option<Pubkey>,
     /// PDA bump seed
     pub bump: u8,
     /// Members of the multisig with their permissions
     pub members: Vec<Member>,
+    /// Bytes following the known fields (reserved space, padding, or fields added by
+    /// newer program versions)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub unknown_tail: Vec<u8>,
+}
+
+/// Bounds-checked reader over raw account data
+struct AccountReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> AccountReader<'a> {
+    fn new(data: &'a [u8], offset: usize) -> Self {
+        Self { data, offset }
+    }
+
+    /// Take the next `len` bytes, failing with an error naming `field` if the data ends
+    fn take(&mut self, len: usize, field: &str) -> Result<&'a [u8], std::io::Error> {
+        let bytes = self
+            .offset
+            .checked_add(len)
+            .and_then(|end| self.data.get(self.offset..end))
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    format!("Not enough bytes for {}", field),
+                )
+            })?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self, field: &str) -> Result<[u8; N], std::io::Error> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.take(N, field)?);
+        Ok(array)
+    }
+
+    fn u8(&mut self, field: &str) -> Result<u8, std::io::Error> {
+        Ok(self.array::<1>(field)?[0])
+    }
+
+    fn pubkey(&mut self, field: &str) -> Result<Pubkey, std::io::Error> {
+        Ok(Pubkey::new_from_array(self.array(field)?))
+    }
+
+    fn remaining(&self) -> &'a [u8] {
+        &self.data[self.offset..]
+    }
 }
 
 impl Multisig {
     /// Deserialize a Multisig account from raw account data
+    ///
+    /// Truncated data fails with an error instead of panicking, however many members the
+    /// account claims to have.
     pub fn try_from_slice(data: &[u8]) -> Result<Self, std::io::Error> {
-        // Skip the 8-byte Anchor discriminator
-        if data.len() < 8 {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Account data too short",
-            ));
-        }
-        
+        check_discriminator::<Self>(data)?;
+
         // Manual deserialization to handle on-chain format quirks
-        let mut offset = 8; // Skip discriminator
-        
-        let create_key = Pubkey::try_from(&data[offset..offset+32])
-            .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid create_key"))?;
-        offset += 32;
-        
-        let config_authority = Pubkey::try_from(&data[offset..offset+32])
-            .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid config_authority"))?;
-        offset += 32;
-        
-        let threshold = u16::from_le_bytes([data[offset], data[offset+1]]);
-        offset += 2;
-        
-        let time_lock = u32::from_le_bytes([
-            data[offset], data[offset+1], data[offset+2], data[offset+3]
-        ]);
-        offset += 4;
-        
-        let transaction_index = u64::from_le_bytes([
-            data[offset], data[offset+1], data[offset+2], data[offset+3],
-            data[offset+4], data[offset+5], data[offset+6], data[offset+7]
-        ]);
-        offset += 8;
-        
-        let stale_transaction_index = u64::from_le_bytes([
-            data[offset], data[offset+1], data[offset+2], data[offset+3],
-            data[offset+4], data[offset+5], data[offset+6], data[offset+7]
-        ]);
-        offset += 8;
-        
+        let mut reader = AccountReader::new(data, 8);
+
+        let create_key = reader.pubkey("create_key")?;
+        let config_authority = reader.pubkey("config_authority")?;
+        let threshold = u16::from_le_bytes(reader.array("threshold")?);
+        let time_lock = u32::from_le_bytes(reader.array("time_lock")?);
+        let transaction_index = u64::from_le_bytes(reader.array("transaction_index")?);
+        let stale_transaction_index = u64::from_le_bytes(reader.array("stale_transaction_index")?);
+
         // rent_collector: 1 byte flag + 32 bytes ONLY if flag is 1
-        let has_rent_collector = data[offset];
-        offset += 1;
-        
-        let rent_collector = if has_rent_collector == 1 {
-            let pk = Pubkey::try_from(&data[offset..offset+32])
-                .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid rent_collector"))?;
-            offset += 32;
-            Some(pk)
+        let rent_collector = if reader.u8("rent_collector")? == 1 {
+            Some(reader.pubkey("rent_collector")?)
         } else {
             // No padding when None - bump comes immediately after
             None
         };
-        
-        let bump = data[offset];
-        offset += 1;
-        
+
+        let bump = reader.u8("bump")?;
+
         // Manually deserialize members Vec to handle trailing padding bytes
-        // Vec format: u32 length + items
-        let members_len = u32::from_le_bytes([
-            data[offset], data[offset+1], data[offset+2], data[offset+3]
-        ]) as usize;
-        offset += 4;
-        
-        let mut members = Vec::with_capacity(members_len);
+        // Vec format: u32 length + items, each Member is Pubkey (32 bytes) + Permissions (1 byte)
+        let members_len = u32::from_le_bytes(reader.array("members length")?) as usize;
+        // The length is untrusted, so never reserve more than the data can hold
+        let mut members = Vec::with_capacity(members_len.min(reader.remaining().len() / 33));
         for _ in 0..members_len {
-            // Each Member is: Pubkey (32 bytes) + Permissions (1 byte)
-            if offset + 33 > data.len() {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::InvalidData,
-                    "Not enough bytes for member",
-                ));
-            }
-            
-            let key = Pubkey::try_from(&data[offset..offset+32])
-                .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid member key"))?;
-            offset += 32;
-            
-            let permissions_mask = data[offset];
-            offset += 1;
-            
+            let key = reader.pubkey("member")?;
+            let permissions_mask = reader.u8("member")?;
             members.push(Member {
                 key,
                 permissions: crate::types::Permissions::from_mask(permissions_mask),
             });
         }
-        
-        // Ignore any trailing padding bytes (typically 32 bytes of zeros)
-        
+
+        // Keep any trailing bytes (typically padding) instead of rejecting them
+        let unknown_tail = reader.remaining().to_vec();
+
         Ok(Self {
             create_key,
             config_authority,
@@ -135,8 +281,175 @@ impl Multisig {
             rent_collector,
             bump,
             members,
+            unknown_tail,
+        })
+    }
+}
+
+impl Multisig {
+    /// Deserialize a Multisig account with the given parse options
+    ///
+    /// The program never shrinks a multisig account, so its tail holds the 32 bytes
+    /// reserved for the rent collector when none is set, followed by stale 33-byte
+    /// member entries left behind by removed members. Neither is zeroed, so strict mode
+    /// only checks that the tail splits into these parts and otherwise fails with
+    /// [`SquadsError::UnexpectedTrailingBytes`].
+    pub fn try_from_slice_with(data: &[u8], options: &ParseOptions) -> SquadsResult<Self> {
+        let multisig = Self::try_from_slice(data)?;
+        if options.is_strict() {
+            let reserved = if multisig.rent_collector.is_none() {
+                32
+            } else {
+                0
+            };
+            let tail = &multisig.unknown_tail;
+            if tail.len() < reserved || (tail.len() - reserved) % 33 != 0 {
+                return Err(SquadsError::UnexpectedTrailingBytes {
+                    count: tail.len(),
+                    bytes: tail.clone(),
+                });
+            }
+        }
+        Ok(multisig)
+    }
+}
+
+/// Borrowing view over Multisig account data
+///
+/// [`Multisig::try_from_slice`] allocates the member list of every account it decodes.
+/// `MultisigRef` validates the layout once and then reads each field straight from the
+/// borrowed bytes, which suits scanning many accounts for a few fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MultisigRef<'a> {
+    data: &'a [u8],
+    rent_collector: bool,
+    members: &'a [u8],
+    unknown_tail: &'a [u8],
+}
+
+impl<'a> MultisigRef<'a> {
+    /// Offset of the rent collector option flag
+    const RENT_COLLECTOR_OFFSET: usize = 94;
+
+    /// Check the discriminator and layout of Multisig account data and borrow it
+    ///
+    /// Fails on the same inputs as [`Multisig::try_from_slice`].
+    pub fn new(data: &'a [u8]) -> Result<Self, std::io::Error> {
+        check_discriminator::<Multisig>(data)?;
+
+        let mut reader = AccountReader::new(data, Self::RENT_COLLECTOR_OFFSET);
+        let rent_collector = reader.u8("rent_collector")? == 1;
+        if rent_collector {
+            reader.take(32, "rent_collector")?;
+        }
+        reader.u8("bump")?;
+        let members_len = u32::from_le_bytes(reader.array("members length")?) as usize;
+        let members = reader.take(members_len.saturating_mul(33), "member")?;
+
+        Ok(Self {
+            data,
+            rent_collector,
+            members,
+            unknown_tail: reader.remaining(),
         })
     }
+
+    fn field<const N: usize>(&self, offset: usize) -> [u8; N] {
+        let mut field = [0u8; N];
+        field.copy_from_slice(&self.data[offset..offset + N]);
+        field
+    }
+
+    /// Key that is used to seed the multisig PDA
+    pub fn create_key(&self) -> Pubkey {
+        Pubkey::new_from_array(self.field(8))
+    }
+
+    /// Authority that can change the config, `Pubkey::default()` if autonomous
+    pub fn config_authority(&self) -> Pubkey {
+        Pubkey::new_from_array(self.field(40))
+    }
+
+    /// Threshold for approval
+    pub fn threshold(&self) -> u16 {
+        u16::from_le_bytes(self.field(72))
+    }
+
+    /// Time lock in seconds
+    pub fn time_lock(&self) -> u32 {
+        u32::from_le_bytes(self.field(74))
+    }
+
+    /// Last transaction index
+    pub fn transaction_index(&self) -> u64 {
+        u64::from_le_bytes(self.field(78))
+    }
+
+    /// Stale transaction index
+    pub fn stale_transaction_index(&self) -> u64 {
+        u64::from_le_bytes(self.field(86))
+    }
+
+    /// Address where rent can be reclaimed, if set
+    pub fn rent_collector(&self) -> Option<Pubkey> {
+        self.rent_collector
+            .then(|| Pubkey::new_from_array(self.field(Self::RENT_COLLECTOR_OFFSET + 1)))
+    }
+
+    /// PDA bump seed
+    pub fn bump(&self) -> u8 {
+        let offset = Self::RENT_COLLECTOR_OFFSET + if self.rent_collector { 33 } else { 1 };
+        self.data[offset]
+    }
+
+    /// Transaction and stale transaction indices
+    pub fn snapshot(&self) -> MultisigSnapshot {
+        MultisigSnapshot {
+            transaction_index: self.transaction_index(),
+            stale_transaction_index: self.stale_transaction_index(),
+        }
+    }
+
+    /// Number of members
+    pub fn member_count(&self) -> usize {
+        self.members.len() / 33
+    }
+
+    /// Members in account order, decoded one at a time
+    pub fn members(&self) -> impl ExactSizeIterator<Item = Member> + 'a {
+        self.members.chunks_exact(33).map(|member| Member {
+            key: Pubkey::new_from_array(member[..32].try_into().expect("32 byte key")),
+            permissions: crate::types::Permissions::from_mask(member[32]),
+        })
+    }
+
+    /// Check whether a pubkey is a member
+    pub fn is_member(&self, pubkey: &Pubkey) -> bool {
+        self.members
+            .chunks_exact(33)
+            .any(|member| member[..32] == pubkey.to_bytes())
+    }
+
+    /// Bytes following the known fields
+    pub fn unknown_tail(&self) -> &'a [u8] {
+        self.unknown_tail
+    }
+
+    /// Decode the full account
+    pub fn to_multisig(&self) -> Multisig {
+        Multisig {
+            create_key: self.create_key(),
+            config_authority: self.config_authority(),
+            threshold: self.threshold(),
+            time_lock: self.time_lock(),
+            transaction_index: self.transaction_index(),
+            stale_transaction_index: self.stale_transaction_index(),
+            rent_collector: self.rent_collector(),
+            bump: self.bump(),
+            members: self.members().collect(),
+            unknown_tail: self.unknown_tail.to_vec(),
+        }
+    }
 }
 
 // Minimal Borsh implementations for compatibility
@@ -170,7 +483,7 @@ impl BorshDeserialize for Multisig {
     fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
         // Use try_from_slice which handles the format properly
         // Note: this assumes discriminator was already skipped
-        let full_data = [&[0u8; 8][..], *buf].concat();
+        let full_data = [&Multisig::DISCRIMINATOR[..], *buf].concat();
         Self::try_from_slice(&full_data)
     }
     
@@ -209,24 +522,120 @@ impl Multisig {
     }
 
     /// Calculate the rejection cutoff (minimum rejections to reject a proposal)
+    ///
+    /// A threshold above the number of voters, which the program never stores but
+    /// decoded data or a replayed diff can hold, gives a cutoff of 1.
     pub fn cutoff(&self) -> usize {
         self.num_voters()
-            .checked_sub(usize::from(self.threshold))
-            .unwrap()
-            .checked_add(1)
-            .unwrap()
+            .saturating_sub(usize::from(self.threshold))
+            .saturating_add(1)
     }
 
     /// Check if a pubkey is a member
     pub fn is_member(&self, pubkey: &Pubkey) -> bool {
         self.members.iter().any(|m| &m.key == pubkey)
     }
+
+    /// Record the indices used to detect concurrent changes to this multisig
+    pub fn snapshot(&self) -> MultisigSnapshot {
+        MultisigSnapshot {
+            transaction_index: self.transaction_index,
+            stale_transaction_index: self.stale_transaction_index,
+        }
+    }
+
+    /// Apply a config action to this copy of the multisig, as `config_transaction_execute`
+    /// would
+    ///
+    /// Members are kept sorted by key like the program does. Spending limit actions do
+    /// not change the multisig account and are ignored. Call [`Self::check_invariants`]
+    /// after applying all actions of a transaction.
+    pub fn apply_config_action(&mut self, action: &ConfigAction) {
+        match action {
+            ConfigAction::AddMember { new_member } => {
+                self.members.push(new_member.clone());
+                self.members.sort_by_key(|m| m.key);
+            }
+            ConfigAction::RemoveMember { old_member } => {
+                self.members.retain(|m| &m.key != old_member);
+            }
+            ConfigAction::ChangeThreshold { new_threshold } => self.threshold = *new_threshold,
+            ConfigAction::SetTimeLock { new_time_lock } => self.time_lock = *new_time_lock,
+            ConfigAction::SetRentCollector { new_rent_collector } => {
+                self.rent_collector = *new_rent_collector;
+            }
+            ConfigAction::SetConfigAuthority {
+                new_config_authority,
+            } => self.config_authority = new_config_authority.unwrap_or_default(),
+            ConfigAction::AddSpendingLimit { .. } | ConfigAction::RemoveSpendingLimit { .. } => {}
+        }
+        // Changing the config invalidates all pending transactions
+        if action.invalidates_pending_transactions() {
+            self.stale_transaction_index = self.transaction_index;
+        }
+    }
+
+    /// Check the invariants the program enforces after a config change
+    ///
+    /// Members must be unique, there must be at least one proposer, voter and executor,
+    /// and the threshold must be between 1 and the number of voters.
+    pub fn check_invariants(&self) -> SquadsResult<()> {
+        let mut keys: Vec<&Pubkey> = self.members.iter().map(|m| &m.key).collect();
+        keys.sort();
+        if keys.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(SquadsError::InvalidPermissions(
+                "duplicate member".to_string(),
+            ));
+        }
+        if self.num_voters() == 0 {
+            return Err(SquadsError::NoVotingMembers);
+        }
+        if self.num_proposers() == 0 || self.num_executors() == 0 {
+            return Err(SquadsError::InvalidPermissions(
+                "at least one member must be able to initiate and execute".to_string(),
+            ));
+        }
+        if self.threshold == 0 || usize::from(self.threshold) > self.num_voters() {
+            return Err(SquadsError::InvalidThreshold);
+        }
+        Ok(())
+    }
+}
+
+/// Transaction indices of a multisig at the time it was read
+///
+/// Every new transaction advances `transaction_index` and every config change advances
+/// `stale_transaction_index` (unless no transaction was pending), so comparing them
+/// detects that a cached multisig is outdated before building on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MultisigSnapshot {
+    /// Last transaction index
+    pub transaction_index: u64,
+    /// Stale transaction index
+    pub stale_transaction_index: u64,
+}
+
+impl MultisigSnapshot {
+    /// Check that `current` still matches this snapshot
+    pub fn verify(&self, current: &Multisig) -> SquadsResult<()> {
+        if current.snapshot() == *self {
+            return Ok(());
+        }
+        Err(SquadsError::StaleSnapshot {
+            expected_transaction_index: self.transaction_index,
+            expected_stale_transaction_index: self.stale_transaction_index,
+            found_transaction_index: current.transaction_index,
+            found_stale_transaction_index: current.stale_transaction_index,
+        })
+    }
 }
 
 /// Proposal account that tracks voting status for a transaction
 #[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Proposal {
     /// The multisig this proposal belongs to
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::base58"))]
     pub multisig: Pubkey,
     /// Index of the transaction this proposal is for
     pub transaction_index: u64,
@@ -235,24 +644,72 @@ pub struct Proposal {
     /// PDA bump seed
     pub bump: u8,
     /// Members who have approved
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::vec_base58"))]
     pub approved: Vec<Pubkey>,
     /// Members who have rejected
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::vec_base58"))]
     pub rejected: Vec<Pubkey>,
     /// Members who have cancelled (only applicable when status is Approved)
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::vec_base58"))]
     pub cancelled: Vec<Pubkey>,
+    /// Bytes following the known fields (reserved space, padding, or fields added by
+    /// newer program versions)
+    #[borsh(skip)]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub unknown_tail: Vec<u8>,
 }
 
 impl Proposal {
     /// Deserialize a Proposal account from raw account data
     pub fn try_from_slice(data: &[u8]) -> Result<Self, std::io::Error> {
-        // Skip the 8-byte Anchor discriminator
-        if data.len() < 8 {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Account data too short",
-            ));
+        let (mut account, unknown_tail) = deserialize_account::<Self>(data)?;
+        account.unknown_tail = unknown_tail;
+        Ok(account)
+    }
+
+    /// Size in bytes of a proposal account that can record `num_voters` votes
+    ///
+    /// The program allocates room for every member in each of the approved, rejected
+    /// and cancelled lists, so pass the multisig's member count to get the size
+    /// `proposal_create` will allocate.
+    pub fn size_for(num_voters: usize) -> usize {
+        8 + // anchor account discriminator
+        32 + // multisig
+        8 + // transaction_index
+        1 + // status enum variant
+        8 + // status timestamp
+        1 + // bump
+        3 * (4 + num_voters * 32) // approved, rejected and cancelled vecs
+    }
+
+    /// Check whether the program allows closing this proposal and its `kind` of
+    /// transaction
+    ///
+    /// Executed, Rejected and Cancelled proposals can always be closed, and so can other
+    /// stale proposals, except that approved stale vault transactions and batches remain
+    /// executable and cannot be closed. Approved stale config transactions can no longer
+    /// execute, so they are closable.
+    pub fn is_closable(&self, kind: TransactionKind, stale_transaction_index: u64) -> bool {
+        let stale = self.transaction_index <= stale_transaction_index;
+        match self.status {
+            ProposalStatus::Executed { .. }
+            | ProposalStatus::Rejected { .. }
+            | ProposalStatus::Cancelled { .. } => true,
+            ProposalStatus::Approved { .. } => stale && kind == TransactionKind::Config,
+            ProposalStatus::Draft { .. } | ProposalStatus::Active { .. } => stale,
+        }
+    }
+
+    /// Build the transition conditions for this proposal under the given multisig
+    pub fn transition_context(&self, multisig: &Multisig) -> TransitionContext {
+        TransitionContext {
+            approvals: self.approved.len(),
+            rejections: self.rejected.len(),
+            cancellations: self.cancelled.len(),
+            threshold: usize::from(multisig.threshold),
+            cutoff: multisig.cutoff(),
+            time_lock: multisig.time_lock,
         }
-        Self::deserialize(&mut &data[8..])
     }
 
     /// Check if a member has approved
@@ -273,10 +730,13 @@ impl Proposal {
 
 /// Vault transaction account
 #[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VaultTransaction {
     /// The multisig this transaction belongs to
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::base58"))]
     pub multisig: Pubkey,
     /// Creator of the transaction
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::base58"))]
     pub creator: Pubkey,
     /// Transaction index within the multisig
     pub index: u64,
@@ -290,24 +750,25 @@ pub struct VaultTransaction {
     pub ephemeral_signer_bumps: Vec<u8>,
     /// The transaction message to execute
     pub message: VaultTransactionMessage,
+    /// Bytes following the known fields (reserved space, padding, or fields added by
+    /// newer program versions)
+    #[borsh(skip)]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub unknown_tail: Vec<u8>,
 }
 
 impl VaultTransaction {
     /// Deserialize a VaultTransaction account from raw account data
     pub fn try_from_slice(data: &[u8]) -> Result<Self, std::io::Error> {
-        // Skip the 8-byte Anchor discriminator
-        if data.len() < 8 {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Account data too short",
-            ));
-        }
-        Self::deserialize(&mut &data[8..])
+        let (mut account, unknown_tail) = deserialize_account::<Self>(data)?;
+        account.unknown_tail = unknown_tail;
+        Ok(account)
     }
 }
 
 /// Transaction message for vault transactions
 #[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VaultTransactionMessage {
     /// Number of signer pubkeys
     pub num_signers: u8,
@@ -316,6 +777,7 @@ pub struct VaultTransactionMessage {
     /// Number of writable non-signer pubkeys
     pub num_writable_non_signers: u8,
     /// Account keys required for the transaction
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::vec_base58"))]
     pub account_keys: Vec<Pubkey>,
     /// Instructions to execute
     pub instructions: Vec<CompiledInstruction>,
@@ -335,37 +797,205 @@ impl VaultTransactionMessage {
         self.account_keys.len() + num_from_lookups
     }
 
-    /// Check if an account index is a static writable account
-    pub fn is_static_writable_index(&self, key_index: usize) -> bool {
+    /// Role of the account at `key_index` in the message's full key list: static keys,
+    /// then writable lookup keys, then readonly lookup keys
+    ///
+    /// Returns `None` if the index is out of range.
+    pub fn key_role(&self, key_index: usize) -> Option<KeyRole> {
         let num_account_keys = self.account_keys.len();
         let num_signers = usize::from(self.num_signers);
         let num_writable_signers = usize::from(self.num_writable_signers);
         let num_writable_non_signers = usize::from(self.num_writable_non_signers);
 
-        if key_index >= num_account_keys {
-            return false;
-        }
-
-        if key_index < num_writable_signers {
-            return true;
+        if key_index < num_account_keys {
+            return Some(if key_index < num_writable_signers {
+                KeyRole::WritableSigner
+            } else if key_index < num_signers {
+                KeyRole::ReadonlySigner
+            } else if key_index - num_signers < num_writable_non_signers {
+                KeyRole::WritableNonSigner
+            } else {
+                KeyRole::Readonly
+            });
         }
 
-        if key_index >= num_signers {
-            let index_into_non_signers = key_index.saturating_sub(num_signers);
-            return index_into_non_signers < num_writable_non_signers;
+        let num_lookup_writable: usize = self
+            .address_table_lookups
+            .iter()
+            .map(|lookup| lookup.writable_indexes.len())
+            .sum();
+        let lookup_index = key_index - num_account_keys;
+        if lookup_index < num_lookup_writable {
+            Some(KeyRole::LookupWritable)
+        } else if key_index < self.num_all_account_keys() {
+            Some(KeyRole::LookupReadonly)
+        } else {
+            None
         }
+    }
 
-        false
+    /// Check if an account index is a static writable account
+    #[deprecated(note = "use `key_role`, which also covers lookup keys")]
+    pub fn is_static_writable_index(&self, key_index: usize) -> bool {
+        matches!(
+            self.key_role(key_index),
+            Some(KeyRole::WritableSigner | KeyRole::WritableNonSigner)
+        )
     }
 
     /// Check if an account index is a signer
+    #[deprecated(note = "use `key_role`, which also covers lookup keys")]
     pub fn is_signer_index(&self, key_index: usize) -> bool {
         key_index < usize::from(self.num_signers)
     }
+
+    /// Reconstruct the instructions of a message that uses no address table lookups
+    ///
+    /// Returns `None` if the message has lookups (their accounts cannot be resolved
+    /// without fetching the tables) or references an out-of-range account index.
+    pub fn to_instructions(&self) -> Option<Vec<Instruction>> {
+        self.to_instructions_with_tables(&[])
+    }
+
+    /// Reconstruct the instructions of a message, resolving lookups from `tables`
+    ///
+    /// Returns `None` if a lookup table is missing from `tables`, a lookup index is out
+    /// of range, or the message references an out-of-range account index.
+    pub fn to_instructions_with_tables(
+        &self,
+        tables: &[AddressLookupTableAccount],
+    ) -> Option<Vec<Instruction>> {
+        let metas = self.account_metas(tables)?;
+
+        self.instructions
+            .iter()
+            .map(|ix| {
+                Some(Instruction {
+                    program_id: metas.get(usize::from(ix.program_id_index))?.pubkey,
+                    accounts: ix
+                        .account_indexes
+                        .iter()
+                        .map(|index| metas.get(usize::from(*index)).cloned())
+                        .collect::<Option<Vec<_>>>()?,
+                    data: ix.data.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Accounts to pass as `remaining_accounts` to `vault_transaction_execute`
+    ///
+    /// Only valid for messages without address table lookups, returns `None` otherwise.
+    /// Signers are passed as non-signers since the program signs for the vault PDAs.
+    pub fn execute_remaining_accounts(&self) -> Option<Vec<AccountMeta>> {
+        self.execute_remaining_accounts_with_tables(&[])
+    }
+
+    /// Accounts to pass as `remaining_accounts` to `vault_transaction_execute`,
+    /// resolving lookups from `tables`
+    ///
+    /// The program expects the lookup table accounts first, in the order of the
+    /// message's lookups, followed by the message accounts. Returns `None` if a lookup
+    /// cannot be resolved.
+    pub fn execute_remaining_accounts_with_tables(
+        &self,
+        tables: &[AddressLookupTableAccount],
+    ) -> Option<Vec<AccountMeta>> {
+        let metas = self.account_metas(tables)?;
+        let table_accounts = self
+            .address_table_lookups
+            .iter()
+            .map(|lookup| AccountMeta::new_readonly(lookup.account_key, false));
+
+        Some(
+            table_accounts
+                .chain(metas.into_iter().map(|meta| AccountMeta {
+                    is_signer: false,
+                    ..meta
+                }))
+                .collect(),
+        )
+    }
+
+    /// Metas of all message accounts: static keys, then writable and readonly lookups
+    fn account_metas(&self, tables: &[AddressLookupTableAccount]) -> Option<Vec<AccountMeta>> {
+        let mut metas: Vec<AccountMeta> = self
+            .account_keys
+            .iter()
+            .enumerate()
+            .map(|(index, key)| {
+                let role = self.key_role(index).unwrap_or(KeyRole::Readonly);
+                AccountMeta {
+                    pubkey: *key,
+                    is_signer: role.is_signer(),
+                    is_writable: role.is_writable(),
+                }
+            })
+            .collect();
+
+        let resolve = |lookup: &MessageAddressTableLookup, indexes: &[u8], is_writable: bool| {
+            let table = tables.iter().find(|t| t.key == lookup.account_key)?;
+            indexes
+                .iter()
+                .map(|index| {
+                    Some(AccountMeta {
+                        pubkey: *table.addresses.get(usize::from(*index))?,
+                        is_signer: false,
+                        is_writable,
+                    })
+                })
+                .collect::<Option<Vec<_>>>()
+        };
+        for lookup in &self.address_table_lookups {
+            metas.extend(resolve(lookup, &lookup.writable_indexes, true)?);
+        }
+        for lookup in &self.address_table_lookups {
+            metas.extend(resolve(lookup, &lookup.readonly_indexes, false)?);
+        }
+        Some(metas)
+    }
+}
+
+/// Role of an account key in a [`VaultTransactionMessage`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyRole {
+    /// Static key that signs and is writable
+    WritableSigner,
+    /// Static key that signs and is readonly
+    ReadonlySigner,
+    /// Static key that is writable and does not sign
+    WritableNonSigner,
+    /// Static key that is readonly and does not sign
+    Readonly,
+    /// Key loaded from a lookup table as writable
+    LookupWritable,
+    /// Key loaded from a lookup table as readonly
+    LookupReadonly,
+}
+
+impl KeyRole {
+    /// Whether the key signs the message (vault and ephemeral signer PDAs)
+    pub fn is_signer(&self) -> bool {
+        matches!(self, KeyRole::WritableSigner | KeyRole::ReadonlySigner)
+    }
+
+    /// Whether the key is writable
+    pub fn is_writable(&self) -> bool {
+        matches!(
+            self,
+            KeyRole::WritableSigner | KeyRole::WritableNonSigner | KeyRole::LookupWritable
+        )
+    }
+
+    /// Whether the key is loaded from a lookup table
+    pub fn is_lookup(&self) -> bool {
+        matches!(self, KeyRole::LookupWritable | KeyRole::LookupReadonly)
+    }
 }
 
 /// Compiled instruction for vault transactions
 #[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CompiledInstruction {
     /// Index of the program ID in the account keys
     pub program_id_index: u8,
@@ -377,8 +1007,10 @@ pub struct CompiledInstruction {
 
 /// Address table lookup for loading additional accounts
 #[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MessageAddressTableLookup {
     /// Address of the lookup table account
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::base58"))]
     pub account_key: Pubkey,
     /// Indexes of writable accounts to load
     pub writable_indexes: Vec<u8>,
@@ -388,10 +1020,13 @@ pub struct MessageAddressTableLookup {
 
 /// Config transaction account for multisig configuration changes
 #[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConfigTransaction {
     /// The multisig this config transaction belongs to
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::base58"))]
     pub multisig: Pubkey,
     /// Creator of the transaction
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::base58"))]
     pub creator: Pubkey,
     /// Transaction index within the multisig
     pub index: u64,
@@ -399,85 +1034,94 @@ pub struct ConfigTransaction {
     pub bump: u8,
     /// Configuration actions to execute
     pub actions: Vec<ConfigAction>,
+    /// Bytes following the known fields (reserved space, padding, or fields added by
+    /// newer program versions)
+    #[borsh(skip)]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub unknown_tail: Vec<u8>,
 }
 
 impl ConfigTransaction {
     /// Deserialize a ConfigTransaction account from raw account data
     pub fn try_from_slice(data: &[u8]) -> Result<Self, std::io::Error> {
-        // Skip the 8-byte Anchor discriminator
-        if data.len() < 8 {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Account data too short",
-            ));
-        }
-        Self::deserialize(&mut &data[8..])
+        let (mut account, unknown_tail) = deserialize_account::<Self>(data)?;
+        account.unknown_tail = unknown_tail;
+        Ok(account)
     }
 }
 
 /// Program configuration account
 #[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProgramConfig {
     /// Authority that can update program config
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::base58"))]
     pub authority: Pubkey,
     /// Fee charged for creating a multisig (in lamports)
     pub multisig_creation_fee: u64,
     /// Treasury account that receives fees
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::base58"))]
     pub treasury: Pubkey,
+    /// Bytes following the known fields (reserved space, padding, or fields added by
+    /// newer program versions)
+    #[borsh(skip)]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub unknown_tail: Vec<u8>,
 }
 
 impl ProgramConfig {
     /// Deserialize a ProgramConfig account from raw account data
     pub fn try_from_slice(data: &[u8]) -> Result<Self, std::io::Error> {
-        // Skip the 8-byte Anchor discriminator
-        if data.len() < 8 {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Account data too short",
-            ));
-        }
-        Self::deserialize(&mut &data[8..])
+        let (mut account, unknown_tail) = deserialize_account::<Self>(data)?;
+        account.unknown_tail = unknown_tail;
+        Ok(account)
     }
 }
 
 /// Spending limit account for controlled token transfers
 #[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SpendingLimit {
     /// The multisig this spending limit belongs to
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::base58"))]
     pub multisig: Pubkey,
     /// Unique key for this spending limit
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::base58"))]
     pub create_key: Pubkey,
     /// Vault index this limit applies to
     pub vault_index: u8,
     /// Token mint (system program ID for SOL)
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::base58"))]
     pub mint: Pubkey,
     /// Maximum amount that can be spent per period
     pub amount: u64,
     /// Time period for the limit
     pub period: Period,
     /// Members who can use this spending limit
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::vec_base58"))]
     pub members: Vec<Pubkey>,
     /// Allowed destination addresses
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::vec_base58"))]
     pub destinations: Vec<Pubkey>,
     /// Amount remaining in the current period
     pub remaining_amount: u64,
-    /// Unix timestamp when the current period ends
+    /// Unix timestamp the current period started at
     pub last_reset: i64,
     /// PDA bump seed
     pub bump: u8,
+    /// Bytes following the known fields (reserved space, padding, or fields added by
+    /// newer program versions)
+    #[borsh(skip)]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub unknown_tail: Vec<u8>,
 }
 
 impl SpendingLimit {
     /// Deserialize a SpendingLimit account from raw account data
     pub fn try_from_slice(data: &[u8]) -> Result<Self, std::io::Error> {
-        // Skip the 8-byte Anchor discriminator
-        if data.len() < 8 {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Account data too short",
-            ));
-        }
-        Self::deserialize(&mut &data[8..])
+        let (mut account, unknown_tail) = deserialize_account::<Self>(data)?;
+        account.unknown_tail = unknown_tail;
+        Ok(account)
     }
 
     /// Check if a member can use this spending limit
@@ -489,12 +1133,368 @@ impl SpendingLimit {
     pub fn is_destination_allowed(&self, destination: &Pubkey) -> bool {
         self.destinations.is_empty() || self.destinations.contains(destination)
     }
+
+    /// Reset window a use at `now` would be counted in, or None for one-time limits
+    pub fn window_at(&self, now: i64) -> Option<PeriodWindow> {
+        self.period.window_containing(self.last_reset, now)
+    }
+
+    /// Amount available to a use at `now`, accounting for a reset the program would apply
+    pub fn remaining_at(&self, now: i64) -> u64 {
+        match self.window_at(now) {
+            Some(window) if window.start != self.last_reset => self.amount,
+            _ => self.remaining_amount,
+        }
+    }
+}
+
+/// Batch account grouping vault transactions that execute in order
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct Batch {
+    /// The multisig this batch belongs to
+    pub multisig: Pubkey,
+    /// Member that created the batch
+    pub creator: Pubkey,
+    /// Transaction index of the batch within the multisig
+    pub index: u64,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Vault index the batch transactions execute from
+    pub vault_index: u8,
+    /// Vault PDA bump
+    pub vault_bump: u8,
+    /// Number of transactions in the batch
+    pub size: u32,
+    /// Index of the last executed transaction (0 means none have executed)
+    pub executed_transaction_index: u32,
+    /// Bytes following the known fields (reserved space, padding, or fields added by
+    /// newer program versions)
+    #[borsh(skip)]
+    pub unknown_tail: Vec<u8>,
+}
+
+impl Batch {
+    /// Deserialize a Batch account from raw account data
+    pub fn try_from_slice(data: &[u8]) -> Result<Self, std::io::Error> {
+        let (mut account, unknown_tail) = deserialize_account::<Self>(data)?;
+        account.unknown_tail = unknown_tail;
+        Ok(account)
+    }
+
+    /// Index of the next transaction added to the batch, for
+    /// [`crate::pda::get_batch_transaction_pda`]
+    pub fn next_transaction_index(&self) -> u32 {
+        self.size + 1
+    }
+
+    /// Whether every transaction in the batch has executed
+    pub fn is_fully_executed(&self) -> bool {
+        self.executed_transaction_index >= self.size
+    }
+}
+
+/// Vault transaction stored inside a [`Batch`]
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct VaultBatchTransaction {
+    /// PDA bump seed
+    pub bump: u8,
+    /// Bumps for ephemeral signers (additional PDAs used as signers)
+    pub ephemeral_signer_bumps: Vec<u8>,
+    /// The transaction message to execute
+    pub message: VaultTransactionMessage,
+    /// Bytes following the known fields (reserved space, padding, or fields added by
+    /// newer program versions)
+    #[borsh(skip)]
+    pub unknown_tail: Vec<u8>,
+}
+
+impl VaultBatchTransaction {
+    /// Deserialize a VaultBatchTransaction account from raw account data
+    pub fn try_from_slice(data: &[u8]) -> Result<Self, std::io::Error> {
+        let (mut account, unknown_tail) = deserialize_account::<Self>(data)?;
+        account.unknown_tail = unknown_tail;
+        Ok(account)
+    }
+}
+
+/// Buffer a large vault transaction message is uploaded into over several transactions
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct TransactionBuffer {
+    /// The multisig this buffer belongs to
+    pub multisig: Pubkey,
+    /// Member that created the buffer
+    pub creator: Pubkey,
+    /// Index distinguishing the creator's buffers
+    pub buffer_index: u8,
+    /// Vault index the transaction will execute from
+    pub vault_index: u8,
+    /// SHA-256 hash of the complete serialized message
+    pub final_buffer_hash: [u8; 32],
+    /// Size of the complete serialized message in bytes
+    pub final_buffer_size: u16,
+    /// Bytes of the message uploaded so far
+    pub buffer: Vec<u8>,
+    /// Bytes following the known fields (reserved space, padding, or fields added by
+    /// newer program versions)
+    #[borsh(skip)]
+    pub unknown_tail: Vec<u8>,
+}
+
+impl TransactionBuffer {
+    /// Deserialize a TransactionBuffer account from raw account data
+    pub fn try_from_slice(data: &[u8]) -> Result<Self, std::io::Error> {
+        let (mut account, unknown_tail) = deserialize_account::<Self>(data)?;
+        account.unknown_tail = unknown_tail;
+        Ok(account)
+    }
+
+    /// Bytes still to upload
+    pub fn remaining_bytes(&self) -> usize {
+        usize::from(self.final_buffer_size).saturating_sub(self.buffer.len())
+    }
+
+    /// Whether the whole message has been uploaded
+    pub fn is_complete(&self) -> bool {
+        self.buffer.len() == usize::from(self.final_buffer_size)
+    }
+
+    /// Whether the buffer is complete and hashes to `final_buffer_hash`, as the program
+    /// checks when creating the vault transaction from it
+    pub fn verify(&self) -> bool {
+        self.is_complete()
+            && solana_program::hash::hash(&self.buffer).to_bytes() == self.final_buffer_hash
+    }
+}
+
+/// Account types that exist on mainnet but are not decoded by this client
+///
+/// Covers the account types of the Squads Smart Account program and its forks.
+/// [`parse_any`] reports these by name instead of treating them as unknown data.
+pub const UNDECODED_ACCOUNT_NAMES: &[&str] = &[
+    "Settings",
+    "SettingsTransaction",
+    "Transaction",
+    "BatchTransaction",
+];
+
+/// Any account owned by the Squads program, classified by its discriminator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SquadsAccount {
+    /// Multisig account
+    Multisig(Multisig),
+    /// Proposal account
+    Proposal(Proposal),
+    /// Vault transaction account
+    VaultTransaction(VaultTransaction),
+    /// Config transaction account
+    ConfigTransaction(ConfigTransaction),
+    /// Spending limit account
+    SpendingLimit(SpendingLimit),
+    /// Program config account
+    ProgramConfig(ProgramConfig),
+    /// Batch account
+    Batch(Batch),
+    /// Transaction of a batch
+    VaultBatchTransaction(VaultBatchTransaction),
+    /// Transaction buffer account
+    TransactionBuffer(TransactionBuffer),
+    /// A known account type that this client does not decode (see
+    /// [`UNDECODED_ACCOUNT_NAMES`])
+    Undecoded {
+        /// Anchor account name
+        name: &'static str,
+        /// Raw account data, including the discriminator
+        data: Vec<u8>,
+    },
+    /// An account with an unrecognised discriminator
+    Unknown {
+        /// First 8 bytes of the account data
+        discriminator: [u8; 8],
+        /// Raw account data, including the discriminator
+        data: Vec<u8>,
+    },
+}
+
+impl SquadsAccount {
+    /// Anchor account name, or None for unknown accounts
+    pub fn name(&self) -> Option<&'static str> {
+        match self {
+            SquadsAccount::Multisig(_) => Some(Multisig::NAME),
+            SquadsAccount::Proposal(_) => Some(Proposal::NAME),
+            SquadsAccount::VaultTransaction(_) => Some(VaultTransaction::NAME),
+            SquadsAccount::ConfigTransaction(_) => Some(ConfigTransaction::NAME),
+            SquadsAccount::SpendingLimit(_) => Some(SpendingLimit::NAME),
+            SquadsAccount::ProgramConfig(_) => Some(ProgramConfig::NAME),
+            SquadsAccount::Batch(_) => Some(Batch::NAME),
+            SquadsAccount::VaultBatchTransaction(_) => Some(VaultBatchTransaction::NAME),
+            SquadsAccount::TransactionBuffer(_) => Some(TransactionBuffer::NAME),
+            SquadsAccount::Undecoded { name, .. } => Some(name),
+            SquadsAccount::Unknown { .. } => None,
+        }
+    }
+
+    /// Classify and deserialize raw account data by its Anchor discriminator
+    ///
+    /// Same as [`parse_any`], with decoding failures reported as
+    /// [`SquadsError::DeserializationError`] and [`SquadsError::DiscriminatorMismatch`].
+    pub fn parse(data: &[u8]) -> SquadsResult<Self> {
+        parse_any(data).map_err(SquadsError::from_decode_error)
+    }
+}
+
+/// The account at a transaction index: a vault transaction, config transaction or batch
+///
+/// All three live at the transaction PDA of their index, so readers that list
+/// transactions decode them with [`SquadsTransaction::try_from_slice`] instead of trying
+/// each type in turn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SquadsTransaction {
+    /// Vault transaction account
+    Vault(VaultTransaction),
+    /// Config transaction account
+    Config(ConfigTransaction),
+    /// Batch account
+    Batch(Batch),
+}
+
+impl TransactionKind {
+    /// Kind of transaction account the data holds, from its discriminator
+    pub fn of(data: &[u8]) -> Option<Self> {
+        match data.get(..8)? {
+            d if d == VaultTransaction::DISCRIMINATOR => Some(TransactionKind::Vault),
+            d if d == ConfigTransaction::DISCRIMINATOR => Some(TransactionKind::Config),
+            d if d == Batch::DISCRIMINATOR => Some(TransactionKind::Batch),
+            _ => None,
+        }
+    }
+}
+
+impl SquadsTransaction {
+    /// Deserialize a transaction account of any kind from raw account data
+    ///
+    /// Fails if the data is not a vault transaction, config transaction or batch.
+    pub fn try_from_slice(data: &[u8]) -> Result<Self, std::io::Error> {
+        match TransactionKind::of(data) {
+            Some(TransactionKind::Vault) => VaultTransaction::try_from_slice(data).map(Self::Vault),
+            Some(TransactionKind::Config) => {
+                ConfigTransaction::try_from_slice(data).map(Self::Config)
+            }
+            Some(TransactionKind::Batch) => Batch::try_from_slice(data).map(Self::Batch),
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Not a transaction account",
+            )),
+        }
+    }
+
+    /// Kind of the transaction
+    pub fn kind(&self) -> TransactionKind {
+        match self {
+            SquadsTransaction::Vault(_) => TransactionKind::Vault,
+            SquadsTransaction::Config(_) => TransactionKind::Config,
+            SquadsTransaction::Batch(_) => TransactionKind::Batch,
+        }
+    }
+
+    /// Multisig the transaction belongs to
+    pub fn multisig(&self) -> &Pubkey {
+        match self {
+            SquadsTransaction::Vault(transaction) => &transaction.multisig,
+            SquadsTransaction::Config(transaction) => &transaction.multisig,
+            SquadsTransaction::Batch(batch) => &batch.multisig,
+        }
+    }
+
+    /// Member that created the transaction
+    pub fn creator(&self) -> &Pubkey {
+        match self {
+            SquadsTransaction::Vault(transaction) => &transaction.creator,
+            SquadsTransaction::Config(transaction) => &transaction.creator,
+            SquadsTransaction::Batch(batch) => &batch.creator,
+        }
+    }
+
+    /// Transaction index within the multisig
+    pub fn index(&self) -> u64 {
+        match self {
+            SquadsTransaction::Vault(transaction) => transaction.index,
+            SquadsTransaction::Config(transaction) => transaction.index,
+            SquadsTransaction::Batch(batch) => batch.index,
+        }
+    }
+}
+
+/// Classify and deserialize raw account data by its Anchor discriminator
+///
+/// Accounts whose discriminator matches no known type are returned as
+/// [`SquadsAccount::Unknown`] rather than failing, so scanners keep working when a newer
+/// program version introduces account types. Errors are only returned for data shorter
+/// than a discriminator or for known account types whose data fails to decode.
+pub fn parse_any(data: &[u8]) -> Result<SquadsAccount, std::io::Error> {
+    if data.len() < 8 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "Account data too short",
+        ));
+    }
+
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&data[..8]);
+    let account = match discriminator {
+        Multisig::DISCRIMINATOR => SquadsAccount::Multisig(Multisig::try_from_slice(data)?),
+        Proposal::DISCRIMINATOR => SquadsAccount::Proposal(Proposal::try_from_slice(data)?),
+        VaultTransaction::DISCRIMINATOR => {
+            SquadsAccount::VaultTransaction(VaultTransaction::try_from_slice(data)?)
+        }
+        ConfigTransaction::DISCRIMINATOR => {
+            SquadsAccount::ConfigTransaction(ConfigTransaction::try_from_slice(data)?)
+        }
+        SpendingLimit::DISCRIMINATOR => {
+            SquadsAccount::SpendingLimit(SpendingLimit::try_from_slice(data)?)
+        }
+        ProgramConfig::DISCRIMINATOR => {
+            SquadsAccount::ProgramConfig(ProgramConfig::try_from_slice(data)?)
+        }
+        Batch::DISCRIMINATOR => SquadsAccount::Batch(Batch::try_from_slice(data)?),
+        VaultBatchTransaction::DISCRIMINATOR => {
+            SquadsAccount::VaultBatchTransaction(VaultBatchTransaction::try_from_slice(data)?)
+        }
+        TransactionBuffer::DISCRIMINATOR => {
+            SquadsAccount::TransactionBuffer(TransactionBuffer::try_from_slice(data)?)
+        }
+        _ => match UNDECODED_ACCOUNT_NAMES
+            .iter()
+            .find(|name| has_discriminator(data, name))
+        {
+            Some(name) => SquadsAccount::Undecoded {
+                name,
+                data: data.to_vec(),
+            },
+            None => SquadsAccount::Unknown {
+                discriminator,
+                data: data.to_vec(),
+            },
+        },
+    };
+    Ok(account)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// A System program transfer, built by hand since the system interface crate is
+    /// only available with the `client` feature
+    fn system_transfer(from: Pubkey, to: Pubkey, lamports: u64) -> Instruction {
+        let mut data = 2u32.to_le_bytes().to_vec();
+        data.extend_from_slice(&lamports.to_le_bytes());
+        Instruction::new_with_bytes(
+            solana_sdk_ids::system_program::ID,
+            &data,
+            vec![AccountMeta::new(from, true), AccountMeta::new(to, false)],
+        )
+    }
+
     #[test]
     fn test_multisig_calculations() {
         use crate::types::Permissions;
@@ -513,6 +1513,7 @@ mod tests {
                 Member::new(Pubkey::new_unique()),
                 Member::with_permissions(Pubkey::new_unique(), Permissions::from_mask(0)),
             ],
+            unknown_tail: vec![],
         };
 
         assert_eq!(multisig.num_voters(), 2);
@@ -529,11 +1530,12 @@ mod tests {
         let proposal = Proposal {
             multisig: Pubkey::new_unique(),
             transaction_index: 1,
-            status: ProposalStatus::Active,
+            status: ProposalStatus::Active { timestamp: 0 },
             bump: 255,
             approved: vec![member1],
             rejected: vec![member2],
             cancelled: vec![],
+            unknown_tail: vec![],
         };
 
         assert!(proposal.has_approved(&member1));
@@ -541,4 +1543,634 @@ mod tests {
         assert!(proposal.has_rejected(&member2));
         assert!(!proposal.has_rejected(&member1));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_proposal_size_for() {
+        let voters: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let proposal = Proposal {
+            multisig: Pubkey::new_unique(),
+            transaction_index: 1,
+            status: ProposalStatus::Active { timestamp: 0 },
+            bump: 255,
+            approved: voters.clone(),
+            rejected: voters.clone(),
+            cancelled: voters,
+            unknown_tail: vec![],
+        };
+
+        let data = borsh::to_vec(&proposal).unwrap();
+        assert_eq!(Proposal::size_for(5), 8 + data.len());
+        assert_eq!(Proposal::size_for(0), 70);
+    }
+
+    #[test]
+    fn test_multisig_strict_parsing() {
+        let members = vec![Member::new(Pubkey::new_unique())];
+        let mut data = account_discriminator("Multisig").to_vec();
+        let fields = (
+            Pubkey::new_unique(),
+            Pubkey::default(),
+            1u16,
+            0u32,
+            3u64,
+            0u64,
+            None::<Pubkey>,
+            255u8,
+            members,
+        );
+        fields.serialize(&mut data).unwrap();
+        data.extend_from_slice(&[0u8; 32]);
+
+        let strict = ParseOptions::new().strict(true);
+        let multisig = Multisig::try_from_slice_with(&data, &strict).unwrap();
+        assert_eq!(multisig.unknown_tail.len(), 32);
+
+        // A removed member leaves a stale entry behind
+        data.extend_from_slice(&[0x11; 33]);
+        assert!(Multisig::try_from_slice_with(&data, &strict).is_ok());
+
+        data.extend_from_slice(&[0xab, 0xcd]);
+        assert!(Multisig::try_from_slice_with(&data, &ParseOptions::new()).is_ok());
+        match Multisig::try_from_slice_with(&data, &strict) {
+            Err(SquadsError::UnexpectedTrailingBytes { count, bytes }) => {
+                assert_eq!(count, 67);
+                assert_eq!(&bytes[65..], &[0xab, 0xcd]);
+            }
+            other => panic!("expected trailing bytes error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_spending_limit_remaining_at() {
+        let limit = SpendingLimit {
+            multisig: Pubkey::new_unique(),
+            create_key: Pubkey::new_unique(),
+            vault_index: 0,
+            mint: Pubkey::default(),
+            amount: 1_000,
+            period: Period::Day,
+            members: vec![],
+            destinations: vec![],
+            remaining_amount: 250,
+            last_reset: 1_000_000,
+            bump: 255,
+            unknown_tail: vec![],
+        };
+        assert_eq!(limit.remaining_at(1_000_000 + 86_400), 250);
+        assert_eq!(limit.remaining_at(1_000_000 + 86_401), 1_000);
+
+        let one_time = SpendingLimit {
+            period: Period::OneTime,
+            ..limit
+        };
+        assert_eq!(one_time.window_at(i64::MAX), None);
+        assert_eq!(one_time.remaining_at(i64::MAX), 250);
+    }
+
+    #[test]
+    fn test_discriminators_are_checked() {
+        fn check<T: AnchorAccount>() {
+            assert_eq!(
+                T::DISCRIMINATOR,
+                account_discriminator(T::NAME),
+                "{}",
+                T::NAME
+            );
+        }
+        check::<Multisig>();
+        check::<Proposal>();
+        check::<VaultTransaction>();
+        check::<ConfigTransaction>();
+        check::<SpendingLimit>();
+        check::<ProgramConfig>();
+        check::<Batch>();
+        check::<VaultBatchTransaction>();
+        check::<TransactionBuffer>();
+
+        let mut data = Proposal::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&[0u8; 200]);
+        let err: SquadsError = Multisig::try_from_slice(&data).unwrap_err().into();
+        assert!(matches!(
+            err,
+            SquadsError::DiscriminatorMismatch(DiscriminatorMismatch {
+                account: "Multisig",
+                found: Proposal::DISCRIMINATOR,
+                ..
+            })
+        ));
+        let err: SquadsError = ProgramConfig::try_from_slice(&data).unwrap_err().into();
+        assert!(matches!(err, SquadsError::DiscriminatorMismatch(_)));
+    }
+
+    #[test]
+    fn test_program_config_tolerates_trailing_bytes() {
+        let config = ProgramConfig {
+            authority: Pubkey::new_unique(),
+            multisig_creation_fee: 1_000,
+            treasury: Pubkey::new_unique(),
+            unknown_tail: vec![],
+        };
+
+        let mut data = ProgramConfig::DISCRIMINATOR.to_vec();
+        config.serialize(&mut data).unwrap();
+        data.extend_from_slice(&[7u8; 64]);
+
+        let parsed = ProgramConfig::try_from_slice(&data).unwrap();
+        assert_eq!(parsed.authority, config.authority);
+        assert_eq!(parsed.treasury, config.treasury);
+        assert_eq!(parsed.unknown_tail, vec![7u8; 64]);
+    }
+
+    #[test]
+    fn test_message_to_instructions() {
+        let vault = Pubkey::new_unique();
+        let destination = Pubkey::new_unique();
+        let transfer = system_transfer(vault, destination, 5);
+
+        let message = VaultTransactionMessage {
+            num_signers: 1,
+            num_writable_signers: 1,
+            num_writable_non_signers: 1,
+            account_keys: vec![vault, destination, transfer.program_id],
+            instructions: vec![CompiledInstruction {
+                program_id_index: 2,
+                account_indexes: vec![0, 1],
+                data: transfer.data.clone(),
+            }],
+            address_table_lookups: vec![],
+        };
+
+        assert_eq!(message.to_instructions(), Some(vec![transfer.clone()]));
+
+        let remaining = message.execute_remaining_accounts().unwrap();
+        assert_eq!(remaining.len(), 3);
+        assert!(remaining.iter().all(|meta| !meta.is_signer));
+        assert!(remaining[0].is_writable && remaining[1].is_writable);
+        assert!(!remaining[2].is_writable);
+
+        // The same transfer with the destination loaded from a lookup table
+        let table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: vec![Pubkey::new_unique(), destination],
+        };
+        let message = VaultTransactionMessage {
+            num_writable_non_signers: 0,
+            account_keys: vec![vault, transfer.program_id],
+            instructions: vec![CompiledInstruction {
+                program_id_index: 1,
+                account_indexes: vec![0, 2],
+                data: transfer.data.clone(),
+            }],
+            address_table_lookups: vec![MessageAddressTableLookup {
+                account_key: table.key,
+                writable_indexes: vec![1],
+                readonly_indexes: vec![],
+            }],
+            ..message
+        };
+        assert_eq!(message.to_instructions(), None);
+        assert_eq!(
+            message.to_instructions_with_tables(std::slice::from_ref(&table)),
+            Some(vec![transfer])
+        );
+
+        let remaining = message
+            .execute_remaining_accounts_with_tables(std::slice::from_ref(&table))
+            .unwrap();
+        let keys: Vec<Pubkey> = remaining.iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(
+            keys,
+            vec![
+                table.key,
+                vault,
+                solana_sdk_ids::system_program::ID,
+                destination
+            ]
+        );
+        assert!(remaining[3].is_writable && !remaining[0].is_writable);
+    }
+
+    #[test]
+    fn test_message_key_roles() {
+        let message = VaultTransactionMessage {
+            num_signers: 2,
+            num_writable_signers: 1,
+            num_writable_non_signers: 1,
+            account_keys: (0..4).map(|_| Pubkey::new_unique()).collect(),
+            instructions: vec![],
+            address_table_lookups: vec![
+                MessageAddressTableLookup {
+                    account_key: Pubkey::new_unique(),
+                    writable_indexes: vec![0],
+                    readonly_indexes: vec![1, 2],
+                },
+                MessageAddressTableLookup {
+                    account_key: Pubkey::new_unique(),
+                    writable_indexes: vec![3],
+                    readonly_indexes: vec![],
+                },
+            ],
+        };
+
+        let roles: Vec<_> = (0..message.num_all_account_keys())
+            .map(|index| message.key_role(index).unwrap())
+            .collect();
+        assert_eq!(
+            roles,
+            vec![
+                KeyRole::WritableSigner,
+                KeyRole::ReadonlySigner,
+                KeyRole::WritableNonSigner,
+                KeyRole::Readonly,
+                KeyRole::LookupWritable,
+                KeyRole::LookupWritable,
+                KeyRole::LookupReadonly,
+                KeyRole::LookupReadonly,
+            ]
+        );
+        assert_eq!(message.key_role(8), None);
+        assert!(roles[4].is_writable() && roles[4].is_lookup() && !roles[4].is_signer());
+    }
+
+    #[test]
+    fn test_proposal_is_closable() {
+        let mut proposal = Proposal {
+            multisig: Pubkey::new_unique(),
+            transaction_index: 3,
+            status: ProposalStatus::Executed { timestamp: 0 },
+            bump: 255,
+            approved: vec![],
+            rejected: vec![],
+            cancelled: vec![],
+            unknown_tail: vec![],
+        };
+        assert!(proposal.is_closable(TransactionKind::Vault, 0));
+
+        proposal.status = ProposalStatus::Active { timestamp: 0 };
+        assert!(!proposal.is_closable(TransactionKind::Vault, 2));
+        assert!(proposal.is_closable(TransactionKind::Vault, 3));
+
+        // Stale approved vault transactions can still execute; config transactions cannot
+        proposal.status = ProposalStatus::Approved { timestamp: 0 };
+        assert!(!proposal.is_closable(TransactionKind::Vault, 3));
+        assert!(!proposal.is_closable(TransactionKind::Batch, 3));
+        assert!(!proposal.is_closable(TransactionKind::Config, 2));
+        assert!(proposal.is_closable(TransactionKind::Config, 3));
+    }
+
+    #[test]
+    fn test_parse_any() {
+        let config = ProgramConfig {
+            authority: Pubkey::new_unique(),
+            multisig_creation_fee: 0,
+            treasury: Pubkey::new_unique(),
+            unknown_tail: vec![],
+        };
+        let mut data = account_discriminator("ProgramConfig").to_vec();
+        config.serialize(&mut data).unwrap();
+        assert_eq!(
+            parse_any(&data).unwrap(),
+            SquadsAccount::ProgramConfig(config)
+        );
+
+        let batch = Batch {
+            multisig: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            index: 1,
+            bump: 255,
+            vault_index: 0,
+            vault_bump: 255,
+            size: 0,
+            executed_transaction_index: 0,
+            unknown_tail: vec![],
+        };
+        let mut batch_data = Batch::DISCRIMINATOR.to_vec();
+        batch.serialize(&mut batch_data).unwrap();
+        let parsed = SquadsAccount::parse(&batch_data).unwrap();
+        assert_eq!(parsed.name(), Some("Batch"));
+        assert_eq!(parsed, SquadsAccount::Batch(batch));
+        assert!(matches!(
+            SquadsAccount::parse(&batch_data[..20]),
+            Err(SquadsError::DeserializationError)
+        ));
+
+        let settings = account_discriminator("Settings");
+        assert_eq!(parse_any(&settings).unwrap().name(), Some("Settings"));
+
+        let other = [9u8; 40];
+        match parse_any(&other).unwrap() {
+            SquadsAccount::Unknown {
+                discriminator,
+                data,
+            } => {
+                assert_eq!(discriminator, [9u8; 8]);
+                assert_eq!(data.len(), 40);
+            }
+            account => panic!("unexpected account {:?}", account),
+        }
+
+        assert!(parse_any(&[0u8; 4]).is_err());
+    }
+
+    #[test]
+    fn test_squads_transaction() {
+        let multisig = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let config = ConfigTransaction {
+            multisig,
+            creator,
+            index: 3,
+            bump: 255,
+            actions: vec![ConfigAction::ChangeThreshold { new_threshold: 2 }],
+            unknown_tail: vec![],
+        };
+        let mut data = ConfigTransaction::DISCRIMINATOR.to_vec();
+        config.serialize(&mut data).unwrap();
+
+        assert_eq!(TransactionKind::of(&data), Some(TransactionKind::Config));
+        let transaction = SquadsTransaction::try_from_slice(&data).unwrap();
+        assert_eq!(transaction.kind(), TransactionKind::Config);
+        assert_eq!(transaction.index(), 3);
+        assert_eq!(transaction.creator(), &creator);
+        assert_eq!(transaction, SquadsTransaction::Config(config));
+
+        // Other accounts are rejected rather than decoded as the wrong type
+        let other = multisig_data(1, None);
+        assert_eq!(TransactionKind::of(&other), None);
+        assert!(SquadsTransaction::try_from_slice(&other).is_err());
+        assert!(SquadsTransaction::try_from_slice(&data[..4]).is_err());
+    }
+
+    #[test]
+    fn test_batch_account() {
+        let batch = Batch {
+            multisig: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            index: 7,
+            bump: 254,
+            vault_index: 1,
+            vault_bump: 253,
+            size: 3,
+            executed_transaction_index: 1,
+            unknown_tail: vec![],
+        };
+        let mut data = account_discriminator("Batch").to_vec();
+        batch.serialize(&mut data).unwrap();
+        assert_eq!(data.len(), 8 + 32 + 32 + 8 + 3 + 4 + 4);
+        data.push(0);
+
+        let parsed = Batch::try_from_slice(&data).unwrap();
+        assert_eq!(parsed.unknown_tail, vec![0]);
+        assert_eq!(
+            Batch {
+                unknown_tail: vec![],
+                ..parsed.clone()
+            },
+            batch
+        );
+        assert_eq!(parsed.next_transaction_index(), 4);
+        assert!(!parsed.is_fully_executed());
+
+        data[..8].copy_from_slice(&account_discriminator("Proposal"));
+        assert!(Batch::try_from_slice(&data).is_err());
+        assert!(Batch::try_from_slice(&data[..4]).is_err());
+    }
+
+    #[test]
+    fn test_vault_batch_transaction_account() {
+        let vault = Pubkey::new_unique();
+        let destination = Pubkey::new_unique();
+        let transfer = system_transfer(vault, destination, 5);
+        let transaction = VaultBatchTransaction {
+            bump: 254,
+            ephemeral_signer_bumps: vec![253],
+            message: VaultTransactionMessage {
+                num_signers: 1,
+                num_writable_signers: 1,
+                num_writable_non_signers: 1,
+                account_keys: vec![vault, destination, transfer.program_id],
+                instructions: vec![CompiledInstruction {
+                    program_id_index: 2,
+                    account_indexes: vec![0, 1],
+                    data: transfer.data.clone(),
+                }],
+                address_table_lookups: vec![],
+            },
+            unknown_tail: vec![],
+        };
+        let mut data = account_discriminator("VaultBatchTransaction").to_vec();
+        transaction.serialize(&mut data).unwrap();
+
+        let parsed = VaultBatchTransaction::try_from_slice(&data).unwrap();
+        assert_eq!(parsed, transaction);
+        assert_eq!(parsed.message.to_instructions().unwrap(), vec![transfer]);
+
+        data[..8].copy_from_slice(&account_discriminator("VaultTransaction"));
+        assert!(VaultBatchTransaction::try_from_slice(&data).is_err());
+    }
+
+    #[test]
+    fn test_transaction_buffer_account() {
+        let message = vec![7u8; 300];
+        let mut buffer = TransactionBuffer {
+            multisig: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            buffer_index: 0,
+            vault_index: 0,
+            final_buffer_hash: solana_program::hash::hash(&message).to_bytes(),
+            final_buffer_size: 300,
+            buffer: message[..200].to_vec(),
+            unknown_tail: vec![],
+        };
+        let mut data = account_discriminator("TransactionBuffer").to_vec();
+        buffer.serialize(&mut data).unwrap();
+
+        let parsed = TransactionBuffer::try_from_slice(&data).unwrap();
+        assert_eq!(parsed, buffer);
+        assert_eq!(parsed.remaining_bytes(), 100);
+        assert!(!parsed.is_complete());
+        assert!(!parsed.verify());
+
+        buffer.buffer.extend_from_slice(&message[200..]);
+        assert!(buffer.verify());
+        buffer.buffer[0] = 0;
+        assert!(buffer.is_complete());
+        assert!(!buffer.verify());
+
+        data[..8].copy_from_slice(&account_discriminator("Batch"));
+        assert!(TransactionBuffer::try_from_slice(&data).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_account_json() {
+        let data = multisig_data(2, None);
+        let multisig = Multisig::try_from_slice(&data).unwrap();
+        let json = serde_json::to_value(&multisig).unwrap();
+        assert_eq!(json["create_key"], multisig.create_key.to_string());
+        assert_eq!(json["rent_collector"], serde_json::Value::Null);
+        assert_eq!(
+            json["members"][1]["key"],
+            multisig.members[1].key.to_string()
+        );
+        assert_eq!(serde_json::from_value::<Multisig>(json).unwrap(), multisig);
+
+        let voter = Pubkey::new_unique();
+        let proposal = Proposal {
+            multisig: Pubkey::new_unique(),
+            transaction_index: 4,
+            status: ProposalStatus::Active { timestamp: 10 },
+            bump: 255,
+            approved: vec![voter],
+            rejected: vec![],
+            cancelled: vec![],
+            unknown_tail: vec![],
+        };
+        let json = serde_json::to_value(&proposal).unwrap();
+        assert_eq!(json["approved"][0], voter.to_string());
+        assert_eq!(json["status"]["Active"]["timestamp"], 10);
+        assert_eq!(serde_json::from_value::<Proposal>(json).unwrap(), proposal);
+    }
+
+    #[test]
+    fn test_multisig_snapshot() {
+        let mut multisig = Multisig {
+            create_key: Pubkey::new_unique(),
+            config_authority: Pubkey::default(),
+            threshold: 1,
+            time_lock: 0,
+            transaction_index: 4,
+            stale_transaction_index: 2,
+            rent_collector: None,
+            bump: 255,
+            members: vec![Member::new(Pubkey::new_unique())],
+            unknown_tail: vec![],
+        };
+        let snapshot = multisig.snapshot();
+        assert!(snapshot.verify(&multisig).is_ok());
+
+        multisig.apply_config_action(&ConfigAction::ChangeThreshold { new_threshold: 1 });
+        match snapshot.verify(&multisig) {
+            Err(SquadsError::StaleSnapshot {
+                expected_stale_transaction_index,
+                found_stale_transaction_index,
+                ..
+            }) => {
+                assert_eq!(expected_stale_transaction_index, 2);
+                assert_eq!(found_stale_transaction_index, 4);
+            }
+            result => panic!("expected StaleSnapshot, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_check_owner() {
+        let program_id = crate::program_id();
+        assert!(check_owner(&program_id, &program_id).is_ok());
+
+        let other = solana_sdk_ids::system_program::ID;
+        match check_owner(&other, &program_id) {
+            Err(SquadsError::WrongOwner { expected, found }) => {
+                assert_eq!(expected, program_id);
+                assert_eq!(found, other);
+            }
+            result => panic!("expected WrongOwner, got {:?}", result),
+        }
+    }
+
+    fn multisig_data(members: usize, rent_collector: Option<Pubkey>) -> Vec<u8> {
+        let members: Vec<Member> = (0..members)
+            .map(|_| Member::new(Pubkey::new_unique()))
+            .collect();
+        let mut data = account_discriminator("Multisig").to_vec();
+        let fields = (
+            Pubkey::new_unique(),
+            Pubkey::default(),
+            1u16,
+            0u32,
+            3u64,
+            0u64,
+            rent_collector,
+            255u8,
+            members,
+        );
+        fields.serialize(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_multisig_borsh_deserialize_without_discriminator() {
+        let data = multisig_data(2, Some(Pubkey::new_unique()));
+        let multisig = <Multisig as BorshDeserialize>::deserialize(&mut &data[8..]).unwrap();
+        assert_eq!(multisig, Multisig::try_from_slice(&data).unwrap());
+    }
+
+    #[test]
+    fn test_multisig_ref() {
+        for rent_collector in [None, Some(Pubkey::new_unique())] {
+            let data = multisig_data(3, rent_collector);
+            let multisig = Multisig::try_from_slice(&data).unwrap();
+            let view = MultisigRef::new(&data).unwrap();
+            assert_eq!(view.to_multisig(), multisig);
+            assert_eq!(view.threshold(), 1);
+            assert_eq!(view.transaction_index(), 3);
+            assert_eq!(view.rent_collector(), rent_collector);
+            assert_eq!(view.bump(), 255);
+            assert_eq!(view.member_count(), 3);
+            assert!(view.members().eq(multisig.members.iter().cloned()));
+            assert!(view.is_member(&multisig.members[2].key));
+            assert!(!view.is_member(&multisig.create_key));
+        }
+
+        // The view rejects what the decoder rejects
+        let data = multisig_data(3, None);
+        assert!(MultisigRef::new(&data[..data.len() - 1]).is_err());
+        let proposal = [&Proposal::DISCRIMINATOR[..], &data[8..]].concat();
+        assert!(MultisigRef::new(&proposal).is_err());
+    }
+
+    #[test]
+    fn test_multisig_large_member_list() {
+        let data = multisig_data(500, Some(Pubkey::new_unique()));
+        let multisig = Multisig::try_from_slice(&data).unwrap();
+        assert_eq!(multisig.members.len(), 500);
+        assert!(multisig.unknown_tail.is_empty());
+
+        // A member count larger than the data fails instead of panicking or over-allocating
+        let mut oversized = data.clone();
+        let len_offset = data.len() - 500 * 33 - 4;
+        oversized[len_offset..len_offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        let err = Multisig::try_from_slice(&oversized).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+
+    mod proptests {
+        use super::*;
+        use proptest::prelude::*;
+
+        proptest! {
+            #[test]
+            fn truncated_multisig_data_is_rejected(
+                members in 0usize..40,
+                rent_collector in any::<bool>(),
+                cut in any::<prop::sample::Index>(),
+            ) {
+                let data = multisig_data(members, rent_collector.then(Pubkey::new_unique));
+                let len = cut.index(data.len());
+                prop_assert!(Multisig::try_from_slice(&data[..len]).is_err());
+                prop_assert!(MultisigRef::new(&data[..len]).is_err());
+                prop_assert_eq!(Multisig::try_from_slice(&data).unwrap().members.len(), members);
+            }
+
+            #[test]
+            fn arbitrary_multisig_data_never_panics(
+                data in prop::collection::vec(any::<u8>(), 0..2000)
+            ) {
+                let _ = Multisig::try_from_slice(&data);
+                if let Ok(view) = MultisigRef::new(&data) {
+                    prop_assert_eq!(view.to_multisig(), Multisig::try_from_slice(&data).unwrap());
+                }
+            }
+        }
+    }
+}
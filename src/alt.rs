@@ -0,0 +1,121 @@
+//! Address lookup table account parsing
+//!
+//! [`parse`] decodes the on-chain layout of an address lookup table so vault
+//! transactions that use lookups can be resolved without depending on
+//! solana-address-lookup-table-program directly.
+
+use solana_sdk::{message::AddressLookupTableAccount, pubkey::Pubkey};
+
+use crate::error::{SquadsError, SquadsResult};
+
+/// Size of the lookup table header that precedes the addresses
+pub const LOOKUP_TABLE_META_SIZE: usize = 56;
+
+/// Slot value marking a lookup table that has not been deactivated
+const ACTIVE_DEACTIVATION_SLOT: u64 = u64::MAX;
+
+/// Account type tag of an initialized lookup table
+const LOOKUP_TABLE_TYPE: u32 = 1;
+
+/// A decoded address lookup table account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LookupTable {
+    /// Slot the table was deactivated in, `u64::MAX` while active
+    pub deactivation_slot: u64,
+    /// Slot the table was last extended in
+    pub last_extended_slot: u64,
+    /// Number of addresses before the last extension
+    pub last_extended_slot_start_index: u8,
+    /// Authority that can extend, deactivate and close the table (None if frozen)
+    pub authority: Option<Pubkey>,
+    /// Stored addresses
+    pub addresses: Vec<Pubkey>,
+}
+
+impl LookupTable {
+    /// Whether the table has been deactivated
+    pub fn is_deactivated(&self) -> bool {
+        self.deactivation_slot != ACTIVE_DEACTIVATION_SLOT
+    }
+
+    /// Convert into the account type used when compiling v0 messages
+    pub fn into_account(self, key: Pubkey) -> AddressLookupTableAccount {
+        AddressLookupTableAccount {
+            key,
+            addresses: self.addresses,
+        }
+    }
+}
+
+/// Decode raw address lookup table account data
+pub fn parse(data: &[u8]) -> SquadsResult<LookupTable> {
+    if data.len() < LOOKUP_TABLE_META_SIZE {
+        return Err(SquadsError::InvalidAddressLookupTableAccount);
+    }
+    let u64_at = |offset: usize| {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&data[offset..offset + 8]);
+        u64::from_le_bytes(bytes)
+    };
+
+    let mut type_tag = [0u8; 4];
+    type_tag.copy_from_slice(&data[..4]);
+    if u32::from_le_bytes(type_tag) != LOOKUP_TABLE_TYPE {
+        return Err(SquadsError::InvalidAddressLookupTableAccount);
+    }
+
+    let authority = match data[21] {
+        0 => None,
+        1 => Some(Pubkey::try_from(&data[22..54]).expect("32-byte slice")),
+        _ => return Err(SquadsError::InvalidAddressLookupTableAccount),
+    };
+
+    let addresses = &data[LOOKUP_TABLE_META_SIZE..];
+    if !addresses.len().is_multiple_of(32) {
+        return Err(SquadsError::InvalidAddressLookupTableAccount);
+    }
+
+    Ok(LookupTable {
+        deactivation_slot: u64_at(4),
+        last_extended_slot: u64_at(12),
+        last_extended_slot_start_index: data[20],
+        authority,
+        addresses: addresses
+            .chunks_exact(32)
+            .map(|chunk| Pubkey::try_from(chunk).expect("32-byte chunk"))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_address_lookup_table_interface::state::{AddressLookupTable, LookupTableMeta};
+    use std::borrow::Cow;
+
+    #[test]
+    fn test_parse_matches_program_layout() {
+        let authority = Pubkey::new_unique();
+        let addresses: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let table = AddressLookupTable {
+            meta: LookupTableMeta {
+                last_extended_slot: 42,
+                last_extended_slot_start_index: 1,
+                authority: Some(authority),
+                ..LookupTableMeta::default()
+            },
+            addresses: Cow::Borrowed(&addresses),
+        };
+        let data = table.serialize_for_tests().unwrap();
+
+        let parsed = parse(&data).unwrap();
+        assert_eq!(parsed.authority, Some(authority));
+        assert_eq!(parsed.last_extended_slot, 42);
+        assert_eq!(parsed.last_extended_slot_start_index, 1);
+        assert!(!parsed.is_deactivated());
+        assert_eq!(parsed.addresses, addresses);
+
+        assert!(parse(&data[..LOOKUP_TABLE_META_SIZE - 1]).is_err());
+        assert!(parse(&data[..data.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,86 @@
+//! Blockhash sources for signing
+//!
+//! Every transaction [`SquadsClient`](crate::client::SquadsClient) sends is signed with the
+//! blockhash returned by its [`BlockhashProvider`]. The default, [`RpcBlockhash`], asks the
+//! client's RPC node for the latest blockhash. Services that batch-fetch blockhashes or
+//! use a dedicated blockhash oracle can plug in their own provider, and tests can pin the
+//! blockhash with [`FixedBlockhash`].
+//!
+//! This module is only available with the `async` feature enabled.
+
+use std::future::Future;
+use std::pin::Pin;
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::hash::Hash;
+
+use crate::error::SquadsResult;
+
+/// Future returned by [`BlockhashProvider::blockhash`]
+pub type BlockhashFuture<'a> = Pin<Box<dyn Future<Output = SquadsResult<Hash>> + Send + 'a>>;
+
+/// A source of recent blockhashes
+pub trait BlockhashProvider: Send + Sync {
+    /// Blockhash to sign the next transaction with
+    ///
+    /// `rpc` is the client's RPC connection, for providers that use or fall back to it.
+    fn blockhash<'a>(&'a self, rpc: &'a RpcClient) -> BlockhashFuture<'a>;
+}
+
+/// Fetches the latest blockhash from the client's RPC node for every transaction
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RpcBlockhash;
+
+impl BlockhashProvider for RpcBlockhash {
+    fn blockhash<'a>(&'a self, rpc: &'a RpcClient) -> BlockhashFuture<'a> {
+        Box::pin(async move { Ok(rpc.get_latest_blockhash().await?) })
+    }
+}
+
+/// Always returns the same blockhash, for deterministic transactions in tests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedBlockhash(pub Hash);
+
+impl BlockhashProvider for FixedBlockhash {
+    fn blockhash<'a>(&'a self, _rpc: &'a RpcClient) -> BlockhashFuture<'a> {
+        Box::pin(std::future::ready(Ok(self.0)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU8, Ordering};
+    use std::sync::Arc;
+
+    use crate::client::SquadsClient;
+
+    /// Hands out blockhashes from a pre-fetched batch
+    struct Batch(AtomicU8);
+
+    impl BlockhashProvider for Batch {
+        fn blockhash<'a>(&'a self, _rpc: &'a RpcClient) -> BlockhashFuture<'a> {
+            let next = self.0.fetch_add(1, Ordering::Relaxed);
+            Box::pin(async move { Ok(Hash::new_from_array([next; 32])) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_blockhash_provider() {
+        let mut client = SquadsClient::new("http://127.0.0.1:1".to_string());
+        let fixed = Hash::new_from_array([9; 32]);
+        client.blockhash_provider = Arc::new(FixedBlockhash(fixed));
+        let first = client.blockhash_provider.blockhash(&client.rpc).await;
+        assert_eq!(first.unwrap(), fixed);
+
+        client.blockhash_provider = Arc::new(Batch(AtomicU8::new(1)));
+        for expected in 1..=3 {
+            let hash = client
+                .blockhash_provider
+                .blockhash(&client.rpc)
+                .await
+                .unwrap();
+            assert_eq!(hash, Hash::new_from_array([expected; 32]));
+        }
+    }
+}
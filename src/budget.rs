@@ -0,0 +1,345 @@
+//! Compute and CPI depth budgeting for vault transaction execution
+//!
+//! Executing through Squads wraps every inner instruction in a CPI from the Squads program,
+//! which costs compute units and one level of the runtime's instruction stack. This module
+//! statically checks inner instructions against the remaining CPI depth and parses
+//! simulation logs; `SquadsClient::estimate_execute_budget` (with the `async` feature)
+//! combines both with a simulation of the raw and wrapped instructions.
+//!
+//! [`TransactionOptions`] carries the compute unit limit and price to prepend to a
+//! transaction; `SquadsClient::get_recommended_priority_fee` samples recent prioritization
+//! fees into a [`PriorityFeeEstimate`] to choose the price from.
+
+use std::collections::HashMap;
+
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use solana_sdk_ids::{compute_budget, system_program};
+
+use crate::accounts::VaultTransactionMessage;
+use crate::programs::{
+    ASSOCIATED_TOKEN_PROGRAM_ID, MEMO_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID,
+};
+
+/// Maximum instruction stack height allowed by the Solana runtime
+pub const MAX_INSTRUCTION_STACK_HEIGHT: usize = 5;
+
+/// Stack height at which Squads invokes the inner instructions
+/// (the top-level execute instruction runs at height 1)
+pub const INNER_INSTRUCTION_STACK_HEIGHT: usize = 2;
+
+/// Number of nested CPI levels still available to an inner instruction's program
+pub const AVAILABLE_NESTED_CPI_DEPTH: usize =
+    MAX_INSTRUCTION_STACK_HEIGHT - INNER_INSTRUCTION_STACK_HEIGHT;
+
+/// Known nested CPI depth of common programs
+///
+/// The depth is the number of additional stack levels a program uses beneath itself:
+/// 0 for programs that never CPI, 1 for programs that CPI into leaf programs, and so on.
+pub fn known_program_cpi_depths() -> HashMap<Pubkey, usize> {
+    HashMap::from([
+        (system_program::ID, 0),
+        (compute_budget::ID, 0),
+        (TOKEN_PROGRAM_ID, 0),
+        (TOKEN_2022_PROGRAM_ID, 0),
+        (MEMO_PROGRAM_ID, 0),
+        (ASSOCIATED_TOKEN_PROGRAM_ID, 1),
+    ])
+}
+
+/// CPI depth assessment for a single inner instruction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionDepth {
+    /// Position of the instruction in the message
+    pub index: usize,
+    /// Program invoked by the instruction (None if the index could not be resolved)
+    pub program_id: Option<Pubkey>,
+    /// Known nested CPI depth of the program (None if unknown)
+    pub nested_depth: Option<usize>,
+}
+
+impl InstructionDepth {
+    /// Whether the program is known to nest deeper than the available CPI depth
+    pub fn exceeds_limit(&self) -> bool {
+        self.nested_depth
+            .is_some_and(|depth| depth > AVAILABLE_NESTED_CPI_DEPTH)
+    }
+}
+
+/// Static CPI depth report for a vault transaction message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CpiDepthReport {
+    /// Assessment per inner instruction
+    pub instructions: Vec<InstructionDepth>,
+}
+
+impl CpiDepthReport {
+    /// Instructions known to exceed the CPI depth limit
+    pub fn exceeding(&self) -> Vec<&InstructionDepth> {
+        self.instructions
+            .iter()
+            .filter(|ix| ix.exceeds_limit())
+            .collect()
+    }
+
+    /// Instructions whose programs have unknown CPI depth and should be simulated
+    pub fn unknown(&self) -> Vec<&InstructionDepth> {
+        self.instructions
+            .iter()
+            .filter(|ix| ix.nested_depth.is_none())
+            .collect()
+    }
+
+    /// Whether any instruction is known to exceed, or may exceed, the CPI depth limit
+    pub fn at_risk(&self) -> bool {
+        !self.exceeding().is_empty() || !self.unknown().is_empty()
+    }
+}
+
+/// Check the inner instructions of a message against the available CPI depth
+///
+/// # Arguments
+/// * `message` - The vault transaction message
+/// * `known_depths` - Nested CPI depth per program (see [`known_program_cpi_depths`])
+pub fn check_cpi_depth(
+    message: &VaultTransactionMessage,
+    known_depths: &HashMap<Pubkey, usize>,
+) -> CpiDepthReport {
+    let instructions = message
+        .instructions
+        .iter()
+        .enumerate()
+        .map(|(index, ix)| {
+            let program_id = message
+                .account_keys
+                .get(usize::from(ix.program_id_index))
+                .copied();
+            InstructionDepth {
+                index,
+                program_id,
+                nested_depth: program_id.and_then(|id| known_depths.get(&id).copied()),
+            }
+        })
+        .collect();
+
+    CpiDepthReport { instructions }
+}
+
+/// Highest instruction stack height observed in program logs
+///
+/// Parses `Program <id> invoke [<height>]` lines emitted by the runtime.
+pub fn max_stack_height_from_logs(logs: &[String]) -> usize {
+    logs.iter()
+        .filter_map(|line| {
+            let rest = line.strip_prefix("Program ")?;
+            let (_, height) = rest.split_once(" invoke [")?;
+            height.strip_suffix(']')?.parse::<usize>().ok()
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Result of simulating a vault transaction raw and wrapped in Squads execute
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecuteBudgetEstimate {
+    /// Compute units consumed by the inner instructions on their own
+    pub raw_units: Option<u64>,
+    /// Compute units consumed by the full `vault_transaction_execute`
+    pub wrapped_units: Option<u64>,
+    /// Highest stack height reached during the wrapped simulation
+    pub max_stack_height: usize,
+    /// Simulation error of the wrapped execution, if any
+    pub error: Option<String>,
+    /// Static CPI depth assessment
+    pub cpi_depth: CpiDepthReport,
+}
+
+impl ExecuteBudgetEstimate {
+    /// Compute units added by executing through Squads
+    pub fn squads_overhead(&self) -> Option<u64> {
+        Some(self.wrapped_units?.saturating_sub(self.raw_units?))
+    }
+
+    /// Whether the wrapped execution reached the runtime's stack height limit
+    pub fn hit_stack_limit(&self) -> bool {
+        self.max_stack_height >= MAX_INSTRUCTION_STACK_HEIGHT
+    }
+}
+
+/// Build a compute budget `SetComputeUnitLimit` instruction
+pub fn set_compute_unit_limit(units: u32) -> Instruction {
+    let mut data = vec![2];
+    data.extend_from_slice(&units.to_le_bytes());
+    Instruction::new_with_bytes(compute_budget::ID, &data, Vec::<AccountMeta>::new())
+}
+
+/// Build a compute budget `SetComputeUnitPrice` instruction
+///
+/// # Arguments
+/// * `micro_lamports` - Price per compute unit in micro-lamports
+pub fn set_compute_unit_price(micro_lamports: u64) -> Instruction {
+    let mut data = vec![3];
+    data.extend_from_slice(&micro_lamports.to_le_bytes());
+    Instruction::new_with_bytes(compute_budget::ID, &data, Vec::<AccountMeta>::new())
+}
+
+/// Compute budget settings applied when sending a transaction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransactionOptions {
+    /// Compute unit limit (runtime default if None)
+    pub compute_unit_limit: Option<u32>,
+    /// Priority fee in micro-lamports per compute unit (none if None)
+    pub compute_unit_price: Option<u64>,
+}
+
+impl TransactionOptions {
+    /// Set the compute unit limit
+    pub fn with_compute_unit_limit(mut self, units: u32) -> Self {
+        self.compute_unit_limit = Some(units);
+        self
+    }
+
+    /// Set the priority fee in micro-lamports per compute unit
+    pub fn with_compute_unit_price(mut self, micro_lamports: u64) -> Self {
+        self.compute_unit_price = Some(micro_lamports);
+        self
+    }
+
+    /// Compute budget instructions for these options
+    pub fn compute_budget_instructions(&self) -> Vec<Instruction> {
+        self.compute_unit_limit
+            .map(set_compute_unit_limit)
+            .into_iter()
+            .chain(self.compute_unit_price.map(set_compute_unit_price))
+            .collect()
+    }
+
+    /// Prepend the compute budget instructions to `instructions`
+    pub fn apply(&self, instructions: &[Instruction]) -> Vec<Instruction> {
+        let mut all = self.compute_budget_instructions();
+        all.extend_from_slice(instructions);
+        all
+    }
+}
+
+/// Suggested priority fees derived from recent prioritization fees
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PriorityFeeEstimate {
+    /// Median fee, in micro-lamports per compute unit
+    pub p50: u64,
+    /// 75th percentile fee, in micro-lamports per compute unit
+    pub p75: u64,
+    /// 90th percentile fee, in micro-lamports per compute unit
+    pub p90: u64,
+    /// Number of slots sampled
+    pub samples: usize,
+}
+
+impl PriorityFeeEstimate {
+    /// Compute the percentiles of sampled per-slot fees (nearest-rank)
+    ///
+    /// All percentiles are zero if there are no samples.
+    pub fn from_fees(fees: &[u64]) -> Self {
+        let mut sorted = fees.to_vec();
+        sorted.sort_unstable();
+
+        let percentile = |p: usize| -> u64 {
+            if sorted.is_empty() {
+                return 0;
+            }
+            let rank = (p * sorted.len()).div_ceil(100).max(1);
+            sorted[rank - 1]
+        };
+
+        Self {
+            p50: percentile(50),
+            p75: percentile(75),
+            p90: percentile(90),
+            samples: sorted.len(),
+        }
+    }
+}
+
+/// Accounts written by `instructions`, deduplicated in first-seen order
+///
+/// These are the accounts to pass to `getRecentPrioritizationFees`, since priority fee
+/// markets are local to write-locked accounts.
+pub fn writable_accounts(instructions: &[Instruction]) -> Vec<Pubkey> {
+    let mut accounts: Vec<Pubkey> = Vec::new();
+    for meta in instructions.iter().flat_map(|ix| &ix.accounts) {
+        if meta.is_writable && !accounts.contains(&meta.pubkey) {
+            accounts.push(meta.pubkey);
+        }
+    }
+    accounts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accounts::CompiledInstruction;
+
+    #[test]
+    fn test_check_cpi_depth() {
+        let unknown_program = Pubkey::new_unique();
+        let message = VaultTransactionMessage {
+            num_signers: 1,
+            num_writable_signers: 1,
+            num_writable_non_signers: 0,
+            account_keys: vec![Pubkey::new_unique(), system_program::ID, unknown_program],
+            instructions: vec![
+                CompiledInstruction {
+                    program_id_index: 1,
+                    account_indexes: vec![0],
+                    data: vec![],
+                },
+                CompiledInstruction {
+                    program_id_index: 2,
+                    account_indexes: vec![],
+                    data: vec![],
+                },
+            ],
+            address_table_lookups: vec![],
+        };
+
+        let mut depths = known_program_cpi_depths();
+        let report = check_cpi_depth(&message, &depths);
+        assert_eq!(report.instructions[0].nested_depth, Some(0));
+        assert_eq!(report.unknown().len(), 1);
+        assert!(report.at_risk());
+
+        depths.insert(unknown_program, AVAILABLE_NESTED_CPI_DEPTH + 1);
+        let report = check_cpi_depth(&message, &depths);
+        assert_eq!(report.exceeding().len(), 1);
+    }
+
+    #[test]
+    fn test_max_stack_height_from_logs() {
+        let logs = vec![
+            "Program SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf invoke [1]".to_string(),
+            "Program 11111111111111111111111111111111 invoke [2]".to_string(),
+            "Program 11111111111111111111111111111111 success".to_string(),
+            "Program log: invoke [9] is not a runtime line".to_string(),
+        ];
+        assert_eq!(max_stack_height_from_logs(&logs), 2);
+    }
+
+    #[test]
+    fn test_priority_fee_estimate() {
+        let fees: Vec<u64> = (1..=10).map(|i| i * 100).collect();
+        let estimate = PriorityFeeEstimate::from_fees(&fees);
+        assert_eq!((estimate.p50, estimate.p75, estimate.p90), (500, 800, 900));
+        assert_eq!(estimate.samples, 10);
+        assert_eq!(PriorityFeeEstimate::from_fees(&[]).p90, 0);
+
+        let options = TransactionOptions::default()
+            .with_compute_unit_limit(200_000)
+            .with_compute_unit_price(estimate.p75);
+        let ixs = options.apply(&[set_compute_unit_price(1)]);
+        assert_eq!(ixs.len(), 3);
+        assert_eq!(ixs[0].data[0], 2);
+        assert_eq!(ixs[1].data, [&[3u8][..], &800u64.to_le_bytes()].concat());
+    }
+}
@@ -0,0 +1,218 @@
+//! Async client for the Squads v4 protocol
+//!
+//! [`SquadsClient`] combines instruction building with RPC calls to make common operations
+//! easier. Its methods are layered, each layer built only on the ones below it:
+//!
+//! 1. Builders: [`crate::instructions`] builds single instructions without any I/O.
+//! 2. Operations: [`reads`] fetch and decode state, [`writes`] send one transaction per
+//!    call, typically wrapping a single builder.
+//! 3. Workflows: [`workflows`] chain reads and writes into multi-step operations such as
+//!    replacing a transaction or closing executed ones.
+//!
+//! New methods belong in the lowest layer that can express them; anything that sends more
+//! than one transaction, or decides what to send from fetched state, is a workflow. All of
+//! them are inherent methods of [`SquadsClient`] and the report types are re-exported
+//! here, so the layering does not show in calling code.
+//!
+//! Renamed methods keep their old name as a `#[deprecated]` method forwarding to the new
+//! one for at least one minor release before it is removed.
+//!
+//! # Features
+//! This module is only available with the `async` feature enabled.
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSendTransactionConfig;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::{
+    instruction::Instruction,
+    message::{v0, AddressLookupTableAccount, VersionedMessage},
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::{Transaction, VersionedTransaction},
+};
+
+use std::sync::Arc;
+
+use crate::{
+    blockhash::{BlockhashProvider, RpcBlockhash},
+    budget::PriorityFeeEstimate,
+    error::{SquadsError, SquadsResult},
+    pda,
+    portfolio::PortfolioSummary,
+    runtime::RetryPolicy,
+};
+
+pub mod reads;
+pub mod workflows;
+pub mod writes;
+
+pub use reads::{OrphanedTransaction, ProposalListing};
+pub use workflows::{
+    CloseReport, ClosedTransaction, ReplacementReport, SupersededProposal, UpgradeReport,
+    DEFAULT_CLOSE_BATCH_SIZE,
+};
+
+/// High-level async client for Squads v4 protocol
+pub struct SquadsClient {
+    /// RPC client for communicating with Solana
+    pub rpc: RpcClient,
+    /// Program ID to use (defaults to canonical Squads program ID)
+    pub program_id: Pubkey,
+    /// Retry schedule of reads that wait for a write (the `*_after` methods)
+    pub read_retry: RetryPolicy,
+    /// Source of the blockhash every sent transaction is signed with
+    pub blockhash_provider: Arc<dyn BlockhashProvider>,
+}
+
+impl SquadsClient {
+    /// Create a new SquadsClient with the default program ID
+    pub fn new(rpc_url: String) -> Self {
+        Self {
+            rpc: RpcClient::new(rpc_url),
+            program_id: crate::program_id(),
+            read_retry: RetryPolicy::default(),
+            blockhash_provider: Arc::new(RpcBlockhash),
+        }
+    }
+
+    /// Create a new SquadsClient with a custom program ID
+    pub fn new_with_program_id(rpc_url: String, program_id: Pubkey) -> Self {
+        Self {
+            rpc: RpcClient::new(rpc_url),
+            program_id,
+            read_retry: RetryPolicy::default(),
+            blockhash_provider: Arc::new(RpcBlockhash),
+        }
+    }
+
+    /// Create a client with an existing RpcClient
+    pub fn from_rpc_client(rpc: RpcClient) -> Self {
+        Self {
+            rpc,
+            program_id: crate::program_id(),
+            read_retry: RetryPolicy::default(),
+            blockhash_provider: Arc::new(RpcBlockhash),
+        }
+    }
+
+    /// Get the vault PDA for a multisig
+    pub fn get_vault_pda(&self, multisig: &Pubkey, vault_index: u8) -> (Pubkey, u8) {
+        pda::get_vault_pda(multisig, vault_index, Some(&self.program_id))
+    }
+
+    /// Get the proposal PDA for a transaction
+    pub fn get_proposal_pda(&self, multisig: &Pubkey, transaction_index: u64) -> (Pubkey, u8) {
+        pda::get_proposal_pda(multisig, transaction_index, Some(&self.program_id))
+    }
+
+    /// Get the transaction PDA
+    pub fn get_transaction_pda(&self, multisig: &Pubkey, transaction_index: u64) -> (Pubkey, u8) {
+        pda::get_transaction_pda(multisig, transaction_index, Some(&self.program_id))
+    }
+
+    /// Sign and send a v0 transaction using the given lookup tables
+    async fn send_and_confirm_v0_transaction(
+        &self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+        lookup_tables: &[AddressLookupTableAccount],
+    ) -> SquadsResult<Signature> {
+        let recent_blockhash = self.blockhash_provider.blockhash(&self.rpc).await?;
+
+        let message = v0::Message::try_compile(
+            &signers[0].pubkey(),
+            instructions,
+            lookup_tables,
+            recent_blockhash,
+        )
+        .map_err(|_| SquadsError::InvalidTransactionMessage)?;
+        let transaction = VersionedTransaction::try_new(VersionedMessage::V0(message), signers)
+            .map_err(|e| SquadsError::SigningError(e.to_string()))?;
+
+        let config = RpcSendTransactionConfig {
+            skip_preflight: false,
+            preflight_commitment: Some(CommitmentConfig::confirmed().commitment),
+            ..Default::default()
+        };
+
+        self.rpc
+            .send_and_confirm_transaction_with_spinner_and_config(
+                &transaction,
+                CommitmentConfig::confirmed(),
+                config,
+            )
+            .await
+            .map_err(SquadsError::ClientError)
+    }
+
+    /// Sign and send a legacy transaction, paid by the first signer
+    async fn send_and_confirm_transaction(
+        &self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> SquadsResult<Signature> {
+        let recent_blockhash = self.blockhash_provider.blockhash(&self.rpc).await?;
+
+        let mut transaction = Transaction::new_with_payer(instructions, Some(&signers[0].pubkey()));
+        transaction.sign(signers, recent_blockhash);
+
+        let config = RpcSendTransactionConfig {
+            skip_preflight: false,
+            preflight_commitment: Some(CommitmentConfig::confirmed().commitment),
+            ..Default::default()
+        };
+
+        self.rpc
+            .send_and_confirm_transaction_with_spinner_and_config(
+                &transaction,
+                CommitmentConfig::confirmed(),
+                config,
+            )
+            .await
+            .map_err(SquadsError::ClientError)
+    }
+}
+
+/// Old method names, kept until the next breaking release
+impl SquadsClient {
+    /// Suggest priority fees from recent prioritization fees paid for `accounts`
+    #[deprecated(note = "renamed to `get_recommended_priority_fee`")]
+    pub async fn recommended_priority_fee(
+        &self,
+        accounts: &[Pubkey],
+    ) -> SquadsResult<PriorityFeeEstimate> {
+        self.get_recommended_priority_fee(accounts).await
+    }
+
+    /// Detect the token, stake and open orders positions held by a vault
+    #[deprecated(note = "renamed to `get_vault_positions`")]
+    pub async fn vault_positions(
+        &self,
+        multisig: &Pubkey,
+        vault_index: u8,
+    ) -> SquadsResult<PortfolioSummary> {
+        self.get_vault_positions(multisig, vault_index).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_creation() {
+        let client = SquadsClient::new("https://api.mainnet-beta.solana.com".to_string());
+        assert_eq!(client.program_id, crate::program_id());
+    }
+
+    #[test]
+    fn test_client_with_custom_program_id() {
+        let custom_program_id = Pubkey::new_unique();
+        let client = SquadsClient::new_with_program_id(
+            "https://api.mainnet-beta.solana.com".to_string(),
+            custom_program_id,
+        );
+        assert_eq!(client.program_id, custom_program_id);
+    }
+}
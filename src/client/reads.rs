@@ -0,0 +1,602 @@
+//! Reads: fetch and decode accounts, transactions and fee data
+//!
+//! Methods here never sign or send a transaction. Account getters are named `get_*` and
+//! index range queries `list_*`.
+
+use solana_client::rpc_config::{RpcSimulateTransactionConfig, RpcTransactionConfig};
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::{
+    hash::Hash,
+    message::{AddressLookupTableAccount, Message},
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::Transaction,
+};
+use solana_transaction_status_client_types::UiTransactionEncoding;
+
+use std::ops::RangeInclusive;
+
+use super::SquadsClient;
+use crate::{
+    accounts::{
+        check_owner, ConfigTransaction, Multisig, Proposal, SpendingLimit, SquadsTransaction,
+        VaultTransaction,
+    },
+    alt,
+    budget::{self, ExecuteBudgetEstimate, PriorityFeeEstimate},
+    error::{SquadsError, SquadsResult},
+    history::TransactionRecord,
+    indexer::{self, BackfillConfig, BackfillSummary, Checkpoint},
+    portfolio::{self, PortfolioSummary},
+    prefetch::{ExecutePrefetch, PrefetchedExecution},
+    reader::{
+        fetch_account, fetch_account_at_slot, fetch_creation_memo, fetch_proposals, index_chunks,
+        sort_proposals, SquadsReader,
+    },
+    report::ExecutionReport,
+    runtime,
+    types::SortOrder,
+};
+
+/// A transaction account without a proposal, found by
+/// [`SquadsClient::find_orphaned_transactions`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrphanedTransaction {
+    /// Transaction index
+    pub transaction_index: u64,
+    /// Transaction account
+    pub transaction: Pubkey,
+    /// Whether the transaction is stale (a later config change invalidated it), in which
+    /// case creating its proposal is pointless
+    pub stale: bool,
+}
+
+/// A proposal with the memo its transaction was created with, listed by
+/// [`SquadsClient::list_proposals_with_memos`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProposalListing {
+    /// Transaction index
+    pub transaction_index: u64,
+    /// The proposal
+    pub proposal: Proposal,
+    /// Memo of the transaction or batch create instruction, e.g. "Add Alice as member"
+    pub memo: Option<String>,
+}
+
+impl SquadsClient {
+    /// Fetch and deserialize a Multisig account
+    pub async fn get_multisig(&self, multisig: &Pubkey) -> SquadsResult<Multisig> {
+        fetch_account(
+            &self.rpc,
+            &self.program_id,
+            multisig,
+            Multisig::try_from_slice,
+        )
+        .await
+    }
+
+    /// Fetch and deserialize a Proposal account
+    pub async fn get_proposal(&self, proposal: &Pubkey) -> SquadsResult<Proposal> {
+        fetch_account(
+            &self.rpc,
+            &self.program_id,
+            proposal,
+            Proposal::try_from_slice,
+        )
+        .await
+    }
+
+    /// Fetch and deserialize a VaultTransaction account
+    pub async fn get_vault_transaction(
+        &self,
+        transaction: &Pubkey,
+    ) -> SquadsResult<VaultTransaction> {
+        fetch_account(
+            &self.rpc,
+            &self.program_id,
+            transaction,
+            VaultTransaction::try_from_slice,
+        )
+        .await
+    }
+
+    /// Fetch and deserialize a ConfigTransaction account
+    pub async fn get_config_transaction(
+        &self,
+        transaction: &Pubkey,
+    ) -> SquadsResult<ConfigTransaction> {
+        fetch_account(
+            &self.rpc,
+            &self.program_id,
+            transaction,
+            ConfigTransaction::try_from_slice,
+        )
+        .await
+    }
+
+    /// Fetch the transaction at `transaction_index`, whatever its kind
+    ///
+    /// The account is decoded by its discriminator as a vault transaction, config
+    /// transaction or batch, so callers do not need to know the kind up front.
+    pub async fn get_transaction_any(
+        &self,
+        multisig: &Pubkey,
+        transaction_index: u64,
+    ) -> SquadsResult<SquadsTransaction> {
+        let transaction = self.get_transaction_pda(multisig, transaction_index).0;
+        fetch_account(
+            &self.rpc,
+            &self.program_id,
+            &transaction,
+            SquadsTransaction::try_from_slice,
+        )
+        .await
+    }
+
+    /// Fetch and deserialize a SpendingLimit account
+    pub async fn get_spending_limit(&self, spending_limit: &Pubkey) -> SquadsResult<SpendingLimit> {
+        fetch_account(
+            &self.rpc,
+            &self.program_id,
+            spending_limit,
+            SpendingLimit::try_from_slice,
+        )
+        .await
+    }
+
+    /// Slot in which a transaction was processed
+    pub async fn get_signature_slot(&self, signature: &Signature) -> SquadsResult<u64> {
+        let statuses = self.rpc.get_signature_statuses(&[*signature]).await?;
+        statuses
+            .value
+            .into_iter()
+            .next()
+            .flatten()
+            .map(|status| status.slot)
+            .ok_or_else(|| SquadsError::TransactionNotFound(signature.to_string()))
+    }
+
+    /// Fetch a Multisig account as of the write `signature`
+    ///
+    /// Right after a transaction confirms, the account may not be visible yet at the
+    /// read commitment, or the RPC node serving the read may lag behind. The read is
+    /// retried with [`Self::read_retry`] until a node that has processed the write's slot
+    /// returns the account.
+    pub async fn get_multisig_after(
+        &self,
+        multisig: &Pubkey,
+        signature: &Signature,
+    ) -> SquadsResult<Multisig> {
+        self.fetch_after(multisig, signature, Multisig::try_from_slice)
+            .await
+    }
+
+    /// Fetch a Proposal account as of the write `signature`, see
+    /// [`get_multisig_after`](Self::get_multisig_after)
+    pub async fn get_proposal_after(
+        &self,
+        proposal: &Pubkey,
+        signature: &Signature,
+    ) -> SquadsResult<Proposal> {
+        self.fetch_after(proposal, signature, Proposal::try_from_slice)
+            .await
+    }
+
+    /// Fetch a VaultTransaction account as of the write `signature`, see
+    /// [`get_multisig_after`](Self::get_multisig_after)
+    pub async fn get_vault_transaction_after(
+        &self,
+        transaction: &Pubkey,
+        signature: &Signature,
+    ) -> SquadsResult<VaultTransaction> {
+        self.fetch_after(transaction, signature, VaultTransaction::try_from_slice)
+            .await
+    }
+
+    /// Fetch a ConfigTransaction account as of the write `signature`, see
+    /// [`get_multisig_after`](Self::get_multisig_after)
+    pub async fn get_config_transaction_after(
+        &self,
+        transaction: &Pubkey,
+        signature: &Signature,
+    ) -> SquadsResult<ConfigTransaction> {
+        self.fetch_after(transaction, signature, ConfigTransaction::try_from_slice)
+            .await
+    }
+
+    /// Fetch and decode address lookup tables, in the order given
+    ///
+    /// Used to resolve the lookups of vault transaction messages with
+    /// [`crate::accounts::VaultTransactionMessage::execute_remaining_accounts_with_tables`].
+    pub async fn get_lookup_tables(
+        &self,
+        tables: &[Pubkey],
+    ) -> SquadsResult<Vec<AddressLookupTableAccount>> {
+        let accounts = self.rpc.get_multiple_accounts(tables).await?;
+        tables
+            .iter()
+            .zip(accounts)
+            .map(|(key, account)| {
+                let account =
+                    account.ok_or_else(|| SquadsError::AccountNotFound(key.to_string()))?;
+                check_owner(
+                    &account.owner,
+                    &solana_address_lookup_table_interface::program::ID,
+                )?;
+                Ok(alt::parse(&account.data)?.into_account(*key))
+            })
+            .collect()
+    }
+
+    /// Load everything needed to execute a vault transaction in one `getMultipleAccounts`
+    /// round trip
+    ///
+    /// Fetches the multisig, proposal and transaction together with `lookup_tables`.
+    /// Lookup tables the transaction uses but that were not passed are fetched in a
+    /// second round trip, so pass them when known to keep execution to a single one.
+    ///
+    /// # Arguments
+    /// * `multisig` - Multisig account
+    /// * `transaction_index` - Index of the vault transaction
+    /// * `lookup_tables` - Lookup tables the transaction message is expected to use
+    pub async fn get_execution_accounts(
+        &self,
+        multisig: &Pubkey,
+        transaction_index: u64,
+        lookup_tables: &[Pubkey],
+    ) -> SquadsResult<PrefetchedExecution> {
+        let plan = ExecutePrefetch::new(*multisig, transaction_index, Some(&self.program_id))
+            .with_lookup_tables(lookup_tables.iter().copied());
+        let accounts = self.rpc.get_multiple_accounts(&plan.addresses()).await?;
+        let mut prefetched = plan.resolve(accounts)?;
+
+        let missing = prefetched.missing_lookup_tables();
+        if !missing.is_empty() {
+            prefetched
+                .lookup_tables
+                .extend(self.get_lookup_tables(&missing).await?);
+        }
+        Ok(prefetched)
+    }
+
+    /// Suggest priority fees from recent prioritization fees paid for `accounts`
+    ///
+    /// Pass the writable accounts of the transaction to price (see
+    /// [`budget::writable_accounts`]) and feed the chosen percentile into
+    /// [`budget::TransactionOptions::with_compute_unit_price`].
+    pub async fn get_recommended_priority_fee(
+        &self,
+        accounts: &[Pubkey],
+    ) -> SquadsResult<PriorityFeeEstimate> {
+        let fees = self.rpc.get_recent_prioritization_fees(accounts).await?;
+        let fees: Vec<u64> = fees.iter().map(|fee| fee.prioritization_fee).collect();
+        Ok(PriorityFeeEstimate::from_fees(&fees))
+    }
+
+    /// Detect the token, stake and open orders positions held by a vault
+    ///
+    /// Uses [`portfolio::default_detectors`]; call [`portfolio::vault_positions`] directly
+    /// to add custom detectors.
+    pub async fn get_vault_positions(
+        &self,
+        multisig: &Pubkey,
+        vault_index: u8,
+    ) -> SquadsResult<PortfolioSummary> {
+        portfolio::vault_positions(
+            &self.rpc,
+            multisig,
+            vault_index,
+            Some(&self.program_id),
+            &portfolio::default_detectors(),
+        )
+        .await
+    }
+
+    /// List the proposals that exist for transaction indexes in `start_index..=end_index`
+    ///
+    /// Results are in ascending index order.
+    pub async fn list_proposals(
+        &self,
+        multisig: &Pubkey,
+        start_index: u64,
+        end_index: u64,
+    ) -> SquadsResult<Vec<(u64, Proposal)>> {
+        fetch_proposals(
+            &self.rpc,
+            &self.program_id,
+            multisig,
+            start_index,
+            end_index,
+        )
+        .await
+    }
+
+    /// List proposals like [`list_proposals`](Self::list_proposals), sorted by
+    /// transaction index and then status in the given order
+    pub async fn list_proposals_ordered(
+        &self,
+        multisig: &Pubkey,
+        start_index: u64,
+        end_index: u64,
+        order: SortOrder,
+    ) -> SquadsResult<Vec<(u64, Proposal)>> {
+        let mut proposals = self
+            .list_proposals(multisig, start_index, end_index)
+            .await?;
+        sort_proposals(&mut proposals, order);
+        Ok(proposals)
+    }
+
+    /// List proposals like [`list_proposals_ordered`](Self::list_proposals_ordered), each
+    /// with the memo of its transaction
+    ///
+    /// Memos are read from the create transactions, costing at least two RPC calls per
+    /// proposal.
+    pub async fn list_proposals_with_memos(
+        &self,
+        multisig: &Pubkey,
+        start_index: u64,
+        end_index: u64,
+        order: SortOrder,
+    ) -> SquadsResult<Vec<ProposalListing>> {
+        let proposals = self
+            .list_proposals_ordered(multisig, start_index, end_index, order)
+            .await?;
+        let mut listings = Vec::with_capacity(proposals.len());
+        for (transaction_index, proposal) in proposals {
+            let memo = self.get_creation_memo(multisig, transaction_index).await?;
+            listings.push(ProposalListing {
+                transaction_index,
+                proposal,
+                memo,
+            });
+        }
+        Ok(listings)
+    }
+
+    /// Fetch the memo the transaction (or batch) at `transaction_index` was created with
+    ///
+    /// Returns None if the transaction was created without a memo or never existed.
+    pub async fn get_creation_memo(
+        &self,
+        multisig: &Pubkey,
+        transaction_index: u64,
+    ) -> SquadsResult<Option<String>> {
+        let transaction = self.get_transaction_pda(multisig, transaction_index).0;
+        fetch_creation_memo(&self.rpc, &self.program_id, &transaction).await
+    }
+
+    /// List transactions in `range` that have no proposal
+    ///
+    /// Results are in ascending index order.
+    ///
+    /// # Arguments
+    /// * `multisig` - Multisig account
+    /// * `range` - Transaction indexes to check (None for all indexes of the multisig)
+    pub async fn find_orphaned_transactions(
+        &self,
+        multisig: &Pubkey,
+        range: Option<RangeInclusive<u64>>,
+    ) -> SquadsResult<Vec<OrphanedTransaction>> {
+        self.find_orphaned_transactions_ordered(multisig, range, SortOrder::Ascending)
+            .await
+    }
+
+    /// Find orphaned transactions like
+    /// [`find_orphaned_transactions`](Self::find_orphaned_transactions), sorted by
+    /// transaction index in the given order
+    pub async fn find_orphaned_transactions_ordered(
+        &self,
+        multisig: &Pubkey,
+        range: Option<RangeInclusive<u64>>,
+        order: SortOrder,
+    ) -> SquadsResult<Vec<OrphanedTransaction>> {
+        let multisig_account = self.get_multisig(multisig).await?;
+        let range = range.unwrap_or(1..=multisig_account.transaction_index);
+
+        let mut orphaned = Vec::new();
+        for chunk in index_chunks(range, 50) {
+            let chunk: Vec<u64> = chunk.collect();
+            let mut addresses = Vec::with_capacity(chunk.len() * 2);
+            for index in &chunk {
+                addresses.push(self.get_transaction_pda(multisig, *index).0);
+                addresses.push(self.get_proposal_pda(multisig, *index).0);
+            }
+            let accounts = self.rpc.get_multiple_accounts(&addresses).await?;
+
+            for (i, index) in chunk.iter().enumerate() {
+                if accounts[2 * i].is_some() && accounts[2 * i + 1].is_none() {
+                    orphaned.push(OrphanedTransaction {
+                        transaction_index: *index,
+                        transaction: addresses[2 * i],
+                        stale: *index <= multisig_account.stale_transaction_index,
+                    });
+                }
+            }
+        }
+
+        order.sort_by_key(&mut orphaned, |o| o.transaction_index);
+        Ok(orphaned)
+    }
+
+    /// Fetch the current durable nonce value stored in a nonce account
+    pub async fn get_durable_nonce(&self, nonce_account: &Pubkey) -> SquadsResult<Hash> {
+        let account = self.rpc.get_account(nonce_account).await?;
+        let data = solana_client::nonce_utils::data_from_account(&account)
+            .map_err(|e| SquadsError::InvalidAccountData(e.to_string()))?;
+        Ok(data.blockhash())
+    }
+
+    /// Fetch a confirmed transaction and report its SOL and token balance changes
+    pub async fn get_execution_report(
+        &self,
+        signature: &Signature,
+    ) -> SquadsResult<ExecutionReport> {
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+        let confirmed = self
+            .rpc
+            .get_transaction_with_config(signature, config)
+            .await?;
+        ExecutionReport::from_confirmed(*signature, &confirmed)
+    }
+
+    /// Walk the transaction history of a multisig with [`indexer::backfill`]
+    pub async fn backfill<F>(
+        &self,
+        multisig: &Pubkey,
+        from_signature: Option<Signature>,
+        config: &BackfillConfig,
+        on_page: F,
+    ) -> SquadsResult<BackfillSummary>
+    where
+        F: FnMut(&[TransactionRecord], &Checkpoint) -> SquadsResult<()>,
+    {
+        indexer::backfill(
+            &self.rpc,
+            multisig,
+            from_signature,
+            Some(&self.program_id),
+            config,
+            on_page,
+        )
+        .await
+    }
+
+    /// Estimate the compute and CPI depth budget of executing a vault transaction
+    ///
+    /// Simulates the inner instructions on their own and wrapped in
+    /// `vault_transaction_execute`, reporting the Squads overhead and the highest stack
+    /// height reached. Signatures are not verified, so no keypair is needed.
+    ///
+    /// # Arguments
+    /// * `multisig` - Multisig account
+    /// * `transaction_index` - Index of the vault transaction
+    /// * `member` - Member that would execute (used as fee payer)
+    pub async fn estimate_execute_budget(
+        &self,
+        multisig: &Pubkey,
+        transaction_index: u64,
+        member: &Pubkey,
+    ) -> SquadsResult<ExecuteBudgetEstimate> {
+        let prefetched = self
+            .get_execution_accounts(multisig, transaction_index, &[])
+            .await?;
+        let raw_instructions = prefetched
+            .transaction
+            .message
+            .to_instructions_with_tables(&prefetched.lookup_tables)
+            .ok_or(SquadsError::InvalidTransactionMessage)?;
+        let execute_ix = prefetched.execute_instruction(*member)?;
+
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(CommitmentConfig::confirmed()),
+            ..Default::default()
+        };
+
+        let raw = Transaction::new_unsigned(Message::new(&raw_instructions, Some(member)));
+        let raw_result = self
+            .rpc
+            .simulate_transaction_with_config(&raw, config.clone())
+            .await?
+            .value;
+
+        let wrapped = Transaction::new_unsigned(Message::new(&[execute_ix], Some(member)));
+        let wrapped_result = self
+            .rpc
+            .simulate_transaction_with_config(&wrapped, config)
+            .await?
+            .value;
+
+        Ok(ExecuteBudgetEstimate {
+            raw_units: raw_result.units_consumed,
+            wrapped_units: wrapped_result.units_consumed,
+            max_stack_height: budget::max_stack_height_from_logs(
+                wrapped_result.logs.as_deref().unwrap_or_default(),
+            ),
+            error: wrapped_result.err.map(|e| e.to_string()),
+            cpi_depth: budget::check_cpi_depth(
+                &prefetched.transaction.message,
+                &budget::known_program_cpi_depths(),
+            ),
+        })
+    }
+
+    /// Fetch an account from a node that has processed the slot of `signature`,
+    /// retrying while the write is not visible yet
+    async fn fetch_after<T>(
+        &self,
+        address: &Pubkey,
+        signature: &Signature,
+        parse: fn(&[u8]) -> Result<T, std::io::Error>,
+    ) -> SquadsResult<T> {
+        // Missing statuses and accounts, and nodes behind the slot, resolve with time
+        let transient = |err: &SquadsError| {
+            matches!(
+                err,
+                SquadsError::TransactionNotFound(_)
+                    | SquadsError::AccountNotFound(_)
+                    | SquadsError::ClientError(_)
+            )
+        };
+        let slot = runtime::retry(&self.read_retry, transient, || {
+            self.get_signature_slot(signature)
+        })
+        .await?;
+        runtime::retry(&self.read_retry, transient, || {
+            fetch_account_at_slot(&self.rpc, &self.program_id, address, parse, slot)
+        })
+        .await
+    }
+}
+
+impl SquadsReader for SquadsClient {
+    fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    async fn get_multisig(&self, multisig: &Pubkey) -> SquadsResult<Multisig> {
+        SquadsClient::get_multisig(self, multisig).await
+    }
+
+    async fn get_proposal(&self, proposal: &Pubkey) -> SquadsResult<Proposal> {
+        SquadsClient::get_proposal(self, proposal).await
+    }
+
+    async fn get_vault_transaction(&self, transaction: &Pubkey) -> SquadsResult<VaultTransaction> {
+        SquadsClient::get_vault_transaction(self, transaction).await
+    }
+
+    async fn get_config_transaction(
+        &self,
+        transaction: &Pubkey,
+    ) -> SquadsResult<ConfigTransaction> {
+        SquadsClient::get_config_transaction(self, transaction).await
+    }
+
+    async fn get_spending_limit(&self, spending_limit: &Pubkey) -> SquadsResult<SpendingLimit> {
+        SquadsClient::get_spending_limit(self, spending_limit).await
+    }
+
+    async fn list_proposals(
+        &self,
+        multisig: &Pubkey,
+        start_index: u64,
+        end_index: u64,
+    ) -> SquadsResult<Vec<(u64, Proposal)>> {
+        SquadsClient::list_proposals(self, multisig, start_index, end_index).await
+    }
+
+    async fn get_creation_memo(
+        &self,
+        multisig: &Pubkey,
+        transaction_index: u64,
+    ) -> SquadsResult<Option<String>> {
+        SquadsClient::get_creation_memo(self, multisig, transaction_index).await
+    }
+}
@@ -0,0 +1,781 @@
+//! Workflows: multi-step operations built from reads and writes
+//!
+//! A workflow sends several transactions, or decides what to send from fetched state,
+//! and reports what it did.
+
+use solana_sdk::{
+    instruction::Instruction,
+    message::AddressLookupTableAccount,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+};
+
+use std::future::Future;
+use std::ops::RangeInclusive;
+
+use super::SquadsClient;
+use crate::{
+    accounts::{check_owner, Proposal},
+    budget::TransactionOptions,
+    diff::MessageDiff,
+    error::{SquadsError, SquadsResult},
+    instructions,
+    memo::{self, MemoPolicy},
+    message::TransactionMessage,
+    nonce, pda,
+    reader::index_chunks,
+    report::ExecutionReport,
+    schedule::{RecurringProposal, TickOutcome},
+    sizing::{self, ConfigExecutionPlan},
+    types::{ConfigAction, ProposalStatus, TransactionKind},
+    upgrade::{self, UpgradePlan},
+};
+
+/// Default number of close instructions packed into one transaction
+pub const DEFAULT_CLOSE_BATCH_SIZE: usize = 5;
+
+/// A transaction whose accounts were closed by [`SquadsClient::close_executed`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClosedTransaction {
+    /// Transaction index
+    pub transaction_index: u64,
+    /// Closed transaction account
+    pub transaction: Pubkey,
+    /// Closed proposal account
+    pub proposal: Pubkey,
+    /// Lamports returned to the rent collector
+    pub reclaimed_lamports: u64,
+    /// Signature of the transaction that closed the accounts
+    pub signature: Signature,
+}
+
+/// Outcome of [`SquadsClient::close_executed`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CloseReport {
+    /// Transactions whose accounts were closed
+    pub closed: Vec<ClosedTransaction>,
+    /// Transaction indexes that were skipped, with the reason
+    pub skipped: Vec<(u64, String)>,
+    /// Transaction indexes whose close transaction failed, with the error
+    pub failed: Vec<(u64, String)>,
+}
+
+impl CloseReport {
+    /// Total lamports returned to the rent collector
+    pub fn total_reclaimed(&self) -> u64 {
+        self.closed.iter().map(|c| c.reclaimed_lamports).sum()
+    }
+}
+
+/// What [`SquadsClient::replace_transaction`] did with the superseded proposal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SupersededProposal {
+    /// The member voted to reject the active proposal
+    RejectVote(Signature),
+    /// The member voted to cancel the approved proposal
+    CancelVote(Signature),
+    /// No vote was cast (no proposal, a draft, or the member already voted); the
+    /// proposal is left to go stale
+    Abandoned,
+    /// The proposal was already rejected or cancelled
+    Final(ProposalStatus),
+}
+
+/// Outcome of [`SquadsClient::replace_transaction`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplacementReport {
+    /// Index of the mis-created transaction
+    pub old_index: u64,
+    /// What was done with its proposal
+    pub old_proposal: SupersededProposal,
+    /// Index of the corrected transaction
+    pub new_index: u64,
+    /// Signature of the corrected vault transaction creation
+    pub create_signature: Signature,
+    /// Signature of the corrected proposal creation
+    pub proposal_signature: Signature,
+    /// Instruction changes from the old transaction (None if it used lookup tables)
+    pub diff: Option<MessageDiff>,
+}
+
+/// Outcome of [`SquadsClient::propose_program_upgrade`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpgradeReport {
+    /// Buffer account holding the new program
+    pub buffer: Pubkey,
+    /// Signature of the buffer creation
+    pub setup_signature: Signature,
+    /// Signatures of the write transactions, in offset order
+    pub write_signatures: Vec<Signature>,
+    /// Signature of the buffer authority handover to the vault
+    pub handover_signature: Signature,
+    /// Index of the upgrade vault transaction
+    pub transaction_index: u64,
+    /// Signature of the proposal creation
+    pub proposal_signature: Signature,
+}
+
+impl SquadsClient {
+    /// Create the proposal of a transaction if it is missing
+    ///
+    /// Creating a transaction and its proposal are separate transactions, so a failed
+    /// proposal creation leaves the transaction index without a proposal and nothing
+    /// to vote on. Returns the signature of the proposal creation, or None if the
+    /// proposal already exists.
+    ///
+    /// # Arguments
+    /// * `multisig` - Multisig account
+    /// * `transaction_index` - Index of the transaction
+    /// * `creator` - Proposal creator (must be member)
+    pub async fn ensure_proposal_exists(
+        &self,
+        multisig: &Pubkey,
+        transaction_index: u64,
+        creator: &Keypair,
+    ) -> SquadsResult<Option<Signature>> {
+        let transaction = self.get_transaction_pda(multisig, transaction_index).0;
+        let proposal = self.get_proposal_pda(multisig, transaction_index).0;
+        let accounts = self
+            .rpc
+            .get_multiple_accounts(&[transaction, proposal])
+            .await?;
+
+        match (&accounts[0], &accounts[1]) {
+            (_, Some(_)) => Ok(None),
+            (None, None) => Err(SquadsError::AccountNotFound(transaction.to_string())),
+            (Some(_), None) => self
+                .create_proposal(multisig, transaction_index, creator, false)
+                .await
+                .map(Some),
+        }
+    }
+
+    /// Propose creating a durable nonce account funded by a vault
+    ///
+    /// The nonce account address is the first ephemeral signer PDA of the new vault
+    /// transaction. Returns the signature, transaction index and nonce account address.
+    ///
+    /// # Arguments
+    /// * `multisig` - Multisig account
+    /// * `creator` - Transaction and proposal creator
+    /// * `vault_index` - Vault that funds the account
+    /// * `authority` - Nonce authority (None to use the vault)
+    pub async fn propose_nonce_account(
+        &self,
+        multisig: &Pubkey,
+        creator: &Keypair,
+        vault_index: u8,
+        authority: Option<Pubkey>,
+    ) -> SquadsResult<(Signature, u64, Pubkey)> {
+        let multisig_account = self.get_multisig(multisig).await?;
+        let transaction_index = multisig_account.transaction_index + 1;
+
+        let (vault_pda, _) = self.get_vault_pda(multisig, vault_index);
+        let (transaction_pda, _) = self.get_transaction_pda(multisig, transaction_index);
+        let (nonce_account, _) =
+            pda::get_ephemeral_signer_pda(&transaction_pda, 0, Some(&self.program_id));
+
+        let lamports = self
+            .rpc
+            .get_minimum_balance_for_rent_exemption(nonce::NONCE_ACCOUNT_SIZE)
+            .await?;
+        let message = nonce::create_nonce_account_message(
+            &vault_pda,
+            &nonce_account,
+            &authority.unwrap_or(vault_pda),
+            lamports,
+        )?;
+
+        let (signature, transaction_index) = self
+            .create_vault_transaction_with_ephemeral_signers(
+                multisig,
+                creator,
+                vault_index,
+                1,
+                &message,
+                None,
+            )
+            .await?;
+        self.create_proposal(multisig, transaction_index, creator, false)
+            .await?;
+
+        Ok((signature, transaction_index, nonce_account))
+    }
+
+    /// Drive a recurring proposal
+    ///
+    /// When the schedule is due at `now`, creates the vault transaction from the template
+    /// together with its proposal, and records the new transaction index. Both are
+    /// created in one transaction, so a failed tick leaves no transaction without a
+    /// proposal behind and the next tick can simply retry.
+    ///
+    /// # Arguments
+    /// * `multisig` - Multisig account
+    /// * `recurring` - Recurring proposal state to advance
+    /// * `creator` - Transaction and proposal creator
+    /// * `vault_index` - Vault the transaction executes from
+    /// * `now` - Current unix timestamp
+    pub async fn tick_recurring(
+        &self,
+        multisig: &Pubkey,
+        recurring: &mut RecurringProposal,
+        creator: &Keypair,
+        vault_index: u8,
+        now: i64,
+    ) -> SquadsResult<TickOutcome> {
+        if !recurring.is_due(now) {
+            return Ok(TickOutcome::NotDue {
+                next_due: recurring.next_due,
+            });
+        }
+
+        let message = recurring.message()?;
+        let memo = memo::check_memo(Some(recurring.template.name.clone()), MemoPolicy::Truncate)?;
+        let transaction_index = self.get_multisig(multisig).await?.transaction_index + 1;
+        let (transaction_pda, _) = self.get_transaction_pda(multisig, transaction_index);
+        let (proposal_pda, _) = self.get_proposal_pda(multisig, transaction_index);
+
+        let create = instructions::vault_transaction_create(
+            *multisig,
+            transaction_pda,
+            creator.pubkey(),
+            creator.pubkey(),
+            instructions::VaultTransactionCreateArgs::new(
+                vault_index,
+                0,
+                borsh::to_vec(&message)?,
+                memo,
+            )?,
+            Some(self.program_id),
+        );
+        let propose = instructions::proposal_create(
+            *multisig,
+            proposal_pda,
+            creator.pubkey(),
+            creator.pubkey(),
+            instructions::ProposalCreateArgs {
+                transaction_index,
+                draft: false,
+            },
+            Some(self.program_id),
+        );
+        self.send_and_confirm_transaction(&[create, propose], &[creator])
+            .await?;
+
+        recurring.record_proposed(now, transaction_index);
+        Ok(TickOutcome::Proposed { transaction_index })
+    }
+
+    /// Replace a mis-created vault transaction with a corrected one
+    ///
+    /// Votes to reject the old proposal if it is active, or to cancel it if it is
+    /// approved, then creates the corrected transaction from the same vault with a
+    /// memo referencing the old index, and proposes it. Executed transactions cannot be
+    /// replaced. The report includes the instruction changes to show voters.
+    ///
+    /// # Arguments
+    /// * `multisig` - Multisig account
+    /// * `bad_index` - Index of the mis-created vault transaction
+    /// * `corrected_instructions` - Instructions for the corrected transaction
+    /// * `member` - Member voting on the old proposal and creating the new transaction
+    pub async fn replace_transaction(
+        &self,
+        multisig: &Pubkey,
+        bad_index: u64,
+        corrected_instructions: &[Instruction],
+        member: &Keypair,
+    ) -> SquadsResult<ReplacementReport> {
+        let (transaction_pda, _) = self.get_transaction_pda(multisig, bad_index);
+        let old_transaction = self.get_vault_transaction(&transaction_pda).await?;
+        let diff = old_transaction
+            .message
+            .to_instructions()
+            .map(|old| MessageDiff::between(&old, corrected_instructions));
+
+        let (proposal_pda, _) = self.get_proposal_pda(multisig, bad_index);
+        let old_proposal = match self.rpc.get_multiple_accounts(&[proposal_pda]).await?.pop() {
+            Some(Some(account)) => {
+                check_owner(&account.owner, &self.program_id)?;
+                Some(
+                    Proposal::try_from_slice(&account.data)
+                        .map_err(SquadsError::from_decode_error)?,
+                )
+            }
+            _ => None,
+        };
+
+        let voter = member.pubkey();
+        let old_proposal = match old_proposal {
+            None => SupersededProposal::Abandoned,
+            Some(proposal) => match proposal.status {
+                ProposalStatus::Executed { .. } => {
+                    return Err(SquadsError::InvalidProposalTransition(format!(
+                        "transaction {} was already executed",
+                        bad_index
+                    )))
+                }
+                ProposalStatus::Rejected { .. } | ProposalStatus::Cancelled { .. } => {
+                    SupersededProposal::Final(proposal.status)
+                }
+                ProposalStatus::Active { .. } if !proposal.has_rejected(&voter) => {
+                    SupersededProposal::RejectVote(
+                        self.reject_proposal(multisig, &proposal_pda, member)
+                            .await?,
+                    )
+                }
+                ProposalStatus::Approved { .. } if !proposal.has_cancelled(&voter) => {
+                    SupersededProposal::CancelVote(
+                        self.cancel_proposal(multisig, &proposal_pda, member)
+                            .await?,
+                    )
+                }
+                _ => SupersededProposal::Abandoned,
+            },
+        };
+
+        let (vault_pda, _) = self.get_vault_pda(multisig, old_transaction.vault_index);
+        let message = TransactionMessage::try_compile(&vault_pda, corrected_instructions)
+            .map_err(|_| SquadsError::InvalidTransactionMessage)?;
+        let memo = memo::check_memo(
+            Some(format!("Replaces transaction #{}", bad_index)),
+            MemoPolicy::Truncate,
+        )?;
+        let (create_signature, new_index) = self
+            .create_vault_transaction(
+                multisig,
+                member,
+                old_transaction.vault_index,
+                &message,
+                memo,
+            )
+            .await?;
+        let proposal_signature = self
+            .create_proposal(multisig, new_index, member, false)
+            .await?;
+
+        Ok(ReplacementReport {
+            old_index: bad_index,
+            old_proposal,
+            new_index,
+            create_signature,
+            proposal_signature,
+            diff,
+        })
+    }
+
+    /// Execute a vault transaction and report the balance changes it caused
+    ///
+    /// Takes the same arguments as
+    /// [`execute_vault_transaction_with_options`](Self::execute_vault_transaction_with_options)
+    /// and fetches the confirmed transaction afterwards (see
+    /// [`get_execution_report`](Self::get_execution_report)).
+    pub async fn execute_vault_transaction_with_report(
+        &self,
+        multisig: &Pubkey,
+        proposal: &Pubkey,
+        transaction: &Pubkey,
+        member: &Keypair,
+        remaining_accounts: Vec<solana_sdk::instruction::AccountMeta>,
+        options: &TransactionOptions,
+    ) -> SquadsResult<ExecutionReport> {
+        let signature = self
+            .execute_vault_transaction_with_options(
+                multisig,
+                proposal,
+                transaction,
+                member,
+                remaining_accounts,
+                options,
+            )
+            .await?;
+        self.get_execution_report(&signature).await
+    }
+
+    /// Create config transactions for `actions`, splitting them if needed
+    ///
+    /// Uses [`sizing::plan_config_transaction`] to check whether the actions fit in a single
+    /// config transaction. If they do not, one config transaction is created per chunk, each
+    /// of which needs its own proposal.
+    ///
+    /// # Returns
+    /// The signature and transaction index of each created config transaction, in order
+    pub async fn create_config_transactions(
+        &self,
+        multisig: &Pubkey,
+        creator: &Keypair,
+        actions: Vec<ConfigAction>,
+    ) -> SquadsResult<Vec<(Signature, u64)>> {
+        let plan = sizing::plan_config_transaction(
+            multisig,
+            &creator.pubkey(),
+            &actions,
+            Some(&self.program_id),
+        )?;
+        let chunks = match plan {
+            ConfigExecutionPlan::Split(chunks) => chunks,
+            ConfigExecutionPlan::Single | ConfigExecutionPlan::LookupTable { .. } => vec![actions],
+        };
+
+        let mut created = Vec::with_capacity(chunks.len());
+        for chunk in chunks {
+            created.push(
+                self.create_config_transaction(multisig, creator, chunk)
+                    .await?,
+            );
+        }
+        Ok(created)
+    }
+
+    /// Execute a config transaction, using an address lookup table if needed
+    ///
+    /// The spending limit accounts are derived from the transaction's actions. If the legacy
+    /// execute transaction would exceed the size limit, a lookup table holding them is
+    /// created first (see [`Self::execute_config_transaction_with_lookup_table`]).
+    pub async fn execute_config_transaction_auto(
+        &self,
+        multisig: &Pubkey,
+        transaction_index: u64,
+        member: &Keypair,
+    ) -> SquadsResult<Signature> {
+        let (transaction, _) = self.get_transaction_pda(multisig, transaction_index);
+        let (proposal, _) = self.get_proposal_pda(multisig, transaction_index);
+
+        let config_transaction = self.get_config_transaction(&transaction).await?;
+        let spending_limit_accounts = sizing::config_spending_limit_accounts(
+            multisig,
+            &config_transaction.actions,
+            Some(&self.program_id),
+        );
+
+        let ix = instructions::config_transaction_execute(
+            *multisig,
+            proposal,
+            transaction,
+            member.pubkey(),
+            Some(member.pubkey()),
+            spending_limit_accounts.clone(),
+            Some(self.program_id),
+        );
+        if sizing::legacy_transaction_size(std::slice::from_ref(&ix), &member.pubkey())
+            <= sizing::MAX_TRANSACTION_SIZE
+        {
+            return self.send_and_confirm_transaction(&[ix], &[member]).await;
+        }
+
+        let (signature, _) = self
+            .execute_config_transaction_with_lookup_table(
+                multisig,
+                &proposal,
+                &transaction,
+                member,
+                spending_limit_accounts,
+            )
+            .await?;
+        Ok(signature)
+    }
+
+    /// Execute a config transaction with a v0 transaction and a new address lookup table
+    ///
+    /// The member creates and pays for a lookup table holding the spending limit accounts.
+    /// The table is left active; deactivate and close it afterwards to reclaim its rent.
+    ///
+    /// # Returns
+    /// The execute signature and the lookup table address
+    pub async fn execute_config_transaction_with_lookup_table(
+        &self,
+        multisig: &Pubkey,
+        proposal: &Pubkey,
+        transaction: &Pubkey,
+        member: &Keypair,
+        spending_limit_accounts: Vec<Pubkey>,
+    ) -> SquadsResult<(Signature, Pubkey)> {
+        let lookup_table = self
+            .create_lookup_table(member, &spending_limit_accounts)
+            .await?;
+
+        let ix = instructions::config_transaction_execute(
+            *multisig,
+            *proposal,
+            *transaction,
+            member.pubkey(),
+            Some(member.pubkey()),
+            spending_limit_accounts.clone(),
+            Some(self.program_id),
+        );
+        let lookup_table_account = AddressLookupTableAccount {
+            key: lookup_table,
+            addresses: spending_limit_accounts,
+        };
+
+        let signature = self
+            .send_and_confirm_v0_transaction(&[ix], &[member], &[lookup_table_account])
+            .await?;
+        Ok((signature, lookup_table))
+    }
+
+    /// Close the transaction and proposal accounts of finished transactions
+    ///
+    /// Validates the rent destination against the multisig's rent collector, then closes
+    /// every closable vault or config transaction in `range`, packing up to `batch_size`
+    /// close instructions into each outer transaction. A batch that fails to land is
+    /// recorded in [`CloseReport::failed`] and the remaining batches are still sent, so
+    /// the report lists every account that was closed.
+    ///
+    /// # Arguments
+    /// * `multisig` - Multisig account
+    /// * `range` - Transaction indexes to consider
+    /// * `payer` - Fee payer (closing is permissionless)
+    /// * `rent_collector` - Expected rent destination (None to use the multisig's)
+    /// * `batch_size` - Maximum close instructions per transaction
+    pub async fn close_executed(
+        &self,
+        multisig: &Pubkey,
+        range: RangeInclusive<u64>,
+        payer: &Keypair,
+        rent_collector: Option<Pubkey>,
+        batch_size: usize,
+    ) -> SquadsResult<CloseReport> {
+        let multisig_account = self.get_multisig(multisig).await?;
+        let configured = multisig_account
+            .rent_collector
+            .ok_or(SquadsError::RentReclamationDisabled)?;
+        if let Some(actual) = rent_collector {
+            if actual != configured {
+                return Err(SquadsError::RentCollectorMismatch {
+                    expected: configured,
+                    actual,
+                });
+            }
+        }
+
+        let mut report = CloseReport::default();
+        let mut pending = Vec::new();
+
+        // Indexes past the multisig's counter have no accounts yet
+        let range = *range.start()..=(*range.end()).min(multisig_account.transaction_index);
+        for chunk in index_chunks(range, 50) {
+            let chunk: Vec<u64> = chunk.collect();
+            let mut addresses = Vec::with_capacity(chunk.len() * 2);
+            for index in &chunk {
+                addresses.push(self.get_transaction_pda(multisig, *index).0);
+                addresses.push(self.get_proposal_pda(multisig, *index).0);
+            }
+            let accounts = self.rpc.get_multiple_accounts(&addresses).await?;
+
+            for (i, index) in chunk.iter().enumerate() {
+                let (transaction, proposal) = (addresses[2 * i], addresses[2 * i + 1]);
+                let (Some(transaction_account), Some(proposal_account)) =
+                    (&accounts[2 * i], &accounts[2 * i + 1])
+                else {
+                    report.skipped.push((
+                        *index,
+                        "transaction or proposal account not found".to_string(),
+                    ));
+                    continue;
+                };
+
+                check_owner(&proposal_account.owner, &self.program_id)?;
+                check_owner(&transaction_account.owner, &self.program_id)?;
+                let proposal_state = Proposal::try_from_slice(&proposal_account.data)
+                    .map_err(SquadsError::from_decode_error)?;
+                let Some(kind) = TransactionKind::of(&transaction_account.data)
+                    .filter(|kind| *kind != TransactionKind::Batch)
+                else {
+                    report
+                        .skipped
+                        .push((*index, "unsupported transaction account type".to_string()));
+                    continue;
+                };
+                if !proposal_state.is_closable(kind, multisig_account.stale_transaction_index) {
+                    report
+                        .skipped
+                        .push((*index, format!("proposal is {:?}", proposal_state.status)));
+                    continue;
+                }
+
+                let ix = if kind == TransactionKind::Vault {
+                    instructions::vault_transaction_accounts_close(
+                        *multisig,
+                        proposal,
+                        transaction,
+                        configured,
+                        Some(self.program_id),
+                    )
+                } else {
+                    instructions::config_transaction_accounts_close(
+                        *multisig,
+                        proposal,
+                        transaction,
+                        configured,
+                        Some(self.program_id),
+                    )
+                };
+
+                let reclaimed = transaction_account.lamports + proposal_account.lamports;
+                pending.push((ix, *index, transaction, proposal, reclaimed));
+            }
+        }
+
+        send_close_batches(&pending, batch_size, &mut report, |ixs| async move {
+            self.send_and_confirm_transaction(&ixs, &[payer]).await
+        })
+        .await;
+
+        Ok(report)
+    }
+
+    /// Upload a program into a buffer and propose upgrading `program` from it
+    ///
+    /// Runs the flow described in [`crate::upgrade`]: creates the buffer, writes the
+    /// program in chunks of `chunk_size` bytes, hands the buffer authority over to the
+    /// vault, then creates the upgrade vault transaction and its proposal. The vault must
+    /// be the program's upgrade authority. If a write fails, the buffer stays owned by
+    /// `member` and can be closed with the loader's close instruction.
+    ///
+    /// # Arguments
+    /// * `multisig` - Multisig account
+    /// * `member` - Pays for and uploads the buffer, and creates the proposal
+    /// * `buffer` - Fresh keypair for the buffer account
+    /// * `vault_index` - Vault holding the upgrade authority
+    /// * `program` - Program to upgrade
+    /// * `program_bytes` - The new program (ELF)
+    /// * `spill` - Account receiving the buffer's lamports after the upgrade
+    /// * `chunk_size` - Bytes per write (None for [`upgrade::DEFAULT_WRITE_CHUNK_SIZE`])
+    #[allow(clippy::too_many_arguments)]
+    pub async fn propose_program_upgrade(
+        &self,
+        multisig: &Pubkey,
+        member: &Keypair,
+        buffer: &Keypair,
+        vault_index: u8,
+        program: &Pubkey,
+        program_bytes: &[u8],
+        spill: &Pubkey,
+        chunk_size: Option<usize>,
+    ) -> SquadsResult<UpgradeReport> {
+        let (vault_pda, _) = self.get_vault_pda(multisig, vault_index);
+        let buffer_lamports = self
+            .rpc
+            .get_minimum_balance_for_rent_exemption(upgrade::buffer_size(program_bytes.len()))
+            .await?;
+        let plan = UpgradePlan::new(
+            member.pubkey(),
+            member.pubkey(),
+            buffer.pubkey(),
+            buffer_lamports,
+            *program,
+            vault_pda,
+            *spill,
+            program_bytes,
+            chunk_size.unwrap_or(upgrade::DEFAULT_WRITE_CHUNK_SIZE),
+        )?;
+
+        let setup_signature = self
+            .send_and_confirm_transaction(&plan.setup, &[member, buffer])
+            .await?;
+        let mut write_signatures = Vec::with_capacity(plan.writes.len());
+        for write in &plan.writes {
+            write_signatures.push(
+                self.send_and_confirm_transaction(std::slice::from_ref(write), &[member])
+                    .await?,
+            );
+        }
+        let handover_signature = self
+            .send_and_confirm_transaction(std::slice::from_ref(&plan.handover), &[member])
+            .await?;
+
+        let message = TransactionMessage::try_compile(&vault_pda, &[plan.upgrade])
+            .map_err(|_| SquadsError::InvalidTransactionMessage)?;
+        let (_, transaction_index) = self
+            .create_vault_transaction(multisig, member, vault_index, &message, None)
+            .await?;
+        let proposal_signature = self
+            .create_proposal(multisig, transaction_index, member, false)
+            .await?;
+
+        Ok(UpgradeReport {
+            buffer: plan.buffer,
+            setup_signature,
+            write_signatures,
+            handover_signature,
+            transaction_index,
+            proposal_signature,
+        })
+    }
+}
+
+/// A close instruction with the transaction index, transaction and proposal accounts it
+/// closes and the lamports they hold
+type PendingClose = (Instruction, u64, Pubkey, Pubkey, u64);
+
+/// Send `pending` close instructions in batches of `batch_size` and record the outcome
+/// of each batch in `report`
+async fn send_close_batches<F, Fut>(
+    pending: &[PendingClose],
+    batch_size: usize,
+    report: &mut CloseReport,
+    mut send: F,
+) where
+    F: FnMut(Vec<Instruction>) -> Fut,
+    Fut: Future<Output = SquadsResult<Signature>>,
+{
+    for batch in pending.chunks(batch_size.max(1)) {
+        let ixs: Vec<Instruction> = batch.iter().map(|(ix, ..)| ix.clone()).collect();
+        match send(ixs).await {
+            Ok(signature) => {
+                for (_, transaction_index, transaction, proposal, reclaimed) in batch {
+                    report.closed.push(ClosedTransaction {
+                        transaction_index: *transaction_index,
+                        transaction: *transaction,
+                        proposal: *proposal,
+                        reclaimed_lamports: *reclaimed,
+                        signature,
+                    });
+                }
+            }
+            Err(err) => {
+                let reason = err.to_string();
+                report.failed.extend(
+                    batch
+                        .iter()
+                        .map(|(_, transaction_index, ..)| (*transaction_index, reason.clone())),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_close_batches_report_partial_failure() {
+        let pending: Vec<PendingClose> = (1..=4)
+            .map(|index| {
+                let ix = Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![]);
+                (ix, index, Pubkey::new_unique(), Pubkey::new_unique(), 1_000)
+            })
+            .collect();
+
+        let mut report = CloseReport::default();
+        let mut calls = 0;
+        send_close_batches(&pending, 2, &mut report, |_| {
+            calls += 1;
+            let result = if calls == 2 {
+                Err(SquadsError::InvalidTransactionMessage)
+            } else {
+                Ok(Signature::default())
+            };
+            async move { result }
+        })
+        .await;
+
+        let closed: Vec<u64> = report.closed.iter().map(|c| c.transaction_index).collect();
+        assert_eq!(closed, vec![1, 2]);
+        assert_eq!(report.total_reclaimed(), 2_000);
+        let failed: Vec<u64> = report.failed.iter().map(|(index, _)| *index).collect();
+        assert_eq!(failed, vec![3, 4]);
+    }
+}
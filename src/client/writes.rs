@@ -0,0 +1,631 @@
+//! Writes: one confirmed transaction per call
+//!
+//! Each method reads the state it needs, builds the instructions of one operation with
+//! [`crate::instructions`], then signs, sends and confirms a single transaction.
+
+use solana_address_lookup_table_interface::instruction as lookup_table_instruction;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+};
+
+use super::SquadsClient;
+use crate::{
+    accounts::{check_owner, Multisig, MultisigSnapshot, ProgramConfig, Proposal},
+    budget::TransactionOptions,
+    error::{SquadsError, SquadsResult},
+    failure,
+    instructions::{self, VoteOptions},
+    message::TransactionMessage,
+    pda,
+    prefetch::PrefetchedExecution,
+    runtime,
+    spec::MultisigSpec,
+    types::{ConfigAction, Member},
+};
+
+/// Maximum addresses added to a lookup table per extend transaction
+const LOOKUP_TABLE_EXTEND_CHUNK: usize = 20;
+
+/// Signer list with `payer` first, as the send helpers expect, and every keypair once
+fn fee_payer_first<'a>(payer: &'a Keypair, signers: &[&'a Keypair]) -> Vec<&'a Keypair> {
+    let mut ordered = vec![payer];
+    for signer in signers {
+        if ordered.iter().all(|s| s.pubkey() != signer.pubkey()) {
+            ordered.push(signer);
+        }
+    }
+    ordered
+}
+
+impl SquadsClient {
+    /// Create a new multisig
+    ///
+    /// # Arguments
+    /// * `create_key` - Keypair for unique multisig PDA derivation
+    /// * `creator` - Creator and fee payer
+    /// * `threshold` - Approval threshold
+    /// * `members` - Initial members
+    /// * `time_lock` - Time lock in seconds (0 for no time lock)
+    /// * `config_authority` - Optional config authority (None for autonomous)
+    /// * `rent_collector` - Optional rent collector
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_multisig(
+        &self,
+        create_key: &Keypair,
+        creator: &Keypair,
+        threshold: u16,
+        members: Vec<Member>,
+        time_lock: u32,
+        config_authority: Option<Pubkey>,
+        rent_collector: Option<Pubkey>,
+    ) -> SquadsResult<Signature> {
+        self.create_multisig_with_payer(
+            create_key,
+            creator,
+            creator,
+            threshold,
+            members,
+            time_lock,
+            config_authority,
+            rent_collector,
+        )
+        .await
+    }
+
+    /// Create a new multisig with a fee payer other than the creator
+    ///
+    /// `payer` signs first and pays the transaction fee; `creator` and `create_key` only
+    /// sign for the instruction. The program still draws the multisig account rent and
+    /// the creation fee from `creator`, so it must hold enough lamports for those. Any
+    /// two of the signers may be the same keypair.
+    ///
+    /// # Arguments
+    /// * `create_key` - Keypair for unique multisig PDA derivation
+    /// * `creator` - Creator, paying the account rent and creation fee
+    /// * `payer` - Transaction fee payer
+    /// * `threshold` - Approval threshold
+    /// * `members` - Initial members
+    /// * `time_lock` - Time lock in seconds (0 for no time lock)
+    /// * `config_authority` - Optional config authority (None for autonomous)
+    /// * `rent_collector` - Optional rent collector
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_multisig_with_payer(
+        &self,
+        create_key: &Keypair,
+        creator: &Keypair,
+        payer: &Keypair,
+        threshold: u16,
+        members: Vec<Member>,
+        time_lock: u32,
+        config_authority: Option<Pubkey>,
+        rent_collector: Option<Pubkey>,
+    ) -> SquadsResult<Signature> {
+        // Validate inputs
+        if threshold == 0 {
+            return Err(SquadsError::InvalidThreshold);
+        }
+
+        let voting_members = members.iter().filter(|m| m.permissions.has_vote()).count();
+        if voting_members == 0 {
+            return Err(SquadsError::NoVotingMembers);
+        }
+
+        if usize::from(threshold) > voting_members {
+            return Err(SquadsError::InvalidThreshold);
+        }
+
+        let args = instructions::MultisigCreateArgsV2 {
+            config_authority,
+            threshold,
+            members,
+            time_lock,
+            rent_collector,
+            memo: None,
+        };
+
+        self.send_multisig_create(create_key, creator, payer, args)
+            .await
+    }
+
+    /// Create a new multisig from a [`MultisigSpec`], such as a Squads app export
+    ///
+    /// The spec name, if any, is recorded as the creation memo. Returns the signature and
+    /// the address of the new multisig.
+    ///
+    /// # Arguments
+    /// * `spec` - Multisig configuration
+    /// * `create_key` - Keypair for unique multisig PDA derivation
+    /// * `creator` - Creator and fee payer
+    pub async fn create_from_spec(
+        &self,
+        spec: &MultisigSpec,
+        create_key: &Keypair,
+        creator: &Keypair,
+    ) -> SquadsResult<(Signature, Pubkey)> {
+        let args = spec.create_args()?;
+        let (multisig_pda, _) = pda::get_multisig_pda(&create_key.pubkey(), Some(&self.program_id));
+        let signature = self
+            .send_multisig_create(create_key, creator, creator, args)
+            .await?;
+        Ok((signature, multisig_pda))
+    }
+
+    async fn send_multisig_create(
+        &self,
+        create_key: &Keypair,
+        creator: &Keypair,
+        payer: &Keypair,
+        args: instructions::MultisigCreateArgsV2,
+    ) -> SquadsResult<Signature> {
+        // Derive PDAs
+        let (multisig_pda, _) = pda::get_multisig_pda(&create_key.pubkey(), Some(&self.program_id));
+        let (program_config_pda, _) = pda::get_program_config_pda(Some(&self.program_id));
+
+        // Get program config to find treasury
+        let program_config_account = self.rpc.get_account(&program_config_pda).await?;
+        check_owner(&program_config_account.owner, &self.program_id)?;
+        let treasury = ProgramConfig::try_from_slice(&program_config_account.data)
+            .map_err(SquadsError::from_decode_error)?
+            .treasury;
+
+        let ix = instructions::multisig_create_v2(
+            program_config_pda,
+            treasury,
+            multisig_pda,
+            create_key.pubkey(),
+            creator.pubkey(),
+            args,
+            Some(self.program_id),
+        );
+
+        let signers = fee_payer_first(payer, &[creator, create_key]);
+        self.send_and_confirm_transaction(&[ix], &signers).await
+    }
+
+    /// Create a proposal for a transaction
+    ///
+    /// # Arguments
+    /// * `multisig` - Multisig account
+    /// * `transaction_index` - Index of the transaction
+    /// * `creator` - Proposal creator (must be member)
+    /// * `draft` - Whether to create as draft
+    ///
+    /// The creator also pays rent for the proposal account, which is sized for every
+    /// member of the multisig (see [`Proposal::size_for`]). Fails with
+    /// [`SquadsError::InsufficientFunds`] before sending if the creator cannot cover it.
+    pub async fn create_proposal(
+        &self,
+        multisig: &Pubkey,
+        transaction_index: u64,
+        creator: &Keypair,
+        draft: bool,
+    ) -> SquadsResult<Signature> {
+        let (proposal_pda, _) = self.get_proposal_pda(multisig, transaction_index);
+
+        let multisig_account = self.get_multisig(multisig).await?;
+        let rent = self
+            .rpc
+            .get_minimum_balance_for_rent_exemption(Proposal::size_for(
+                multisig_account.members.len(),
+            ))
+            .await?;
+        let balance = self.rpc.get_balance(&creator.pubkey()).await?;
+        if balance < rent {
+            return Err(SquadsError::InsufficientFunds {
+                required: rent,
+                available: balance,
+            });
+        }
+
+        let args = instructions::ProposalCreateArgs {
+            transaction_index,
+            draft,
+        };
+
+        let ix = instructions::proposal_create(
+            *multisig,
+            proposal_pda,
+            creator.pubkey(),
+            creator.pubkey(),
+            args,
+            Some(self.program_id),
+        );
+
+        self.send_and_confirm_transaction(&[ix], &[creator]).await
+    }
+
+    /// Approve a proposal
+    pub async fn approve_proposal(
+        &self,
+        multisig: &Pubkey,
+        proposal: &Pubkey,
+        member: &Keypair,
+    ) -> SquadsResult<Signature> {
+        self.approve_proposal_with_options(multisig, proposal, member, &VoteOptions::default())
+            .await
+    }
+
+    /// Approve a proposal with a memo, optionally also emitted as an SPL Memo instruction
+    pub async fn approve_proposal_with_options(
+        &self,
+        multisig: &Pubkey,
+        proposal: &Pubkey,
+        member: &Keypair,
+        options: &VoteOptions,
+    ) -> SquadsResult<Signature> {
+        let ix = instructions::proposal_approve(
+            *multisig,
+            *proposal,
+            member.pubkey(),
+            options.args()?,
+            Some(self.program_id),
+        );
+
+        let ixs = options.instructions(ix, &member.pubkey());
+        self.send_and_confirm_transaction(&ixs, &[member]).await
+    }
+
+    /// Reject a proposal
+    pub async fn reject_proposal(
+        &self,
+        multisig: &Pubkey,
+        proposal: &Pubkey,
+        member: &Keypair,
+    ) -> SquadsResult<Signature> {
+        self.reject_proposal_with_options(multisig, proposal, member, &VoteOptions::default())
+            .await
+    }
+
+    /// Reject a proposal with a memo, optionally also emitted as an SPL Memo instruction
+    pub async fn reject_proposal_with_options(
+        &self,
+        multisig: &Pubkey,
+        proposal: &Pubkey,
+        member: &Keypair,
+        options: &VoteOptions,
+    ) -> SquadsResult<Signature> {
+        let ix = instructions::proposal_reject(
+            *multisig,
+            *proposal,
+            member.pubkey(),
+            options.args()?,
+            Some(self.program_id),
+        );
+
+        let ixs = options.instructions(ix, &member.pubkey());
+        self.send_and_confirm_transaction(&ixs, &[member]).await
+    }
+
+    /// Cancel an approved proposal
+    pub async fn cancel_proposal(
+        &self,
+        multisig: &Pubkey,
+        proposal: &Pubkey,
+        member: &Keypair,
+    ) -> SquadsResult<Signature> {
+        self.cancel_proposal_with_options(multisig, proposal, member, &VoteOptions::default())
+            .await
+    }
+
+    /// Cancel an approved proposal with a memo, optionally also emitted as an SPL Memo instruction
+    pub async fn cancel_proposal_with_options(
+        &self,
+        multisig: &Pubkey,
+        proposal: &Pubkey,
+        member: &Keypair,
+        options: &VoteOptions,
+    ) -> SquadsResult<Signature> {
+        let ix = instructions::proposal_cancel(
+            *multisig,
+            *proposal,
+            member.pubkey(),
+            options.args()?,
+            Some(self.program_id),
+        );
+
+        let ixs = options.instructions(ix, &member.pubkey());
+        self.send_and_confirm_transaction(&ixs, &[member]).await
+    }
+
+    /// Create a config transaction
+    ///
+    /// # Arguments
+    /// * `multisig` - Multisig account
+    /// * `creator` - Transaction creator
+    /// * `actions` - Configuration actions to execute
+    pub async fn create_config_transaction(
+        &self,
+        multisig: &Pubkey,
+        creator: &Keypair,
+        actions: Vec<ConfigAction>,
+    ) -> SquadsResult<(Signature, u64)> {
+        // Get current transaction index
+        let multisig_account = self.get_multisig(multisig).await?;
+        self.send_config_transaction_create(multisig, creator, actions, &multisig_account)
+            .await
+    }
+
+    /// Create a config transaction only if the multisig still matches a snapshot
+    ///
+    /// Use this when `actions` were derived from a cached multisig (for example a
+    /// threshold computed from its member list). The multisig is re-fetched right
+    /// before sending and [`SquadsError::StaleSnapshot`] is returned if it changed.
+    ///
+    /// # Arguments
+    /// * `multisig` - Multisig account
+    /// * `creator` - Transaction creator (must have Initiate permission)
+    /// * `actions` - Config actions to perform
+    /// * `expected` - Snapshot of the multisig the actions were built from
+    pub async fn create_config_transaction_checked(
+        &self,
+        multisig: &Pubkey,
+        creator: &Keypair,
+        actions: Vec<ConfigAction>,
+        expected: &MultisigSnapshot,
+    ) -> SquadsResult<(Signature, u64)> {
+        let multisig_account = self.get_multisig(multisig).await?;
+        expected.verify(&multisig_account)?;
+        self.send_config_transaction_create(multisig, creator, actions, &multisig_account)
+            .await
+    }
+
+    async fn send_config_transaction_create(
+        &self,
+        multisig: &Pubkey,
+        creator: &Keypair,
+        actions: Vec<ConfigAction>,
+        multisig_account: &Multisig,
+    ) -> SquadsResult<(Signature, u64)> {
+        let transaction_index = multisig_account.transaction_index + 1;
+
+        let (transaction_pda, _) = self.get_transaction_pda(multisig, transaction_index);
+
+        let args = instructions::ConfigTransactionCreateArgs {
+            actions,
+            memo: None,
+        };
+
+        let ix = instructions::config_transaction_create(
+            *multisig,
+            transaction_pda,
+            creator.pubkey(),
+            creator.pubkey(),
+            args,
+            Some(self.program_id),
+        );
+
+        let sig = self.send_and_confirm_transaction(&[ix], &[creator]).await?;
+        Ok((sig, transaction_index))
+    }
+
+    /// Create a vault transaction
+    ///
+    /// # Arguments
+    /// * `multisig` - Multisig account
+    /// * `creator` - Transaction creator (must have Initiate permission)
+    /// * `vault_index` - Vault the transaction executes from
+    /// * `message` - Compiled transaction message to execute
+    /// * `memo` - Optional memo
+    pub async fn create_vault_transaction(
+        &self,
+        multisig: &Pubkey,
+        creator: &Keypair,
+        vault_index: u8,
+        message: &TransactionMessage,
+        memo: Option<String>,
+    ) -> SquadsResult<(Signature, u64)> {
+        self.create_vault_transaction_with_ephemeral_signers(
+            multisig,
+            creator,
+            vault_index,
+            0,
+            message,
+            memo,
+        )
+        .await
+    }
+
+    /// Create a vault transaction that uses ephemeral signer PDAs
+    ///
+    /// # Arguments
+    /// * `multisig` - Multisig account
+    /// * `creator` - Transaction creator (must have Initiate permission)
+    /// * `vault_index` - Vault the transaction executes from
+    /// * `ephemeral_signers` - Number of ephemeral signer PDAs the message uses
+    /// * `message` - Compiled transaction message to execute
+    /// * `memo` - Optional memo
+    pub async fn create_vault_transaction_with_ephemeral_signers(
+        &self,
+        multisig: &Pubkey,
+        creator: &Keypair,
+        vault_index: u8,
+        ephemeral_signers: u8,
+        message: &TransactionMessage,
+        memo: Option<String>,
+    ) -> SquadsResult<(Signature, u64)> {
+        // Get current transaction index
+        let multisig_account = self.get_multisig(multisig).await?;
+        let transaction_index = multisig_account.transaction_index + 1;
+
+        let (transaction_pda, _) = self.get_transaction_pda(multisig, transaction_index);
+
+        let args = instructions::VaultTransactionCreateArgs::new(
+            vault_index,
+            ephemeral_signers,
+            borsh::to_vec(message)?,
+            memo,
+        )?;
+
+        let ix = instructions::vault_transaction_create(
+            *multisig,
+            transaction_pda,
+            creator.pubkey(),
+            creator.pubkey(),
+            args,
+            Some(self.program_id),
+        );
+
+        let sig = self.send_and_confirm_transaction(&[ix], &[creator]).await?;
+        Ok((sig, transaction_index))
+    }
+
+    /// Execute a vault transaction
+    ///
+    /// # Arguments
+    /// * `multisig` - Multisig account
+    /// * `proposal` - Proposal account
+    /// * `transaction` - Transaction to execute
+    /// * `member` - Member executing (must have Execute permission)
+    /// * `remaining_accounts` - Accounts required by the transaction
+    pub async fn execute_vault_transaction(
+        &self,
+        multisig: &Pubkey,
+        proposal: &Pubkey,
+        transaction: &Pubkey,
+        member: &Keypair,
+        remaining_accounts: Vec<solana_sdk::instruction::AccountMeta>,
+    ) -> SquadsResult<Signature> {
+        self.execute_vault_transaction_with_options(
+            multisig,
+            proposal,
+            transaction,
+            member,
+            remaining_accounts,
+            &TransactionOptions::default(),
+        )
+        .await
+    }
+
+    /// Execute a vault transaction with a compute unit limit and priority fee
+    ///
+    /// See [`execute_vault_transaction`](Self::execute_vault_transaction) for the
+    /// arguments; `options` is typically built from
+    /// [`get_recommended_priority_fee`](Self::get_recommended_priority_fee).
+    ///
+    /// When preflight shows that an instruction of the vault transaction failed, the
+    /// error is [`SquadsError::InstructionFailed`] with the index of that instruction.
+    pub async fn execute_vault_transaction_with_options(
+        &self,
+        multisig: &Pubkey,
+        proposal: &Pubkey,
+        transaction: &Pubkey,
+        member: &Keypair,
+        remaining_accounts: Vec<solana_sdk::instruction::AccountMeta>,
+        options: &TransactionOptions,
+    ) -> SquadsResult<Signature> {
+        let ix = instructions::vault_transaction_execute(
+            *multisig,
+            *proposal,
+            *transaction,
+            member.pubkey(),
+            remaining_accounts,
+            Some(self.program_id),
+        );
+
+        self.send_and_confirm_transaction(&options.apply(&[ix]), &[member])
+            .await
+            .map_err(|err| failure::with_location(err, Some(&self.program_id)))
+    }
+
+    /// Execute a vault transaction from accounts loaded with
+    /// [`get_execution_accounts`](Self::get_execution_accounts)
+    ///
+    /// Skips the account fetches [`execute_vault_transaction`](Self::execute_vault_transaction)
+    /// callers otherwise make, so the only round trips left are the blockhash and the send.
+    pub async fn execute_prefetched(
+        &self,
+        prefetched: &PrefetchedExecution,
+        member: &Keypair,
+        options: &TransactionOptions,
+    ) -> SquadsResult<Signature> {
+        let ix = prefetched.execute_instruction(member.pubkey())?;
+        self.send_and_confirm_transaction(&options.apply(&[ix]), &[member])
+            .await
+            .map_err(|err| failure::with_location(err, Some(&self.program_id)))
+    }
+
+    /// Execute a config transaction
+    pub async fn execute_config_transaction(
+        &self,
+        multisig: &Pubkey,
+        proposal: &Pubkey,
+        transaction: &Pubkey,
+        member: &Keypair,
+        spending_limit_accounts: Vec<Pubkey>,
+    ) -> SquadsResult<Signature> {
+        let ix = instructions::config_transaction_execute(
+            *multisig,
+            *proposal,
+            *transaction,
+            member.pubkey(),
+            Some(member.pubkey()),
+            spending_limit_accounts,
+            Some(self.program_id),
+        );
+
+        self.send_and_confirm_transaction(&[ix], &[member]).await
+    }
+
+    /// Create a lookup table with `addresses` and wait until it can be used
+    pub(super) async fn create_lookup_table(
+        &self,
+        authority: &Keypair,
+        addresses: &[Pubkey],
+    ) -> SquadsResult<Pubkey> {
+        let recent_slot = self
+            .rpc
+            .get_slot_with_commitment(CommitmentConfig::finalized())
+            .await?;
+        let (create_ix, lookup_table) = lookup_table_instruction::create_lookup_table(
+            authority.pubkey(),
+            authority.pubkey(),
+            recent_slot,
+        );
+        self.send_and_confirm_transaction(&[create_ix], &[authority])
+            .await?;
+
+        for chunk in addresses.chunks(LOOKUP_TABLE_EXTEND_CHUNK) {
+            let extend_ix = lookup_table_instruction::extend_lookup_table(
+                lookup_table,
+                authority.pubkey(),
+                Some(authority.pubkey()),
+                chunk.to_vec(),
+            );
+            self.send_and_confirm_transaction(&[extend_ix], &[authority])
+                .await?;
+        }
+
+        // Addresses become usable in the slot after they were added
+        let extended_slot = self.rpc.get_slot().await?;
+        while self.rpc.get_slot().await? <= extended_slot {
+            runtime::sleep(std::time::Duration::from_millis(400)).await;
+        }
+
+        Ok(lookup_table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_payer_first() {
+        let (create_key, creator, payer) = (Keypair::new(), Keypair::new(), Keypair::new());
+        let keys = |signers: Vec<&Keypair>| -> Vec<Pubkey> {
+            signers.iter().map(|s| s.pubkey()).collect()
+        };
+
+        assert_eq!(
+            keys(fee_payer_first(&payer, &[&creator, &create_key])),
+            vec![payer.pubkey(), creator.pubkey(), create_key.pubkey()]
+        );
+        assert_eq!(
+            keys(fee_payer_first(&creator, &[&creator, &create_key])),
+            vec![creator.pubkey(), create_key.pubkey()]
+        );
+    }
+}
@@ -0,0 +1,200 @@
+//! Pluggable serialization formats
+//!
+//! A common encode/decode abstraction over the serde formats the crate supports, so
+//! exported data (templates, schedules and other serde types) can use compact binary
+//! formats for archives while APIs keep using JSON. JSON is always available; bincode and
+//! MessagePack are enabled by the `bincode` and `msgpack` features.
+//!
+//! Formats are available both as zero-sized types implementing [`Format`] for static
+//! dispatch and as the [`Encoding`] enum when the format is chosen at runtime.
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::error::{SquadsError, SquadsResult};
+
+/// A serde-based serialization format
+pub trait Format {
+    /// Short name of the format (also used as the file extension)
+    const NAME: &'static str;
+
+    /// Serialize a value
+    fn encode<T: Serialize + ?Sized>(value: &T) -> SquadsResult<Vec<u8>>;
+
+    /// Deserialize a value
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> SquadsResult<T>;
+}
+
+fn encoding_error(format: &str, err: impl std::fmt::Display) -> SquadsError {
+    SquadsError::EncodingError(format!("{}: {}", format, err))
+}
+
+/// JSON
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json;
+
+impl Format for Json {
+    const NAME: &'static str = "json";
+
+    fn encode<T: Serialize + ?Sized>(value: &T) -> SquadsResult<Vec<u8>> {
+        serde_json::to_vec(value).map_err(|e| encoding_error(Self::NAME, e))
+    }
+
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> SquadsResult<T> {
+        serde_json::from_slice(bytes).map_err(|e| encoding_error(Self::NAME, e))
+    }
+}
+
+/// bincode (requires the `bincode` feature)
+#[cfg(feature = "bincode")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Bincode;
+
+#[cfg(feature = "bincode")]
+impl Format for Bincode {
+    const NAME: &'static str = "bincode";
+
+    fn encode<T: Serialize + ?Sized>(value: &T) -> SquadsResult<Vec<u8>> {
+        bincode::serialize(value).map_err(|e| encoding_error(Self::NAME, e))
+    }
+
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> SquadsResult<T> {
+        bincode::deserialize(bytes).map_err(|e| encoding_error(Self::NAME, e))
+    }
+}
+
+/// MessagePack (requires the `msgpack` feature)
+#[cfg(feature = "msgpack")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MessagePack;
+
+#[cfg(feature = "msgpack")]
+impl Format for MessagePack {
+    const NAME: &'static str = "msgpack";
+
+    fn encode<T: Serialize + ?Sized>(value: &T) -> SquadsResult<Vec<u8>> {
+        rmp_serde::to_vec_named(value).map_err(|e| encoding_error(Self::NAME, e))
+    }
+
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> SquadsResult<T> {
+        rmp_serde::from_slice(bytes).map_err(|e| encoding_error(Self::NAME, e))
+    }
+}
+
+/// Encode any serializable value with a [`Format`]
+pub trait Encode: Serialize {
+    /// Serialize `self` with format `F`
+    fn encode_as<F: Format>(&self) -> SquadsResult<Vec<u8>> {
+        F::encode(self)
+    }
+}
+
+impl<T: Serialize + ?Sized> Encode for T {}
+
+/// Decode any deserializable value with a [`Format`]
+pub trait Decode: DeserializeOwned {
+    /// Deserialize a value of this type with format `F`
+    fn decode_from<F: Format>(bytes: &[u8]) -> SquadsResult<Self> {
+        F::decode(bytes)
+    }
+}
+
+impl<T: DeserializeOwned> Decode for T {}
+
+/// Serialization format selected at runtime
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Encoding {
+    /// JSON
+    #[default]
+    Json,
+    /// bincode (requires the `bincode` feature)
+    #[cfg(feature = "bincode")]
+    Bincode,
+    /// MessagePack (requires the `msgpack` feature)
+    #[cfg(feature = "msgpack")]
+    MessagePack,
+}
+
+impl Encoding {
+    /// Short name of the format (also used as the file extension)
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encoding::Json => Json::NAME,
+            #[cfg(feature = "bincode")]
+            Encoding::Bincode => Bincode::NAME,
+            #[cfg(feature = "msgpack")]
+            Encoding::MessagePack => MessagePack::NAME,
+        }
+    }
+
+    /// Look up an encoding by name, if it is enabled
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "json" => Some(Encoding::Json),
+            #[cfg(feature = "bincode")]
+            "bincode" => Some(Encoding::Bincode),
+            #[cfg(feature = "msgpack")]
+            "msgpack" => Some(Encoding::MessagePack),
+            _ => None,
+        }
+    }
+
+    /// Serialize a value
+    pub fn encode<T: Serialize + ?Sized>(&self, value: &T) -> SquadsResult<Vec<u8>> {
+        match self {
+            Encoding::Json => Json::encode(value),
+            #[cfg(feature = "bincode")]
+            Encoding::Bincode => Bincode::encode(value),
+            #[cfg(feature = "msgpack")]
+            Encoding::MessagePack => MessagePack::encode(value),
+        }
+    }
+
+    /// Deserialize a value
+    pub fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> SquadsResult<T> {
+        match self {
+            Encoding::Json => Json::decode(bytes),
+            #[cfg(feature = "bincode")]
+            Encoding::Bincode => Bincode::decode(bytes),
+            #[cfg(feature = "msgpack")]
+            Encoding::MessagePack => MessagePack::decode(bytes),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::templates::Template;
+    use solana_sdk::pubkey::Pubkey;
+
+    fn sample() -> Template {
+        Template::sol_transfer(Pubkey::new_unique())
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let template = sample();
+        let bytes = template.encode_as::<Json>().unwrap();
+        assert_eq!(Template::decode_from::<Json>(&bytes).unwrap(), template);
+        assert!(Json::decode::<Template>(b"not json").is_err());
+    }
+
+    #[test]
+    fn test_enabled_encodings_round_trip() {
+        let template = sample();
+        let encodings = [
+            Encoding::Json,
+            #[cfg(feature = "bincode")]
+            Encoding::Bincode,
+            #[cfg(feature = "msgpack")]
+            Encoding::MessagePack,
+        ];
+
+        for encoding in encodings {
+            assert_eq!(Encoding::from_name(encoding.name()), Some(encoding));
+            let bytes = encoding.encode(&template).unwrap();
+            let decoded: Template = encoding.decode(&bytes).unwrap();
+            assert_eq!(decoded, template);
+        }
+    }
+}
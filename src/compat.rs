@@ -0,0 +1,124 @@
+//! Conversions to and from other solana-sdk major versions
+//!
+//! This crate is built against solana-sdk 3.x. Consumers still on the 2.x component
+//! crates (`solana-pubkey` / `solana-instruction` 2.x, as used by solana-sdk 2.x) can
+//! enable the `sdk-v2` feature and convert with [`CompatFrom`] / [`CompatInto`]:
+//!
+//! ```rust,ignore
+//! use squads_v4_client_v3::compat::CompatInto;
+//!
+//! let ix: solana_instruction_v2::Instruction = squads_ix.compat_into();
+//! ```
+//!
+//! solana-sdk 1.x cannot be supported this way: it pins `zeroize < 1.4` through
+//! `curve25519-dalek` 3, which cannot resolve alongside the 3.x dependency graph, so
+//! both versions can never be linked into the same build.
+
+/// Conversion from an equivalent type of another solana-sdk version
+pub trait CompatFrom<T>: Sized {
+    /// Convert `value` into `Self`
+    fn compat_from(value: T) -> Self;
+}
+
+/// Conversion into an equivalent type of another solana-sdk version
+///
+/// Implemented for every type whose target implements [`CompatFrom`].
+pub trait CompatInto<T> {
+    /// Convert `self` into `T`
+    fn compat_into(self) -> T;
+}
+
+impl<T, U: CompatFrom<T>> CompatInto<U> for T {
+    fn compat_into(self) -> U {
+        U::compat_from(self)
+    }
+}
+
+#[cfg(feature = "sdk-v2")]
+mod v2 {
+    use solana_instruction_v2 as instruction_v2;
+    use solana_pubkey_v2 as pubkey_v2;
+    use solana_sdk::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+    };
+
+    use super::{CompatFrom, CompatInto};
+
+    impl CompatFrom<pubkey_v2::Pubkey> for Pubkey {
+        fn compat_from(value: pubkey_v2::Pubkey) -> Self {
+            Pubkey::new_from_array(value.to_bytes())
+        }
+    }
+
+    impl CompatFrom<Pubkey> for pubkey_v2::Pubkey {
+        fn compat_from(value: Pubkey) -> Self {
+            pubkey_v2::Pubkey::new_from_array(value.to_bytes())
+        }
+    }
+
+    impl CompatFrom<instruction_v2::AccountMeta> for AccountMeta {
+        fn compat_from(value: instruction_v2::AccountMeta) -> Self {
+            AccountMeta {
+                pubkey: value.pubkey.compat_into(),
+                is_signer: value.is_signer,
+                is_writable: value.is_writable,
+            }
+        }
+    }
+
+    impl CompatFrom<AccountMeta> for instruction_v2::AccountMeta {
+        fn compat_from(value: AccountMeta) -> Self {
+            instruction_v2::AccountMeta {
+                pubkey: value.pubkey.compat_into(),
+                is_signer: value.is_signer,
+                is_writable: value.is_writable,
+            }
+        }
+    }
+
+    impl CompatFrom<instruction_v2::Instruction> for Instruction {
+        fn compat_from(value: instruction_v2::Instruction) -> Self {
+            Instruction {
+                program_id: value.program_id.compat_into(),
+                accounts: value
+                    .accounts
+                    .into_iter()
+                    .map(CompatInto::compat_into)
+                    .collect(),
+                data: value.data,
+            }
+        }
+    }
+
+    impl CompatFrom<Instruction> for instruction_v2::Instruction {
+        fn compat_from(value: Instruction) -> Self {
+            instruction_v2::Instruction {
+                program_id: value.program_id.compat_into(),
+                accounts: value
+                    .accounts
+                    .into_iter()
+                    .map(CompatInto::compat_into)
+                    .collect(),
+                data: value.data,
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_instruction_round_trip() {
+            let ix = crate::memo::memo_instruction("compat", &[Pubkey::new_unique()]);
+
+            let old: instruction_v2::Instruction = ix.clone().compat_into();
+            assert_eq!(old.program_id.to_bytes(), ix.program_id.to_bytes());
+            assert!(old.accounts[0].is_signer);
+
+            let back: Instruction = old.compat_into();
+            assert_eq!(back, ix);
+        }
+    }
+}
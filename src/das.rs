@@ -0,0 +1,435 @@
+//! Compressed NFTs held by vaults, via the DAS API
+//!
+//! Compressed NFTs live as leaves of a merkle tree rather than in token accounts, so
+//! plain RPC cannot list them and a transfer must carry a proof of the leaf. This module
+//! queries a DAS-compatible endpoint (the `getAssetsByOwner`, `getAsset` and
+//! `getAssetProof` methods most RPC providers serve next to the regular JSON-RPC API)
+//! and builds the Bubblegum transfer a vault transaction needs to move an asset.
+//!
+//! This module is only available with the `das` feature enabled.
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer};
+use serde_json::json;
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_request::RpcRequest};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+};
+use solana_sdk_ids::system_program;
+
+use crate::{
+    client::SquadsClient,
+    dialect,
+    error::{SquadsError, SquadsResult},
+    message::TransactionMessage,
+    programs::{BUBBLEGUM_PROGRAM_ID, SPL_ACCOUNT_COMPRESSION_PROGRAM_ID, SPL_NOOP_PROGRAM_ID},
+};
+
+/// Page size used when listing assets
+const PAGE_LIMIT: usize = 1000;
+
+/// A compressed NFT, as reported by the DAS API
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressedAsset {
+    /// Asset ID
+    pub id: Pubkey,
+    /// Merkle tree holding the asset's leaf
+    pub tree: Pubkey,
+    /// Leaf index in the tree, also used as the leaf nonce
+    pub leaf_id: u64,
+    /// Hash of the asset metadata
+    pub data_hash: [u8; 32],
+    /// Hash of the asset creators
+    pub creator_hash: [u8; 32],
+    /// Current owner
+    pub owner: Pubkey,
+    /// Current delegate (the owner when none is set)
+    pub delegate: Pubkey,
+}
+
+/// Merkle proof of a compressed NFT's leaf
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetProof {
+    /// Current root of the tree
+    pub root: [u8; 32],
+    /// Proof nodes, from the leaf up
+    pub proof: Vec<Pubkey>,
+    /// Merkle tree the proof is for
+    pub tree: Pubkey,
+}
+
+/// Derive the Bubblegum tree config PDA of a merkle tree
+pub fn get_tree_config_pda(merkle_tree: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[merkle_tree.as_ref()], &BUBBLEGUM_PROGRAM_ID)
+}
+
+/// Build a Bubblegum `transfer` of a compressed NFT
+///
+/// The tree's canopy caches its top `canopy_depth` proof levels on chain, so those nodes
+/// are left out of the instruction; pass 0 when the tree has no canopy.
+///
+/// # Arguments
+/// * `asset` - Asset to transfer, as fetched from the DAS API
+/// * `proof` - Current proof of the asset's leaf
+/// * `new_owner` - Recipient
+/// * `canopy_depth` - Canopy depth of the asset's tree
+pub fn bubblegum_transfer(
+    asset: &CompressedAsset,
+    proof: &AssetProof,
+    new_owner: &Pubkey,
+    canopy_depth: usize,
+) -> SquadsResult<Instruction> {
+    if proof.tree != asset.tree {
+        return Err(SquadsError::InvalidDasResponse(format!(
+            "proof is for tree {}, asset is in tree {}",
+            proof.tree, asset.tree
+        )));
+    }
+    let index = u32::try_from(asset.leaf_id).map_err(|_| {
+        SquadsError::InvalidDasResponse(format!("leaf id {} out of range", asset.leaf_id))
+    })?;
+    let proof_len = proof.proof.len().saturating_sub(canopy_depth);
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(get_tree_config_pda(&asset.tree).0, false),
+        AccountMeta::new_readonly(asset.owner, true),
+        AccountMeta::new_readonly(asset.delegate, false),
+        AccountMeta::new_readonly(*new_owner, false),
+        AccountMeta::new(asset.tree, false),
+        AccountMeta::new_readonly(SPL_NOOP_PROGRAM_ID, false),
+        AccountMeta::new_readonly(SPL_ACCOUNT_COMPRESSION_PROGRAM_ID, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+    accounts.extend(
+        proof.proof[..proof_len]
+            .iter()
+            .map(|node| AccountMeta::new_readonly(*node, false)),
+    );
+
+    let mut data = dialect::discriminator_from(dialect::DEFAULT_NAMESPACE, "transfer").to_vec();
+    data.extend_from_slice(&proof.root);
+    data.extend_from_slice(&asset.data_hash);
+    data.extend_from_slice(&asset.creator_hash);
+    data.extend_from_slice(&asset.leaf_id.to_le_bytes());
+    data.extend_from_slice(&index.to_le_bytes());
+
+    Ok(Instruction {
+        program_id: BUBBLEGUM_PROGRAM_ID,
+        accounts,
+        data,
+    })
+}
+
+/// List the compressed NFTs owned by `owner`
+///
+/// Pages through `getAssetsByOwner` and skips uncompressed assets.
+pub async fn get_compressed_assets(
+    rpc: &RpcClient,
+    owner: &Pubkey,
+) -> SquadsResult<Vec<CompressedAsset>> {
+    let mut assets = Vec::new();
+    for page in 1.. {
+        let response: AssetPage = rpc
+            .send(
+                RpcRequest::Custom {
+                    method: "getAssetsByOwner",
+                },
+                json!({
+                    "ownerAddress": owner.to_string(),
+                    "page": page,
+                    "limit": PAGE_LIMIT,
+                }),
+            )
+            .await?;
+        let count = response.items.len();
+        assets.extend(response.items.into_iter().filter_map(RawAsset::compressed));
+        if count < PAGE_LIMIT {
+            break;
+        }
+    }
+    Ok(assets)
+}
+
+/// Fetch a compressed NFT by ID
+///
+/// Fails with [`SquadsError::InvalidDasResponse`] if the asset is not compressed.
+pub async fn get_compressed_asset(rpc: &RpcClient, id: &Pubkey) -> SquadsResult<CompressedAsset> {
+    let asset: RawAsset = rpc
+        .send(
+            RpcRequest::Custom { method: "getAsset" },
+            json!({ "id": id.to_string() }),
+        )
+        .await?;
+    asset
+        .compressed()
+        .ok_or_else(|| SquadsError::InvalidDasResponse(format!("asset {} is not compressed", id)))
+}
+
+/// Fetch the current merkle proof of a compressed NFT
+pub async fn get_asset_proof(rpc: &RpcClient, id: &Pubkey) -> SquadsResult<AssetProof> {
+    let proof: RawProof = rpc
+        .send(
+            RpcRequest::Custom {
+                method: "getAssetProof",
+            },
+            json!({ "id": id.to_string() }),
+        )
+        .await?;
+    Ok(AssetProof {
+        root: proof.root,
+        proof: proof.proof,
+        tree: proof.tree_id,
+    })
+}
+
+impl SquadsClient {
+    /// List the compressed NFTs held by a vault
+    ///
+    /// The client's RPC endpoint must serve the DAS API.
+    pub async fn get_vault_compressed_assets(
+        &self,
+        multisig: &Pubkey,
+        vault_index: u8,
+    ) -> SquadsResult<Vec<CompressedAsset>> {
+        let (vault, _) = self.get_vault_pda(multisig, vault_index);
+        get_compressed_assets(&self.rpc, &vault).await
+    }
+
+    /// Propose transferring a compressed NFT out of a vault
+    ///
+    /// Fetches the asset and its proof, then creates a vault transaction with the
+    /// Bubblegum transfer and its proposal. The proof goes stale once the tree has
+    /// changed more times than its change log buffer holds, so execute the proposal
+    /// promptly or propose again.
+    ///
+    /// # Arguments
+    /// * `multisig` - Multisig account
+    /// * `creator` - Proposer (must have Initiate permission)
+    /// * `vault_index` - Vault holding the asset
+    /// * `asset_id` - Asset to transfer
+    /// * `destination` - Recipient
+    /// * `canopy_depth` - Canopy depth of the asset's tree
+    ///
+    /// Returns the signature of the proposal creation and the transaction index.
+    pub async fn propose_compressed_asset_transfer(
+        &self,
+        multisig: &Pubkey,
+        creator: &Keypair,
+        vault_index: u8,
+        asset_id: &Pubkey,
+        destination: &Pubkey,
+        canopy_depth: usize,
+    ) -> SquadsResult<(Signature, u64)> {
+        let (vault, _) = self.get_vault_pda(multisig, vault_index);
+        let asset = get_compressed_asset(&self.rpc, asset_id).await?;
+        if asset.owner != vault {
+            return Err(SquadsError::InvalidDasResponse(format!(
+                "asset {} is owned by {}, not vault {}",
+                asset_id, asset.owner, vault
+            )));
+        }
+        let proof = get_asset_proof(&self.rpc, asset_id).await?;
+        let transfer = bubblegum_transfer(&asset, &proof, destination, canopy_depth)?;
+        let message = TransactionMessage::try_compile(&vault, &[transfer])
+            .map_err(|_| SquadsError::InvalidTransactionMessage)?;
+
+        let (_, transaction_index) = self
+            .create_vault_transaction(multisig, creator, vault_index, &message, None)
+            .await?;
+        let signature = self
+            .create_proposal(multisig, transaction_index, creator, false)
+            .await?;
+        Ok((signature, transaction_index))
+    }
+}
+
+/// Deserialize a 32-byte hash from its base58 string
+fn hash<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 32], D::Error> {
+    let value = String::deserialize(deserializer)?;
+    Pubkey::from_str(value.trim())
+        .map(|key| key.to_bytes())
+        .map_err(|_| serde::de::Error::custom(format!("invalid hash {}", value)))
+}
+
+/// `getAssetsByOwner` result page
+#[derive(Deserialize)]
+struct AssetPage {
+    items: Vec<RawAsset>,
+}
+
+/// DAS asset, reduced to the fields a transfer needs
+#[derive(Deserialize)]
+struct RawAsset {
+    #[serde(deserialize_with = "crate::serde_utils::pubkey")]
+    id: Pubkey,
+    compression: Option<RawCompression>,
+    ownership: RawOwnership,
+}
+
+impl RawAsset {
+    fn compressed(self) -> Option<CompressedAsset> {
+        let compression = self.compression.filter(|c| c.compressed)?;
+        let owner = self.ownership.owner;
+        Some(CompressedAsset {
+            id: self.id,
+            tree: compression.tree?,
+            leaf_id: compression.leaf_id,
+            data_hash: compression.data_hash?,
+            creator_hash: compression.creator_hash?,
+            owner,
+            delegate: self.ownership.delegate.unwrap_or(owner),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct RawCompression {
+    compressed: bool,
+    #[serde(default, deserialize_with = "optional_hash")]
+    data_hash: Option<[u8; 32]>,
+    #[serde(default, deserialize_with = "optional_hash")]
+    creator_hash: Option<[u8; 32]>,
+    #[serde(default, deserialize_with = "optional_pubkey")]
+    tree: Option<Pubkey>,
+    #[serde(default)]
+    leaf_id: u64,
+}
+
+#[derive(Deserialize)]
+struct RawOwnership {
+    #[serde(deserialize_with = "crate::serde_utils::pubkey")]
+    owner: Pubkey,
+    #[serde(default, deserialize_with = "optional_pubkey")]
+    delegate: Option<Pubkey>,
+}
+
+#[derive(Deserialize)]
+struct RawProof {
+    #[serde(deserialize_with = "hash")]
+    root: [u8; 32],
+    #[serde(deserialize_with = "crate::serde_utils::vec_pubkey")]
+    proof: Vec<Pubkey>,
+    #[serde(deserialize_with = "crate::serde_utils::pubkey")]
+    tree_id: Pubkey,
+}
+
+/// Uncompressed assets report empty strings instead of hashes and tree addresses
+fn optional_hash<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<[u8; 32]>, D::Error> {
+    let value = Option::<String>::deserialize(deserializer)?;
+    Ok(value.and_then(|value| {
+        Pubkey::from_str(value.trim())
+            .ok()
+            .map(|key| key.to_bytes())
+    }))
+}
+
+/// Like [`optional_hash`], for addresses
+fn optional_pubkey<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Pubkey>, D::Error> {
+    Ok(optional_hash(deserializer)?.map(Pubkey::new_from_array))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_assets_and_build_transfer() {
+        let vault = Pubkey::new_unique();
+        let tree = Pubkey::new_unique();
+        let nodes: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let data_hash = Pubkey::new_from_array([1; 32]);
+        let creator_hash = Pubkey::new_from_array([2; 32]);
+        let root = Pubkey::new_from_array([3; 32]);
+        let compressed_id = Pubkey::new_unique();
+
+        let page: AssetPage = serde_json::from_value(json!({
+            "total": 2,
+            "limit": 1000,
+            "page": 1,
+            "items": [
+                {
+                    "id": compressed_id.to_string(),
+                    "interface": "V1_NFT",
+                    "compression": {
+                        "eligible": false,
+                        "compressed": true,
+                        "data_hash": data_hash.to_string(),
+                        "creator_hash": creator_hash.to_string(),
+                        "asset_hash": root.to_string(),
+                        "tree": tree.to_string(),
+                        "seq": 12,
+                        "leaf_id": 7
+                    },
+                    "ownership": {
+                        "owner": vault.to_string(),
+                        "delegate": null,
+                        "delegated": false
+                    }
+                },
+                {
+                    "id": Pubkey::new_unique().to_string(),
+                    "interface": "V1_NFT",
+                    "compression": {
+                        "eligible": false,
+                        "compressed": false,
+                        "data_hash": "",
+                        "creator_hash": "",
+                        "asset_hash": "",
+                        "tree": "",
+                        "seq": 0,
+                        "leaf_id": 0
+                    },
+                    "ownership": { "owner": vault.to_string(), "delegate": null }
+                }
+            ]
+        }))
+        .unwrap();
+        let assets: Vec<CompressedAsset> = page
+            .items
+            .into_iter()
+            .filter_map(RawAsset::compressed)
+            .collect();
+        assert_eq!(assets.len(), 1);
+        let asset = &assets[0];
+        assert_eq!(asset.id, compressed_id);
+        assert_eq!(asset.leaf_id, 7);
+        assert_eq!(asset.delegate, vault);
+        assert_eq!(asset.data_hash, [1; 32]);
+
+        let proof: RawProof = serde_json::from_value(json!({
+            "root": root.to_string(),
+            "proof": nodes.iter().map(|node| node.to_string()).collect::<Vec<_>>(),
+            "node_index": 39,
+            "leaf": Pubkey::new_unique().to_string(),
+            "tree_id": tree.to_string()
+        }))
+        .unwrap();
+        let proof = AssetProof {
+            root: proof.root,
+            proof: proof.proof,
+            tree: proof.tree_id,
+        };
+
+        let destination = Pubkey::new_unique();
+        let ix = bubblegum_transfer(asset, &proof, &destination, 2).unwrap();
+        assert_eq!(ix.program_id, BUBBLEGUM_PROGRAM_ID);
+        assert_eq!(ix.accounts.len(), 8 + 3);
+        assert_eq!(ix.accounts[0].pubkey, get_tree_config_pda(&tree).0);
+        assert!(ix.accounts[1].is_signer);
+        assert_eq!(ix.accounts[3].pubkey, destination);
+        assert!(ix.accounts[4].is_writable);
+        assert_eq!(ix.accounts[8].pubkey, nodes[0]);
+        assert_eq!(ix.data.len(), 8 + 32 * 3 + 8 + 4);
+        assert_eq!(&ix.data[8..40], &[3; 32]);
+        assert_eq!(&ix.data[104..112], &7u64.to_le_bytes());
+
+        let other_tree = AssetProof {
+            tree: Pubkey::new_unique(),
+            ..proof
+        };
+        assert!(bubblegum_transfer(asset, &other_tree, &destination, 0).is_err());
+    }
+}
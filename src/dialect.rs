@@ -0,0 +1,185 @@
+//! Instruction discriminator schemes for forked programs
+//!
+//! Builders and the parser derive instruction discriminators from Anchor's
+//! `global:<name>` scheme. Forks that rename instructions or use another namespace can
+//! [`register`] a [`ProgramDialect`] for their program ID at runtime; builders called
+//! with that program ID, and the parser when decoding its instructions, then use the
+//! dialect's discriminators instead.
+//!
+//! ```rust
+//! use squads_v4_client_v3::dialect::{self, ProgramDialect};
+//! use squads_v4_client_v3::instructions::{proposal_approve, ProposalVoteArgs};
+//! use solana_sdk::pubkey::Pubkey;
+//!
+//! let fork = Pubkey::new_unique();
+//! dialect::register(fork, ProgramDialect::new().rename("proposal_approve", "approve"));
+//!
+//! let args = ProposalVoteArgs::new(None).unwrap();
+//! let (multisig, proposal, member) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+//! let ix = proposal_approve(multisig, proposal, member, args, Some(fork));
+//! assert_eq!(&ix.data[..8], &dialect::discriminator_from("global", "approve"));
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+use solana_sdk::pubkey::Pubkey;
+
+/// Namespace Anchor uses for instruction discriminators
+pub const DEFAULT_NAMESPACE: &str = "global";
+
+static DIALECTS: RwLock<BTreeMap<Pubkey, ProgramDialect>> = RwLock::new(BTreeMap::new());
+
+/// Compute the discriminator of `name` in `namespace`
+/// Discriminator is the first 8 bytes of SHA256("namespace:name")
+pub fn discriminator_from(namespace: &str, name: &str) -> [u8; 8] {
+    use solana_sdk::hash::hash;
+    let preimage = format!("{}:{}", namespace, name);
+    let hash_result = hash(preimage.as_bytes());
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash_result.to_bytes()[..8]);
+    discriminator
+}
+
+/// Instruction discriminator scheme of a program
+///
+/// Names are the Squads v4 instruction names in snake_case (e.g. `proposal_approve`).
+/// Explicit discriminators take precedence over renames, which take precedence over
+/// the namespace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramDialect {
+    namespace: String,
+    renames: BTreeMap<String, String>,
+    overrides: BTreeMap<String, [u8; 8]>,
+}
+
+impl Default for ProgramDialect {
+    fn default() -> Self {
+        Self {
+            namespace: DEFAULT_NAMESPACE.to_string(),
+            renames: BTreeMap::new(),
+            overrides: BTreeMap::new(),
+        }
+    }
+}
+
+impl ProgramDialect {
+    /// The Squads v4 scheme, to be customized with the other methods
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Derive discriminators in `namespace` instead of `global`
+    pub fn namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = namespace.into();
+        self
+    }
+
+    /// Derive the discriminator of `name` from the fork's name for it
+    pub fn rename(mut self, name: impl Into<String>, fork_name: impl Into<String>) -> Self {
+        self.renames.insert(name.into(), fork_name.into());
+        self
+    }
+
+    /// Use a fixed discriminator for `name`
+    pub fn discriminator(mut self, name: impl Into<String>, discriminator: [u8; 8]) -> Self {
+        self.overrides.insert(name.into(), discriminator);
+        self
+    }
+
+    /// Discriminator of the instruction `name` under this dialect
+    pub fn instruction_discriminator(&self, name: &str) -> [u8; 8] {
+        if let Some(discriminator) = self.overrides.get(name) {
+            return *discriminator;
+        }
+        let name = self.renames.get(name).map_or(name, String::as_str);
+        discriminator_from(&self.namespace, name)
+    }
+}
+
+/// Register the dialect of `program_id`, replacing any previous one
+pub fn register(program_id: Pubkey, dialect: ProgramDialect) {
+    DIALECTS
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(program_id, dialect);
+}
+
+/// Remove the dialect of `program_id`, returning it if one was registered
+pub fn unregister(program_id: &Pubkey) -> Option<ProgramDialect> {
+    DIALECTS
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .remove(program_id)
+}
+
+/// The dialect registered for `program_id`, if any
+pub fn dialect_for(program_id: &Pubkey) -> Option<ProgramDialect> {
+    DIALECTS
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(program_id)
+        .cloned()
+}
+
+/// Discriminator of the instruction `name` for `program_id`, using its registered
+/// dialect if there is one
+pub fn instruction_discriminator(program_id: &Pubkey, name: &str) -> [u8; 8] {
+    DIALECTS
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(program_id)
+        .map_or_else(
+            || discriminator_from(DEFAULT_NAMESPACE, name),
+            |dialect| dialect.instruction_discriminator(name),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{parse_instruction, SquadsInstruction};
+
+    #[test]
+    fn test_program_dialect() {
+        let fork = Pubkey::new_unique();
+        let fixed = [7u8; 8];
+        register(
+            fork,
+            ProgramDialect::new()
+                .namespace("squads")
+                .rename("proposal_reject", "reject")
+                .discriminator("proposal_cancel", fixed),
+        );
+
+        assert_eq!(
+            instruction_discriminator(&fork, "proposal_approve"),
+            discriminator_from("squads", "proposal_approve")
+        );
+        assert_eq!(
+            instruction_discriminator(&fork, "proposal_reject"),
+            discriminator_from("squads", "reject")
+        );
+        assert_eq!(instruction_discriminator(&fork, "proposal_cancel"), fixed);
+        assert_eq!(
+            instruction_discriminator(&crate::program_id(), "proposal_approve"),
+            crate::instructions::instruction_discriminator("proposal_approve")
+        );
+
+        let ix = crate::instructions::proposal_reject(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            crate::instructions::ProposalVoteArgs::new(None).unwrap(),
+            Some(fork),
+        );
+        assert_eq!(&ix.data[..8], &discriminator_from("squads", "reject"));
+        assert!(matches!(
+            parse_instruction(&ix, Some(&fork)).unwrap(),
+            Some(SquadsInstruction::ProposalReject { .. })
+        ));
+
+        assert!(unregister(&fork).is_some());
+        assert!(dialect_for(&fork).is_none());
+    }
+}
@@ -0,0 +1,725 @@
+//! Differences between multisig snapshots and between transaction messages
+//!
+//! [`Multisig::diff`] compares two states of the same multisig, for example before and
+//! after a config transaction, and lists what changed. The result can be turned back
+//! into the [`ConfigAction`]s that produce the change. [`MultisigDiff::member_changes`]
+//! groups a single removed and added member with the same permissions into a key
+//! rotation, so a member replacing a lost or compromised key is shown as one change
+//! rather than two unrelated ones.
+//!
+//! [`VaultTransactionMessage::diff`] compares the instructions of two vault
+//! transactions, so voters on a replacement proposal can see what it changes.
+
+use std::fmt;
+
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use solana_sdk_ids::system_program;
+
+use crate::accounts::{Multisig, VaultTransactionMessage};
+use crate::programs::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+use crate::types::{ConfigAction, Member, Permissions};
+
+/// An old and a new value of a setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Change<T> {
+    /// Value in the first snapshot
+    pub old: T,
+    /// Value in the second snapshot
+    pub new: T,
+}
+
+impl<T: PartialEq> Change<T> {
+    fn between(old: T, new: T) -> Option<Self> {
+        (old != new).then_some(Self { old, new })
+    }
+}
+
+/// A member whose permissions changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PermissionChange {
+    /// Member key
+    pub key: Pubkey,
+    /// Old and new permissions
+    pub permissions: Change<Permissions>,
+}
+
+/// A member key replaced by another, found by [`MultisigDiff::member_changes`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemberRotation {
+    /// Member in the first snapshot
+    pub old: Member,
+    /// Member in the second snapshot
+    pub new: Member,
+}
+
+impl MemberRotation {
+    /// Old and new permissions, if the rotation changed them
+    pub fn permission_change(&self) -> Option<Change<Permissions>> {
+        Change::between(self.old.permissions, self.new.permissions)
+    }
+}
+
+/// One change to the member list, with key rotations grouped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MemberChange {
+    /// A member was added
+    Added(Member),
+    /// A member was removed
+    Removed(Member),
+    /// A member kept its key and got different permissions
+    PermissionsChanged(PermissionChange),
+    /// A member moved to a new key
+    Rotated(MemberRotation),
+}
+
+impl fmt::Display for MemberChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MemberChange::Added(member) => write!(
+                f,
+                "add {} ({})",
+                member.key,
+                PermissionNames(member.permissions)
+            ),
+            MemberChange::Removed(member) => write!(f, "remove {}", member.key),
+            MemberChange::PermissionsChanged(change) => write!(
+                f,
+                "change permissions of {}: {} -> {}",
+                change.key,
+                PermissionNames(change.permissions.old),
+                PermissionNames(change.permissions.new)
+            ),
+            MemberChange::Rotated(rotation) => {
+                write!(f, "rotate {} -> {}", rotation.old.key, rotation.new.key)?;
+                match rotation.permission_change() {
+                    Some(change) => write!(
+                        f,
+                        ", permissions {} -> {}",
+                        PermissionNames(change.old),
+                        PermissionNames(change.new)
+                    ),
+                    None => write!(f, " ({})", PermissionNames(rotation.new.permissions)),
+                }
+            }
+        }
+    }
+}
+
+/// Permissions formatted as a list of names
+struct PermissionNames(Permissions);
+
+impl fmt::Display for PermissionNames {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = [
+            (self.0.has_initiate(), "initiate"),
+            (self.0.has_vote(), "vote"),
+            (self.0.has_execute(), "execute"),
+        ]
+        .into_iter()
+        .filter_map(|(granted, name)| granted.then_some(name))
+        .collect();
+        if names.is_empty() {
+            write!(f, "no permissions")
+        } else {
+            write!(f, "{}", names.join(", "))
+        }
+    }
+}
+
+/// Differences between two snapshots of a multisig
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MultisigDiff {
+    /// Members only in the second snapshot
+    pub added: Vec<Member>,
+    /// Members only in the first snapshot
+    pub removed: Vec<Member>,
+    /// Members in both snapshots with different permissions
+    pub permission_changes: Vec<PermissionChange>,
+    /// Threshold change
+    pub threshold: Option<Change<u16>>,
+    /// Time lock change
+    pub time_lock: Option<Change<u32>>,
+    /// Config authority change
+    pub config_authority: Option<Change<Pubkey>>,
+    /// Rent collector change
+    pub rent_collector: Option<Change<Option<Pubkey>>>,
+}
+
+impl MultisigDiff {
+    /// Whether the snapshots have the same configuration
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Member changes, with a single member replacing its key reported as a rotation
+    ///
+    /// A rotation is only inferred when exactly one member was removed and one added,
+    /// with identical permissions. Anything else could be unrelated members joining and
+    /// leaving, so it is reported as additions and removals; use
+    /// [`member_changes_with_rotations`](Self::member_changes_with_rotations) when the
+    /// pairing is known.
+    pub fn member_changes(&self) -> Vec<MemberChange> {
+        match (self.removed.as_slice(), self.added.as_slice()) {
+            ([old], [new]) if old.permissions == new.permissions => {
+                self.member_changes_with_rotations(&[(old.key, new.key)])
+            }
+            _ => self.member_changes_with_rotations(&[]),
+        }
+    }
+
+    /// Member changes, with the given `(old key, new key)` pairs reported as rotations
+    ///
+    /// Pairs whose old key was not removed or whose new key was not added are ignored.
+    /// Other members are reported as added or removed, and permission changes of kept
+    /// keys follow.
+    pub fn member_changes_with_rotations(
+        &self,
+        rotations: &[(Pubkey, Pubkey)],
+    ) -> Vec<MemberChange> {
+        let mut changes = Vec::new();
+        let mut added: Vec<&Member> = self.added.iter().collect();
+        let mut removed: Vec<&Member> = self.removed.iter().collect();
+
+        for (old_key, new_key) in rotations {
+            let old = removed.iter().position(|member| member.key == *old_key);
+            let new = added.iter().position(|member| member.key == *new_key);
+            if let (Some(old), Some(new)) = (old, new) {
+                changes.push(MemberChange::Rotated(MemberRotation {
+                    old: removed.remove(old).clone(),
+                    new: added.remove(new).clone(),
+                }));
+            }
+        }
+
+        changes.extend(added.into_iter().cloned().map(MemberChange::Added));
+        changes.extend(removed.into_iter().cloned().map(MemberChange::Removed));
+        changes.extend(
+            self.permission_changes
+                .iter()
+                .copied()
+                .map(MemberChange::PermissionsChanged),
+        );
+        changes
+    }
+
+    /// Config actions that turn the first snapshot into the second
+    ///
+    /// Permission changes become a removal followed by an addition, since the program
+    /// has no action that edits a member in place. Thresholds are lowered before and
+    /// raised after member changes so each action leaves a valid configuration where
+    /// possible.
+    ///
+    /// A config authority change is left out: the program's `ConfigAction` has no such
+    /// variant, and only the config authority of a controlled multisig can change it,
+    /// with the program's `multisig_set_config_authority` instruction.
+    pub fn config_actions(&self) -> Vec<ConfigAction> {
+        let mut actions = Vec::new();
+        let lowered = self.threshold.filter(|change| change.new < change.old);
+        let raised = self.threshold.filter(|change| change.new > change.old);
+
+        if let Some(change) = lowered {
+            actions.push(ConfigAction::ChangeThreshold {
+                new_threshold: change.new,
+            });
+        }
+        actions.extend(self.added.iter().map(|member| ConfigAction::AddMember {
+            new_member: member.clone(),
+        }));
+        for change in &self.permission_changes {
+            actions.push(ConfigAction::RemoveMember {
+                old_member: change.key,
+            });
+            actions.push(ConfigAction::AddMember {
+                new_member: Member::with_permissions(change.key, change.permissions.new),
+            });
+        }
+        actions.extend(
+            self.removed
+                .iter()
+                .map(|member| ConfigAction::RemoveMember {
+                    old_member: member.key,
+                }),
+        );
+        if let Some(change) = raised {
+            actions.push(ConfigAction::ChangeThreshold {
+                new_threshold: change.new,
+            });
+        }
+        if let Some(change) = self.time_lock {
+            actions.push(ConfigAction::SetTimeLock {
+                new_time_lock: change.new,
+            });
+        }
+        if let Some(change) = self.rent_collector {
+            actions.push(ConfigAction::SetRentCollector {
+                new_rent_collector: change.new,
+            });
+        }
+        actions
+    }
+}
+
+impl Multisig {
+    /// Compare this snapshot with a later one
+    ///
+    /// Members are matched by key and reported in the order they appear in their
+    /// snapshot. Index counters and the bump are not compared.
+    pub fn diff(&self, other: &Multisig) -> MultisigDiff {
+        let find = |multisig: &Multisig, key: &Pubkey| {
+            multisig.members.iter().find(|m| &m.key == key).cloned()
+        };
+
+        MultisigDiff {
+            added: other
+                .members
+                .iter()
+                .filter(|m| !self.is_member(&m.key))
+                .cloned()
+                .collect(),
+            removed: self
+                .members
+                .iter()
+                .filter(|m| !other.is_member(&m.key))
+                .cloned()
+                .collect(),
+            permission_changes: self
+                .members
+                .iter()
+                .filter_map(|old| {
+                    let new = find(other, &old.key)?;
+                    Some(PermissionChange {
+                        key: old.key,
+                        permissions: Change::between(old.permissions, new.permissions)?,
+                    })
+                })
+                .collect(),
+            threshold: Change::between(self.threshold, other.threshold),
+            time_lock: Change::between(self.time_lock, other.time_lock),
+            config_authority: Change::between(self.config_authority, other.config_authority),
+            rent_collector: Change::between(self.rent_collector, other.rent_collector),
+        }
+    }
+}
+
+/// A SOL or token transfer decoded from an instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transfer {
+    /// System, Token or Token-2022 program
+    pub program_id: Pubkey,
+    /// Account debited
+    pub source: Pubkey,
+    /// Account credited
+    pub destination: Pubkey,
+    /// Token mint, known only for `TransferChecked`
+    pub mint: Option<Pubkey>,
+    /// Lamports or token base units
+    pub amount: u64,
+}
+
+impl Transfer {
+    /// Decode a System `Transfer` or a Token `Transfer` / `TransferChecked` instruction
+    pub fn decode(instruction: &Instruction) -> Option<Self> {
+        let key = |index: usize| instruction.accounts.get(index).map(|meta| meta.pubkey);
+        let u64_at = |offset: usize| {
+            let bytes = instruction.data.get(offset..offset + 8)?;
+            Some(u64::from_le_bytes(bytes.try_into().ok()?))
+        };
+        let program_id = instruction.program_id;
+
+        if program_id == system_program::ID {
+            if instruction.data.get(..4)? != 2u32.to_le_bytes() {
+                return None;
+            }
+            return Some(Self {
+                program_id,
+                source: key(0)?,
+                destination: key(1)?,
+                mint: None,
+                amount: u64_at(4)?,
+            });
+        }
+        if program_id != TOKEN_PROGRAM_ID && program_id != TOKEN_2022_PROGRAM_ID {
+            return None;
+        }
+        match instruction.data.first()? {
+            3 => Some(Self {
+                program_id,
+                source: key(0)?,
+                destination: key(1)?,
+                mint: None,
+                amount: u64_at(1)?,
+            }),
+            12 => Some(Self {
+                program_id,
+                source: key(0)?,
+                destination: key(2)?,
+                mint: Some(key(1)?),
+                amount: u64_at(1)?,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// An instruction present in both messages with different accounts or data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModifiedInstruction {
+    /// Position in the first message
+    pub old_index: usize,
+    /// Position in the second message
+    pub new_index: usize,
+    /// Program invoked by both instructions
+    pub program_id: Pubkey,
+    /// Account positions whose key changed; None where one instruction has fewer accounts
+    pub accounts: Vec<(usize, Change<Option<Pubkey>>)>,
+    /// Whether the instruction data differs
+    pub data_changed: bool,
+    /// Transfer amount change, for decodable transfers
+    pub amount: Option<Change<u64>>,
+    /// Transfer destination change, for decodable transfers
+    pub destination: Option<Change<Pubkey>>,
+}
+
+/// One instruction-level difference between two messages
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstructionChange {
+    /// Instruction only in the second message
+    Added {
+        /// Position in the second message
+        index: usize,
+        /// The added instruction
+        instruction: Instruction,
+    },
+    /// Instruction only in the first message
+    Removed {
+        /// Position in the first message
+        index: usize,
+        /// The removed instruction
+        instruction: Instruction,
+    },
+    /// Instruction changed in place
+    Modified(ModifiedInstruction),
+}
+
+/// Instruction-level differences between two transaction messages
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MessageDiff {
+    /// Changes in message order
+    pub changes: Vec<InstructionChange>,
+}
+
+impl MessageDiff {
+    /// Whether the messages have the same instructions
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Compare two instruction lists
+    ///
+    /// Identical instructions are matched first, preserving order, so inserting an
+    /// instruction does not mark everything after it as changed. Between matches,
+    /// instructions of the same program at the same relative position are reported as
+    /// modified and the rest as removed or added.
+    pub fn between(old: &[Instruction], new: &[Instruction]) -> Self {
+        // Longest common subsequence of identical instructions
+        let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+        for i in (0..old.len()).rev() {
+            for j in (0..new.len()).rev() {
+                lengths[i][j] = if old[i] == new[j] {
+                    lengths[i + 1][j + 1] + 1
+                } else {
+                    lengths[i + 1][j].max(lengths[i][j + 1])
+                };
+            }
+        }
+
+        let mut diff = Self::default();
+        let (mut i, mut j) = (0, 0);
+        let (mut gap_old, mut gap_new) = (0, 0);
+        while i < old.len() || j < new.len() {
+            if i < old.len() && j < new.len() && old[i] == new[j] {
+                diff.push_gap(old, new, gap_old..i, gap_new..j);
+                i += 1;
+                j += 1;
+                gap_old = i;
+                gap_new = j;
+            } else if i < old.len() && (j == new.len() || lengths[i + 1][j] >= lengths[i][j + 1]) {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        diff.push_gap(old, new, gap_old..old.len(), gap_new..new.len());
+        diff
+    }
+
+    /// Record the unmatched instructions between two matched ones
+    fn push_gap(
+        &mut self,
+        old: &[Instruction],
+        new: &[Instruction],
+        old_range: std::ops::Range<usize>,
+        new_range: std::ops::Range<usize>,
+    ) {
+        let mut new_indexes = new_range.clone();
+        for old_index in old_range {
+            let paired = new_indexes
+                .clone()
+                .next()
+                .filter(|j| new[*j].program_id == old[old_index].program_id);
+            match paired {
+                Some(new_index) => {
+                    new_indexes.next();
+                    self.changes.push(InstructionChange::Modified(modified(
+                        old_index,
+                        &old[old_index],
+                        new_index,
+                        &new[new_index],
+                    )));
+                }
+                None => self.changes.push(InstructionChange::Removed {
+                    index: old_index,
+                    instruction: old[old_index].clone(),
+                }),
+            }
+        }
+        for index in new_indexes {
+            self.changes.push(InstructionChange::Added {
+                index,
+                instruction: new[index].clone(),
+            });
+        }
+    }
+}
+
+fn modified(
+    old_index: usize,
+    old: &Instruction,
+    new_index: usize,
+    new: &Instruction,
+) -> ModifiedInstruction {
+    let key = |ix: &Instruction, position: usize| ix.accounts.get(position).map(|m| m.pubkey);
+    let accounts = (0..old.accounts.len().max(new.accounts.len()))
+        .filter_map(|position| {
+            let change = Change::between(key(old, position), key(new, position))?;
+            Some((position, change))
+        })
+        .collect();
+    let (old_transfer, new_transfer) = (Transfer::decode(old), Transfer::decode(new));
+    let transfers = old_transfer.zip(new_transfer);
+
+    ModifiedInstruction {
+        old_index,
+        new_index,
+        program_id: old.program_id,
+        accounts,
+        data_changed: old.data != new.data,
+        amount: transfers.and_then(|(o, n)| Change::between(o.amount, n.amount)),
+        destination: transfers.and_then(|(o, n)| Change::between(o.destination, n.destination)),
+    }
+}
+
+impl VaultTransactionMessage {
+    /// Compare the instructions of this message with those of a later one
+    ///
+    /// Returns `None` if either message uses address table lookups, see
+    /// [`VaultTransactionMessage::to_instructions`].
+    pub fn diff(&self, other: &VaultTransactionMessage) -> Option<MessageDiff> {
+        Some(MessageDiff::between(
+            &self.to_instructions()?,
+            &other.to_instructions()?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Permission;
+
+    #[test]
+    fn test_multisig_diff() {
+        let keys: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let before = Multisig {
+            create_key: Pubkey::new_unique(),
+            config_authority: Pubkey::default(),
+            threshold: 2,
+            time_lock: 0,
+            transaction_index: 4,
+            stale_transaction_index: 0,
+            rent_collector: None,
+            bump: 255,
+            members: keys[..3].iter().map(|key| Member::new(*key)).collect(),
+            unknown_tail: vec![],
+        };
+        assert!(before.diff(&before).is_empty());
+
+        let mut after = before.clone();
+        after.members.retain(|m| m.key != keys[0]);
+        after.members[0].permissions = Permissions::from_vec(&[Permission::Vote]);
+        after.members.push(Member::new(keys[3]));
+        after.threshold = 3;
+        after.time_lock = 60;
+
+        let diff = before.diff(&after);
+        assert_eq!(diff.added, vec![Member::new(keys[3])]);
+        assert_eq!(diff.removed, vec![Member::new(keys[0])]);
+        assert_eq!(diff.permission_changes.len(), 1);
+        assert_eq!(diff.permission_changes[0].key, keys[1]);
+        assert_eq!(diff.threshold, Some(Change { old: 2, new: 3 }));
+        assert_eq!(diff.time_lock, Some(Change { old: 0, new: 60 }));
+        assert_eq!(diff.rent_collector, None);
+
+        let mut replayed = before.clone();
+        for action in diff.config_actions() {
+            replayed.apply_config_action(&action);
+        }
+        assert!(replayed.diff(&after).is_empty());
+
+        // A config authority change is not a config action, and in particular never
+        // encodes as tag 6, the program's SetRentCollector
+        let mut controlled = after.clone();
+        controlled.config_authority = Pubkey::new_unique();
+        let diff = after.diff(&controlled);
+        assert!(diff.config_authority.is_some());
+        assert!(diff.config_actions().is_empty());
+        for action in before.diff(&controlled).config_actions() {
+            assert_ne!(borsh::to_vec(&action).unwrap()[0], 6);
+        }
+    }
+
+    #[test]
+    fn test_member_rotations() {
+        let keys: Vec<Pubkey> = (0..6).map(|_| Pubkey::new_unique()).collect();
+        let voter = Permissions::from_vec(&[Permission::Vote]);
+        let member = |key: Pubkey, permissions| Member::with_permissions(key, permissions);
+
+        // A single removal and addition with the same permissions is a rotation
+        let diff = MultisigDiff {
+            removed: vec![member(keys[0], voter)],
+            added: vec![member(keys[1], voter)],
+            permission_changes: vec![PermissionChange {
+                key: keys[2],
+                permissions: Change {
+                    old: voter,
+                    new: Permissions::none(),
+                },
+            }],
+            ..MultisigDiff::default()
+        };
+        let changes = diff.member_changes();
+        assert_eq!(
+            changes[0],
+            MemberChange::Rotated(MemberRotation {
+                old: member(keys[0], voter),
+                new: member(keys[1], voter),
+            })
+        );
+        assert_eq!(
+            changes[0].to_string(),
+            format!("rotate {} -> {} (vote)", keys[0], keys[1])
+        );
+        assert_eq!(
+            changes[1].to_string(),
+            format!("change permissions of {}: vote -> no permissions", keys[2])
+        );
+
+        // Different permissions, or several members coming and going, could be
+        // unrelated members, so nothing is paired
+        let mismatched = MultisigDiff {
+            removed: vec![Member::new(keys[0])],
+            added: vec![member(keys[1], voter)],
+            ..MultisigDiff::default()
+        };
+        let several = MultisigDiff {
+            removed: vec![Member::new(keys[0]), member(keys[1], voter)],
+            added: vec![member(keys[2], voter), Member::new(keys[3])],
+            ..MultisigDiff::default()
+        };
+        for diff in [&mismatched, &several] {
+            assert!(diff
+                .member_changes()
+                .iter()
+                .all(|change| !matches!(change, MemberChange::Rotated(_))));
+        }
+        assert_eq!(several.member_changes().len(), 4);
+
+        // Rotations the caller knows about are reported even if permissions change
+        let changes = mismatched.member_changes_with_rotations(&[(keys[0], keys[1])]);
+        let [MemberChange::Rotated(rotation)] = changes.as_slice() else {
+            panic!("expected a rotation, got {changes:?}");
+        };
+        assert_eq!(
+            rotation.permission_change(),
+            Some(Change {
+                old: Permissions::full(),
+                new: voter
+            })
+        );
+        assert_eq!(
+            changes[0].to_string(),
+            format!(
+                "rotate {} -> {}, permissions initiate, vote, execute -> vote",
+                keys[0], keys[1]
+            )
+        );
+        let changes =
+            several.member_changes_with_rotations(&[(keys[1], keys[2]), (keys[4], keys[5])]);
+        assert_eq!(changes.len(), 3);
+        assert_eq!(
+            changes[0],
+            MemberChange::Rotated(MemberRotation {
+                old: member(keys[1], voter),
+                new: member(keys[2], voter),
+            })
+        );
+    }
+
+    #[test]
+    fn test_message_diff() {
+        use solana_system_interface::instruction::transfer;
+
+        let vault = Pubkey::new_unique();
+        let alice = Pubkey::new_unique();
+        let bob = Pubkey::new_unique();
+        let memo = Instruction::new_with_bytes(crate::programs::MEMO_PROGRAM_ID, b"hi", vec![]);
+
+        let old = vec![transfer(&vault, &alice, 100), memo.clone()];
+        assert!(MessageDiff::between(&old, &old).is_empty());
+
+        let new = vec![memo.clone(), transfer(&vault, &bob, 250)];
+        let diff = MessageDiff::between(&old, &new);
+        assert_eq!(diff.changes.len(), 2);
+        assert!(matches!(
+            diff.changes[0],
+            InstructionChange::Removed { index: 0, .. }
+        ));
+        assert!(matches!(
+            diff.changes[1],
+            InstructionChange::Added { index: 1, .. }
+        ));
+
+        // Same position and program: reported as modified with the transfer changes
+        let new = vec![transfer(&vault, &bob, 250), memo.clone(), memo];
+        let diff = MessageDiff::between(&old, &new);
+        let InstructionChange::Modified(change) = &diff.changes[0] else {
+            panic!("expected a modified instruction, got {:?}", diff.changes[0]);
+        };
+        assert_eq!(change.amount, Some(Change { old: 100, new: 250 }));
+        assert_eq!(
+            change.destination,
+            Some(Change {
+                old: alice,
+                new: bob
+            })
+        );
+        assert_eq!(
+            change.accounts,
+            vec![(1, Change::between(Some(alice), Some(bob)).unwrap())]
+        );
+        assert!(matches!(
+            diff.changes[1],
+            InstructionChange::Added { index: 2, .. }
+        ));
+    }
+}
@@ -1,5 +1,6 @@
 //! Error types for the Squads v4 client library
 
+use solana_program::pubkey::Pubkey;
 use thiserror::Error;
 
 /// Result type for Squads operations
@@ -9,6 +10,7 @@ pub type SquadsResult<T> = Result<T, SquadsError>;
 #[derive(Debug, Error)]
 pub enum SquadsError {
     /// Error from the Solana client
+    #[cfg(feature = "client")]
     #[error("Solana client error: {0}")]
     ClientError(#[from] solana_client::client_error::ClientError),
 
@@ -55,10 +57,173 @@ pub enum SquadsError {
     /// No voting members
     #[error("At least one member must have voting permissions")]
     NoVotingMembers,
+
+    /// A template placeholder had no value supplied
+    #[error("Missing template parameter: {0}")]
+    MissingTemplateParam(String),
+
+    /// A deep link could not be parsed or encoded
+    #[error("Invalid link: {0}")]
+    InvalidLink(String),
+
+    /// Faucet airdrop could not be completed
+    #[error("Airdrop failed: {0}")]
+    AirdropFailed(String),
+
+    /// The multisig has no rent collector, so accounts cannot be closed
+    #[error("Rent reclamation is disabled for this multisig")]
+    RentReclamationDisabled,
+
+    /// The requested rent destination does not match the multisig's rent collector
+    #[error("Rent collector mismatch: multisig uses {expected}, got {actual}")]
+    RentCollectorMismatch {
+        /// Rent collector configured on the multisig
+        expected: Pubkey,
+        /// Rent destination supplied by the caller
+        actual: Pubkey,
+    },
+
+    /// A proposal status transition is not allowed
+    #[error("Invalid proposal transition: {0}")]
+    InvalidProposalTransition(String),
+
+    /// A memo exceeds the maximum length
+    #[error("Memo too long: {len} bytes (max {max})")]
+    MemoTooLong {
+        /// Length of the memo in bytes
+        len: usize,
+        /// Maximum allowed length in bytes
+        max: usize,
+    },
+
+    /// Example configuration is missing or invalid
+    #[error("Invalid example config: {0}")]
+    InvalidConfig(String),
+
+    /// A transaction exceeds the maximum serialized size
+    #[error("Transaction too large: {size} bytes (max {max})")]
+    TransactionTooLarge {
+        /// Estimated size in bytes
+        size: usize,
+        /// Maximum allowed size in bytes
+        max: usize,
+    },
+
+    /// A transaction could not be signed
+    #[error("Signing error: {0}")]
+    SigningError(String),
+
+    /// A value could not be encoded or decoded with the selected format
+    #[error("Encoding error: {0}")]
+    EncodingError(String),
+
+    /// An account does not hold enough lamports for an operation
+    #[error("Insufficient funds: {required} lamports required, {available} available")]
+    InsufficientFunds {
+        /// Lamports required
+        required: u64,
+        /// Lamports available
+        available: u64,
+    },
+
+    /// A key-loss recovery cannot be carried out with the reachable members
+    #[error("Recovery infeasible: {0}")]
+    RecoveryInfeasible(String),
+
+    /// State derived by replaying transactions differs from the on-chain accounts
+    #[error("Replayed state does not match on-chain state: {0}")]
+    StateMismatch(String),
+
+    /// Account data starts with the discriminator of a different account type
+    #[error(transparent)]
+    DiscriminatorMismatch(#[from] DiscriminatorMismatch),
+
+    /// Account data has bytes after its known fields that strict parsing does not accept
+    #[error("Unexpected trailing bytes: {count} bytes after known fields: {bytes:02x?}")]
+    UnexpectedTrailingBytes {
+        /// Number of unexpected bytes
+        count: usize,
+        /// The unexpected bytes
+        bytes: Vec<u8>,
+    },
+
+    /// An account is not owned by the expected program
+    #[error("Wrong account owner: expected {expected}, found {found}")]
+    WrongOwner {
+        /// Program the account should be owned by
+        expected: Pubkey,
+        /// Actual owner of the account
+        found: Pubkey,
+    },
+
+    /// The multisig changed since the snapshot an operation was built from
+    #[error("Stale snapshot: expected transaction index {expected_transaction_index} (stale {expected_stale_transaction_index}), found {found_transaction_index} (stale {found_stale_transaction_index})")]
+    StaleSnapshot {
+        /// Transaction index in the snapshot
+        expected_transaction_index: u64,
+        /// Stale transaction index in the snapshot
+        expected_stale_transaction_index: u64,
+        /// Current transaction index
+        found_transaction_index: u64,
+        /// Current stale transaction index
+        found_stale_transaction_index: u64,
+    },
+
+    /// A transaction signature has no status on the cluster
+    #[error("Transaction not found: {0}")]
+    TransactionNotFound(String),
+
+    /// An instruction of a vault transaction failed during execution
+    #[cfg(feature = "client")]
+    #[error("Vault transaction instruction {} ({}) failed: {}", failure.index, failure.program_id, failure.message)]
+    InstructionFailed {
+        /// The failing instruction
+        failure: crate::failure::InstructionFailure,
+        /// Log messages of the failed simulation
+        logs: Vec<String>,
+    },
+
+    /// A websocket subscription failed or closed
+    #[error("Subscription error: {0}")]
+    SubscriptionError(String),
+
+    /// A DAS API response could not be decoded
+    #[error("Invalid DAS response: {0}")]
+    InvalidDasResponse(String),
 }
 
 impl From<std::io::Error> for SquadsError {
     fn from(err: std::io::Error) -> Self {
-        SquadsError::SerializationError(err)
+        match err
+            .get_ref()
+            .and_then(|inner| inner.downcast_ref::<DiscriminatorMismatch>())
+        {
+            Some(mismatch) => SquadsError::DiscriminatorMismatch(mismatch.clone()),
+            None => SquadsError::SerializationError(err),
+        }
+    }
+}
+
+impl SquadsError {
+    /// Map an account decoding error to [`SquadsError::DiscriminatorMismatch`] when the
+    /// data belongs to another account type, and to
+    /// [`SquadsError::DeserializationError`] otherwise
+    pub fn from_decode_error(err: std::io::Error) -> Self {
+        match SquadsError::from(err) {
+            mismatch @ SquadsError::DiscriminatorMismatch(_) => mismatch,
+            _ => SquadsError::DeserializationError,
+        }
     }
-}
\ No newline at end of file
+}
+
+/// Account data does not start with the discriminator of the type it is parsed as
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Not a {account} account: expected discriminator {expected:02x?}, found {found:02x?}")]
+pub struct DiscriminatorMismatch {
+    /// Account type the data was parsed as
+    pub account: &'static str,
+    /// Discriminator of that type
+    pub expected: [u8; 8],
+    /// First 8 bytes of the data
+    pub found: [u8; 8],
+}
@@ -0,0 +1,208 @@
+//! Decoding of program logs into structured events
+//!
+//! Anchor programs log `Instruction: <Name>` when an instruction starts, and events
+//! emitted with `emit!` appear as `Program data: <base64>` lines whose payload starts
+//! with the event discriminator. [`decode_logs`] follows the invocation stack in the
+//! logs so each entry is attributed to the program that produced it.
+//!
+//! The Squads v4 program logs instruction names but does not emit Anchor events of
+//! its own. Events from forks or CPI'd programs can be decoded into typed structs by
+//! implementing [`AnchorEvent`].
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use borsh::BorshDeserialize;
+use solana_sdk::pubkey::Pubkey;
+
+/// Compute the Anchor event discriminator
+/// Discriminator is the first 8 bytes of SHA256("event:EventName")
+pub fn event_discriminator(name: &str) -> [u8; 8] {
+    use solana_sdk::hash::hash;
+    let preimage = format!("event:{}", name);
+    let hash_result = hash(preimage.as_bytes());
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash_result.to_bytes()[..8]);
+    discriminator
+}
+
+/// An Anchor event with a Borsh-encoded payload
+pub trait AnchorEvent: BorshDeserialize {
+    /// Event struct name as declared in the program
+    const NAME: &'static str;
+
+    /// Discriminator prefixed to the event payload
+    fn discriminator() -> [u8; 8] {
+        event_discriminator(Self::NAME)
+    }
+}
+
+/// A structured entry decoded from program logs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogEvent {
+    /// An Anchor instruction started (`Program log: Instruction: <Name>`)
+    Instruction {
+        /// Program that logged the line
+        program_id: Pubkey,
+        /// Invocation depth, 1 for top-level instructions
+        depth: usize,
+        /// Instruction name in PascalCase, e.g. `ProposalApprove`
+        name: String,
+    },
+    /// An event emitted with `emit!` (`Program data: <base64>`)
+    Event {
+        /// Program that emitted the event
+        program_id: Pubkey,
+        /// Invocation depth, 1 for top-level instructions
+        depth: usize,
+        /// First 8 bytes of the payload
+        discriminator: [u8; 8],
+        /// Payload after the discriminator
+        data: Vec<u8>,
+    },
+}
+
+impl LogEvent {
+    /// Program the entry is attributed to
+    pub fn program_id(&self) -> &Pubkey {
+        match self {
+            LogEvent::Instruction { program_id, .. } | LogEvent::Event { program_id, .. } => {
+                program_id
+            }
+        }
+    }
+
+    /// Decode the entry as event `E`, if it is one
+    pub fn decode<E: AnchorEvent>(&self) -> Option<E> {
+        match self {
+            LogEvent::Event {
+                discriminator,
+                data,
+                ..
+            } if *discriminator == E::discriminator() => E::try_from_slice(data).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Decode the instruction and event entries of a transaction's log messages
+///
+/// Lines that are not attributable to a program, and data lines that are not valid
+/// base64 or are shorter than a discriminator, are skipped. Decoding stops at a
+/// `Log truncated` line.
+pub fn decode_logs(logs: &[String]) -> Vec<LogEvent> {
+    let mut stack: Vec<Pubkey> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs {
+        if line == "Log truncated" {
+            break;
+        }
+        if let Some(rest) = line.strip_prefix("Program log: Instruction: ") {
+            if let Some(program_id) = stack.last() {
+                events.push(LogEvent::Instruction {
+                    program_id: *program_id,
+                    depth: stack.len(),
+                    name: rest.trim().to_string(),
+                });
+            }
+        } else if let Some(rest) = line.strip_prefix("Program data: ") {
+            let Some(program_id) = stack.last() else {
+                continue;
+            };
+            let Ok(payload) = STANDARD.decode(rest.trim()) else {
+                continue;
+            };
+            if payload.len() < 8 {
+                continue;
+            }
+            let mut discriminator = [0u8; 8];
+            discriminator.copy_from_slice(&payload[..8]);
+            events.push(LogEvent::Event {
+                program_id: *program_id,
+                depth: stack.len(),
+                discriminator,
+                data: payload[8..].to_vec(),
+            });
+        } else if let Some(rest) = line.strip_prefix("Program ") {
+            let mut words = rest.split_whitespace();
+            let (Some(program), Some(action)) = (words.next(), words.next()) else {
+                continue;
+            };
+            let Ok(program_id) = program.parse::<Pubkey>() else {
+                continue;
+            };
+            match action {
+                "invoke" => stack.push(program_id),
+                "success" | "failed:" => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+    events
+}
+
+/// Decode the log entries attributed to the Squads program
+///
+/// # Arguments
+/// * `logs` - Log messages of a transaction
+/// * `program_id` - Optional custom program ID (uses canonical ID if None)
+pub fn decode_program_logs(logs: &[String], program_id: Option<&Pubkey>) -> Vec<LogEvent> {
+    let program_id = program_id.copied().unwrap_or_else(crate::program_id);
+    decode_logs(logs)
+        .into_iter()
+        .filter(|event| *event.program_id() == program_id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, BorshDeserialize, borsh::BorshSerialize)]
+    struct Approved {
+        index: u64,
+    }
+
+    impl AnchorEvent for Approved {
+        const NAME: &'static str = "Approved";
+    }
+
+    #[test]
+    fn test_decode_logs() {
+        let squads = crate::program_id();
+        let other = Pubkey::new_unique();
+        let mut payload = Approved::discriminator().to_vec();
+        payload.extend(borsh::to_vec(&Approved { index: 7 }).unwrap());
+
+        let logs = vec![
+            format!("Program {} invoke [1]", squads),
+            "Program log: Instruction: ProposalApprove".to_string(),
+            format!("Program {} invoke [2]", other),
+            "Program log: Instruction: Transfer".to_string(),
+            format!("Program {} success", other),
+            format!("Program data: {}", STANDARD.encode(&payload)),
+            format!("Program {} consumed 5000 of 200000 compute units", squads),
+            format!("Program {} success", squads),
+        ];
+
+        let events = decode_logs(&logs);
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events[1],
+            LogEvent::Instruction {
+                program_id: other,
+                depth: 2,
+                name: "Transfer".to_string(),
+            }
+        );
+
+        let squads_events = decode_program_logs(&logs, None);
+        assert_eq!(squads_events.len(), 2);
+        assert_eq!(
+            squads_events[1].decode::<Approved>(),
+            Some(Approved { index: 7 })
+        );
+        assert_eq!(squads_events[0].decode::<Approved>(), None);
+    }
+}
@@ -0,0 +1,273 @@
+//! Shared configuration and helpers for the examples
+//!
+//! Examples read their RPC URL, keypair paths and account addresses from the environment
+//! or from a `KEY=VALUE` config file instead of hardcoding them. The file is read from
+//! `SQUADS_EXAMPLES_CONFIG`, or `squads-examples.env` in the working directory if it exists;
+//! environment variables take precedence over the file.
+//!
+//! | Variable            | Meaning                                        | Default                       |
+//! |---------------------|------------------------------------------------|-------------------------------|
+//! | `SQUADS_RPC_URL`    | RPC endpoint                                   | devnet                        |
+//! | `SQUADS_PROGRAM_ID` | Squads program ID                              | canonical program ID          |
+//! | `SQUADS_KEYPAIRS`   | Comma-separated keypair paths, payer first     | `~/.config/solana/id.json`    |
+//! | `SQUADS_MULTISIG`   | Existing multisig address                      | none                          |
+//! | `SQUADS_PROPOSAL`   | Existing proposal address                      | none                          |
+//! | `SQUADS_WALLET`     | Wallet address to inspect                      | none                          |
+//! | `SQUADS_MEMBERS`    | Comma-separated member pubkeys (e.g. hardware) | none                          |
+//!
+//! See `squads-examples.env.example` in the repository for a template.
+
+use std::{collections::HashMap, path::PathBuf, str::FromStr};
+
+use solana_client::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signature},
+    signer::Signer,
+    transaction::Transaction,
+};
+
+use crate::accounts::{check_owner, ProgramConfig};
+use crate::error::{SquadsError, SquadsResult};
+use crate::pda;
+
+/// Environment variable naming the config file
+pub const CONFIG_FILE_VAR: &str = "SQUADS_EXAMPLES_CONFIG";
+
+/// Config file read from the working directory when [`CONFIG_FILE_VAR`] is unset
+pub const DEFAULT_CONFIG_FILE: &str = "squads-examples.env";
+
+/// RPC endpoint used when none is configured
+pub const DEFAULT_RPC_URL: &str = "https://api.devnet.solana.com";
+
+/// Configuration shared by the examples
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExampleConfig {
+    /// RPC endpoint
+    pub rpc_url: String,
+    /// Squads program ID
+    pub program_id: Pubkey,
+    /// Keypair file paths; the first one pays fees
+    pub keypair_paths: Vec<PathBuf>,
+    /// Existing multisig to operate on
+    pub multisig: Option<Pubkey>,
+    /// Existing proposal to inspect
+    pub proposal: Option<Pubkey>,
+    /// Wallet to inspect
+    pub wallet: Option<Pubkey>,
+    /// Additional member pubkeys without local keypairs
+    pub members: Vec<Pubkey>,
+}
+
+impl ExampleConfig {
+    /// Load the configuration from the config file and the environment
+    pub fn load() -> SquadsResult<Self> {
+        let mut vars = match std::env::var(CONFIG_FILE_VAR) {
+            Ok(path) => {
+                let contents = std::fs::read_to_string(&path).map_err(|e| {
+                    SquadsError::InvalidConfig(format!("cannot read {}: {}", path, e))
+                })?;
+                parse_config_file(&contents)
+            }
+            Err(_) => std::fs::read_to_string(DEFAULT_CONFIG_FILE)
+                .map(|contents| parse_config_file(&contents))
+                .unwrap_or_default(),
+        };
+        vars.extend(std::env::vars().filter(|(key, _)| key.starts_with("SQUADS_")));
+
+        Self::from_vars(&vars)
+    }
+
+    /// Build the configuration from a map of variables
+    pub fn from_vars(vars: &HashMap<String, String>) -> SquadsResult<Self> {
+        let get = |key: &str| {
+            vars.get(key)
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+        };
+
+        let keypair_paths = match get("SQUADS_KEYPAIRS") {
+            Some(paths) => split_list(paths).map(expand_home).collect(),
+            None => default_keypair_path().into_iter().collect(),
+        };
+
+        Ok(Self {
+            rpc_url: get("SQUADS_RPC_URL").unwrap_or(DEFAULT_RPC_URL).to_string(),
+            program_id: get("SQUADS_PROGRAM_ID")
+                .map(|value| parse_pubkey("SQUADS_PROGRAM_ID", value))
+                .transpose()?
+                .unwrap_or_else(crate::program_id),
+            keypair_paths,
+            multisig: get("SQUADS_MULTISIG")
+                .map(|value| parse_pubkey("SQUADS_MULTISIG", value))
+                .transpose()?,
+            proposal: get("SQUADS_PROPOSAL")
+                .map(|value| parse_pubkey("SQUADS_PROPOSAL", value))
+                .transpose()?,
+            wallet: get("SQUADS_WALLET")
+                .map(|value| parse_pubkey("SQUADS_WALLET", value))
+                .transpose()?,
+            members: get("SQUADS_MEMBERS")
+                .map(|value| {
+                    split_list(value)
+                        .map(|key| parse_pubkey("SQUADS_MEMBERS", key))
+                        .collect::<SquadsResult<Vec<_>>>()
+                })
+                .transpose()?
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Blocking RPC client for the configured endpoint with `confirmed` commitment
+    pub fn rpc_client(&self) -> RpcClient {
+        RpcClient::new_with_commitment(self.rpc_url.clone(), CommitmentConfig::confirmed())
+    }
+
+    /// Load the keypair at position `index` in `SQUADS_KEYPAIRS`
+    pub fn keypair(&self, index: usize) -> SquadsResult<Keypair> {
+        let path = self.keypair_paths.get(index).ok_or_else(|| {
+            SquadsError::InvalidConfig(format!(
+                "SQUADS_KEYPAIRS needs at least {} keypair path(s)",
+                index + 1
+            ))
+        })?;
+        read_keypair_file(path).map_err(|e| {
+            SquadsError::InvalidConfig(format!("cannot read keypair {}: {}", path.display(), e))
+        })
+    }
+
+    /// Load the fee payer keypair (the first entry of `SQUADS_KEYPAIRS`)
+    pub fn payer(&self) -> SquadsResult<Keypair> {
+        self.keypair(0)
+    }
+
+    /// Load the first `count` keypairs
+    pub fn keypairs(&self, count: usize) -> SquadsResult<Vec<Keypair>> {
+        (0..count).map(|index| self.keypair(index)).collect()
+    }
+
+    /// The configured multisig, or an error naming the missing variable
+    pub fn require_multisig(&self) -> SquadsResult<Pubkey> {
+        self.multisig.ok_or_else(|| missing("SQUADS_MULTISIG"))
+    }
+
+    /// The configured proposal, or an error naming the missing variable
+    pub fn require_proposal(&self) -> SquadsResult<Pubkey> {
+        self.proposal.ok_or_else(|| missing("SQUADS_PROPOSAL"))
+    }
+
+    /// The configured wallet, or an error naming the missing variable
+    pub fn require_wallet(&self) -> SquadsResult<Pubkey> {
+        self.wallet.ok_or_else(|| missing("SQUADS_WALLET"))
+    }
+}
+
+/// Fetch the treasury from the program config account
+pub fn fetch_treasury(rpc: &RpcClient, program_id: &Pubkey) -> SquadsResult<Pubkey> {
+    let (program_config, _) = pda::get_program_config_pda(Some(program_id));
+    let account = rpc.get_account(&program_config)?;
+    check_owner(&account.owner, program_id)?;
+    let config = ProgramConfig::try_from_slice(&account.data)
+        .map_err(|_| SquadsError::DeserializationError)?;
+    Ok(config.treasury)
+}
+
+/// Sign and send instructions with a blocking client; the first signer pays fees
+pub fn send_instructions(
+    rpc: &RpcClient,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> SquadsResult<Signature> {
+    let payer = signers
+        .first()
+        .ok_or_else(|| SquadsError::InvalidConfig("at least one signer is required".into()))?;
+    let recent_blockhash = rpc.get_latest_blockhash()?;
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        signers,
+        recent_blockhash,
+    );
+    Ok(rpc.send_and_confirm_transaction(&transaction)?)
+}
+
+/// Parse `KEY=VALUE` lines, ignoring blank lines and `#` comments
+fn parse_config_file(contents: &str) -> HashMap<String, String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim().trim_matches('"');
+            (key.trim().to_string(), value.to_string())
+        })
+        .collect()
+}
+
+fn split_list(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+fn parse_pubkey(var: &str, value: &str) -> SquadsResult<Pubkey> {
+    Pubkey::from_str(value).map_err(|_| {
+        SquadsError::InvalidConfig(format!("{} is not a valid pubkey: {}", var, value))
+    })
+}
+
+/// Expand a leading `~/` to the home directory
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+fn default_keypair_path() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config/solana/id.json"))
+}
+
+fn missing(var: &str) -> SquadsError {
+    SquadsError::InvalidConfig(format!("{} is not set", var))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_config_file() {
+        let multisig = Pubkey::new_unique();
+        let member = Pubkey::new_unique();
+        let contents = format!(
+            "# example config\nSQUADS_RPC_URL = \"http://localhost:8899\"\n\
+             SQUADS_KEYPAIRS=/tmp/a.json, /tmp/b.json\nSQUADS_MULTISIG={}\nSQUADS_MEMBERS={}\n",
+            multisig, member
+        );
+
+        let config = ExampleConfig::from_vars(&parse_config_file(&contents)).unwrap();
+        assert_eq!(config.rpc_url, "http://localhost:8899");
+        assert_eq!(config.program_id, crate::program_id());
+        assert_eq!(
+            config.keypair_paths,
+            vec![PathBuf::from("/tmp/a.json"), PathBuf::from("/tmp/b.json")]
+        );
+        assert_eq!(config.require_multisig().unwrap(), multisig);
+        assert_eq!(config.members, vec![member]);
+        assert!(config.require_proposal().is_err());
+    }
+
+    #[test]
+    fn test_invalid_pubkey() {
+        let vars = HashMap::from([("SQUADS_MULTISIG".to_string(), "nope".to_string())]);
+        assert!(matches!(
+            ExampleConfig::from_vars(&vars),
+            Err(SquadsError::InvalidConfig(_))
+        ));
+    }
+}
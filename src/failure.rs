@@ -0,0 +1,180 @@
+//! Locating the failing instruction of a vault execution
+//!
+//! `vault_transaction_execute` invokes the instructions of the vault transaction message
+//! one after the other, so when one of them fails the transaction error only points at
+//! the execute instruction. [`locate_failure`] walks the program logs to find which
+//! compiled instruction of the message failed, and [`InstructionFailure::instruction`]
+//! maps it back to the instruction the proposer supplied.
+
+use solana_client::client_error::ClientErrorKind;
+use solana_client::rpc_request::{RpcError, RpcResponseErrorData};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+use crate::error::SquadsError;
+
+/// Squads instructions that invoke the instructions of a vault transaction message
+const EXECUTE_INSTRUCTIONS: [&str; 2] = ["VaultTransactionExecute", "VaultBatchTransactionExecute"];
+
+/// The instruction of a vault transaction that made its execution fail
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionFailure {
+    /// Index of the instruction in the vault transaction message
+    pub index: usize,
+    /// Program the instruction invoked
+    pub program_id: Pubkey,
+    /// Failure logged by the runtime, e.g. `custom program error: 0x1`
+    pub message: String,
+}
+
+impl InstructionFailure {
+    /// The failing instruction among the instructions the message was compiled from
+    ///
+    /// Compiling a message keeps the instruction order, so `instructions` can be the
+    /// list passed to [`TransactionMessage::try_compile`](crate::TransactionMessage::try_compile)
+    /// or the result of [`VaultTransactionMessage::to_instructions`](crate::accounts::VaultTransactionMessage::to_instructions).
+    pub fn instruction<'a>(&self, instructions: &'a [Instruction]) -> Option<&'a Instruction> {
+        instructions
+            .get(self.index)
+            .filter(|ix| ix.program_id == self.program_id)
+    }
+}
+
+/// Find the failing vault transaction instruction in the logs of a failed execution
+///
+/// Returns None if no execute instruction of the Squads program failed because of one
+/// of its inner instructions, or if the logs were truncated before the failure.
+///
+/// # Arguments
+/// * `logs` - Log messages of the transaction or its simulation
+/// * `program_id` - Optional custom program ID (uses canonical ID if None)
+pub fn locate_failure(logs: &[String], program_id: Option<&Pubkey>) -> Option<InstructionFailure> {
+    let program_id = program_id.copied().unwrap_or_else(crate::program_id);
+    let mut stack: Vec<Pubkey> = Vec::new();
+    // Stack height of the Squads execute instruction being traced
+    let mut execute_height = None;
+    let mut next_index = 0;
+    let mut current = None;
+
+    for line in logs {
+        if line == "Log truncated" {
+            return None;
+        }
+        if let Some(name) = line.strip_prefix("Program log: Instruction: ") {
+            if stack.last() == Some(&program_id) && EXECUTE_INSTRUCTIONS.contains(&name.trim()) {
+                execute_height = Some(stack.len());
+                next_index = 0;
+            }
+            continue;
+        }
+        let Some(rest) = line.strip_prefix("Program ") else {
+            continue;
+        };
+        let Some((program, action)) = rest.split_once(' ') else {
+            continue;
+        };
+        let Ok(program) = program.parse::<Pubkey>() else {
+            continue;
+        };
+
+        if action.starts_with("invoke") {
+            stack.push(program);
+            if execute_height.is_some_and(|height| stack.len() == height + 1) {
+                current = Some(next_index);
+                next_index += 1;
+            }
+        } else if action == "success" || action.starts_with("failed:") {
+            let height = stack.len();
+            stack.pop();
+            if let Some(message) = action.strip_prefix("failed:") {
+                if execute_height.is_some_and(|execute| height == execute + 1) {
+                    return current.map(|index| InstructionFailure {
+                        index,
+                        program_id: program,
+                        message: message.trim().to_string(),
+                    });
+                }
+            }
+            if execute_height == Some(height) {
+                execute_height = None;
+                current = None;
+            }
+        }
+    }
+    None
+}
+
+/// Log messages of the simulation behind a failed preflight check, if `err` is one
+pub fn preflight_logs(err: &SquadsError) -> Option<&[String]> {
+    let SquadsError::ClientError(err) = err else {
+        return None;
+    };
+    match err.kind() {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            data: RpcResponseErrorData::SendTransactionPreflightFailure(result),
+            ..
+        }) => result.logs.as_deref(),
+        _ => None,
+    }
+}
+
+/// Turn a failed execution into [`SquadsError::InstructionFailed`] when its preflight
+/// logs show which vault transaction instruction failed
+///
+/// Other errors are returned unchanged.
+pub fn with_location(err: SquadsError, program_id: Option<&Pubkey>) -> SquadsError {
+    let Some(logs) = preflight_logs(&err) else {
+        return err;
+    };
+    match locate_failure(logs, program_id) {
+        Some(failure) => SquadsError::InstructionFailed {
+            failure,
+            logs: logs.to_vec(),
+        },
+        None => err,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locate_failure() {
+        let squads = crate::program_id();
+        let system = solana_sdk_ids::system_program::ID;
+        let token = Pubkey::new_unique();
+        let logs: Vec<String> = [
+            format!("Program {} invoke [1]", squads),
+            "Program log: Instruction: VaultTransactionExecute".to_string(),
+            format!("Program {} invoke [2]", system),
+            format!("Program {} success", system),
+            format!("Program {} invoke [2]", token),
+            format!("Program {} invoke [3]", system),
+            format!("Program {} success", system),
+            format!("Program {} consumed 3000 of 190000 compute units", token),
+            format!("Program {} failed: custom program error: 0x1", token),
+            format!("Program {} failed: custom program error: 0x1", squads),
+        ]
+        .into();
+
+        let failure = locate_failure(&logs, None).unwrap();
+        assert_eq!(failure.index, 1);
+        assert_eq!(failure.program_id, token);
+        assert_eq!(failure.message, "custom program error: 0x1");
+
+        let instructions = vec![
+            Instruction::new_with_bytes(system, &[], vec![]),
+            Instruction::new_with_bytes(token, &[7], vec![]),
+        ];
+        assert_eq!(failure.instruction(&instructions), Some(&instructions[1]));
+        assert_eq!(failure.instruction(&instructions[..1]), None);
+
+        // The execute instruction itself failing is not an inner failure
+        let own_failure = [
+            format!("Program {} invoke [1]", squads),
+            "Program log: Instruction: VaultTransactionExecute".to_string(),
+            format!("Program {} failed: custom program error: 0x177b", squads),
+        ];
+        assert_eq!(locate_failure(&own_failure, None), None);
+    }
+}
@@ -0,0 +1,387 @@
+//! Static checks for instructions that fail or misbehave inside vault execution
+//!
+//! `vault_transaction_execute` runs every instruction of a vault transaction as a CPI
+//! signed by the vault PDA. Some instructions cannot run that way: precompiles and most
+//! loader instructions are rejected by the runtime, compute budget instructions have no
+//! effect, and instruction introspection sees the execute transaction instead of the
+//! proposed one. [`CpiGuard`] runs the instructions of a message through a list of
+//! [`GuardRule`]s before it is proposed, so these surface before the squad votes.
+//!
+//! ```rust
+//! use squads_v4_client_v3::guard::{CpiGuard, Severity};
+//! use squads_v4_client_v3::message::TransactionMessage;
+//! use solana_sdk::pubkey::Pubkey;
+//!
+//! let vault = Pubkey::new_unique();
+//! let transfer = solana_system_interface::instruction::transfer(&vault, &Pubkey::new_unique(), 1);
+//! let message = TransactionMessage::try_compile(&vault, &[transfer]).unwrap();
+//!
+//! let report = CpiGuard::default().check_message(&message, &[], &[]).unwrap();
+//! assert!(report.is_clean());
+//! ```
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::{instruction::Instruction, message::AddressLookupTableAccount, pubkey::Pubkey};
+use solana_sdk_ids::{
+    bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable, compute_budget, ed25519_program,
+    native_loader, secp256k1_program, secp256r1_program, sysvar,
+};
+
+use crate::accounts::VaultTransactionMessage;
+use crate::error::{SquadsError, SquadsResult};
+use crate::message::TransactionMessage;
+
+/// How serious a finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Severity {
+    /// The instruction runs, but not as it would in a top-level transaction
+    Warning,
+    /// Execution will fail
+    Error,
+}
+
+/// Accounts the Squads program signs for during execution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuardContext {
+    /// Vault executing the transaction
+    pub vault: Pubkey,
+    /// Ephemeral signer PDAs of the transaction
+    pub ephemeral_signers: Vec<Pubkey>,
+}
+
+impl GuardContext {
+    /// Whether the Squads program signs for `key` during execution
+    pub fn is_program_signer(&self, key: &Pubkey) -> bool {
+        *key == self.vault || self.ephemeral_signers.contains(key)
+    }
+}
+
+type Check = dyn Fn(&Instruction, &GuardContext) -> Option<String> + Send + Sync;
+
+/// A named check of a [`CpiGuard`]
+pub struct GuardRule {
+    /// Name recorded in findings
+    pub name: String,
+    /// Severity of the findings of the rule
+    pub severity: Severity,
+    check: Box<Check>,
+}
+
+impl GuardRule {
+    /// A rule flagging the instructions for which `check` returns a reason
+    pub fn new<F>(name: impl Into<String>, severity: Severity, check: F) -> Self
+    where
+        F: Fn(&Instruction, &GuardContext) -> Option<String> + Send + Sync + 'static,
+    {
+        Self {
+            name: name.into(),
+            severity,
+            check: Box::new(check),
+        }
+    }
+
+    /// Programs the runtime does not allow to be invoked through CPI
+    pub fn uncallable_program() -> Self {
+        Self::new("uncallable program", Severity::Error, |ix, _| {
+            let kind = if ix.program_id == native_loader::ID
+                || ix.program_id == bpf_loader::ID
+                || ix.program_id == bpf_loader_deprecated::ID
+            {
+                "loader"
+            } else if ix.program_id == ed25519_program::ID
+                || ix.program_id == secp256k1_program::ID
+                || ix.program_id == secp256r1_program::ID
+            {
+                "precompile"
+            } else {
+                return None;
+            };
+            Some(format!(
+                "{} {} cannot be invoked through CPI",
+                kind, ix.program_id
+            ))
+        })
+    }
+
+    /// Upgradeable loader instructions other than upgrade, set authority, close and
+    /// extend, which the runtime rejects in CPI (e.g. buffer writes)
+    pub fn loader_instruction() -> Self {
+        Self::new("loader instruction", Severity::Error, |ix, _| {
+            if ix.program_id != bpf_loader_upgradeable::ID {
+                return None;
+            }
+            // Upgrade, SetAuthority, Close, ExtendProgram, SetAuthorityChecked,
+            // ExtendProgramChecked
+            const CPI_ALLOWED: [u32; 6] = [3, 4, 5, 6, 7, 9];
+            let tag = ix
+                .data
+                .get(..4)
+                .and_then(|tag| tag.try_into().ok())
+                .map(u32::from_le_bytes);
+            (!tag.is_some_and(|tag| CPI_ALLOWED.contains(&tag)))
+                .then(|| "upgradeable loader instruction is not allowed in CPI".to_string())
+        })
+    }
+
+    /// Compute budget instructions, which only apply at the top level of a transaction
+    pub fn compute_budget() -> Self {
+        Self::new("compute budget", Severity::Warning, |ix, _| {
+            (ix.program_id == compute_budget::ID).then(|| {
+                "compute budget instructions have no effect inside a vault transaction; set \
+                 them on the execute transaction"
+                    .to_string()
+            })
+        })
+    }
+
+    /// Instructions reading the instructions sysvar, which describes the execute
+    /// transaction rather than the vault transaction
+    pub fn instruction_introspection() -> Self {
+        Self::new("instruction introspection", Severity::Warning, |ix, _| {
+            ix.accounts
+                .iter()
+                .any(|meta| meta.pubkey == sysvar::instructions::ID)
+                .then(|| {
+                    "the instructions sysvar describes the execute transaction, not this one"
+                        .to_string()
+                })
+        })
+    }
+
+    /// Deprecated sysvars, which newer programs do not accept
+    pub fn deprecated_sysvar() -> Self {
+        Self::new("deprecated sysvar", Severity::Warning, |ix, _| {
+            ix.accounts
+                .iter()
+                .find(|meta| {
+                    meta.pubkey == sysvar::recent_blockhashes::ID || meta.pubkey == sysvar::fees::ID
+                })
+                .map(|meta| format!("sysvar {} is deprecated", meta.pubkey))
+        })
+    }
+
+    /// Signers the Squads program cannot sign for, e.g. a fee payer other than the vault;
+    /// they must also sign the execute transaction
+    pub fn external_signer() -> Self {
+        Self::new("external signer", Severity::Warning, |ix, context| {
+            ix.accounts
+                .iter()
+                .find(|meta| meta.is_signer && !context.is_program_signer(&meta.pubkey))
+                .map(|meta| {
+                    format!(
+                        "{} must sign the execute transaction, only the vault and ephemeral \
+                         signers are signed for",
+                        meta.pubkey
+                    )
+                })
+        })
+    }
+
+    /// The rules of [`CpiGuard::default`]
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::uncallable_program(),
+            Self::loader_instruction(),
+            Self::compute_budget(),
+            Self::instruction_introspection(),
+            Self::deprecated_sysvar(),
+            Self::external_signer(),
+        ]
+    }
+}
+
+impl std::fmt::Debug for GuardRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GuardRule")
+            .field("name", &self.name)
+            .field("severity", &self.severity)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An instruction flagged by a [`GuardRule`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Finding {
+    /// Position of the instruction in the message
+    pub index: usize,
+    /// Program the instruction calls
+    pub program_id: Pubkey,
+    /// Name of the rule
+    pub rule: String,
+    /// Severity of the rule
+    pub severity: Severity,
+    /// Why the instruction was flagged
+    pub reason: String,
+}
+
+/// Result of [`CpiGuard::check`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuardReport {
+    /// Findings, in instruction order
+    pub findings: Vec<Finding>,
+}
+
+impl GuardReport {
+    /// Whether nothing was flagged
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Findings that make execution fail
+    pub fn errors(&self) -> impl Iterator<Item = &Finding> {
+        self.findings
+            .iter()
+            .filter(|finding| finding.severity == Severity::Error)
+    }
+
+    /// Whether execution will fail
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+}
+
+/// Checks a vault transaction against a list of rules
+///
+/// [`CpiGuard::default`] uses [`GuardRule::defaults`]; add project-specific checks with
+/// [`CpiGuard::with_rule`].
+#[derive(Debug)]
+pub struct CpiGuard {
+    rules: Vec<GuardRule>,
+}
+
+impl Default for CpiGuard {
+    fn default() -> Self {
+        Self {
+            rules: GuardRule::defaults(),
+        }
+    }
+}
+
+impl CpiGuard {
+    /// A guard without rules
+    pub fn empty() -> Self {
+        Self { rules: vec![] }
+    }
+
+    /// Add a rule
+    pub fn with_rule(mut self, rule: GuardRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Check instructions executed by `context.vault`
+    pub fn check(&self, instructions: &[Instruction], context: &GuardContext) -> GuardReport {
+        let findings = instructions
+            .iter()
+            .enumerate()
+            .flat_map(|(index, instruction)| {
+                self.rules.iter().filter_map(move |rule| {
+                    (rule.check)(instruction, context).map(|reason| Finding {
+                        index,
+                        program_id: instruction.program_id,
+                        rule: rule.name.clone(),
+                        severity: rule.severity,
+                        reason,
+                    })
+                })
+            })
+            .collect();
+        GuardReport { findings }
+    }
+
+    /// Check a compiled message, whose first account key is the vault
+    ///
+    /// Lookups are resolved from `tables`; a message that cannot be resolved is reported
+    /// as [`SquadsError::InvalidTransactionMessage`].
+    ///
+    /// # Arguments
+    /// * `message` - Message to propose
+    /// * `ephemeral_signers` - Ephemeral signer PDAs the transaction will use
+    /// * `tables` - Lookup tables referenced by the message
+    pub fn check_message(
+        &self,
+        message: &TransactionMessage,
+        ephemeral_signers: &[Pubkey],
+        tables: &[AddressLookupTableAccount],
+    ) -> SquadsResult<GuardReport> {
+        let vault = *message
+            .account_keys
+            .as_slice()
+            .first()
+            .ok_or(SquadsError::InvalidTransactionMessage)?;
+        let instructions = VaultTransactionMessage::from(message)
+            .to_instructions_with_tables(tables)
+            .ok_or(SquadsError::InvalidTransactionMessage)?;
+        let context = GuardContext {
+            vault,
+            ephemeral_signers: ephemeral_signers.to_vec(),
+        };
+        Ok(self.check(&instructions, &context))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::upgrade;
+    use solana_sdk::instruction::AccountMeta;
+
+    #[test]
+    fn test_guard_flags_problematic_instructions() {
+        let vault = Pubkey::new_unique();
+        let ephemeral = Pubkey::new_unique();
+        let cosigner = Pubkey::new_unique();
+        let buffer = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        let instructions = vec![
+            solana_system_interface::instruction::transfer(&vault, &ephemeral, 1),
+            Instruction::new_with_bytes(compute_budget::ID, &[2, 0, 0, 0, 0], vec![]),
+            upgrade::write(buffer, vault, 0, &[1, 2, 3]),
+            upgrade::upgrade(program, buffer, vault, vault),
+            Instruction::new_with_bytes(ed25519_program::ID, &[0], vec![]),
+            Instruction::new_with_bytes(
+                program,
+                &[],
+                vec![
+                    AccountMeta::new(cosigner, true),
+                    AccountMeta::new_readonly(sysvar::instructions::ID, false),
+                ],
+            ),
+        ];
+        let context = GuardContext {
+            vault,
+            ephemeral_signers: vec![ephemeral],
+        };
+
+        let report = CpiGuard::default().check(&instructions, &context);
+        let flagged: Vec<(usize, &str)> = report
+            .findings
+            .iter()
+            .map(|finding| (finding.index, finding.rule.as_str()))
+            .collect();
+        assert_eq!(
+            flagged,
+            vec![
+                (1, "compute budget"),
+                (2, "loader instruction"),
+                (4, "uncallable program"),
+                (5, "instruction introspection"),
+                (5, "external signer"),
+            ]
+        );
+        assert_eq!(report.errors().count(), 2);
+
+        let custom = CpiGuard::empty().with_rule(GuardRule::new(
+            "no transfers",
+            Severity::Error,
+            |ix: &Instruction, _: &GuardContext| {
+                (ix.program_id == solana_sdk_ids::system_program::ID)
+                    .then(|| "transfers need a spending limit".to_string())
+            },
+        ));
+        let message = TransactionMessage::try_compile(&vault, &instructions[..2]).unwrap();
+        let report = custom.check_message(&message, &[], &[]).unwrap();
+        assert_eq!(report.findings.len(), 1);
+        assert!(report.has_errors());
+    }
+}
@@ -0,0 +1,215 @@
+//! Structured history of confirmed transactions
+//!
+//! [`TransactionRecord`] pairs the decoded top-level Squads instructions of a
+//! transaction with the entries decoded from its logs (see [`crate::events`]), so
+//! transaction history can be processed without parsing raw messages or logs.
+
+use solana_sdk::{message::VersionedMessage, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status_client_types::{
+    option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta,
+    UiTransactionStatusMeta,
+};
+
+use crate::error::SquadsResult;
+use crate::events::{decode_program_logs, LogEvent};
+use crate::parser::{parse_program_instruction_data, SquadsInstruction};
+use crate::report::decode_confirmed;
+
+/// A confirmed transaction decoded into Squads instructions and log events
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionRecord {
+    /// Transaction signature
+    pub signature: Signature,
+    /// Slot the transaction was confirmed in
+    pub slot: u64,
+    /// Block time, if known
+    pub block_time: Option<i64>,
+    /// Whether the transaction succeeded
+    pub success: bool,
+    /// Top-level Squads instructions, in message order
+    pub instructions: Vec<SquadsInstruction>,
+    /// Log entries attributed to the Squads program, in log order
+    pub events: Vec<LogEvent>,
+}
+
+impl TransactionRecord {
+    /// Decode a transaction fetched with a binary encoding
+    ///
+    /// # Arguments
+    /// * `signature` - Transaction signature
+    /// * `confirmed` - Transaction as returned by `getTransaction`
+    /// * `program_id` - Optional custom program ID (uses canonical ID if None)
+    pub fn from_confirmed(
+        signature: Signature,
+        confirmed: &EncodedConfirmedTransactionWithStatusMeta,
+        program_id: Option<&Pubkey>,
+    ) -> SquadsResult<Self> {
+        let (transaction, account_keys) = decode_confirmed(confirmed)?;
+        Ok(Self::from_parts(
+            signature,
+            confirmed.slot,
+            confirmed.block_time,
+            &transaction.message,
+            &account_keys,
+            confirmed.transaction.meta.as_ref(),
+            program_id,
+        ))
+    }
+
+    /// Decode a transaction from its message, full account key list and status meta
+    ///
+    /// Instructions that do not decode as Squads instructions are skipped.
+    pub fn from_parts(
+        signature: Signature,
+        slot: u64,
+        block_time: Option<i64>,
+        message: &VersionedMessage,
+        account_keys: &[Pubkey],
+        meta: Option<&UiTransactionStatusMeta>,
+        program_id: Option<&Pubkey>,
+    ) -> Self {
+        let squads = program_id.copied().unwrap_or_else(crate::program_id);
+
+        let instructions = message
+            .instructions()
+            .iter()
+            .filter(|compiled| {
+                account_keys.get(usize::from(compiled.program_id_index)) == Some(&squads)
+            })
+            .filter_map(|compiled| {
+                let accounts: Vec<Pubkey> = compiled
+                    .accounts
+                    .iter()
+                    .filter_map(|index| account_keys.get(usize::from(*index)).copied())
+                    .collect();
+                parse_program_instruction_data(&squads, &accounts, &compiled.data).ok()
+            })
+            .collect();
+
+        let events = match meta.map(|meta| &meta.log_messages) {
+            Some(OptionSerializer::Some(logs)) => decode_program_logs(logs, Some(&squads)),
+            _ => Vec::new(),
+        };
+
+        Self {
+            signature,
+            slot,
+            block_time,
+            success: meta.is_none_or(|meta| meta.err.is_none()),
+            instructions,
+            events,
+        }
+    }
+
+    /// Names of the Squads instructions logged by the program, in log order
+    pub fn logged_instructions(&self) -> impl Iterator<Item = &str> {
+        self.events.iter().filter_map(|event| match event {
+            LogEvent::Instruction { name, .. } => Some(name.as_str()),
+            LogEvent::Event { .. } => None,
+        })
+    }
+
+    /// Memo of the instruction that created `transaction` (a transaction or batch account)
+    ///
+    /// Returns None if the record does not create the account or was created without a
+    /// memo.
+    pub fn creation_memo(&self, transaction: &Pubkey) -> Option<&str> {
+        self.instructions
+            .iter()
+            .find(|instruction| instruction.created_transaction() == Some(transaction))
+            .and_then(SquadsInstruction::memo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::{
+        config_transaction_create, proposal_approve, ConfigTransactionCreateArgs, ProposalVoteArgs,
+    };
+    use crate::types::ConfigAction;
+    use solana_sdk::message::Message;
+
+    #[test]
+    fn test_record_from_parts() {
+        let squads = crate::program_id();
+        let member = Pubkey::new_unique();
+        let multisig = Pubkey::new_unique();
+        let proposal = Pubkey::new_unique();
+        let approve = proposal_approve(
+            multisig,
+            proposal,
+            member,
+            ProposalVoteArgs::new(None).unwrap(),
+            None,
+        );
+        let message = Message::new(&[approve], Some(&member));
+        let account_keys = message.account_keys.clone();
+
+        let meta: UiTransactionStatusMeta = serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+            "logMessages": [
+                format!("Program {} invoke [1]", squads),
+                "Program log: Instruction: ProposalApprove",
+                format!("Program {} success", squads),
+            ]
+        }))
+        .unwrap();
+
+        let record = TransactionRecord::from_parts(
+            Signature::default(),
+            10,
+            Some(1_700_000_000),
+            &VersionedMessage::Legacy(message),
+            &account_keys,
+            Some(&meta),
+            None,
+        );
+
+        assert!(record.success);
+        assert_eq!(record.instructions.len(), 1);
+        assert!(matches!(
+            record.instructions[0],
+            SquadsInstruction::ProposalApprove { .. }
+        ));
+        assert_eq!(
+            record.logged_instructions().collect::<Vec<_>>(),
+            ["ProposalApprove"]
+        );
+    }
+
+    #[test]
+    fn test_creation_memo() {
+        let creator = Pubkey::new_unique();
+        let multisig = Pubkey::new_unique();
+        let transaction = Pubkey::new_unique();
+        let args = ConfigTransactionCreateArgs::new(
+            vec![ConfigAction::ChangeThreshold { new_threshold: 2 }],
+            Some("Raise threshold to 2".to_string()),
+        )
+        .unwrap();
+        let create = config_transaction_create(multisig, transaction, creator, creator, args, None);
+        let message = Message::new(&[create], Some(&creator));
+        let account_keys = message.account_keys.clone();
+
+        let record = TransactionRecord::from_parts(
+            Signature::default(),
+            10,
+            None,
+            &VersionedMessage::Legacy(message),
+            &account_keys,
+            None,
+            None,
+        );
+
+        assert_eq!(
+            record.creation_memo(&transaction),
+            Some("Raise threshold to 2")
+        );
+        assert_eq!(record.creation_memo(&multisig), None);
+    }
+}
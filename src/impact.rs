@@ -0,0 +1,236 @@
+//! Effect of a config change on pending proposals
+//!
+//! Executing a config transaction that changes members, threshold, time lock, rent
+//! collector or config authority advances the multisig's `stale_transaction_index`, so
+//! every transaction created before it can no longer be voted on, and approved config
+//! transactions can no longer be executed. [`of_config_change`] lists which pending
+//! proposals a change would hit, so proposers can let routine proposals finish first.
+
+use crate::accounts::{Multisig, Proposal};
+use crate::diff::MultisigDiff;
+use crate::types::{ConfigAction, ProposalStatus};
+
+pub use crate::types::TransactionKind;
+
+/// A pending proposal, with the kind of transaction it is for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingProposal {
+    /// Transaction index
+    pub transaction_index: u64,
+    /// Kind of transaction
+    pub kind: TransactionKind,
+    /// The proposal
+    pub proposal: Proposal,
+}
+
+/// What a config change does to a pending proposal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// The proposal stays votable and executable
+    Unaffected,
+    /// The proposal becomes stale: it can no longer be activated or voted on, only
+    /// closed to reclaim rent
+    Stale,
+    /// The approved config transaction becomes stale and can no longer be executed
+    Unexecutable,
+}
+
+/// Effect of a config change on one pending proposal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProposalImpact {
+    /// Transaction index
+    pub transaction_index: u64,
+    /// Kind of transaction
+    pub kind: TransactionKind,
+    /// Current status of the proposal
+    pub status: ProposalStatus,
+    /// What the change does to it
+    pub outcome: Outcome,
+}
+
+/// Result of [`of_config_change`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigChangeImpact {
+    /// Multisig after the config transaction is created and executed; check it with
+    /// [`Multisig::check_invariants`]
+    pub resulting: Multisig,
+    /// Configuration changes, e.g. for a preview; see [`MultisigDiff::member_changes`]
+    /// for member key rotations
+    pub changes: MultisigDiff,
+    /// Whether executing the change makes pending transactions stale
+    pub invalidates_pending: bool,
+    /// Pending proposals that are not stale yet, in the order given
+    pub proposals: Vec<ProposalImpact>,
+}
+
+impl ConfigChangeImpact {
+    /// Proposals the change makes stale or unexecutable
+    pub fn affected(&self) -> impl Iterator<Item = &ProposalImpact> {
+        self.proposals
+            .iter()
+            .filter(|impact| impact.outcome != Outcome::Unaffected)
+    }
+}
+
+/// Work out which pending proposals a config change would invalidate
+///
+/// Assumes the config transaction is created next and executed before any other
+/// transaction is created; transactions created in between are invalidated too.
+/// Approved vault transactions and batches stay executable after becoming stale, while
+/// approved config transactions do not. Proposals that are already stale or no longer
+/// pending are left out.
+///
+/// # Arguments
+/// * `multisig` - Current state of the multisig
+/// * `actions` - Actions of the config transaction
+/// * `pending` - Pending proposals of the multisig
+pub fn of_config_change(
+    multisig: &Multisig,
+    actions: &[ConfigAction],
+    pending: &[PendingProposal],
+) -> ConfigChangeImpact {
+    let invalidates_pending = actions
+        .iter()
+        .any(ConfigAction::invalidates_pending_transactions);
+
+    let mut resulting = multisig.clone();
+    resulting.transaction_index = resulting.transaction_index.saturating_add(1);
+    for action in actions {
+        resulting.apply_config_action(action);
+    }
+
+    let proposals = pending
+        .iter()
+        .filter(|p| p.transaction_index > multisig.stale_transaction_index)
+        .filter_map(|p| {
+            let outcome = match (&p.proposal.status, p.kind) {
+                _ if !invalidates_pending => Outcome::Unaffected,
+                (ProposalStatus::Draft { .. } | ProposalStatus::Active { .. }, _) => Outcome::Stale,
+                (ProposalStatus::Approved { .. }, TransactionKind::Config) => Outcome::Unexecutable,
+                (ProposalStatus::Approved { .. }, _) => Outcome::Unaffected,
+                _ => return None,
+            };
+            Some(ProposalImpact {
+                transaction_index: p.transaction_index,
+                kind: p.kind,
+                status: p.proposal.status.clone(),
+                outcome,
+            })
+        })
+        .collect();
+
+    ConfigChangeImpact {
+        changes: multisig.diff(&resulting),
+        resulting,
+        invalidates_pending,
+        proposals,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Member;
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn test_threshold_change_impact() {
+        let members: Vec<Member> = (0..3).map(|_| Member::new(Pubkey::new_unique())).collect();
+        let multisig = Multisig {
+            create_key: Pubkey::new_unique(),
+            config_authority: Pubkey::default(),
+            threshold: 2,
+            time_lock: 0,
+            transaction_index: 5,
+            stale_transaction_index: 1,
+            rent_collector: None,
+            bump: 255,
+            members,
+            unknown_tail: vec![],
+        };
+        let pending = |transaction_index, kind, status| PendingProposal {
+            transaction_index,
+            kind,
+            proposal: Proposal {
+                multisig: Pubkey::new_unique(),
+                transaction_index,
+                status,
+                bump: 255,
+                approved: vec![],
+                rejected: vec![],
+                cancelled: vec![],
+                unknown_tail: vec![],
+            },
+        };
+        let active = ProposalStatus::Active { timestamp: 0 };
+        let approved = ProposalStatus::Approved { timestamp: 0 };
+        let pending = vec![
+            pending(1, TransactionKind::Vault, active.clone()),
+            pending(2, TransactionKind::Vault, active),
+            pending(3, TransactionKind::Vault, approved.clone()),
+            pending(4, TransactionKind::Config, approved),
+            pending(
+                5,
+                TransactionKind::Batch,
+                ProposalStatus::Executed { timestamp: 0 },
+            ),
+        ];
+
+        let impact = of_config_change(
+            &multisig,
+            &[ConfigAction::ChangeThreshold { new_threshold: 3 }],
+            &pending,
+        );
+        assert!(impact.invalidates_pending);
+        assert_eq!(impact.resulting.threshold, 3);
+        assert_eq!(impact.resulting.stale_transaction_index, 6);
+        let outcomes: Vec<_> = impact
+            .proposals
+            .iter()
+            .map(|p| (p.transaction_index, p.outcome))
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                (2, Outcome::Stale),
+                (3, Outcome::Unaffected),
+                (4, Outcome::Unexecutable)
+            ]
+        );
+        assert_eq!(impact.affected().count(), 2);
+
+        // Replacing a member's key shows up as a rotation in the preview
+        let lost = multisig.members[0].key;
+        let replacement = Member::new(Pubkey::new_unique());
+        let impact = of_config_change(
+            &multisig,
+            &[
+                ConfigAction::AddMember {
+                    new_member: replacement.clone(),
+                },
+                ConfigAction::RemoveMember { old_member: lost },
+            ],
+            &pending,
+        );
+        assert_eq!(
+            impact.changes.member_changes(),
+            vec![crate::diff::MemberChange::Rotated(
+                crate::diff::MemberRotation {
+                    old: Member::new(lost),
+                    new: replacement,
+                }
+            )]
+        );
+
+        let impact = of_config_change(
+            &multisig,
+            &[ConfigAction::RemoveSpendingLimit {
+                spending_limit: Pubkey::new_unique(),
+            }],
+            &pending,
+        );
+        assert!(!impact.invalidates_pending);
+        assert_eq!(impact.affected().count(), 0);
+        assert_eq!(impact.resulting.stale_transaction_index, 1);
+    }
+}
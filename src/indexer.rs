@@ -0,0 +1,272 @@
+//! Full-history backfill of a multisig's transactions
+//!
+//! [`backfill`] walks the signature history of a multisig from the newest transaction
+//! back to the oldest, one `getSignaturesForAddress` page at a time, and decodes each
+//! transaction into a [`TransactionRecord`](crate::history::TransactionRecord). RPC
+//! requests are paced by [`BackfillConfig::request_interval`] and failed requests are
+//! retried with exponential backoff, so years of activity can be rebuilt on rate-limited
+//! RPC plans.
+//!
+//! After each page the caller receives a [`Checkpoint`] to persist. Passing its
+//! `last_signature` as `from_signature` resumes the walk after the last processed
+//! transaction.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::signature::Signature;
+
+/// Maximum page size accepted by `getSignaturesForAddress`
+pub const MAX_PAGE_SIZE: usize = 1000;
+
+/// Pacing and paging of a backfill
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackfillConfig {
+    /// Signatures requested per page (at most [`MAX_PAGE_SIZE`])
+    pub page_size: usize,
+    /// Minimum time between two RPC requests
+    pub request_interval: Duration,
+    /// Retries of a failed RPC request before giving up
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each further retry
+    pub retry_backoff: Duration,
+    /// Upper bound of the retry delay
+    pub max_backoff: Duration,
+    /// Stop after this many pages, e.g. to spread a backfill over several runs (None to
+    /// walk the whole history)
+    pub max_pages: Option<usize>,
+    /// Stop at this signature, exclusive, e.g. the newest signature of a previous
+    /// backfill (None to walk back to the first transaction)
+    pub until: Option<Signature>,
+}
+
+impl Default for BackfillConfig {
+    fn default() -> Self {
+        Self {
+            page_size: MAX_PAGE_SIZE,
+            request_interval: Duration::from_millis(250),
+            max_retries: 5,
+            retry_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+            max_pages: None,
+            until: None,
+        }
+    }
+}
+
+impl BackfillConfig {
+    /// Delay before retry number `attempt` (starting at 1)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u32
+            .checked_shl(attempt.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.retry_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Progress of a backfill, to be persisted after each page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Oldest signature processed so far; pass it as `from_signature` to resume
+    pub last_signature: Signature,
+    /// Slot of `last_signature`
+    pub slot: u64,
+}
+
+/// Outcome of a [`backfill`] run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BackfillSummary {
+    /// Pages fetched in this run
+    pub pages: usize,
+    /// Transactions processed in this run
+    pub processed: u64,
+    /// Checkpoint after the last page (None if no transaction was processed)
+    pub checkpoint: Option<Checkpoint>,
+    /// Whether the walk reached the first transaction or `until`
+    pub complete: bool,
+}
+
+#[cfg(feature = "async")]
+pub use fetch::backfill;
+
+#[cfg(feature = "async")]
+mod fetch {
+    use std::future::Future;
+    use std::time::Instant;
+
+    use solana_client::{
+        nonblocking::rpc_client::RpcClient, rpc_client::GetConfirmedSignaturesForAddress2Config,
+        rpc_config::RpcTransactionConfig,
+    };
+    use solana_commitment_config::CommitmentConfig;
+    use solana_sdk::pubkey::Pubkey;
+    use solana_transaction_status_client_types::UiTransactionEncoding;
+
+    use super::*;
+    use crate::error::{SquadsError, SquadsResult};
+    use crate::history::TransactionRecord;
+    use crate::runtime;
+
+    /// Paces RPC requests and retries the failed ones
+    struct Throttle<'a> {
+        config: &'a BackfillConfig,
+        next_request: Option<Instant>,
+    }
+
+    impl Throttle<'_> {
+        async fn request<T, F, Fut>(&mut self, mut send: F) -> SquadsResult<T>
+        where
+            F: FnMut() -> Fut,
+            Fut: Future<Output = SquadsResult<T>>,
+        {
+            let mut attempt = 0;
+            loop {
+                if let Some(next_request) = self.next_request {
+                    let wait = next_request.saturating_duration_since(Instant::now());
+                    if !wait.is_zero() {
+                        runtime::sleep(wait).await;
+                    }
+                }
+                self.next_request = Some(Instant::now() + self.config.request_interval);
+
+                match send().await {
+                    Ok(value) => return Ok(value),
+                    Err(_) if attempt < self.config.max_retries => {
+                        attempt += 1;
+                        runtime::sleep(self.config.backoff(attempt)).await;
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+    }
+
+    /// Walk the transaction history of a multisig, newest first
+    ///
+    /// Each page of signatures is fetched, its transactions are decoded into
+    /// [`TransactionRecord`]s (newest first, failed transactions included) and passed to
+    /// `on_page` together with the checkpoint reached after the page. Persist the
+    /// checkpoint in `on_page`; returning an error stops the backfill before the next
+    /// page.
+    ///
+    /// # Arguments
+    /// * `rpc` - RPC client (the endpoint must keep the history to be rebuilt)
+    /// * `multisig` - Multisig account whose history to walk
+    /// * `from_signature` - Last processed signature of an earlier run, from its
+    ///   [`Checkpoint`] (None to start at the newest transaction)
+    /// * `program_id` - Optional custom program ID (uses canonical ID if None)
+    /// * `config` - Paging and pacing
+    /// * `on_page` - Called with the records and checkpoint of each page
+    pub async fn backfill<F>(
+        rpc: &RpcClient,
+        multisig: &Pubkey,
+        from_signature: Option<Signature>,
+        program_id: Option<&Pubkey>,
+        config: &BackfillConfig,
+        mut on_page: F,
+    ) -> SquadsResult<BackfillSummary>
+    where
+        F: FnMut(&[TransactionRecord], &Checkpoint) -> SquadsResult<()>,
+    {
+        let page_size = config.page_size.clamp(1, MAX_PAGE_SIZE);
+        let mut throttle = Throttle {
+            config,
+            next_request: None,
+        };
+        let mut summary = BackfillSummary::default();
+        let mut before = from_signature;
+
+        loop {
+            if config.max_pages.is_some_and(|max| summary.pages >= max) {
+                break;
+            }
+
+            let statuses = throttle
+                .request(|| async {
+                    let page_config = GetConfirmedSignaturesForAddress2Config {
+                        before,
+                        until: config.until,
+                        limit: Some(page_size),
+                        commitment: Some(CommitmentConfig::confirmed()),
+                    };
+                    Ok(rpc
+                        .get_signatures_for_address_with_config(multisig, page_config)
+                        .await?)
+                })
+                .await?;
+            summary.pages += 1;
+
+            let mut records = Vec::with_capacity(statuses.len());
+            for status in &statuses {
+                let signature: Signature = status.signature.parse().map_err(|_| {
+                    SquadsError::EncodingError(format!("invalid signature {}", status.signature))
+                })?;
+                let confirmed = throttle
+                    .request(|| async {
+                        let tx_config = RpcTransactionConfig {
+                            encoding: Some(UiTransactionEncoding::Base64),
+                            commitment: Some(CommitmentConfig::confirmed()),
+                            max_supported_transaction_version: Some(0),
+                        };
+                        Ok(rpc
+                            .get_transaction_with_config(&signature, tx_config)
+                            .await?)
+                    })
+                    .await?;
+                records.push(TransactionRecord::from_confirmed(
+                    signature, &confirmed, program_id,
+                )?);
+            }
+
+            if let Some(last) = records.last() {
+                let next = Checkpoint {
+                    last_signature: last.signature,
+                    slot: last.slot,
+                };
+                on_page(&records, &next)?;
+                summary.processed += records.len() as u64;
+                before = Some(next.last_signature);
+                summary.checkpoint = Some(next);
+            }
+
+            if statuses.len() < page_size {
+                summary.complete = true;
+                break;
+            }
+        }
+
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_and_checkpoint() {
+        let config = BackfillConfig {
+            retry_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(3),
+            ..BackfillConfig::default()
+        };
+        let delays: Vec<_> = (1..=5).map(|attempt| config.backoff(attempt)).collect();
+        assert_eq!(
+            delays,
+            [500, 1_000, 2_000, 3_000, 3_000].map(Duration::from_millis)
+        );
+        assert_eq!(config.backoff(64), Duration::from_secs(3));
+
+        let checkpoint = Checkpoint {
+            last_signature: Signature::from([7u8; 64]),
+            slot: 42,
+        };
+        let json = serde_json::to_string(&checkpoint).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Checkpoint>(&json).unwrap(),
+            checkpoint
+        );
+    }
+}
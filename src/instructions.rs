@@ -4,30 +4,38 @@
 //! the Squads multisig program. Each function creates a properly formatted instruction
 //! with the correct accounts and instruction data.
 
-use borsh::BorshSerialize;
+use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "serde-args")]
+use serde::Deserialize;
 use solana_sdk::{
+    hash::hash,
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
 };
 use solana_sdk_ids::system_program;
 
-use crate::types::{ConfigAction, Member};
+use crate::dialect;
+use crate::error::{SquadsError, SquadsResult};
+use crate::memo::{check_memo, memo_instruction, MemoPolicy};
+use crate::message::TransactionMessage;
+use crate::types::{ConfigAction, Member, Period};
 
 /// Helper function to compute Anchor instruction discriminator
 /// Discriminator is the first 8 bytes of SHA256("global:instruction_name")
-fn instruction_discriminator(name: &str) -> [u8; 8] {
-    use solana_sdk::hash::hash;
-    let preimage = format!("global:{}", name);
-    let hash_result = hash(preimage.as_bytes());
-    let mut discriminator = [0u8; 8];
-    discriminator.copy_from_slice(&hash_result.to_bytes()[..8]);
-    discriminator
+#[cfg(test)]
+pub(crate) fn instruction_discriminator(name: &str) -> [u8; 8] {
+    dialect::discriminator_from(dialect::DEFAULT_NAMESPACE, name)
 }
 
 /// Arguments for creating a multisig
-#[derive(Debug, Clone, BorshSerialize)]
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
 pub struct MultisigCreateArgsV2 {
     /// Config authority (None for autonomous multisig)
+    #[cfg_attr(
+        feature = "serde-args",
+        serde(default, deserialize_with = "crate::serde_utils::option_pubkey")
+    )]
     pub config_authority: Option<Pubkey>,
     /// Approval threshold
     pub threshold: u16,
@@ -36,11 +44,36 @@ pub struct MultisigCreateArgsV2 {
     /// Time lock in seconds
     pub time_lock: u32,
     /// Rent collector (None to disable rent reclamation)
+    #[cfg_attr(
+        feature = "serde-args",
+        serde(default, deserialize_with = "crate::serde_utils::option_pubkey")
+    )]
     pub rent_collector: Option<Pubkey>,
     /// Optional memo for indexing
     pub memo: Option<String>,
 }
 
+impl MultisigCreateArgsV2 {
+    /// Create multisig arguments, rejecting memos over [`crate::memo::MAX_MEMO_LENGTH`]
+    pub fn new(
+        config_authority: Option<Pubkey>,
+        threshold: u16,
+        members: Vec<Member>,
+        time_lock: u32,
+        rent_collector: Option<Pubkey>,
+        memo: Option<String>,
+    ) -> SquadsResult<Self> {
+        Ok(Self {
+            config_authority,
+            threshold,
+            members,
+            time_lock,
+            rent_collector,
+            memo: check_memo(memo, MemoPolicy::Error)?,
+        })
+    }
+}
+
 /// Create a new multisig
 ///
 /// # Arguments
@@ -71,7 +104,7 @@ pub fn multisig_create_v2(
         AccountMeta::new_readonly(system_program::ID, false),
     ];
 
-    let mut data = instruction_discriminator("multisig_create_v2").to_vec();
+    let mut data = dialect::instruction_discriminator(&program_id, "multisig_create_v2").to_vec();
     args.serialize(&mut data).unwrap();
 
     Instruction {
@@ -81,43 +114,70 @@ pub fn multisig_create_v2(
     }
 }
 
-/// Arguments for creating a proposal
-#[derive(Debug, Clone, BorshSerialize)]
-pub struct ProposalCreateArgs {
-    /// Transaction index this proposal is for
-    pub transaction_index: u64,
-    /// Whether to create as draft
-    pub draft: bool,
+/// Arguments for the legacy `multisig_create` instruction
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
+pub struct MultisigCreateArgs {
+    /// Config authority (None for autonomous multisig)
+    #[cfg_attr(
+        feature = "serde-args",
+        serde(default, deserialize_with = "crate::serde_utils::option_pubkey")
+    )]
+    pub config_authority: Option<Pubkey>,
+    /// Approval threshold
+    pub threshold: u16,
+    /// Members of the multisig
+    pub members: Vec<Member>,
+    /// Time lock in seconds
+    pub time_lock: u32,
+    /// Optional memo for indexing
+    pub memo: Option<String>,
 }
 
-/// Create a new proposal for a transaction
+impl From<MultisigCreateArgs> for MultisigCreateArgsV2 {
+    /// The same multisig created with `multisig_create_v2`, with rent reclamation disabled
+    fn from(args: MultisigCreateArgs) -> Self {
+        Self {
+            config_authority: args.config_authority,
+            threshold: args.threshold,
+            members: args.members,
+            time_lock: args.time_lock,
+            rent_collector: None,
+            memo: args.memo,
+        }
+    }
+}
+
+/// Create a new multisig with the legacy v1 instruction
+///
+/// Current deployments of the Squads program reject `multisig_create`; it only works
+/// against older deployments that predate the program config. Use
+/// [`multisig_create_v2`] for anything else.
 ///
 /// # Arguments
-/// * `multisig` - Multisig account
-/// * `proposal` - Proposal PDA to create
-/// * `creator` - Proposal creator (must be member)
-/// * `rent_payer` - Rent payer for the proposal account
-/// * `args` - Proposal creation arguments
+/// * `multisig` - Multisig PDA to create
+/// * `create_key` - Unique key for multisig PDA derivation (must be signer)
+/// * `creator` - Creator and fee payer
+/// * `args` - Multisig creation arguments
 /// * `program_id` - Optional custom program ID
-pub fn proposal_create(
+#[deprecated(note = "rejected by current program deployments, use `multisig_create_v2`")]
+pub fn multisig_create(
     multisig: Pubkey,
-    proposal: Pubkey,
+    create_key: Pubkey,
     creator: Pubkey,
-    rent_payer: Pubkey,
-    args: ProposalCreateArgs,
+    args: MultisigCreateArgs,
     program_id: Option<Pubkey>,
 ) -> Instruction {
     let program_id = program_id.unwrap_or_else(crate::program_id);
 
     let accounts = vec![
-        AccountMeta::new_readonly(multisig, false),
-        AccountMeta::new(proposal, false),
-        AccountMeta::new_readonly(creator, true),
-        AccountMeta::new(rent_payer, true),
+        AccountMeta::new(multisig, false),
+        AccountMeta::new_readonly(create_key, true),
+        AccountMeta::new(creator, true),
         AccountMeta::new_readonly(system_program::ID, false),
     ];
 
-    let mut data = instruction_discriminator("proposal_create").to_vec();
+    let mut data = dialect::instruction_discriminator(&program_id, "multisig_create").to_vec();
     args.serialize(&mut data).unwrap();
 
     Instruction {
@@ -127,37 +187,54 @@ pub fn proposal_create(
     }
 }
 
-/// Arguments for voting on a proposal
-#[derive(Debug, Clone, BorshSerialize)]
-pub struct ProposalVoteArgs {
-    /// Optional memo
+/// Arguments for adding a member to a controlled multisig
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
+pub struct MultisigAddMemberArgs {
+    /// Member to add
+    pub new_member: Member,
+    /// Optional memo for indexing
     pub memo: Option<String>,
 }
 
-/// Approve a proposal
+impl MultisigAddMemberArgs {
+    /// Create add member arguments, rejecting memos over [`crate::memo::MAX_MEMO_LENGTH`]
+    pub fn new(new_member: Member, memo: Option<String>) -> SquadsResult<Self> {
+        Ok(Self {
+            new_member,
+            memo: check_memo(memo, MemoPolicy::Error)?,
+        })
+    }
+}
+
+/// Add a member to a controlled multisig directly, without a config transaction
+///
+/// Only valid for multisigs with a config authority. The multisig account is
+/// reallocated if needed, with `rent_payer` funding the extra space.
 ///
 /// # Arguments
 /// * `multisig` - Multisig account
-/// * `proposal` - Proposal to approve
-/// * `member` - Member voting (must have Vote permission)
-/// * `args` - Vote arguments
+/// * `config_authority` - Config authority of the multisig (must be signer)
+/// * `rent_payer` - Pays for reallocating the multisig account (must be signer)
+/// * `args` - Add member arguments
 /// * `program_id` - Optional custom program ID
-pub fn proposal_approve(
+pub fn multisig_add_member(
     multisig: Pubkey,
-    proposal: Pubkey,
-    member: Pubkey,
-    args: ProposalVoteArgs,
+    config_authority: Pubkey,
+    rent_payer: Pubkey,
+    args: MultisigAddMemberArgs,
     program_id: Option<Pubkey>,
 ) -> Instruction {
     let program_id = program_id.unwrap_or_else(crate::program_id);
 
     let accounts = vec![
-        AccountMeta::new_readonly(multisig, false),
-        AccountMeta::new(member, true),
-        AccountMeta::new(proposal, false),
+        AccountMeta::new(multisig, false),
+        AccountMeta::new_readonly(config_authority, true),
+        AccountMeta::new(rent_payer, true),
+        AccountMeta::new_readonly(system_program::ID, false),
     ];
 
-    let mut data = instruction_discriminator("proposal_approve").to_vec();
+    let mut data = dialect::instruction_discriminator(&program_id, "multisig_add_member").to_vec();
     args.serialize(&mut data).unwrap();
 
     Instruction {
@@ -167,30 +244,58 @@ pub fn proposal_approve(
     }
 }
 
-/// Reject a proposal
+/// Arguments for removing a member from a controlled multisig
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
+pub struct MultisigRemoveMemberArgs {
+    /// Key of the member to remove
+    #[cfg_attr(
+        feature = "serde-args",
+        serde(deserialize_with = "crate::serde_utils::pubkey")
+    )]
+    pub old_member: Pubkey,
+    /// Optional memo for indexing
+    pub memo: Option<String>,
+}
+
+impl MultisigRemoveMemberArgs {
+    /// Create remove member arguments, rejecting memos over [`crate::memo::MAX_MEMO_LENGTH`]
+    pub fn new(old_member: Pubkey, memo: Option<String>) -> SquadsResult<Self> {
+        Ok(Self {
+            old_member,
+            memo: check_memo(memo, MemoPolicy::Error)?,
+        })
+    }
+}
+
+/// Remove a member from a controlled multisig directly, without a config transaction
+///
+/// Only valid for multisigs with a config authority. Removing a member never grows the
+/// account, so the optional rent payer and system program are passed as absent.
 ///
 /// # Arguments
 /// * `multisig` - Multisig account
-/// * `proposal` - Proposal to reject
-/// * `member` - Member voting (must have Vote permission)
-/// * `args` - Vote arguments
+/// * `config_authority` - Config authority of the multisig (must be signer)
+/// * `args` - Remove member arguments
 /// * `program_id` - Optional custom program ID
-pub fn proposal_reject(
+pub fn multisig_remove_member(
     multisig: Pubkey,
-    proposal: Pubkey,
-    member: Pubkey,
-    args: ProposalVoteArgs,
+    config_authority: Pubkey,
+    args: MultisigRemoveMemberArgs,
     program_id: Option<Pubkey>,
 ) -> Instruction {
     let program_id = program_id.unwrap_or_else(crate::program_id);
 
+    // Optional accounts are passed as the program ID when absent
     let accounts = vec![
-        AccountMeta::new_readonly(multisig, false),
-        AccountMeta::new(member, true),
-        AccountMeta::new(proposal, false),
+        AccountMeta::new(multisig, false),
+        AccountMeta::new_readonly(config_authority, true),
+        AccountMeta::new_readonly(program_id, false),
+        AccountMeta::new_readonly(program_id, false),
     ];
 
-    let mut data = instruction_discriminator("proposal_reject").to_vec();
+    let mut data =
+        dialect::instruction_discriminator(&program_id, "multisig_remove_member").to_vec();
     args.serialize(&mut data).unwrap();
 
     Instruction {
@@ -200,30 +305,53 @@ pub fn proposal_reject(
     }
 }
 
-/// Cancel an approved proposal
+/// Arguments for changing the time lock of a controlled multisig
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
+pub struct MultisigSetTimeLockArgs {
+    /// New time lock in seconds
+    pub time_lock: u32,
+    /// Optional memo for indexing
+    pub memo: Option<String>,
+}
+
+impl MultisigSetTimeLockArgs {
+    /// Create set time lock arguments, rejecting memos over [`crate::memo::MAX_MEMO_LENGTH`]
+    pub fn new(time_lock: u32, memo: Option<String>) -> SquadsResult<Self> {
+        Ok(Self {
+            time_lock,
+            memo: check_memo(memo, MemoPolicy::Error)?,
+        })
+    }
+}
+
+/// Change the time lock of a controlled multisig directly, without a config transaction
+///
+/// The config transaction equivalent is [`ConfigAction::SetTimeLock`].
 ///
 /// # Arguments
 /// * `multisig` - Multisig account
-/// * `proposal` - Proposal to cancel (must be Approved)
-/// * `member` - Member voting (must have Vote permission)
-/// * `args` - Vote arguments
+/// * `config_authority` - Config authority of the multisig (must be signer)
+/// * `args` - Set time lock arguments
 /// * `program_id` - Optional custom program ID
-pub fn proposal_cancel(
+pub fn multisig_set_time_lock(
     multisig: Pubkey,
-    proposal: Pubkey,
-    member: Pubkey,
-    args: ProposalVoteArgs,
+    config_authority: Pubkey,
+    args: MultisigSetTimeLockArgs,
     program_id: Option<Pubkey>,
 ) -> Instruction {
     let program_id = program_id.unwrap_or_else(crate::program_id);
 
+    // Optional accounts are passed as the program ID when absent
     let accounts = vec![
-        AccountMeta::new_readonly(multisig, false),
-        AccountMeta::new(member, true),
-        AccountMeta::new(proposal, false),
+        AccountMeta::new(multisig, false),
+        AccountMeta::new_readonly(config_authority, true),
+        AccountMeta::new_readonly(program_id, false),
+        AccountMeta::new_readonly(program_id, false),
     ];
 
-    let mut data = instruction_discriminator("proposal_cancel").to_vec();
+    let mut data =
+        dialect::instruction_discriminator(&program_id, "multisig_set_time_lock").to_vec();
     args.serialize(&mut data).unwrap();
 
     Instruction {
@@ -233,47 +361,75 @@ pub fn proposal_cancel(
     }
 }
 
-/// Arguments for creating a vault transaction
-#[derive(Debug, Clone, BorshSerialize)]
-pub struct VaultTransactionCreateArgs {
-    /// Vault index
-    pub vault_index: u8,
-    /// Number of ephemeral signers
-    pub ephemeral_signers: u8,
-    /// Serialized transaction message
-    pub transaction_message: Vec<u8>,
-    /// Optional memo
+/// Arguments for changing the rent collector of a controlled multisig
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
+pub struct MultisigSetRentCollectorArgs {
+    /// New rent collector (None to disable rent reclamation)
+    #[cfg_attr(
+        feature = "serde-args",
+        serde(default, deserialize_with = "crate::serde_utils::option_pubkey")
+    )]
+    pub rent_collector: Option<Pubkey>,
+    /// Optional memo for indexing
     pub memo: Option<String>,
 }
 
-/// Create a new vault transaction
+impl MultisigSetRentCollectorArgs {
+    /// Create set rent collector arguments, rejecting memos over [`crate::memo::MAX_MEMO_LENGTH`]
+    pub fn new(rent_collector: Option<Pubkey>, memo: Option<String>) -> SquadsResult<Self> {
+        Ok(Self {
+            rent_collector,
+            memo: check_memo(memo, MemoPolicy::Error)?,
+        })
+    }
+}
+
+/// Change the rent collector of a controlled multisig directly, without a config transaction
+///
+/// Setting a rent collector enables reclaiming rent from executed, rejected and
+/// cancelled transaction accounts. The config transaction equivalent is
+/// [`ConfigAction::SetRentCollector`].
+///
+/// Setting a rent collector on a multisig without one grows the account, so the
+/// program needs a rent payer and the system program for the realloc.
 ///
 /// # Arguments
 /// * `multisig` - Multisig account
-/// * `transaction` - Transaction PDA to create
-/// * `creator` - Transaction creator (must have Initiate permission)
-/// * `rent_payer` - Rent payer for the transaction account
-/// * `args` - Transaction creation arguments
+/// * `config_authority` - Config authority of the multisig (must be signer)
+/// * `rent_payer` - Pays for reallocating the multisig account (must be signer), None if
+///   the account does not grow
+/// * `args` - Set rent collector arguments
 /// * `program_id` - Optional custom program ID
-pub fn vault_transaction_create(
+pub fn multisig_set_rent_collector(
     multisig: Pubkey,
-    transaction: Pubkey,
-    creator: Pubkey,
-    rent_payer: Pubkey,
-    args: VaultTransactionCreateArgs,
+    config_authority: Pubkey,
+    rent_payer: Option<Pubkey>,
+    args: MultisigSetRentCollectorArgs,
     program_id: Option<Pubkey>,
 ) -> Instruction {
     let program_id = program_id.unwrap_or_else(crate::program_id);
 
+    // Optional accounts are passed as the program ID when absent
     let accounts = vec![
         AccountMeta::new(multisig, false),
-        AccountMeta::new(transaction, false),
-        AccountMeta::new_readonly(creator, true),
-        AccountMeta::new(rent_payer, true),
-        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new_readonly(config_authority, true),
+        match rent_payer {
+            Some(rent_payer) => AccountMeta::new(rent_payer, true),
+            None => AccountMeta::new_readonly(program_id, false),
+        },
+        AccountMeta::new_readonly(
+            if rent_payer.is_some() {
+                system_program::ID
+            } else {
+                program_id
+            },
+            false,
+        ),
     ];
 
-    let mut data = instruction_discriminator("vault_transaction_create").to_vec();
+    let mut data =
+        dialect::instruction_discriminator(&program_id, "multisig_set_rent_collector").to_vec();
     args.serialize(&mut data).unwrap();
 
     Instruction {
@@ -283,34 +439,102 @@ pub fn vault_transaction_create(
     }
 }
 
-/// Execute a vault transaction
+/// Arguments for adding a spending limit to a controlled multisig
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
+pub struct MultisigAddSpendingLimitArgs {
+    /// Unique key used to derive the spending limit PDA
+    #[cfg_attr(
+        feature = "serde-args",
+        serde(deserialize_with = "crate::serde_utils::pubkey")
+    )]
+    pub create_key: Pubkey,
+    /// Vault index the limit applies to
+    pub vault_index: u8,
+    /// Token mint (`Pubkey::default()` for SOL)
+    #[cfg_attr(
+        feature = "serde-args",
+        serde(deserialize_with = "crate::serde_utils::pubkey")
+    )]
+    pub mint: Pubkey,
+    /// Amount that can be spent per period
+    pub amount: u64,
+    /// Reset period of the limit
+    pub period: Period,
+    /// Members who can use the limit
+    #[cfg_attr(
+        feature = "serde-args",
+        serde(deserialize_with = "crate::serde_utils::vec_pubkey")
+    )]
+    pub members: Vec<Pubkey>,
+    /// Allowed destinations (empty allows any destination)
+    #[cfg_attr(
+        feature = "serde-args",
+        serde(deserialize_with = "crate::serde_utils::vec_pubkey")
+    )]
+    pub destinations: Vec<Pubkey>,
+    /// Optional memo for indexing
+    pub memo: Option<String>,
+}
+
+impl MultisigAddSpendingLimitArgs {
+    /// Create add spending limit arguments, rejecting memos over [`crate::memo::MAX_MEMO_LENGTH`]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        create_key: Pubkey,
+        vault_index: u8,
+        mint: Pubkey,
+        amount: u64,
+        period: Period,
+        members: Vec<Pubkey>,
+        destinations: Vec<Pubkey>,
+        memo: Option<String>,
+    ) -> SquadsResult<Self> {
+        Ok(Self {
+            create_key,
+            vault_index,
+            mint,
+            amount,
+            period,
+            members,
+            destinations,
+            memo: check_memo(memo, MemoPolicy::Error)?,
+        })
+    }
+}
+
+/// Add a spending limit to a controlled multisig directly, without a config transaction
+///
+/// The config transaction equivalent is [`ConfigAction::AddSpendingLimit`].
 ///
 /// # Arguments
 /// * `multisig` - Multisig account
-/// * `proposal` - Proposal for the transaction (must be Approved)
-/// * `transaction` - Transaction to execute
-/// * `member` - Member executing (must have Execute permission)
-/// * `remaining_accounts` - Accounts required by the transaction (lookup tables + instruction accounts)
+/// * `config_authority` - Config authority of the multisig (must be signer)
+/// * `spending_limit` - Spending limit PDA (see [`crate::pda::get_spending_limit_pda`] with `args.create_key`)
+/// * `rent_payer` - Pays for the spending limit account (must be signer)
+/// * `args` - Add spending limit arguments
 /// * `program_id` - Optional custom program ID
-pub fn vault_transaction_execute(
+pub fn multisig_add_spending_limit(
     multisig: Pubkey,
-    proposal: Pubkey,
-    transaction: Pubkey,
-    member: Pubkey,
-    remaining_accounts: Vec<AccountMeta>,
+    config_authority: Pubkey,
+    spending_limit: Pubkey,
+    rent_payer: Pubkey,
+    args: MultisigAddSpendingLimitArgs,
     program_id: Option<Pubkey>,
 ) -> Instruction {
     let program_id = program_id.unwrap_or_else(crate::program_id);
 
-    let mut accounts = vec![
+    let accounts = vec![
         AccountMeta::new_readonly(multisig, false),
-        AccountMeta::new(proposal, false),
-        AccountMeta::new_readonly(transaction, false),
-        AccountMeta::new_readonly(member, true),
+        AccountMeta::new_readonly(config_authority, true),
+        AccountMeta::new(spending_limit, false),
+        AccountMeta::new(rent_payer, true),
+        AccountMeta::new_readonly(system_program::ID, false),
     ];
-    accounts.extend(remaining_accounts);
 
-    let data = instruction_discriminator("vault_transaction_execute").to_vec();
+    let mut data =
+        dialect::instruction_discriminator(&program_id, "multisig_add_spending_limit").to_vec();
+    args.serialize(&mut data).unwrap();
 
     Instruction {
         program_id,
@@ -319,43 +543,54 @@ pub fn vault_transaction_execute(
     }
 }
 
-/// Arguments for creating a config transaction
-#[derive(Debug, Clone, BorshSerialize)]
-pub struct ConfigTransactionCreateArgs {
-    /// Configuration actions to execute
-    pub actions: Vec<ConfigAction>,
-    /// Optional memo
+/// Arguments for removing a spending limit from a controlled multisig
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
+pub struct MultisigRemoveSpendingLimitArgs {
+    /// Optional memo for indexing
     pub memo: Option<String>,
 }
 
-/// Create a new config transaction
+impl MultisigRemoveSpendingLimitArgs {
+    /// Create remove spending limit arguments, rejecting memos over [`crate::memo::MAX_MEMO_LENGTH`]
+    pub fn new(memo: Option<String>) -> SquadsResult<Self> {
+        Ok(Self {
+            memo: check_memo(memo, MemoPolicy::Error)?,
+        })
+    }
+}
+
+/// Remove a spending limit from a controlled multisig directly, without a config transaction
+///
+/// The spending limit account is closed and its rent sent to `rent_collector`. The
+/// config transaction equivalent is [`ConfigAction::RemoveSpendingLimit`].
 ///
 /// # Arguments
 /// * `multisig` - Multisig account
-/// * `transaction` - Config transaction PDA to create
-/// * `creator` - Transaction creator
-/// * `rent_payer` - Rent payer for the transaction account
-/// * `args` - Config transaction creation arguments
+/// * `config_authority` - Config authority of the multisig (must be signer)
+/// * `spending_limit` - Spending limit account to remove
+/// * `rent_collector` - Receives the rent of the closed spending limit account
+/// * `args` - Remove spending limit arguments
 /// * `program_id` - Optional custom program ID
-pub fn config_transaction_create(
+pub fn multisig_remove_spending_limit(
     multisig: Pubkey,
-    transaction: Pubkey,
-    creator: Pubkey,
-    rent_payer: Pubkey,
-    args: ConfigTransactionCreateArgs,
+    config_authority: Pubkey,
+    spending_limit: Pubkey,
+    rent_collector: Pubkey,
+    args: MultisigRemoveSpendingLimitArgs,
     program_id: Option<Pubkey>,
 ) -> Instruction {
     let program_id = program_id.unwrap_or_else(crate::program_id);
 
     let accounts = vec![
         AccountMeta::new_readonly(multisig, false),
-        AccountMeta::new_readonly(creator, true),
-        AccountMeta::new(rent_payer, true),
-        AccountMeta::new(transaction, false),
-        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new_readonly(config_authority, true),
+        AccountMeta::new(spending_limit, false),
+        AccountMeta::new(rent_collector, false),
     ];
 
-    let mut data = instruction_discriminator("config_transaction_create").to_vec();
+    let mut data =
+        dialect::instruction_discriminator(&program_id, "multisig_remove_spending_limit").to_vec();
     args.serialize(&mut data).unwrap();
 
     Instruction {
@@ -365,50 +600,45 @@ pub fn config_transaction_create(
     }
 }
 
-/// Execute a config transaction
+/// Arguments for creating a proposal
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
+pub struct ProposalCreateArgs {
+    /// Transaction index this proposal is for
+    pub transaction_index: u64,
+    /// Whether to create as draft
+    pub draft: bool,
+}
+
+/// Create a new proposal for a transaction
 ///
 /// # Arguments
 /// * `multisig` - Multisig account
-/// * `proposal` - Proposal for the transaction (must be Approved)
-/// * `transaction` - Config transaction to execute
-/// * `member` - Member executing (must have Execute permission)
-/// * `rent_payer` - Optional rent payer for reallocation
-/// * `spending_limit_accounts` - Optional spending limit accounts being added/removed
+/// * `proposal` - Proposal PDA to create
+/// * `creator` - Proposal creator (must be member)
+/// * `rent_payer` - Rent payer for the proposal account
+/// * `args` - Proposal creation arguments
 /// * `program_id` - Optional custom program ID
-pub fn config_transaction_execute(
+pub fn proposal_create(
     multisig: Pubkey,
     proposal: Pubkey,
-    transaction: Pubkey,
-    member: Pubkey,
-    rent_payer: Option<Pubkey>,
-    spending_limit_accounts: Vec<Pubkey>,
+    creator: Pubkey,
+    rent_payer: Pubkey,
+    args: ProposalCreateArgs,
     program_id: Option<Pubkey>,
 ) -> Instruction {
     let program_id = program_id.unwrap_or_else(crate::program_id);
 
-    let mut accounts = vec![
-        AccountMeta::new(multisig, false),
-        AccountMeta::new_readonly(member, true),
+    let accounts = vec![
+        AccountMeta::new_readonly(multisig, false),
         AccountMeta::new(proposal, false),
-        AccountMeta::new(transaction, false),
+        AccountMeta::new_readonly(creator, true),
+        AccountMeta::new(rent_payer, true),
+        AccountMeta::new_readonly(system_program::ID, false),
     ];
 
-    // Add rent_payer if provided
-    if let Some(rent_payer) = rent_payer {
-        accounts.push(AccountMeta::new(rent_payer, true));
-    } else {
-        accounts.push(AccountMeta::new_readonly(program_id, false));
-    }
-
-    // Add system_program
-    accounts.push(AccountMeta::new_readonly(system_program::ID, false));
-
-    // Add spending limit accounts
-    for spending_limit in spending_limit_accounts {
-        accounts.push(AccountMeta::new(spending_limit, false));
-    }
-
-    let data = instruction_discriminator("config_transaction_execute").to_vec();
+    let mut data = dialect::instruction_discriminator(&program_id, "proposal_create").to_vec();
+    args.serialize(&mut data).unwrap();
 
     Instruction {
         program_id,
@@ -417,10 +647,850 @@ pub fn config_transaction_execute(
     }
 }
 
-/// Arguments for activating a draft proposal
-#[derive(Debug, Clone, BorshSerialize)]
-pub struct ProposalActivateArgs {}
-
+/// Arguments for voting on a proposal
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
+pub struct ProposalVoteArgs {
+    /// Optional memo
+    pub memo: Option<String>,
+}
+
+impl ProposalVoteArgs {
+    /// Create vote arguments, rejecting memos over [`crate::memo::MAX_MEMO_LENGTH`]
+    pub fn new(memo: Option<String>) -> SquadsResult<Self> {
+        Self::with_memo_policy(memo, MemoPolicy::Error)
+    }
+
+    /// Create vote arguments, handling long memos according to `policy`
+    pub fn with_memo_policy(memo: Option<String>, policy: MemoPolicy) -> SquadsResult<Self> {
+        Ok(Self {
+            memo: check_memo(memo, policy)?,
+        })
+    }
+}
+
+/// Options for voting helpers that can also emit an SPL Memo instruction
+///
+/// Squads vote memos are only visible in the vote instruction data. Enabling
+/// `spl_memo` additionally emits the memo through the SPL Memo program in the same
+/// transaction, signed by the voter, for audit trails that index on-chain memos.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VoteOptions {
+    /// Memo passed in the vote arguments
+    pub memo: Option<String>,
+    /// Also emit the memo as an SPL Memo instruction
+    pub spl_memo: bool,
+}
+
+impl VoteOptions {
+    /// Vote with a memo in the vote arguments only
+    pub fn with_memo(memo: impl Into<String>) -> Self {
+        Self {
+            memo: Some(memo.into()),
+            spl_memo: false,
+        }
+    }
+
+    /// Also emit the memo as an SPL Memo instruction
+    pub fn with_spl_memo(mut self) -> Self {
+        self.spl_memo = true;
+        self
+    }
+
+    /// Vote arguments for these options, rejecting memos over
+    /// [`crate::memo::MAX_MEMO_LENGTH`]
+    pub fn args(&self) -> SquadsResult<ProposalVoteArgs> {
+        ProposalVoteArgs::new(self.memo.clone())
+    }
+
+    /// Instructions for a vote: the SPL Memo instruction, if enabled and a memo is set,
+    /// followed by `vote`
+    ///
+    /// # Arguments
+    /// * `vote` - Approve, reject or cancel instruction built from [`Self::args`]
+    /// * `member` - Member voting, who also signs the SPL Memo instruction
+    pub fn instructions(&self, vote: Instruction, member: &Pubkey) -> Vec<Instruction> {
+        let mut instructions = Vec::with_capacity(2);
+        if let (true, Some(memo)) = (self.spl_memo, &self.memo) {
+            instructions.push(memo_instruction(memo, std::slice::from_ref(member)));
+        }
+        instructions.push(vote);
+        instructions
+    }
+}
+
+/// Approve a proposal
+///
+/// # Arguments
+/// * `multisig` - Multisig account
+/// * `proposal` - Proposal to approve
+/// * `member` - Member voting (must have Vote permission)
+/// * `args` - Vote arguments
+/// * `program_id` - Optional custom program ID
+pub fn proposal_approve(
+    multisig: Pubkey,
+    proposal: Pubkey,
+    member: Pubkey,
+    args: ProposalVoteArgs,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    let program_id = program_id.unwrap_or_else(crate::program_id);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(multisig, false),
+        AccountMeta::new(member, true),
+        AccountMeta::new(proposal, false),
+    ];
+
+    let mut data = dialect::instruction_discriminator(&program_id, "proposal_approve").to_vec();
+    args.serialize(&mut data).unwrap();
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Reject a proposal
+///
+/// # Arguments
+/// * `multisig` - Multisig account
+/// * `proposal` - Proposal to reject
+/// * `member` - Member voting (must have Vote permission)
+/// * `args` - Vote arguments
+/// * `program_id` - Optional custom program ID
+pub fn proposal_reject(
+    multisig: Pubkey,
+    proposal: Pubkey,
+    member: Pubkey,
+    args: ProposalVoteArgs,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    let program_id = program_id.unwrap_or_else(crate::program_id);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(multisig, false),
+        AccountMeta::new(member, true),
+        AccountMeta::new(proposal, false),
+    ];
+
+    let mut data = dialect::instruction_discriminator(&program_id, "proposal_reject").to_vec();
+    args.serialize(&mut data).unwrap();
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Cancel an approved proposal
+///
+/// # Arguments
+/// * `multisig` - Multisig account
+/// * `proposal` - Proposal to cancel (must be Approved)
+/// * `member` - Member voting (must have Vote permission)
+/// * `args` - Vote arguments
+/// * `program_id` - Optional custom program ID
+pub fn proposal_cancel(
+    multisig: Pubkey,
+    proposal: Pubkey,
+    member: Pubkey,
+    args: ProposalVoteArgs,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    let program_id = program_id.unwrap_or_else(crate::program_id);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(multisig, false),
+        AccountMeta::new(member, true),
+        AccountMeta::new(proposal, false),
+    ];
+
+    let mut data = dialect::instruction_discriminator(&program_id, "proposal_cancel").to_vec();
+    args.serialize(&mut data).unwrap();
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Cancel an approved proposal, growing the proposal account if needed
+///
+/// Newer program versions add `proposal_cancel_v2`, which takes the system program
+/// after the [`proposal_cancel`] accounts so the proposal can be reallocated (paid for by
+/// `member`) when the multisig gained members after the proposal was created. Use the v1
+/// builder against deployments that predate it.
+///
+/// # Arguments
+/// * `multisig` - Multisig account
+/// * `proposal` - Proposal to cancel (must be Approved)
+/// * `member` - Member voting (must have Vote permission, pays for any reallocation)
+/// * `args` - Vote arguments
+/// * `program_id` - Optional custom program ID
+pub fn proposal_cancel_v2(
+    multisig: Pubkey,
+    proposal: Pubkey,
+    member: Pubkey,
+    args: ProposalVoteArgs,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    let program_id = program_id.unwrap_or_else(crate::program_id);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(multisig, false),
+        AccountMeta::new(member, true),
+        AccountMeta::new(proposal, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+
+    let mut data = dialect::instruction_discriminator(&program_id, "proposal_cancel_v2").to_vec();
+    args.serialize(&mut data).unwrap();
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Arguments for creating a vault transaction
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
+pub struct VaultTransactionCreateArgs {
+    /// Vault index
+    pub vault_index: u8,
+    /// Number of ephemeral signers
+    pub ephemeral_signers: u8,
+    /// Serialized transaction message
+    pub transaction_message: Vec<u8>,
+    /// Optional memo
+    pub memo: Option<String>,
+}
+
+impl VaultTransactionCreateArgs {
+    /// Create vault transaction arguments, rejecting memos over [`crate::memo::MAX_MEMO_LENGTH`]
+    pub fn new(
+        vault_index: u8,
+        ephemeral_signers: u8,
+        transaction_message: Vec<u8>,
+        memo: Option<String>,
+    ) -> SquadsResult<Self> {
+        Ok(Self {
+            vault_index,
+            ephemeral_signers,
+            transaction_message,
+            memo: check_memo(memo, MemoPolicy::Error)?,
+        })
+    }
+
+    /// Create arguments for [`vault_transaction_create_from_buffer`]
+    ///
+    /// The message is read from the buffer; the program expects an empty placeholder
+    /// message in the arguments.
+    pub fn from_buffer(
+        vault_index: u8,
+        ephemeral_signers: u8,
+        memo: Option<String>,
+    ) -> SquadsResult<Self> {
+        Self::new(
+            vault_index,
+            ephemeral_signers,
+            BUFFERED_MESSAGE_PLACEHOLDER.to_vec(),
+            memo,
+        )
+    }
+}
+
+/// Serialized empty message passed in place of a buffered message: three zero header
+/// bytes and three empty vectors with single-byte lengths
+pub const BUFFERED_MESSAGE_PLACEHOLDER: [u8; 6] = [0; 6];
+
+/// Create a new vault transaction
+///
+/// # Arguments
+/// * `multisig` - Multisig account
+/// * `transaction` - Transaction PDA to create
+/// * `creator` - Transaction creator (must have Initiate permission)
+/// * `rent_payer` - Rent payer for the transaction account
+/// * `args` - Transaction creation arguments
+/// * `program_id` - Optional custom program ID
+pub fn vault_transaction_create(
+    multisig: Pubkey,
+    transaction: Pubkey,
+    creator: Pubkey,
+    rent_payer: Pubkey,
+    args: VaultTransactionCreateArgs,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    let program_id = program_id.unwrap_or_else(crate::program_id);
+
+    let accounts = vec![
+        AccountMeta::new(multisig, false),
+        AccountMeta::new(transaction, false),
+        AccountMeta::new_readonly(creator, true),
+        AccountMeta::new(rent_payer, true),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+
+    let mut data =
+        dialect::instruction_discriminator(&program_id, "vault_transaction_create").to_vec();
+    args.serialize(&mut data).unwrap();
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Arguments for creating a batch
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
+pub struct BatchCreateArgs {
+    /// Vault index the batch's transactions execute from
+    pub vault_index: u8,
+    /// Optional memo for indexing
+    pub memo: Option<String>,
+}
+
+impl BatchCreateArgs {
+    /// Create batch arguments, rejecting memos over [`crate::memo::MAX_MEMO_LENGTH`]
+    pub fn new(vault_index: u8, memo: Option<String>) -> SquadsResult<Self> {
+        Ok(Self {
+            vault_index,
+            memo: check_memo(memo, MemoPolicy::Error)?,
+        })
+    }
+}
+
+/// Create a new batch
+///
+/// A batch takes the next transaction index of the multisig, like a vault
+/// transaction, and holds transactions added with `batch_add_transaction` that are
+/// approved with a single proposal and executed in order.
+///
+/// # Arguments
+/// * `multisig` - Multisig account
+/// * `creator` - Batch creator (must have Initiate permission)
+/// * `rent_payer` - Rent payer for the batch account
+/// * `batch` - Batch PDA to create (see [`crate::pda::get_batch_pda`])
+/// * `args` - Batch creation arguments
+/// * `program_id` - Optional custom program ID
+pub fn batch_create(
+    multisig: Pubkey,
+    creator: Pubkey,
+    rent_payer: Pubkey,
+    batch: Pubkey,
+    args: BatchCreateArgs,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    let program_id = program_id.unwrap_or_else(crate::program_id);
+
+    let accounts = vec![
+        AccountMeta::new(multisig, false),
+        AccountMeta::new_readonly(creator, true),
+        AccountMeta::new(rent_payer, true),
+        AccountMeta::new(batch, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+
+    let mut data = dialect::instruction_discriminator(&program_id, "batch_create").to_vec();
+    args.serialize(&mut data).unwrap();
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Arguments for adding a transaction to a batch
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
+pub struct BatchAddTransactionArgs {
+    /// Number of ephemeral signers
+    pub ephemeral_signers: u8,
+    /// Serialized transaction message
+    pub transaction_message: Vec<u8>,
+}
+
+impl BatchAddTransactionArgs {
+    /// Create batch transaction arguments from a compiled message
+    pub fn new(ephemeral_signers: u8, message: &TransactionMessage) -> SquadsResult<Self> {
+        Ok(Self {
+            ephemeral_signers,
+            transaction_message: borsh::to_vec(message)?,
+        })
+    }
+}
+
+/// Add a transaction to a batch
+///
+/// The batch's proposal must still be a draft. Transactions are numbered from 1 in
+/// the order they are added, so the transaction PDA is
+/// [`crate::pda::get_batch_transaction_pda`] with the batch's current size plus one.
+///
+/// # Arguments
+/// * `multisig` - Multisig account
+/// * `proposal` - Draft proposal of the batch
+/// * `batch` - Batch account
+/// * `transaction` - Batch transaction PDA to create
+/// * `member` - Batch creator (must be signer)
+/// * `rent_payer` - Rent payer for the transaction account
+/// * `args` - Transaction arguments
+/// * `program_id` - Optional custom program ID
+#[allow(clippy::too_many_arguments)]
+pub fn batch_add_transaction(
+    multisig: Pubkey,
+    proposal: Pubkey,
+    batch: Pubkey,
+    transaction: Pubkey,
+    member: Pubkey,
+    rent_payer: Pubkey,
+    args: BatchAddTransactionArgs,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    let program_id = program_id.unwrap_or_else(crate::program_id);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(multisig, false),
+        AccountMeta::new_readonly(proposal, false),
+        AccountMeta::new(batch, false),
+        AccountMeta::new(transaction, false),
+        AccountMeta::new_readonly(member, true),
+        AccountMeta::new(rent_payer, true),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+
+    let mut data =
+        dialect::instruction_discriminator(&program_id, "batch_add_transaction").to_vec();
+    args.serialize(&mut data).unwrap();
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Maximum size of a transaction message uploaded through a transaction buffer
+pub const MAX_TRANSACTION_BUFFER_SIZE: usize = 4000;
+
+/// Arguments for creating a transaction buffer
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
+pub struct TransactionBufferCreateArgs {
+    /// Index distinguishing the creator's buffers
+    pub buffer_index: u8,
+    /// Vault index the transaction will execute from
+    pub vault_index: u8,
+    /// SHA-256 hash of the complete serialized message
+    pub final_buffer_hash: [u8; 32],
+    /// Size of the complete serialized message in bytes
+    pub final_buffer_size: u16,
+    /// Initial slice of the serialized message
+    pub buffer: Vec<u8>,
+}
+
+impl TransactionBufferCreateArgs {
+    /// Create buffer arguments for a serialized message, uploading its first
+    /// `initial_len` bytes
+    ///
+    /// The rest of the message is appended with `transaction_buffer_extend`. Fails if
+    /// the message exceeds [`MAX_TRANSACTION_BUFFER_SIZE`].
+    pub fn new(
+        buffer_index: u8,
+        vault_index: u8,
+        message: &[u8],
+        initial_len: usize,
+    ) -> SquadsResult<Self> {
+        if message.len() > MAX_TRANSACTION_BUFFER_SIZE {
+            return Err(SquadsError::TransactionTooLarge {
+                size: message.len(),
+                max: MAX_TRANSACTION_BUFFER_SIZE,
+            });
+        }
+        Ok(Self {
+            buffer_index,
+            vault_index,
+            final_buffer_hash: hash(message).to_bytes(),
+            final_buffer_size: message.len() as u16,
+            buffer: message[..initial_len.min(message.len())].to_vec(),
+        })
+    }
+}
+
+/// Create a transaction buffer
+///
+/// Vault transactions whose message does not fit in a single transaction are
+/// uploaded in slices to a buffer, then turned into a vault transaction. The program
+/// checks the complete buffer against `final_buffer_hash` and `final_buffer_size`.
+///
+/// # Arguments
+/// * `multisig` - Multisig account
+/// * `transaction_buffer` - Buffer PDA to create (see
+///   [`crate::pda::get_transaction_buffer_pda`])
+/// * `creator` - Buffer creator (must have Initiate permission)
+/// * `rent_payer` - Rent payer for the buffer account
+/// * `args` - Buffer creation arguments
+/// * `program_id` - Optional custom program ID
+pub fn transaction_buffer_create(
+    multisig: Pubkey,
+    transaction_buffer: Pubkey,
+    creator: Pubkey,
+    rent_payer: Pubkey,
+    args: TransactionBufferCreateArgs,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    let program_id = program_id.unwrap_or_else(crate::program_id);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(multisig, false),
+        AccountMeta::new(transaction_buffer, false),
+        AccountMeta::new_readonly(creator, true),
+        AccountMeta::new(rent_payer, true),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+
+    let mut data =
+        dialect::instruction_discriminator(&program_id, "transaction_buffer_create").to_vec();
+    args.serialize(&mut data).unwrap();
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Arguments for extending a transaction buffer
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
+pub struct TransactionBufferExtendArgs {
+    /// Next slice of the serialized message
+    pub buffer: Vec<u8>,
+}
+
+/// Append a slice of the serialized message to a transaction buffer
+///
+/// Slices must be appended in order; the buffer may not grow past the
+/// `final_buffer_size` it was created with.
+///
+/// # Arguments
+/// * `multisig` - Multisig account
+/// * `transaction_buffer` - Buffer account
+/// * `creator` - Buffer creator (must be signer)
+/// * `args` - Slice to append
+/// * `program_id` - Optional custom program ID
+pub fn transaction_buffer_extend(
+    multisig: Pubkey,
+    transaction_buffer: Pubkey,
+    creator: Pubkey,
+    args: TransactionBufferExtendArgs,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    let program_id = program_id.unwrap_or_else(crate::program_id);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(multisig, false),
+        AccountMeta::new(transaction_buffer, false),
+        AccountMeta::new_readonly(creator, true),
+    ];
+
+    let mut data =
+        dialect::instruction_discriminator(&program_id, "transaction_buffer_extend").to_vec();
+    args.serialize(&mut data).unwrap();
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Close a transaction buffer, returning its rent to the creator
+///
+/// Closes buffers that were abandoned or already turned into a vault transaction.
+///
+/// # Arguments
+/// * `multisig` - Multisig account
+/// * `transaction_buffer` - Buffer account to close
+/// * `creator` - Buffer creator (must be signer, receives the rent)
+/// * `program_id` - Optional custom program ID
+pub fn transaction_buffer_close(
+    multisig: Pubkey,
+    transaction_buffer: Pubkey,
+    creator: Pubkey,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    let program_id = program_id.unwrap_or_else(crate::program_id);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(multisig, false),
+        AccountMeta::new(transaction_buffer, false),
+        AccountMeta::new(creator, true),
+    ];
+
+    let data = dialect::instruction_discriminator(&program_id, "transaction_buffer_close").to_vec();
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Create a vault transaction from the message uploaded to a transaction buffer
+///
+/// Completes the buffer workflow: the program checks the buffer against its final hash
+/// and size, creates the vault transaction from it and closes the buffer, returning
+/// its rent to the creator.
+///
+/// # Arguments
+/// * `multisig` - Multisig account
+/// * `transaction` - Transaction PDA to create
+/// * `creator` - Transaction and buffer creator (must have Initiate permission)
+/// * `rent_payer` - Rent payer for the transaction account
+/// * `transaction_buffer` - Buffer holding the complete message
+/// * `args` - Transaction creation arguments (see
+///   [`VaultTransactionCreateArgs::from_buffer`])
+/// * `program_id` - Optional custom program ID
+pub fn vault_transaction_create_from_buffer(
+    multisig: Pubkey,
+    transaction: Pubkey,
+    creator: Pubkey,
+    rent_payer: Pubkey,
+    transaction_buffer: Pubkey,
+    args: VaultTransactionCreateArgs,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    let program_id = program_id.unwrap_or_else(crate::program_id);
+
+    let accounts = vec![
+        AccountMeta::new(multisig, false),
+        AccountMeta::new(transaction, false),
+        AccountMeta::new_readonly(creator, true),
+        AccountMeta::new(rent_payer, true),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new(transaction_buffer, false),
+        AccountMeta::new(creator, true),
+    ];
+
+    let mut data =
+        dialect::instruction_discriminator(&program_id, "vault_transaction_create_from_buffer")
+            .to_vec();
+    args.serialize(&mut data).unwrap();
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Execute a vault transaction
+///
+/// # Arguments
+/// * `multisig` - Multisig account
+/// * `proposal` - Proposal for the transaction (must be Approved)
+/// * `transaction` - Transaction to execute
+/// * `member` - Member executing (must have Execute permission)
+/// * `remaining_accounts` - Accounts required by the transaction (lookup tables + instruction accounts)
+/// * `program_id` - Optional custom program ID
+pub fn vault_transaction_execute(
+    multisig: Pubkey,
+    proposal: Pubkey,
+    transaction: Pubkey,
+    member: Pubkey,
+    remaining_accounts: Vec<AccountMeta>,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    let program_id = program_id.unwrap_or_else(crate::program_id);
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(multisig, false),
+        AccountMeta::new(proposal, false),
+        AccountMeta::new_readonly(transaction, false),
+        AccountMeta::new_readonly(member, true),
+    ];
+    accounts.extend(remaining_accounts);
+
+    let data =
+        dialect::instruction_discriminator(&program_id, "vault_transaction_execute").to_vec();
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Arguments for creating a config transaction
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
+pub struct ConfigTransactionCreateArgs {
+    /// Configuration actions to execute
+    pub actions: Vec<ConfigAction>,
+    /// Optional memo
+    pub memo: Option<String>,
+}
+
+impl ConfigTransactionCreateArgs {
+    /// Create config transaction arguments, rejecting memos over [`crate::memo::MAX_MEMO_LENGTH`]
+    pub fn new(actions: Vec<ConfigAction>, memo: Option<String>) -> SquadsResult<Self> {
+        Ok(Self {
+            actions,
+            memo: check_memo(memo, MemoPolicy::Error)?,
+        })
+    }
+}
+
+/// Create a new config transaction
+///
+/// # Arguments
+/// * `multisig` - Multisig account
+/// * `transaction` - Config transaction PDA to create
+/// * `creator` - Transaction creator
+/// * `rent_payer` - Rent payer for the transaction account
+/// * `args` - Config transaction creation arguments
+/// * `program_id` - Optional custom program ID
+pub fn config_transaction_create(
+    multisig: Pubkey,
+    transaction: Pubkey,
+    creator: Pubkey,
+    rent_payer: Pubkey,
+    args: ConfigTransactionCreateArgs,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    let program_id = program_id.unwrap_or_else(crate::program_id);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(multisig, false),
+        AccountMeta::new_readonly(creator, true),
+        AccountMeta::new(rent_payer, true),
+        AccountMeta::new(transaction, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+
+    let mut data =
+        dialect::instruction_discriminator(&program_id, "config_transaction_create").to_vec();
+    args.serialize(&mut data).unwrap();
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Execute a config transaction
+///
+/// # Arguments
+/// * `multisig` - Multisig account
+/// * `proposal` - Proposal for the transaction (must be Approved)
+/// * `transaction` - Config transaction to execute
+/// * `member` - Member executing (must have Execute permission)
+/// * `rent_payer` - Optional rent payer for reallocation
+/// * `spending_limit_accounts` - Optional spending limit accounts being added/removed
+/// * `program_id` - Optional custom program ID
+pub fn config_transaction_execute(
+    multisig: Pubkey,
+    proposal: Pubkey,
+    transaction: Pubkey,
+    member: Pubkey,
+    rent_payer: Option<Pubkey>,
+    spending_limit_accounts: Vec<Pubkey>,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    let program_id = program_id.unwrap_or_else(crate::program_id);
+
+    let mut accounts = vec![
+        AccountMeta::new(multisig, false),
+        AccountMeta::new_readonly(member, true),
+        AccountMeta::new(proposal, false),
+        AccountMeta::new(transaction, false),
+    ];
+
+    // Add rent_payer if provided
+    if let Some(rent_payer) = rent_payer {
+        accounts.push(AccountMeta::new(rent_payer, true));
+    } else {
+        accounts.push(AccountMeta::new_readonly(program_id, false));
+    }
+
+    // Add system_program
+    accounts.push(AccountMeta::new_readonly(system_program::ID, false));
+
+    // Add spending limit accounts
+    for spending_limit in spending_limit_accounts {
+        accounts.push(AccountMeta::new(spending_limit, false));
+    }
+
+    let data =
+        dialect::instruction_discriminator(&program_id, "config_transaction_execute").to_vec();
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Build a [`ConfigAction::AddSpendingLimit`] together with the spending limit PDA it creates
+///
+/// The program creates the spending limit account when the config transaction executes,
+/// so the returned PDA must be passed in `spending_limit_accounts` of
+/// [`config_transaction_execute`].
+///
+/// # Arguments
+/// * `multisig` - Multisig account
+/// * `create_key` - Unique key the spending limit PDA is derived from
+/// * `vault_index` - Vault index the limit applies to
+/// * `mint` - Token mint (`Pubkey::default()` for SOL)
+/// * `amount` - Amount that can be spent per period
+/// * `period` - Reset period of the limit
+/// * `members` - Members who can use the limit
+/// * `destinations` - Allowed destinations (empty allows any destination)
+/// * `program_id` - Optional custom program ID
+///
+/// # Returns
+/// Tuple of (config action, spending limit PDA)
+#[allow(clippy::too_many_arguments)]
+pub fn add_spending_limit_action(
+    multisig: &Pubkey,
+    create_key: Pubkey,
+    vault_index: u8,
+    mint: Pubkey,
+    amount: u64,
+    period: Period,
+    members: Vec<Pubkey>,
+    destinations: Vec<Pubkey>,
+    program_id: Option<Pubkey>,
+) -> (ConfigAction, Pubkey) {
+    let program_id = program_id.unwrap_or_else(crate::program_id);
+    let (spending_limit, _) =
+        crate::pda::get_spending_limit_pda(multisig, &create_key, Some(&program_id));
+    let action = ConfigAction::AddSpendingLimit {
+        create_key,
+        vault_index,
+        mint,
+        amount,
+        period,
+        members,
+        destinations,
+    };
+    (action, spending_limit)
+}
+
+/// Arguments for activating a draft proposal
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
+pub struct ProposalActivateArgs {}
+
 /// Activate a draft proposal
 ///
 /// # Arguments
@@ -437,12 +1507,375 @@ pub fn proposal_activate(
     let program_id = program_id.unwrap_or_else(crate::program_id);
 
     let accounts = vec![
-        AccountMeta::new_readonly(multisig, false),
-        AccountMeta::new(proposal, false),
-        AccountMeta::new_readonly(member, true),
+        AccountMeta::new_readonly(multisig, false),
+        AccountMeta::new(proposal, false),
+        AccountMeta::new_readonly(member, true),
+    ];
+
+    let data = dialect::instruction_discriminator(&program_id, "proposal_activate").to_vec();
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Arguments for using a spending limit
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
+pub struct SpendingLimitUseArgs {
+    /// Amount to transfer
+    pub amount: u64,
+    /// Token decimals
+    pub decimals: u8,
+    /// Optional memo
+    pub memo: Option<String>,
+}
+
+impl SpendingLimitUseArgs {
+    /// Create spending limit arguments, rejecting memos over [`crate::memo::MAX_MEMO_LENGTH`]
+    pub fn new(amount: u64, decimals: u8, memo: Option<String>) -> SquadsResult<Self> {
+        Ok(Self {
+            amount,
+            decimals,
+            memo: check_memo(memo, MemoPolicy::Error)?,
+        })
+    }
+}
+
+/// Use a spending limit to transfer tokens
+///
+/// # Arguments
+/// * `multisig` - Multisig account
+/// * `member` - Member using the limit
+/// * `spending_limit` - Spending limit account
+/// * `vault` - Vault to transfer from
+/// * `destination` - Destination account
+/// * `mint` - Optional token mint (None for SOL)
+/// * `vault_token_account` - Optional vault token account (for SPL tokens)
+/// * `destination_token_account` - Optional destination token account (for SPL tokens)
+/// * `token_program` - Optional token program (for SPL tokens)
+/// * `args` - Spending limit use arguments
+/// * `program_id` - Optional custom program ID
+#[allow(clippy::too_many_arguments)]
+pub fn spending_limit_use(
+    multisig: Pubkey,
+    member: Pubkey,
+    spending_limit: Pubkey,
+    vault: Pubkey,
+    destination: Pubkey,
+    mint: Option<Pubkey>,
+    vault_token_account: Option<Pubkey>,
+    destination_token_account: Option<Pubkey>,
+    token_program: Option<Pubkey>,
+    args: SpendingLimitUseArgs,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    let program_id = program_id.unwrap_or_else(crate::program_id);
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(multisig, false),
+        AccountMeta::new_readonly(member, true),
+        AccountMeta::new(spending_limit, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new(destination, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+
+    // Add optional accounts
+    accounts.push(if let Some(mint) = mint {
+        AccountMeta::new_readonly(mint, false)
+    } else {
+        AccountMeta::new_readonly(program_id, false)
+    });
+
+    accounts.push(if let Some(vault_token) = vault_token_account {
+        AccountMeta::new(vault_token, false)
+    } else {
+        AccountMeta::new_readonly(program_id, false)
+    });
+
+    accounts.push(if let Some(dest_token) = destination_token_account {
+        AccountMeta::new(dest_token, false)
+    } else {
+        AccountMeta::new_readonly(program_id, false)
+    });
+
+    accounts.push(if let Some(token_prog) = token_program {
+        AccountMeta::new_readonly(token_prog, false)
+    } else {
+        AccountMeta::new_readonly(program_id, false)
+    });
+
+    let mut data = dialect::instruction_discriminator(&program_id, "spending_limit_use").to_vec();
+    args.serialize(&mut data).unwrap();
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Close a config transaction and its proposal, reclaiming rent to the rent collector
+///
+/// The proposal must be Executed, Rejected or Cancelled, or stale. Unlike vault
+/// transactions, stale Approved config transactions cannot execute and can be closed.
+///
+/// # Arguments
+/// * `multisig` - Multisig account
+/// * `proposal` - Proposal for the transaction
+/// * `transaction` - Config transaction to close
+/// * `rent_collector` - Rent collector configured on the multisig
+/// * `program_id` - Optional custom program ID
+pub fn config_transaction_accounts_close(
+    multisig: Pubkey,
+    proposal: Pubkey,
+    transaction: Pubkey,
+    rent_collector: Pubkey,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    let program_id = program_id.unwrap_or_else(crate::program_id);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(multisig, false),
+        AccountMeta::new(proposal, false),
+        AccountMeta::new(transaction, false),
+        AccountMeta::new(rent_collector, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+
+    let data = dialect::instruction_discriminator(&program_id, "config_transaction_accounts_close")
+        .to_vec();
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Close a vault transaction and its proposal, reclaiming rent to the rent collector
+///
+/// The proposal must be Executed, Rejected or Cancelled, or stale and not Approved.
+///
+/// # Arguments
+/// * `multisig` - Multisig account
+/// * `proposal` - Proposal for the transaction
+/// * `transaction` - Vault transaction to close
+/// * `rent_collector` - Rent collector configured on the multisig
+/// * `program_id` - Optional custom program ID
+pub fn vault_transaction_accounts_close(
+    multisig: Pubkey,
+    proposal: Pubkey,
+    transaction: Pubkey,
+    rent_collector: Pubkey,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    let program_id = program_id.unwrap_or_else(crate::program_id);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(multisig, false),
+        AccountMeta::new(proposal, false),
+        AccountMeta::new(transaction, false),
+        AccountMeta::new(rent_collector, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+
+    let data = dialect::instruction_discriminator(&program_id, "vault_transaction_accounts_close")
+        .to_vec();
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Close a batch and its proposal, reclaiming rent to the rent collector
+///
+/// The proposal must be Executed, Rejected or Cancelled, or stale and not Approved.
+/// The batch's transactions must be closed first with
+/// [`vault_batch_transaction_account_close`].
+///
+/// # Arguments
+/// * `multisig` - Multisig account
+/// * `proposal` - Proposal for the batch
+/// * `batch` - Batch to close
+/// * `rent_collector` - Rent collector configured on the multisig
+/// * `program_id` - Optional custom program ID
+pub fn batch_accounts_close(
+    multisig: Pubkey,
+    proposal: Pubkey,
+    batch: Pubkey,
+    rent_collector: Pubkey,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    let program_id = program_id.unwrap_or_else(crate::program_id);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(multisig, false),
+        AccountMeta::new(proposal, false),
+        AccountMeta::new(batch, false),
+        AccountMeta::new(rent_collector, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+
+    let data = dialect::instruction_discriminator(&program_id, "batch_accounts_close").to_vec();
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Close a transaction of a batch, reclaiming rent to the rent collector
+///
+/// Transactions must be closed last to first: `transaction` must be the batch's last
+/// remaining transaction, whose index equals the batch size.
+///
+/// # Arguments
+/// * `multisig` - Multisig account
+/// * `proposal` - Proposal for the batch
+/// * `batch` - Batch the transaction belongs to
+/// * `transaction` - Batch transaction to close
+/// * `rent_collector` - Rent collector configured on the multisig
+/// * `program_id` - Optional custom program ID
+pub fn vault_batch_transaction_account_close(
+    multisig: Pubkey,
+    proposal: Pubkey,
+    batch: Pubkey,
+    transaction: Pubkey,
+    rent_collector: Pubkey,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    let program_id = program_id.unwrap_or_else(crate::program_id);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(multisig, false),
+        AccountMeta::new_readonly(proposal, false),
+        AccountMeta::new(batch, false),
+        AccountMeta::new(transaction, false),
+        AccountMeta::new(rent_collector, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+
+    let data =
+        dialect::instruction_discriminator(&program_id, "vault_batch_transaction_account_close")
+            .to_vec();
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Arguments for initializing the program config
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
+pub struct ProgramConfigInitArgs {
+    /// Authority that can update the program config
+    #[cfg_attr(
+        feature = "serde-args",
+        serde(deserialize_with = "crate::serde_utils::pubkey")
+    )]
+    pub authority: Pubkey,
+    /// Lamports charged for creating a multisig
+    pub multisig_creation_fee: u64,
+    /// Account receiving the multisig creation fees
+    #[cfg_attr(
+        feature = "serde-args",
+        serde(deserialize_with = "crate::serde_utils::pubkey")
+    )]
+    pub treasury: Pubkey,
+}
+
+/// Initialize the program config of a new deployment
+///
+/// Only needed when deploying the Squads program to a localnet or private cluster; the
+/// program only accepts the initializer key it was built with.
+///
+/// # Arguments
+/// * `program_config` - Program config PDA to create
+/// * `initializer` - Initializer hard-coded in the program (must be signer, pays the rent)
+/// * `args` - Initial authority, creation fee and treasury
+/// * `program_id` - Optional custom program ID
+pub fn program_config_init(
+    program_config: Pubkey,
+    initializer: Pubkey,
+    args: ProgramConfigInitArgs,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    let program_id = program_id.unwrap_or_else(crate::program_id);
+
+    let accounts = vec![
+        AccountMeta::new(program_config, false),
+        AccountMeta::new(initializer, true),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+
+    let mut data = dialect::instruction_discriminator(&program_id, "program_config_init").to_vec();
+    args.serialize(&mut data).unwrap();
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Arguments for rotating the program config authority
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
+pub struct ProgramConfigSetAuthorityArgs {
+    /// New program config authority
+    #[cfg_attr(
+        feature = "serde-args",
+        serde(deserialize_with = "crate::serde_utils::pubkey")
+    )]
+    pub new_authority: Pubkey,
+}
+
+/// Arguments for changing the multisig creation fee
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
+pub struct ProgramConfigSetMultisigCreationFeeArgs {
+    /// New fee in lamports
+    pub new_multisig_creation_fee: u64,
+}
+
+/// Arguments for changing the treasury
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
+pub struct ProgramConfigSetTreasuryArgs {
+    /// New account receiving the multisig creation fees
+    #[cfg_attr(
+        feature = "serde-args",
+        serde(deserialize_with = "crate::serde_utils::pubkey")
+    )]
+    pub new_treasury: Pubkey,
+}
+
+/// Build a program config update signed by its authority
+fn program_config_update<A: BorshSerialize>(
+    name: &str,
+    program_config: Pubkey,
+    authority: Pubkey,
+    args: A,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    let program_id = program_id.unwrap_or_else(crate::program_id);
+
+    let accounts = vec![
+        AccountMeta::new(program_config, false),
+        AccountMeta::new_readonly(authority, true),
     ];
 
-    let data = instruction_discriminator("proposal_activate").to_vec();
+    let mut data = dialect::instruction_discriminator(&program_id, name).to_vec();
+    args.serialize(&mut data).unwrap();
 
     Instruction {
         program_id,
@@ -451,87 +1884,531 @@ pub fn proposal_activate(
     }
 }
 
-/// Arguments for using a spending limit
-#[derive(Debug, Clone, BorshSerialize)]
-pub struct SpendingLimitUseArgs {
-    /// Amount to transfer
-    pub amount: u64,
-    /// Token decimals
-    pub decimals: u8,
-    /// Optional memo
-    pub memo: Option<String>,
+/// Rotate the authority of the program config
+///
+/// # Arguments
+/// * `program_config` - Program config PDA
+/// * `authority` - Current program config authority (must be signer)
+/// * `args` - New authority
+/// * `program_id` - Optional custom program ID
+pub fn program_config_set_authority(
+    program_config: Pubkey,
+    authority: Pubkey,
+    args: ProgramConfigSetAuthorityArgs,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    program_config_update(
+        "program_config_set_authority",
+        program_config,
+        authority,
+        args,
+        program_id,
+    )
 }
 
-/// Use a spending limit to transfer tokens
+/// Change the fee charged by `multisig_create_v2`
 ///
 /// # Arguments
-/// * `multisig` - Multisig account
-/// * `member` - Member using the limit
-/// * `spending_limit` - Spending limit account
-/// * `vault` - Vault to transfer from
-/// * `destination` - Destination account
-/// * `mint` - Optional token mint (None for SOL)
-/// * `vault_token_account` - Optional vault token account (for SPL tokens)
-/// * `destination_token_account` - Optional destination token account (for SPL tokens)
-/// * `token_program` - Optional token program (for SPL tokens)
-/// * `args` - Spending limit use arguments
+/// * `program_config` - Program config PDA
+/// * `authority` - Program config authority (must be signer)
+/// * `args` - New creation fee
 /// * `program_id` - Optional custom program ID
-pub fn spending_limit_use(
-    multisig: Pubkey,
-    member: Pubkey,
-    spending_limit: Pubkey,
-    vault: Pubkey,
-    destination: Pubkey,
-    mint: Option<Pubkey>,
-    vault_token_account: Option<Pubkey>,
-    destination_token_account: Option<Pubkey>,
-    token_program: Option<Pubkey>,
-    args: SpendingLimitUseArgs,
+pub fn program_config_set_multisig_creation_fee(
+    program_config: Pubkey,
+    authority: Pubkey,
+    args: ProgramConfigSetMultisigCreationFeeArgs,
     program_id: Option<Pubkey>,
 ) -> Instruction {
-    let program_id = program_id.unwrap_or_else(crate::program_id);
+    program_config_update(
+        "program_config_set_multisig_creation_fee",
+        program_config,
+        authority,
+        args,
+        program_id,
+    )
+}
 
-    let mut accounts = vec![
-        AccountMeta::new_readonly(multisig, false),
-        AccountMeta::new_readonly(member, true),
-        AccountMeta::new(spending_limit, false),
-        AccountMeta::new(vault, false),
-        AccountMeta::new(destination, false),
-        AccountMeta::new_readonly(system_program::ID, false),
+/// Change the treasury receiving multisig creation fees
+///
+/// # Arguments
+/// * `program_config` - Program config PDA
+/// * `authority` - Program config authority (must be signer)
+/// * `args` - New treasury
+/// * `program_id` - Optional custom program ID
+pub fn program_config_set_treasury(
+    program_config: Pubkey,
+    authority: Pubkey,
+    args: ProgramConfigSetTreasuryArgs,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    program_config_update(
+        "program_config_set_treasury",
+        program_config,
+        authority,
+        args,
+        program_id,
+    )
+}
+
+/// Squads instructions with a builder in this module
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum InstructionKind {
+    /// [`multisig_create_v2`]
+    MultisigCreateV2,
+    /// [`multisig_add_member`]
+    MultisigAddMember,
+    /// [`multisig_remove_member`]
+    MultisigRemoveMember,
+    /// [`multisig_set_time_lock`]
+    MultisigSetTimeLock,
+    /// [`multisig_set_rent_collector`]
+    MultisigSetRentCollector,
+    /// [`multisig_add_spending_limit`]
+    MultisigAddSpendingLimit,
+    /// [`multisig_remove_spending_limit`]
+    MultisigRemoveSpendingLimit,
+    /// [`proposal_create`]
+    ProposalCreate,
+    /// [`proposal_approve`]
+    ProposalApprove,
+    /// [`proposal_reject`]
+    ProposalReject,
+    /// [`proposal_cancel`]
+    ProposalCancel,
+    /// [`proposal_activate`]
+    ProposalActivate,
+    /// [`vault_transaction_create`]
+    VaultTransactionCreate,
+    /// [`vault_transaction_execute`]
+    VaultTransactionExecute,
+    /// [`batch_create`]
+    BatchCreate,
+    /// [`batch_add_transaction`]
+    BatchAddTransaction,
+    /// [`config_transaction_create`]
+    ConfigTransactionCreate,
+    /// [`config_transaction_execute`]
+    ConfigTransactionExecute,
+    /// [`spending_limit_use`]
+    SpendingLimitUse,
+    /// [`config_transaction_accounts_close`]
+    ConfigTransactionAccountsClose,
+    /// [`vault_transaction_accounts_close`]
+    VaultTransactionAccountsClose,
+    /// [`batch_accounts_close`]
+    BatchAccountsClose,
+    /// [`vault_batch_transaction_account_close`]
+    VaultBatchTransactionAccountClose,
+    /// [`transaction_buffer_create`]
+    TransactionBufferCreate,
+    /// [`transaction_buffer_extend`]
+    TransactionBufferExtend,
+    /// [`transaction_buffer_close`]
+    TransactionBufferClose,
+    /// [`vault_transaction_create_from_buffer`]
+    VaultTransactionCreateFromBuffer,
+    /// [`program_config_init`]
+    ProgramConfigInit,
+    /// [`program_config_set_authority`]
+    ProgramConfigSetAuthority,
+    /// [`program_config_set_multisig_creation_fee`]
+    ProgramConfigSetMultisigCreationFee,
+    /// [`program_config_set_treasury`]
+    ProgramConfigSetTreasury,
+    /// [`multisig_create`] (legacy)
+    MultisigCreate,
+    /// [`proposal_cancel_v2`]
+    ProposalCancelV2,
+}
+
+impl InstructionKind {
+    /// Every instruction kind, in declaration order
+    pub const ALL: [InstructionKind; 33] = [
+        InstructionKind::MultisigCreateV2,
+        InstructionKind::MultisigAddMember,
+        InstructionKind::MultisigRemoveMember,
+        InstructionKind::MultisigSetTimeLock,
+        InstructionKind::MultisigSetRentCollector,
+        InstructionKind::MultisigAddSpendingLimit,
+        InstructionKind::MultisigRemoveSpendingLimit,
+        InstructionKind::ProposalCreate,
+        InstructionKind::ProposalApprove,
+        InstructionKind::ProposalReject,
+        InstructionKind::ProposalCancel,
+        InstructionKind::ProposalActivate,
+        InstructionKind::VaultTransactionCreate,
+        InstructionKind::VaultTransactionExecute,
+        InstructionKind::BatchCreate,
+        InstructionKind::BatchAddTransaction,
+        InstructionKind::ConfigTransactionCreate,
+        InstructionKind::ConfigTransactionExecute,
+        InstructionKind::SpendingLimitUse,
+        InstructionKind::ConfigTransactionAccountsClose,
+        InstructionKind::VaultTransactionAccountsClose,
+        InstructionKind::BatchAccountsClose,
+        InstructionKind::VaultBatchTransactionAccountClose,
+        InstructionKind::TransactionBufferCreate,
+        InstructionKind::TransactionBufferExtend,
+        InstructionKind::TransactionBufferClose,
+        InstructionKind::VaultTransactionCreateFromBuffer,
+        InstructionKind::ProgramConfigInit,
+        InstructionKind::ProgramConfigSetAuthority,
+        InstructionKind::ProgramConfigSetMultisigCreationFee,
+        InstructionKind::ProgramConfigSetTreasury,
+        InstructionKind::MultisigCreate,
+        InstructionKind::ProposalCancelV2,
     ];
 
-    // Add optional accounts
-    accounts.push(if let Some(mint) = mint {
-        AccountMeta::new_readonly(mint, false)
-    } else {
-        AccountMeta::new_readonly(program_id, false)
-    });
+    /// Program instruction name, as used for the discriminator
+    pub fn name(&self) -> &'static str {
+        match self {
+            InstructionKind::MultisigCreateV2 => "multisig_create_v2",
+            InstructionKind::MultisigAddMember => "multisig_add_member",
+            InstructionKind::MultisigRemoveMember => "multisig_remove_member",
+            InstructionKind::MultisigSetTimeLock => "multisig_set_time_lock",
+            InstructionKind::MultisigSetRentCollector => "multisig_set_rent_collector",
+            InstructionKind::MultisigAddSpendingLimit => "multisig_add_spending_limit",
+            InstructionKind::MultisigRemoveSpendingLimit => "multisig_remove_spending_limit",
+            InstructionKind::ProposalCreate => "proposal_create",
+            InstructionKind::ProposalApprove => "proposal_approve",
+            InstructionKind::ProposalReject => "proposal_reject",
+            InstructionKind::ProposalCancel => "proposal_cancel",
+            InstructionKind::ProposalActivate => "proposal_activate",
+            InstructionKind::VaultTransactionCreate => "vault_transaction_create",
+            InstructionKind::VaultTransactionExecute => "vault_transaction_execute",
+            InstructionKind::BatchCreate => "batch_create",
+            InstructionKind::BatchAddTransaction => "batch_add_transaction",
+            InstructionKind::ConfigTransactionCreate => "config_transaction_create",
+            InstructionKind::ConfigTransactionExecute => "config_transaction_execute",
+            InstructionKind::SpendingLimitUse => "spending_limit_use",
+            InstructionKind::ConfigTransactionAccountsClose => "config_transaction_accounts_close",
+            InstructionKind::VaultTransactionAccountsClose => "vault_transaction_accounts_close",
+            InstructionKind::BatchAccountsClose => "batch_accounts_close",
+            InstructionKind::VaultBatchTransactionAccountClose => {
+                "vault_batch_transaction_account_close"
+            }
+            InstructionKind::TransactionBufferCreate => "transaction_buffer_create",
+            InstructionKind::TransactionBufferExtend => "transaction_buffer_extend",
+            InstructionKind::TransactionBufferClose => "transaction_buffer_close",
+            InstructionKind::VaultTransactionCreateFromBuffer => {
+                "vault_transaction_create_from_buffer"
+            }
+            InstructionKind::ProgramConfigInit => "program_config_init",
+            InstructionKind::ProgramConfigSetAuthority => "program_config_set_authority",
+            InstructionKind::ProgramConfigSetMultisigCreationFee => {
+                "program_config_set_multisig_creation_fee"
+            }
+            InstructionKind::ProgramConfigSetTreasury => "program_config_set_treasury",
+            InstructionKind::MultisigCreate => "multisig_create",
+            InstructionKind::ProposalCancelV2 => "proposal_cancel_v2",
+        }
+    }
+}
 
-    accounts.push(if let Some(vault_token) = vault_token_account {
-        AccountMeta::new(vault_token, false)
-    } else {
-        AccountMeta::new_readonly(program_id, false)
-    });
+/// An account position of an instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountSpec {
+    /// Account name, matching the builder's parameter name
+    pub name: &'static str,
+    /// Whether the account is writable
+    pub writable: bool,
+    /// Whether the account must sign
+    pub signer: bool,
+    /// Whether the account may be omitted, in which case the builder passes the
+    /// program ID (read-only, not a signer) in its place
+    pub optional: bool,
+}
 
-    accounts.push(if let Some(dest_token) = destination_token_account {
-        AccountMeta::new(dest_token, false)
-    } else {
-        AccountMeta::new_readonly(program_id, false)
-    });
+impl AccountSpec {
+    const fn new(name: &'static str, writable: bool, signer: bool) -> Self {
+        Self {
+            name,
+            writable,
+            signer,
+            optional: false,
+        }
+    }
 
-    accounts.push(if let Some(token_prog) = token_program {
-        AccountMeta::new_readonly(token_prog, false)
-    } else {
-        AccountMeta::new_readonly(program_id, false)
-    });
+    const fn optional(name: &'static str, writable: bool, signer: bool) -> Self {
+        Self {
+            name,
+            writable,
+            signer,
+            optional: true,
+        }
+    }
+}
 
-    let mut data = instruction_discriminator("spending_limit_use").to_vec();
-    args.serialize(&mut data).unwrap();
+/// Account layout of an instruction, in the order the builder passes the accounts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstructionMeta {
+    /// Instruction kind
+    pub kind: InstructionKind,
+    /// Fixed account positions
+    pub accounts: &'static [AccountSpec],
+    /// What follows the fixed accounts, if the instruction takes remaining accounts
+    pub remaining_accounts: Option<&'static str>,
+}
 
-    Instruction {
-        program_id,
+impl InstructionMeta {
+    /// Spec of the account at `index`, None for remaining accounts
+    pub fn account(&self, index: usize) -> Option<&AccountSpec> {
+        self.accounts.get(index)
+    }
+}
+
+const SYSTEM_PROGRAM: AccountSpec = AccountSpec::new("system_program", false, false);
+
+const CONTROLLED_CONFIG_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("multisig", true, false),
+    AccountSpec::new("config_authority", false, true),
+    AccountSpec::optional("rent_payer", true, true),
+    AccountSpec::optional("system_program", false, false),
+];
+
+const PROPOSAL_VOTE_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("multisig", false, false),
+    AccountSpec::new("member", true, true),
+    AccountSpec::new("proposal", true, false),
+];
+
+const PROPOSAL_CANCEL_V2_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("multisig", false, false),
+    AccountSpec::new("member", true, true),
+    AccountSpec::new("proposal", true, false),
+    SYSTEM_PROGRAM,
+];
+
+const ACCOUNTS_CLOSE_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("multisig", false, false),
+    AccountSpec::new("proposal", true, false),
+    AccountSpec::new("transaction", true, false),
+    AccountSpec::new("rent_collector", true, false),
+    SYSTEM_PROGRAM,
+];
+
+const MULTISIG_CREATE_V2_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("program_config", false, false),
+    AccountSpec::new("treasury", true, false),
+    AccountSpec::new("multisig", true, false),
+    AccountSpec::new("create_key", false, true),
+    AccountSpec::new("creator", true, true),
+    SYSTEM_PROGRAM,
+];
+
+const MULTISIG_ADD_SPENDING_LIMIT_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("multisig", false, false),
+    AccountSpec::new("config_authority", false, true),
+    AccountSpec::new("spending_limit", true, false),
+    AccountSpec::new("rent_payer", true, true),
+    SYSTEM_PROGRAM,
+];
+
+const MULTISIG_REMOVE_SPENDING_LIMIT_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("multisig", false, false),
+    AccountSpec::new("config_authority", false, true),
+    AccountSpec::new("spending_limit", true, false),
+    AccountSpec::new("rent_collector", true, false),
+];
+
+const PROPOSAL_CREATE_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("multisig", false, false),
+    AccountSpec::new("proposal", true, false),
+    AccountSpec::new("creator", false, true),
+    AccountSpec::new("rent_payer", true, true),
+    SYSTEM_PROGRAM,
+];
+
+const PROPOSAL_ACTIVATE_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("multisig", false, false),
+    AccountSpec::new("proposal", true, false),
+    AccountSpec::new("member", false, true),
+];
+
+const VAULT_TRANSACTION_CREATE_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("multisig", true, false),
+    AccountSpec::new("transaction", true, false),
+    AccountSpec::new("creator", false, true),
+    AccountSpec::new("rent_payer", true, true),
+    SYSTEM_PROGRAM,
+];
+
+const VAULT_TRANSACTION_EXECUTE_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("multisig", false, false),
+    AccountSpec::new("proposal", true, false),
+    AccountSpec::new("transaction", false, false),
+    AccountSpec::new("member", false, true),
+];
+
+const BATCH_CREATE_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("multisig", true, false),
+    AccountSpec::new("creator", false, true),
+    AccountSpec::new("rent_payer", true, true),
+    AccountSpec::new("batch", true, false),
+    SYSTEM_PROGRAM,
+];
+
+const BATCH_ADD_TRANSACTION_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("multisig", false, false),
+    AccountSpec::new("proposal", false, false),
+    AccountSpec::new("batch", true, false),
+    AccountSpec::new("transaction", true, false),
+    AccountSpec::new("member", false, true),
+    AccountSpec::new("rent_payer", true, true),
+    SYSTEM_PROGRAM,
+];
+
+const CONFIG_TRANSACTION_CREATE_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("multisig", false, false),
+    AccountSpec::new("creator", false, true),
+    AccountSpec::new("rent_payer", true, true),
+    AccountSpec::new("transaction", true, false),
+    SYSTEM_PROGRAM,
+];
+
+const CONFIG_TRANSACTION_EXECUTE_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("multisig", true, false),
+    AccountSpec::new("member", false, true),
+    AccountSpec::new("proposal", true, false),
+    AccountSpec::new("transaction", true, false),
+    AccountSpec::optional("rent_payer", true, true),
+    SYSTEM_PROGRAM,
+];
+
+const SPENDING_LIMIT_USE_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("multisig", false, false),
+    AccountSpec::new("member", false, true),
+    AccountSpec::new("spending_limit", true, false),
+    AccountSpec::new("vault", true, false),
+    AccountSpec::new("destination", true, false),
+    SYSTEM_PROGRAM,
+    AccountSpec::optional("mint", false, false),
+    AccountSpec::optional("vault_token_account", true, false),
+    AccountSpec::optional("destination_token_account", true, false),
+    AccountSpec::optional("token_program", false, false),
+];
+
+const BATCH_ACCOUNTS_CLOSE_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("multisig", false, false),
+    AccountSpec::new("proposal", true, false),
+    AccountSpec::new("batch", true, false),
+    AccountSpec::new("rent_collector", true, false),
+    SYSTEM_PROGRAM,
+];
+
+const VAULT_BATCH_TRANSACTION_ACCOUNT_CLOSE_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("multisig", false, false),
+    AccountSpec::new("proposal", false, false),
+    AccountSpec::new("batch", true, false),
+    AccountSpec::new("transaction", true, false),
+    AccountSpec::new("rent_collector", true, false),
+    SYSTEM_PROGRAM,
+];
+
+const TRANSACTION_BUFFER_CREATE_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("multisig", false, false),
+    AccountSpec::new("transaction_buffer", true, false),
+    AccountSpec::new("creator", false, true),
+    AccountSpec::new("rent_payer", true, true),
+    SYSTEM_PROGRAM,
+];
+
+const TRANSACTION_BUFFER_EXTEND_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("multisig", false, false),
+    AccountSpec::new("transaction_buffer", true, false),
+    AccountSpec::new("creator", false, true),
+];
+
+const TRANSACTION_BUFFER_CLOSE_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("multisig", false, false),
+    AccountSpec::new("transaction_buffer", true, false),
+    AccountSpec::new("creator", true, true),
+];
+
+const VAULT_TRANSACTION_CREATE_FROM_BUFFER_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("multisig", true, false),
+    AccountSpec::new("transaction", true, false),
+    AccountSpec::new("creator", false, true),
+    AccountSpec::new("rent_payer", true, true),
+    SYSTEM_PROGRAM,
+    AccountSpec::new("transaction_buffer", true, false),
+    AccountSpec::new("creator", true, true),
+];
+
+const PROGRAM_CONFIG_INIT_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("program_config", true, false),
+    AccountSpec::new("initializer", true, true),
+    SYSTEM_PROGRAM,
+];
+
+const MULTISIG_CREATE_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("multisig", true, false),
+    AccountSpec::new("create_key", false, true),
+    AccountSpec::new("creator", true, true),
+    SYSTEM_PROGRAM,
+];
+
+const PROGRAM_CONFIG_UPDATE_ACCOUNTS: &[AccountSpec] = &[
+    AccountSpec::new("program_config", true, false),
+    AccountSpec::new("authority", false, true),
+];
+
+/// Account layout of the instruction built by the builder for `kind`
+///
+/// UIs can use this to label the accounts of an instruction before it is signed.
+pub fn metadata(kind: InstructionKind) -> InstructionMeta {
+    let (accounts, remaining_accounts): (&'static [AccountSpec], _) = match kind {
+        InstructionKind::MultisigCreateV2 => (MULTISIG_CREATE_V2_ACCOUNTS, None),
+        InstructionKind::MultisigAddMember
+        | InstructionKind::MultisigRemoveMember
+        | InstructionKind::MultisigSetTimeLock
+        | InstructionKind::MultisigSetRentCollector => (CONTROLLED_CONFIG_ACCOUNTS, None),
+        InstructionKind::MultisigAddSpendingLimit => (MULTISIG_ADD_SPENDING_LIMIT_ACCOUNTS, None),
+        InstructionKind::MultisigRemoveSpendingLimit => {
+            (MULTISIG_REMOVE_SPENDING_LIMIT_ACCOUNTS, None)
+        }
+        InstructionKind::ProposalCreate => (PROPOSAL_CREATE_ACCOUNTS, None),
+        InstructionKind::ProposalApprove
+        | InstructionKind::ProposalReject
+        | InstructionKind::ProposalCancel => (PROPOSAL_VOTE_ACCOUNTS, None),
+        InstructionKind::ProposalActivate => (PROPOSAL_ACTIVATE_ACCOUNTS, None),
+        InstructionKind::VaultTransactionCreate => (VAULT_TRANSACTION_CREATE_ACCOUNTS, None),
+        InstructionKind::VaultTransactionExecute => (
+            VAULT_TRANSACTION_EXECUTE_ACCOUNTS,
+            Some("address lookup tables, then the accounts of the vault transaction message"),
+        ),
+        InstructionKind::BatchCreate => (BATCH_CREATE_ACCOUNTS, None),
+        InstructionKind::BatchAddTransaction => (BATCH_ADD_TRANSACTION_ACCOUNTS, None),
+        InstructionKind::ConfigTransactionCreate => (CONFIG_TRANSACTION_CREATE_ACCOUNTS, None),
+        InstructionKind::ConfigTransactionExecute => (
+            CONFIG_TRANSACTION_EXECUTE_ACCOUNTS,
+            Some("spending limit accounts added or removed by the transaction (writable)"),
+        ),
+        InstructionKind::SpendingLimitUse => (SPENDING_LIMIT_USE_ACCOUNTS, None),
+        InstructionKind::ConfigTransactionAccountsClose
+        | InstructionKind::VaultTransactionAccountsClose => (ACCOUNTS_CLOSE_ACCOUNTS, None),
+        InstructionKind::BatchAccountsClose => (BATCH_ACCOUNTS_CLOSE_ACCOUNTS, None),
+        InstructionKind::VaultBatchTransactionAccountClose => {
+            (VAULT_BATCH_TRANSACTION_ACCOUNT_CLOSE_ACCOUNTS, None)
+        }
+        InstructionKind::TransactionBufferCreate => (TRANSACTION_BUFFER_CREATE_ACCOUNTS, None),
+        InstructionKind::TransactionBufferExtend => (TRANSACTION_BUFFER_EXTEND_ACCOUNTS, None),
+        InstructionKind::TransactionBufferClose => (TRANSACTION_BUFFER_CLOSE_ACCOUNTS, None),
+        InstructionKind::VaultTransactionCreateFromBuffer => {
+            (VAULT_TRANSACTION_CREATE_FROM_BUFFER_ACCOUNTS, None)
+        }
+        InstructionKind::ProgramConfigInit => (PROGRAM_CONFIG_INIT_ACCOUNTS, None),
+        InstructionKind::ProgramConfigSetAuthority
+        | InstructionKind::ProgramConfigSetMultisigCreationFee
+        | InstructionKind::ProgramConfigSetTreasury => (PROGRAM_CONFIG_UPDATE_ACCOUNTS, None),
+        InstructionKind::MultisigCreate => (MULTISIG_CREATE_ACCOUNTS, None),
+        InstructionKind::ProposalCancelV2 => (PROPOSAL_CANCEL_V2_ACCOUNTS, None),
+    };
+
+    InstructionMeta {
+        kind,
         accounts,
-        data,
+        remaining_accounts,
     }
 }
 
@@ -570,4 +2447,967 @@ mod tests {
         assert_eq!(ix.accounts.len(), 6);
         assert!(!ix.data.is_empty());
     }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_legacy_multisig_create_instruction() {
+        let create_key = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let (multisig, _) = crate::pda::get_multisig_pda(&create_key, None);
+        let args = MultisigCreateArgs {
+            config_authority: None,
+            threshold: 1,
+            members: vec![Member::new(creator)],
+            time_lock: 0,
+            memo: None,
+        };
+
+        let ix = multisig_create(multisig, create_key, creator, args.clone(), None);
+        let keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(
+            keys,
+            vec![multisig, create_key, creator, system_program::ID]
+        );
+        assert_eq!(&ix.data[..8], &instruction_discriminator("multisig_create"));
+        assert_eq!(
+            MultisigCreateArgs::try_from_slice(&ix.data[8..]).unwrap(),
+            args
+        );
+
+        let v2 = MultisigCreateArgsV2::from(args);
+        assert_eq!(v2.threshold, 1);
+        assert_eq!(v2.rent_collector, None);
+    }
+
+    #[test]
+    fn test_multisig_add_member_instruction() {
+        let multisig = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let member = Member::new(Pubkey::new_unique());
+        let args = MultisigAddMemberArgs::new(member.clone(), None).unwrap();
+
+        let ix = multisig_add_member(multisig, authority, authority, args, None);
+        assert_eq!(ix.accounts.len(), 4);
+        assert!(ix.accounts[0].is_writable);
+        assert!(ix.accounts[1].is_signer && ix.accounts[2].is_signer);
+        assert_eq!(
+            &ix.data[..8],
+            &instruction_discriminator("multisig_add_member")
+        );
+
+        let decoded = MultisigAddMemberArgs::try_from_slice(&ix.data[8..]).unwrap();
+        assert_eq!(decoded.new_member, member);
+        assert_eq!(decoded.memo, None);
+    }
+
+    #[test]
+    fn test_multisig_remove_member_instruction() {
+        let multisig = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let old_member = Pubkey::new_unique();
+        let args = MultisigRemoveMemberArgs::new(old_member, Some("offboard".to_string())).unwrap();
+
+        let ix = multisig_remove_member(multisig, authority, args.clone(), None);
+        assert_eq!(ix.accounts.len(), 4);
+        assert!(ix.accounts[1].is_signer);
+        assert_eq!(ix.accounts[2].pubkey, crate::program_id());
+        assert_eq!(
+            &ix.data[..8],
+            &instruction_discriminator("multisig_remove_member")
+        );
+        assert_eq!(
+            MultisigRemoveMemberArgs::try_from_slice(&ix.data[8..]).unwrap(),
+            args
+        );
+    }
+
+    #[test]
+    fn test_multisig_set_time_lock_instruction() {
+        let multisig = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let args = MultisigSetTimeLockArgs::new(3_600, None).unwrap();
+
+        let ix = multisig_set_time_lock(multisig, authority, args.clone(), None);
+        assert_eq!(ix.accounts.len(), 4);
+        assert!(ix.accounts[0].is_writable && ix.accounts[1].is_signer);
+        assert_eq!(
+            &ix.data[..8],
+            &instruction_discriminator("multisig_set_time_lock")
+        );
+        assert_eq!(
+            MultisigSetTimeLockArgs::try_from_slice(&ix.data[8..]).unwrap(),
+            args
+        );
+    }
+
+    #[test]
+    fn test_multisig_set_rent_collector_instruction() {
+        let multisig = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let args = MultisigSetRentCollectorArgs::new(Some(Pubkey::new_unique()), None).unwrap();
+
+        let ix = multisig_set_rent_collector(multisig, authority, None, args.clone(), None);
+        assert_eq!(ix.accounts.len(), 4);
+        assert!(ix.accounts[0].is_writable && ix.accounts[1].is_signer);
+        assert_eq!(ix.accounts[2].pubkey, crate::program_id());
+        assert_eq!(ix.accounts[3].pubkey, crate::program_id());
+
+        // A rent payer funds the realloc through the system program
+        let payer = Pubkey::new_unique();
+        let ix = multisig_set_rent_collector(multisig, authority, Some(payer), args.clone(), None);
+        assert_eq!(ix.accounts[2].pubkey, payer);
+        assert!(ix.accounts[2].is_signer && ix.accounts[2].is_writable);
+        assert_eq!(ix.accounts[3].pubkey, system_program::ID);
+        assert_eq!(
+            &ix.data[..8],
+            &instruction_discriminator("multisig_set_rent_collector")
+        );
+        assert_eq!(
+            MultisigSetRentCollectorArgs::try_from_slice(&ix.data[8..]).unwrap(),
+            args
+        );
+    }
+
+    #[test]
+    fn test_multisig_add_spending_limit_instruction() {
+        let multisig = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let payer = Pubkey::new_unique();
+        let args = MultisigAddSpendingLimitArgs::new(
+            Pubkey::new_unique(),
+            0,
+            Pubkey::default(),
+            1_000_000_000,
+            Period::Day,
+            vec![authority],
+            vec![],
+            None,
+        )
+        .unwrap();
+        let (spending_limit, _) =
+            crate::pda::get_spending_limit_pda(&multisig, &args.create_key, None);
+
+        let ix = multisig_add_spending_limit(
+            multisig,
+            authority,
+            spending_limit,
+            payer,
+            args.clone(),
+            None,
+        );
+        assert_eq!(ix.accounts.len(), 5);
+        assert!(!ix.accounts[0].is_writable && ix.accounts[1].is_signer);
+        assert_eq!(ix.accounts[2].pubkey, spending_limit);
+        assert!(ix.accounts[3].is_signer && ix.accounts[3].is_writable);
+        assert_eq!(
+            MultisigAddSpendingLimitArgs::try_from_slice(&ix.data[8..]).unwrap(),
+            args
+        );
+    }
+
+    #[test]
+    fn test_multisig_remove_spending_limit_instruction() {
+        let multisig = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let spending_limit = Pubkey::new_unique();
+        let rent_collector = Pubkey::new_unique();
+        let args = MultisigRemoveSpendingLimitArgs::new(Some("cleanup".to_string())).unwrap();
+
+        let ix = multisig_remove_spending_limit(
+            multisig,
+            authority,
+            spending_limit,
+            rent_collector,
+            args.clone(),
+            None,
+        );
+        assert_eq!(ix.accounts.len(), 4);
+        assert!(ix.accounts[1].is_signer);
+        assert!(ix.accounts[2].is_writable && ix.accounts[3].is_writable);
+        assert_eq!(ix.accounts[3].pubkey, rent_collector);
+        assert!(!ix.accounts[3].is_signer);
+        assert_eq!(
+            MultisigRemoveSpendingLimitArgs::try_from_slice(&ix.data[8..]).unwrap(),
+            args
+        );
+    }
+
+    #[test]
+    fn test_batch_create_instruction() {
+        let multisig = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let (batch, _) = crate::pda::get_batch_pda(&multisig, 1, None);
+        let args = BatchCreateArgs::new(0, Some("payroll".to_string())).unwrap();
+
+        let ix = batch_create(multisig, creator, creator, batch, args.clone(), None);
+        assert_eq!(ix.accounts.len(), 5);
+        assert!(ix.accounts[0].is_writable && ix.accounts[1].is_signer);
+        assert_eq!(ix.accounts[3].pubkey, batch);
+        assert_eq!(&ix.data[..8], &instruction_discriminator("batch_create"));
+        assert_eq!(
+            BatchCreateArgs::try_from_slice(&ix.data[8..]).unwrap(),
+            args
+        );
+    }
+
+    #[test]
+    fn test_batch_add_transaction_instruction() {
+        let multisig = Pubkey::new_unique();
+        let member = Pubkey::new_unique();
+        let (batch, _) = crate::pda::get_batch_pda(&multisig, 1, None);
+        let (proposal, _) = crate::pda::get_proposal_pda(&multisig, 1, None);
+        let (transaction, _) = crate::pda::get_batch_transaction_pda(&multisig, 1, 1, None);
+        let message = TransactionMessage::try_compile(&member, &[]).unwrap();
+        let args = BatchAddTransactionArgs::new(1, &message).unwrap();
+
+        let ix = batch_add_transaction(
+            multisig,
+            proposal,
+            batch,
+            transaction,
+            member,
+            member,
+            args.clone(),
+            None,
+        );
+        assert_eq!(ix.accounts.len(), 7);
+        assert!(ix.accounts[2].is_writable && ix.accounts[3].is_writable);
+        assert_eq!(ix.accounts[3].pubkey, transaction);
+        assert!(ix.accounts[4].is_signer && !ix.accounts[4].is_writable);
+        assert_eq!(
+            BatchAddTransactionArgs::try_from_slice(&ix.data[8..]).unwrap(),
+            args
+        );
+    }
+
+    #[test]
+    fn test_vault_transaction_accounts_close_instruction() {
+        let multisig = Pubkey::new_unique();
+        let rent_collector = Pubkey::new_unique();
+        let (proposal, _) = crate::pda::get_proposal_pda(&multisig, 4, None);
+        let (transaction, _) = crate::pda::get_transaction_pda(&multisig, 4, None);
+
+        let ix =
+            vault_transaction_accounts_close(multisig, proposal, transaction, rent_collector, None);
+        let keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(
+            keys,
+            vec![
+                multisig,
+                proposal,
+                transaction,
+                rent_collector,
+                system_program::ID
+            ]
+        );
+        assert!(ix.accounts.iter().all(|meta| !meta.is_signer));
+        assert!(!ix.accounts[0].is_writable && ix.accounts[3].is_writable);
+        assert_eq!(
+            ix.data,
+            instruction_discriminator("vault_transaction_accounts_close")
+        );
+    }
+
+    #[test]
+    fn test_transaction_buffer_create_instruction() {
+        let multisig = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let (buffer, _) = crate::pda::get_transaction_buffer_pda(&multisig, &creator, 0, None);
+        let message = vec![7u8; 1500];
+        let args = TransactionBufferCreateArgs::new(0, 1, &message, 900).unwrap();
+        assert_eq!(args.final_buffer_size, 1500);
+        assert_eq!(args.buffer.len(), 900);
+        assert_eq!(args.final_buffer_hash, hash(&message).to_bytes());
+
+        let ix = transaction_buffer_create(multisig, buffer, creator, creator, args.clone(), None);
+        assert_eq!(ix.accounts.len(), 5);
+        assert!(!ix.accounts[0].is_writable && ix.accounts[1].is_writable);
+        assert_eq!(ix.accounts[1].pubkey, buffer);
+        assert!(ix.accounts[2].is_signer && !ix.accounts[2].is_writable);
+        assert_eq!(
+            &ix.data[..8],
+            &instruction_discriminator("transaction_buffer_create")
+        );
+        assert_eq!(
+            TransactionBufferCreateArgs::try_from_slice(&ix.data[8..]).unwrap(),
+            args
+        );
+
+        let oversized = vec![0u8; MAX_TRANSACTION_BUFFER_SIZE + 1];
+        assert!(matches!(
+            TransactionBufferCreateArgs::new(0, 0, &oversized, 100),
+            Err(SquadsError::TransactionTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_transaction_buffer_extend_instruction() {
+        let multisig = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let (buffer, _) = crate::pda::get_transaction_buffer_pda(&multisig, &creator, 0, None);
+        let message = vec![3u8; 2500];
+        let create = TransactionBufferCreateArgs::new(0, 0, &message, 1000).unwrap();
+        let slices: Vec<_> = message[create.buffer.len()..]
+            .chunks(1000)
+            .map(|slice| TransactionBufferExtendArgs {
+                buffer: slice.to_vec(),
+            })
+            .collect();
+        assert_eq!(slices.len(), 2);
+
+        let ix = transaction_buffer_extend(multisig, buffer, creator, slices[1].clone(), None);
+        assert_eq!(ix.accounts.len(), 3);
+        assert!(ix.accounts[1].is_writable && ix.accounts[2].is_signer);
+        assert_eq!(
+            &ix.data[..8],
+            &instruction_discriminator("transaction_buffer_extend")
+        );
+        assert_eq!(
+            TransactionBufferExtendArgs::try_from_slice(&ix.data[8..])
+                .unwrap()
+                .buffer
+                .len(),
+            500
+        );
+    }
+
+    #[test]
+    fn test_transaction_buffer_close_instruction() {
+        let multisig = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let (buffer, _) = crate::pda::get_transaction_buffer_pda(&multisig, &creator, 2, None);
+
+        let ix = transaction_buffer_close(multisig, buffer, creator, None);
+        let keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(keys, vec![multisig, buffer, creator]);
+        assert!(ix.accounts[2].is_signer && ix.accounts[2].is_writable);
+        assert_eq!(
+            ix.data,
+            instruction_discriminator("transaction_buffer_close")
+        );
+    }
+
+    #[test]
+    fn test_vault_transaction_create_from_buffer_instruction() {
+        let multisig = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let payer = Pubkey::new_unique();
+        let (transaction, _) = crate::pda::get_transaction_pda(&multisig, 5, None);
+        let (buffer, _) = crate::pda::get_transaction_buffer_pda(&multisig, &creator, 0, None);
+        let args =
+            VaultTransactionCreateArgs::from_buffer(1, 0, Some("large".to_string())).unwrap();
+
+        let ix = vault_transaction_create_from_buffer(
+            multisig,
+            transaction,
+            creator,
+            payer,
+            buffer,
+            args.clone(),
+            None,
+        );
+        let keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(
+            keys,
+            vec![
+                multisig,
+                transaction,
+                creator,
+                payer,
+                system_program::ID,
+                buffer,
+                creator
+            ]
+        );
+        assert!(ix.accounts[5].is_writable && !ix.accounts[5].is_signer);
+        assert!(ix.accounts[6].is_writable && ix.accounts[6].is_signer);
+        let decoded = VaultTransactionCreateArgs::try_from_slice(&ix.data[8..]).unwrap();
+        assert_eq!(decoded, args);
+        assert!(
+            TransactionMessage::try_from_slice(&decoded.transaction_message)
+                .unwrap()
+                .instructions
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_program_config_init_instruction() {
+        let (program_config, _) = crate::pda::get_program_config_pda(None);
+        let initializer = Pubkey::new_unique();
+        let args = ProgramConfigInitArgs {
+            authority: Pubkey::new_unique(),
+            multisig_creation_fee: 1_000_000,
+            treasury: Pubkey::new_unique(),
+        };
+
+        let ix = program_config_init(program_config, initializer, args.clone(), None);
+        assert_eq!(ix.accounts.len(), 3);
+        assert!(ix.accounts[0].is_writable && !ix.accounts[0].is_signer);
+        assert!(ix.accounts[1].is_writable && ix.accounts[1].is_signer);
+        assert_eq!(
+            &ix.data[..8],
+            &instruction_discriminator("program_config_init")
+        );
+        assert_eq!(
+            ProgramConfigInitArgs::try_from_slice(&ix.data[8..]).unwrap(),
+            args
+        );
+    }
+
+    #[test]
+    fn test_program_config_setters() {
+        let (program_config, _) = crate::pda::get_program_config_pda(None);
+        let authority = Pubkey::new_unique();
+        let new_key = Pubkey::new_unique();
+
+        let set_authority = program_config_set_authority(
+            program_config,
+            authority,
+            ProgramConfigSetAuthorityArgs {
+                new_authority: new_key,
+            },
+            None,
+        );
+        let set_fee = program_config_set_multisig_creation_fee(
+            program_config,
+            authority,
+            ProgramConfigSetMultisigCreationFeeArgs {
+                new_multisig_creation_fee: 5,
+            },
+            None,
+        );
+        let set_treasury = program_config_set_treasury(
+            program_config,
+            authority,
+            ProgramConfigSetTreasuryArgs {
+                new_treasury: new_key,
+            },
+            None,
+        );
+
+        for (ix, name) in [
+            (&set_authority, "program_config_set_authority"),
+            (&set_fee, "program_config_set_multisig_creation_fee"),
+            (&set_treasury, "program_config_set_treasury"),
+        ] {
+            assert_eq!(ix.accounts[0], AccountMeta::new(program_config, false));
+            assert_eq!(ix.accounts[1], AccountMeta::new_readonly(authority, true));
+            assert_eq!(&ix.data[..8], &instruction_discriminator(name));
+        }
+        assert_eq!(&set_authority.data[8..], new_key.as_ref());
+        assert_eq!(&set_fee.data[8..], &5u64.to_le_bytes());
+        assert_eq!(&set_treasury.data[8..], new_key.as_ref());
+    }
+
+    #[test]
+    fn test_proposal_cancel_v2_instruction() {
+        let multisig = Pubkey::new_unique();
+        let proposal = Pubkey::new_unique();
+        let member = Pubkey::new_unique();
+        let args = ProposalVoteArgs::new(Some("stale".to_string())).unwrap();
+
+        let v1 = proposal_cancel(multisig, proposal, member, args.clone(), None);
+        let v2 = proposal_cancel_v2(multisig, proposal, member, args, None);
+        assert_eq!(&v2.accounts[..3], &v1.accounts[..]);
+        assert_eq!(
+            v2.accounts[3],
+            AccountMeta::new_readonly(system_program::ID, false)
+        );
+        assert_eq!(
+            &v2.data[..8],
+            &instruction_discriminator("proposal_cancel_v2")
+        );
+        assert_eq!(&v2.data[8..], &v1.data[8..]);
+    }
+
+    #[test]
+    fn test_add_spending_limit_action() {
+        let multisig = Pubkey::new_unique();
+        let create_key = Pubkey::new_unique();
+        let member = Pubkey::new_unique();
+        let (action, spending_limit) = add_spending_limit_action(
+            &multisig,
+            create_key,
+            1,
+            Pubkey::default(),
+            500,
+            Period::Week,
+            vec![member],
+            vec![],
+            None,
+        );
+        assert_eq!(
+            spending_limit,
+            crate::pda::get_spending_limit_pda(&multisig, &create_key, None).0
+        );
+        assert!(matches!(
+            action,
+            ConfigAction::AddSpendingLimit {
+                create_key: key,
+                vault_index: 1,
+                amount: 500,
+                period: Period::Week,
+                ..
+            } if key == create_key
+        ));
+
+        let ix = config_transaction_execute(
+            multisig,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            member,
+            Some(member),
+            vec![spending_limit],
+            None,
+        );
+        let last = ix.accounts.last().unwrap();
+        assert_eq!(last.pubkey, spending_limit);
+        assert!(last.is_writable);
+    }
+
+    #[test]
+    fn test_metadata_matches_builders() {
+        let key = Pubkey::new_unique;
+        let vote = || ProposalVoteArgs::new(None).unwrap();
+        let built = |kind: InstructionKind| match kind {
+            InstructionKind::MultisigCreateV2 => multisig_create_v2(
+                key(),
+                key(),
+                key(),
+                key(),
+                key(),
+                MultisigCreateArgsV2::new(None, 1, vec![Member::new(key())], 0, None, None)
+                    .unwrap(),
+                None,
+            ),
+            InstructionKind::MultisigAddMember => multisig_add_member(
+                key(),
+                key(),
+                key(),
+                MultisigAddMemberArgs::new(Member::new(key()), None).unwrap(),
+                None,
+            ),
+            InstructionKind::MultisigRemoveMember => multisig_remove_member(
+                key(),
+                key(),
+                MultisigRemoveMemberArgs::new(key(), None).unwrap(),
+                None,
+            ),
+            InstructionKind::MultisigSetTimeLock => multisig_set_time_lock(
+                key(),
+                key(),
+                MultisigSetTimeLockArgs::new(0, None).unwrap(),
+                None,
+            ),
+            InstructionKind::MultisigSetRentCollector => multisig_set_rent_collector(
+                key(),
+                key(),
+                Some(key()),
+                MultisigSetRentCollectorArgs::new(None, None).unwrap(),
+                None,
+            ),
+            InstructionKind::MultisigAddSpendingLimit => multisig_add_spending_limit(
+                key(),
+                key(),
+                key(),
+                key(),
+                MultisigAddSpendingLimitArgs::new(
+                    key(),
+                    0,
+                    Pubkey::default(),
+                    1,
+                    Period::Day,
+                    vec![],
+                    vec![],
+                    None,
+                )
+                .unwrap(),
+                None,
+            ),
+            InstructionKind::MultisigRemoveSpendingLimit => multisig_remove_spending_limit(
+                key(),
+                key(),
+                key(),
+                key(),
+                MultisigRemoveSpendingLimitArgs::new(None).unwrap(),
+                None,
+            ),
+            InstructionKind::ProposalCreate => proposal_create(
+                key(),
+                key(),
+                key(),
+                key(),
+                ProposalCreateArgs {
+                    transaction_index: 1,
+                    draft: false,
+                },
+                None,
+            ),
+            InstructionKind::ProposalApprove => proposal_approve(key(), key(), key(), vote(), None),
+            InstructionKind::ProposalReject => proposal_reject(key(), key(), key(), vote(), None),
+            InstructionKind::ProposalCancel => proposal_cancel(key(), key(), key(), vote(), None),
+            InstructionKind::ProposalActivate => proposal_activate(key(), key(), key(), None),
+            InstructionKind::VaultTransactionCreate => vault_transaction_create(
+                key(),
+                key(),
+                key(),
+                key(),
+                VaultTransactionCreateArgs::new(0, 0, vec![], None).unwrap(),
+                None,
+            ),
+            InstructionKind::VaultTransactionExecute => {
+                vault_transaction_execute(key(), key(), key(), key(), vec![], None)
+            }
+            InstructionKind::BatchCreate => batch_create(
+                key(),
+                key(),
+                key(),
+                key(),
+                BatchCreateArgs::new(0, None).unwrap(),
+                None,
+            ),
+            InstructionKind::BatchAddTransaction => batch_add_transaction(
+                key(),
+                key(),
+                key(),
+                key(),
+                key(),
+                key(),
+                BatchAddTransactionArgs {
+                    ephemeral_signers: 0,
+                    transaction_message: vec![],
+                },
+                None,
+            ),
+            InstructionKind::ConfigTransactionCreate => config_transaction_create(
+                key(),
+                key(),
+                key(),
+                key(),
+                ConfigTransactionCreateArgs::new(vec![], None).unwrap(),
+                None,
+            ),
+            InstructionKind::ConfigTransactionExecute => {
+                config_transaction_execute(key(), key(), key(), key(), Some(key()), vec![], None)
+            }
+            InstructionKind::SpendingLimitUse => spending_limit_use(
+                key(),
+                key(),
+                key(),
+                key(),
+                key(),
+                Some(key()),
+                Some(key()),
+                Some(key()),
+                Some(key()),
+                SpendingLimitUseArgs::new(1, 0, None).unwrap(),
+                None,
+            ),
+            InstructionKind::ConfigTransactionAccountsClose => {
+                config_transaction_accounts_close(key(), key(), key(), key(), None)
+            }
+            InstructionKind::VaultTransactionAccountsClose => {
+                vault_transaction_accounts_close(key(), key(), key(), key(), None)
+            }
+            InstructionKind::BatchAccountsClose => {
+                batch_accounts_close(key(), key(), key(), key(), None)
+            }
+            InstructionKind::VaultBatchTransactionAccountClose => {
+                vault_batch_transaction_account_close(key(), key(), key(), key(), key(), None)
+            }
+            InstructionKind::TransactionBufferCreate => transaction_buffer_create(
+                key(),
+                key(),
+                key(),
+                key(),
+                TransactionBufferCreateArgs::new(0, 0, &[1, 2, 3], 3).unwrap(),
+                None,
+            ),
+            InstructionKind::TransactionBufferExtend => transaction_buffer_extend(
+                key(),
+                key(),
+                key(),
+                TransactionBufferExtendArgs { buffer: vec![4, 5] },
+                None,
+            ),
+            InstructionKind::TransactionBufferClose => {
+                transaction_buffer_close(key(), key(), key(), None)
+            }
+            InstructionKind::VaultTransactionCreateFromBuffer => {
+                vault_transaction_create_from_buffer(
+                    key(),
+                    key(),
+                    key(),
+                    key(),
+                    key(),
+                    VaultTransactionCreateArgs::from_buffer(0, 0, None).unwrap(),
+                    None,
+                )
+            }
+            InstructionKind::ProgramConfigInit => program_config_init(
+                key(),
+                key(),
+                ProgramConfigInitArgs {
+                    authority: key(),
+                    multisig_creation_fee: 0,
+                    treasury: key(),
+                },
+                None,
+            ),
+            InstructionKind::ProgramConfigSetAuthority => program_config_set_authority(
+                key(),
+                key(),
+                ProgramConfigSetAuthorityArgs {
+                    new_authority: key(),
+                },
+                None,
+            ),
+            InstructionKind::ProgramConfigSetMultisigCreationFee => {
+                program_config_set_multisig_creation_fee(
+                    key(),
+                    key(),
+                    ProgramConfigSetMultisigCreationFeeArgs {
+                        new_multisig_creation_fee: 0,
+                    },
+                    None,
+                )
+            }
+            InstructionKind::ProgramConfigSetTreasury => program_config_set_treasury(
+                key(),
+                key(),
+                ProgramConfigSetTreasuryArgs {
+                    new_treasury: key(),
+                },
+                None,
+            ),
+            #[allow(deprecated)]
+            InstructionKind::MultisigCreate => multisig_create(
+                key(),
+                key(),
+                key(),
+                MultisigCreateArgs {
+                    config_authority: None,
+                    threshold: 1,
+                    members: vec![Member::new(key())],
+                    time_lock: 0,
+                    memo: None,
+                },
+                None,
+            ),
+            InstructionKind::ProposalCancelV2 => {
+                proposal_cancel_v2(key(), key(), key(), vote(), None)
+            }
+        };
+
+        for kind in InstructionKind::ALL {
+            let ix = built(kind);
+            let meta = metadata(kind);
+            assert_eq!(&ix.data[..8], &instruction_discriminator(kind.name()));
+            assert_eq!(ix.accounts.len(), meta.accounts.len(), "{:?}", kind);
+            for (account, spec) in ix.accounts.iter().zip(meta.accounts) {
+                if spec.optional && account.pubkey == ix.program_id {
+                    assert!(!account.is_writable && !account.is_signer);
+                    continue;
+                }
+                let label = format!("{:?} {}", kind, spec.name);
+                assert_eq!(account.is_writable, spec.writable, "{}", label);
+                assert_eq!(account.is_signer, spec.signer, "{}", label);
+            }
+        }
+    }
+
+    #[cfg(feature = "serde-args")]
+    #[test]
+    fn test_multisig_create_args_from_json() {
+        let member = Pubkey::new_unique();
+        let json = format!(
+            r#"{{
+                "threshold": 1,
+                "members": [{{ "key": "{}", "permissions": {{ "mask": 7 }} }}],
+                "time_lock": 0,
+                "rent_collector": "{}",
+                "memo": "from http"
+            }}"#,
+            member, member
+        );
+
+        // Omitted optional keys decode to None
+        let args: MultisigCreateArgsV2 = serde_json::from_str(&json).unwrap();
+        assert_eq!(args.config_authority, None);
+        assert_eq!(args.members[0].key, member);
+        assert_eq!(args.rent_collector, Some(member));
+        assert_eq!(args.memo.as_deref(), Some("from http"));
+
+        let action: ConfigAction = serde_json::from_str(&format!(
+            r#"{{"RemoveMember":{{"old_member":"{}"}}}}"#,
+            member
+        ))
+        .unwrap();
+        assert_eq!(action, ConfigAction::RemoveMember { old_member: member });
+        let action: ConfigAction = serde_json::from_str(r#"{"SetRentCollector":{}}"#).unwrap();
+        assert_eq!(
+            action,
+            ConfigAction::SetRentCollector {
+                new_rent_collector: None
+            }
+        );
+    }
+
+    #[test]
+    fn test_args_reject_long_memo() {
+        let long = "x".repeat(crate::memo::MAX_MEMO_LENGTH + 1);
+        assert!(ProposalVoteArgs::new(Some(long.clone())).is_err());
+        assert!(ConfigTransactionCreateArgs::new(vec![], Some(long.clone())).is_err());
+
+        let args = ProposalVoteArgs::with_memo_policy(Some(long), MemoPolicy::Truncate).unwrap();
+        assert_eq!(args.memo.unwrap().len(), crate::memo::MAX_MEMO_LENGTH);
+    }
+
+    #[test]
+    fn test_vote_options_spl_memo() {
+        let multisig = Pubkey::new_unique();
+        let proposal = Pubkey::new_unique();
+        let member = Pubkey::new_unique();
+        let vote = |options: &VoteOptions| {
+            let ix = proposal_approve(multisig, proposal, member, options.args().unwrap(), None);
+            options.instructions(ix, &member)
+        };
+
+        assert_eq!(vote(&VoteOptions::with_memo("audit")).len(), 1);
+        // Without a memo there is nothing to emit
+        assert_eq!(vote(&VoteOptions::default().with_spl_memo()).len(), 1);
+
+        let ixs = vote(&VoteOptions::with_memo("audit").with_spl_memo());
+        assert_eq!(ixs.len(), 2);
+        assert_eq!(ixs[0].program_id, crate::programs::MEMO_PROGRAM_ID);
+        assert_eq!(ixs[0].data, b"audit");
+        assert!(ixs[0].accounts[0].is_signer && ixs[0].accounts[0].pubkey == member);
+        assert_eq!(ixs[1].program_id, crate::program_id());
+    }
+
+    mod proptests {
+        use super::*;
+        use crate::types::{Member, Period, Permissions};
+        use proptest::prelude::*;
+
+        fn pubkey() -> impl Strategy<Value = Pubkey> {
+            any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+        }
+
+        fn memo() -> impl Strategy<Value = Option<String>> {
+            prop::option::of(".{0,64}")
+        }
+
+        fn member() -> impl Strategy<Value = Member> {
+            (pubkey(), 0u8..8)
+                .prop_map(|(key, mask)| Member::with_permissions(key, Permissions::from_mask(mask)))
+        }
+
+        fn config_action() -> impl Strategy<Value = ConfigAction> {
+            prop_oneof![
+                member().prop_map(|new_member| ConfigAction::AddMember { new_member }),
+                pubkey().prop_map(|old_member| ConfigAction::RemoveMember { old_member }),
+                any::<u16>()
+                    .prop_map(|new_threshold| ConfigAction::ChangeThreshold { new_threshold }),
+                any::<u32>().prop_map(|new_time_lock| ConfigAction::SetTimeLock { new_time_lock }),
+                (
+                    pubkey(),
+                    any::<u8>(),
+                    pubkey(),
+                    any::<u64>(),
+                    prop_oneof![
+                        Just(Period::OneTime),
+                        Just(Period::Day),
+                        Just(Period::Week),
+                        Just(Period::Month)
+                    ],
+                    prop::collection::vec(pubkey(), 0..4),
+                    prop::collection::vec(pubkey(), 0..4),
+                )
+                    .prop_map(
+                        |(create_key, vault_index, mint, amount, period, members, destinations)| {
+                            ConfigAction::AddSpendingLimit {
+                                create_key,
+                                vault_index,
+                                mint,
+                                amount,
+                                period,
+                                members,
+                                destinations,
+                            }
+                        }
+                    ),
+                prop::option::of(pubkey()).prop_map(|new_config_authority| {
+                    ConfigAction::SetConfigAuthority {
+                        new_config_authority,
+                    }
+                }),
+            ]
+        }
+
+        fn round_trip<T: BorshSerialize + BorshDeserialize + PartialEq + std::fmt::Debug>(
+            value: &T,
+        ) -> Result<(), TestCaseError> {
+            let bytes = borsh::to_vec(value).unwrap();
+            let decoded = T::try_from_slice(&bytes).unwrap();
+            prop_assert_eq!(&decoded, value);
+            prop_assert_eq!(borsh::to_vec(&decoded).unwrap(), bytes);
+            Ok(())
+        }
+
+        proptest! {
+            #[test]
+            fn multisig_create_args_round_trip(
+                config_authority in prop::option::of(pubkey()),
+                threshold in any::<u16>(),
+                members in prop::collection::vec(member(), 0..16),
+                time_lock in any::<u32>(),
+                rent_collector in prop::option::of(pubkey()),
+                memo in memo(),
+            ) {
+                round_trip(&MultisigCreateArgsV2 {
+                    config_authority,
+                    threshold,
+                    members,
+                    time_lock,
+                    rent_collector,
+                    memo,
+                })?;
+            }
+
+            #[test]
+            fn vault_transaction_create_args_round_trip(
+                vault_index in any::<u8>(),
+                ephemeral_signers in any::<u8>(),
+                transaction_message in prop::collection::vec(any::<u8>(), 0..1024),
+                memo in memo(),
+            ) {
+                round_trip(&VaultTransactionCreateArgs {
+                    vault_index,
+                    ephemeral_signers,
+                    transaction_message,
+                    memo,
+                })?;
+            }
+
+            #[test]
+            fn config_transaction_create_args_round_trip(
+                actions in prop::collection::vec(config_action(), 0..8),
+                memo in memo(),
+            ) {
+                round_trip(&ConfigTransactionCreateArgs { actions, memo })?;
+            }
+
+            #[test]
+            fn vote_and_spending_limit_args_round_trip(
+                memo in memo(),
+                amount in any::<u64>(),
+                decimals in any::<u8>(),
+            ) {
+                round_trip(&ProposalVoteArgs { memo: memo.clone() })?;
+                round_trip(&SpendingLimitUseArgs { amount, decimals, memo })?;
+            }
+        }
+    }
 }
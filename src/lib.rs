@@ -12,11 +12,23 @@
 //! - **PDA Utilities**: Helper functions for deriving program-derived addresses
 //! - **Standalone**: No dependencies on the Anchor program crate, making it lightweight and flexible
 //!
+//! ## On-chain use
+//!
+//! With default features disabled, only [`accounts`], [`types`], [`pda`], [`error`] and
+//! [`serde_utils`] are built, depending on `solana-program` instead of `solana-sdk` and `solana-client`,
+//! so a program can check a Squads multisig passed to it:
+//!
+//! ```toml
+//! squads-v4-client-v3 = { version = "0.1.0", default-features = false }
+//! ```
+//!
 //! ## Usage
 //!
+//! Commonly used items are available from [`prelude`].
+//!
 //! ```rust
 //! use squads_v4_client_v3::pda;
-//! use solana_sdk::pubkey::Pubkey;
+//! use solana_program::pubkey::Pubkey;
 //!
 //! // Derive a multisig PDA
 //! let create_key = Pubkey::new_unique();
@@ -25,19 +37,126 @@
 
 pub mod accounts;
 pub mod error;
-pub mod instructions;
-pub mod message;
 pub mod pda;
+pub mod serde_utils;
 pub mod types;
 
+#[cfg(feature = "client")]
+pub mod alt;
+#[cfg(feature = "client")]
+pub mod budget;
+#[cfg(feature = "client")]
+pub mod codec;
+#[cfg(feature = "client")]
+pub mod compat;
+#[cfg(feature = "client")]
+pub mod dialect;
+#[cfg(feature = "client")]
+pub mod diff;
+#[cfg(feature = "client")]
+pub mod events;
+#[cfg(feature = "client")]
+pub mod examples_config;
+#[cfg(feature = "client")]
+pub mod failure;
+#[cfg(feature = "client")]
+pub mod guard;
+#[cfg(feature = "client")]
+pub mod history;
+#[cfg(feature = "client")]
+pub mod impact;
+#[cfg(feature = "client")]
+pub mod indexer;
+#[cfg(feature = "client")]
+pub mod instructions;
+#[cfg(feature = "client")]
+pub mod links;
+#[cfg(feature = "client")]
+pub mod memo;
+#[cfg(feature = "client")]
+pub mod message;
+#[cfg(feature = "client")]
+pub mod nonce;
+#[cfg(feature = "client")]
+pub mod parser;
+#[cfg(feature = "client")]
+pub mod policy;
+#[cfg(feature = "client")]
+pub mod portfolio;
+#[cfg(feature = "client")]
+pub mod prefetch;
+#[cfg(feature = "client")]
+pub mod prelude;
+#[cfg(feature = "client")]
+pub mod pricing;
+#[cfg(feature = "client")]
+pub mod programs;
+#[cfg(feature = "client")]
+pub mod reader;
+#[cfg(feature = "client")]
+pub mod recovery;
+#[cfg(feature = "client")]
+pub mod reminders;
+#[cfg(feature = "client")]
+pub mod report;
+#[cfg(feature = "client")]
+pub mod schedule;
+#[cfg(feature = "client")]
+pub mod sim;
+#[cfg(feature = "client")]
+pub mod sizing;
+#[cfg(feature = "client")]
+pub mod spec;
+#[cfg(feature = "client")]
+pub mod templates;
+#[cfg(feature = "client")]
+pub mod testing;
+#[cfg(feature = "client")]
+pub mod token;
+#[cfg(feature = "client")]
+pub mod upgrade;
+#[cfg(feature = "client")]
+pub mod voting;
+#[cfg(feature = "client")]
+pub mod watch;
+#[cfg(feature = "client")]
+pub mod workspace;
+
+#[cfg(feature = "async")]
+pub mod blockhash;
+
 #[cfg(feature = "async")]
 pub mod client;
 
+#[cfg(feature = "das")]
+pub mod das;
+
+#[cfg(feature = "async")]
+pub mod runtime;
+
+#[cfg(all(test, feature = "mainnet-vectors"))]
+mod mainnet_vectors;
+
 // Re-export commonly used types
 pub use error::{SquadsError, SquadsResult};
+#[cfg(feature = "client")]
 pub use message::{CompiledInstruction, MessageAddressTableLookup, TransactionMessage};
 pub use types::{Member, Permission, Permissions};
 
+// Re-export the solana-sdk types used in public signatures, and the sdk itself, so
+// consumers can match the exact version this crate was built against
+pub use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+#[cfg(feature = "client")]
+pub use solana_sdk;
+#[cfg(feature = "client")]
+pub use solana_sdk::{
+    signature::{Keypair, Signature},
+    signer::Signer,
+};
+
 /// The canonical Squads v4 program ID on mainnet-beta
 pub const SQUADS_PROGRAM_ID: &str = "SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf";
 
@@ -51,9 +170,11 @@ pub mod seeds {
     pub const SEED_PROPOSAL: &[u8] = b"proposal";
     pub const SEED_SPENDING_LIMIT: &[u8] = b"spending_limit";
     pub const SEED_EPHEMERAL_SIGNER: &[u8] = b"ephemeral_signer";
+    pub const SEED_BATCH_TRANSACTION: &[u8] = b"batch_transaction";
+    pub const SEED_TRANSACTION_BUFFER: &[u8] = b"transaction_buffer";
 }
 
 /// Returns the canonical Squads v4 program ID
-pub fn program_id() -> solana_sdk::pubkey::Pubkey {
+pub fn program_id() -> Pubkey {
     SQUADS_PROGRAM_ID.parse().unwrap()
 }
@@ -0,0 +1,500 @@
+//! Out-of-band approval request payloads
+//!
+//! This module encodes everything a mobile or hardware signer needs to locate and verify a
+//! proposal — cluster, program, multisig, proposal PDA and a hash of the transaction content —
+//! into a compact `squads://approve?...` deep link. With the `qr` feature enabled the link can
+//! also be rendered as a QR code.
+//!
+//! [`to_app_url`] and [`parse_app_url`] convert to and from the URLs the Squads web app
+//! uses to share transactions, for output that should open in the UI approvers use.
+//!
+//! [`deposit_uri`] and [`DepositRequest`] produce Solana Pay `solana:` transfer request
+//! URIs for funding a vault with SOL or an SPL token, e.g. in invoices.
+
+use std::fmt;
+use std::str::FromStr;
+
+use solana_sdk::{
+    hash::{hash, Hash},
+    pubkey::Pubkey,
+};
+
+use crate::error::{SquadsError, SquadsResult};
+use crate::pda;
+
+/// URI scheme and path used for approval requests
+const APPROVAL_URI_PREFIX: &str = "squads://approve?";
+
+/// URI scheme of Solana Pay transfer requests
+const SOLANA_PAY_SCHEME: &str = "solana:";
+
+/// Base URL of the Squads web app
+pub const SQUADS_APP_URL: &str = "https://app.squads.so";
+
+/// Hosts the Squads web app has been served from
+const SQUADS_APP_HOSTS: [&str; 2] = ["app.squads.so", "v4.squads.so"];
+
+/// Solana cluster a proposal lives on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cluster {
+    /// mainnet-beta
+    Mainnet,
+    /// devnet
+    Devnet,
+    /// testnet
+    Testnet,
+    /// A local validator
+    Localnet,
+    /// Any other cluster, e.g. a custom RPC URL (percent-encoded in links)
+    Custom(String),
+}
+
+impl fmt::Display for Cluster {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cluster::Mainnet => write!(f, "mainnet-beta"),
+            Cluster::Devnet => write!(f, "devnet"),
+            Cluster::Testnet => write!(f, "testnet"),
+            Cluster::Localnet => write!(f, "localnet"),
+            Cluster::Custom(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl FromStr for Cluster {
+    type Err = SquadsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "mainnet-beta" | "mainnet" => Cluster::Mainnet,
+            "devnet" => Cluster::Devnet,
+            "testnet" => Cluster::Testnet,
+            "localnet" => Cluster::Localnet,
+            "" => return Err(SquadsError::InvalidLink("empty cluster".to_string())),
+            other => Cluster::Custom(other.to_string()),
+        })
+    }
+}
+
+/// A request for a member to approve a proposal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalRequest {
+    /// Cluster the multisig lives on
+    pub cluster: Cluster,
+    /// Squads program ID
+    pub program_id: Pubkey,
+    /// Multisig account
+    pub multisig: Pubkey,
+    /// Index of the transaction being approved
+    pub transaction_index: u64,
+    /// Proposal PDA for the transaction
+    pub proposal: Pubkey,
+    /// Hash of the transaction account data, so the signer can verify what it approves
+    pub content_hash: Hash,
+}
+
+impl ApprovalRequest {
+    /// Encode the request as a `squads://approve?...` deep link
+    pub fn to_uri(&self) -> String {
+        format!(
+            "{}cluster={}&program={}&multisig={}&index={}&proposal={}&hash={}",
+            APPROVAL_URI_PREFIX,
+            percent_encode(&self.cluster.to_string()),
+            self.program_id,
+            self.multisig,
+            self.transaction_index,
+            self.proposal,
+            self.content_hash,
+        )
+    }
+
+    /// Parse a deep link produced by [`ApprovalRequest::to_uri`]
+    ///
+    /// The proposal PDA is re-derived and checked against the encoded value.
+    pub fn from_uri(uri: &str) -> SquadsResult<Self> {
+        let query = uri
+            .strip_prefix(APPROVAL_URI_PREFIX)
+            .ok_or_else(|| SquadsError::InvalidLink("not an approval link".to_string()))?;
+
+        let mut cluster = None;
+        let mut program_id = None;
+        let mut multisig = None;
+        let mut transaction_index = None;
+        let mut proposal = None;
+        let mut content_hash = None;
+
+        for pair in query.split('&') {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| SquadsError::InvalidLink(format!("malformed pair: {}", pair)))?;
+            let invalid = || SquadsError::InvalidLink(format!("invalid {}: {}", key, value));
+            match key {
+                "cluster" => {
+                    cluster = Some(
+                        percent_decode(value)
+                            .ok_or_else(invalid)?
+                            .parse::<Cluster>()?,
+                    )
+                }
+                "program" => program_id = Some(value.parse::<Pubkey>().map_err(|_| invalid())?),
+                "multisig" => multisig = Some(value.parse::<Pubkey>().map_err(|_| invalid())?),
+                "index" => transaction_index = Some(value.parse::<u64>().map_err(|_| invalid())?),
+                "proposal" => proposal = Some(value.parse::<Pubkey>().map_err(|_| invalid())?),
+                "hash" => content_hash = Some(value.parse::<Hash>().map_err(|_| invalid())?),
+                // Unknown keys are ignored for forward compatibility
+                _ => {}
+            }
+        }
+
+        let missing = |key: &str| SquadsError::InvalidLink(format!("missing {}", key));
+        let request = Self {
+            cluster: cluster.ok_or_else(|| missing("cluster"))?,
+            program_id: program_id.ok_or_else(|| missing("program"))?,
+            multisig: multisig.ok_or_else(|| missing("multisig"))?,
+            transaction_index: transaction_index.ok_or_else(|| missing("index"))?,
+            proposal: proposal.ok_or_else(|| missing("proposal"))?,
+            content_hash: content_hash.ok_or_else(|| missing("hash"))?,
+        };
+
+        let (expected, _) = pda::get_proposal_pda(
+            &request.multisig,
+            request.transaction_index,
+            Some(&request.program_id),
+        );
+        if expected != request.proposal {
+            return Err(SquadsError::InvalidLink(
+                "proposal does not match multisig and index".to_string(),
+            ));
+        }
+
+        Ok(request)
+    }
+
+    /// Check that the given transaction account data matches the encoded content hash
+    pub fn verify_content(&self, transaction_data: &[u8]) -> bool {
+        content_hash(transaction_data) == self.content_hash
+    }
+
+    /// Render the deep link as an SVG QR code
+    #[cfg(feature = "qr")]
+    pub fn to_qr_svg(&self) -> SquadsResult<String> {
+        use qrcode::{render::svg, QrCode};
+
+        let code = QrCode::new(self.to_uri().as_bytes())
+            .map_err(|e| SquadsError::InvalidLink(e.to_string()))?;
+        Ok(code.render::<svg::Color>().min_dimensions(256, 256).build())
+    }
+}
+
+/// Hash of a transaction account's raw data
+pub fn content_hash(transaction_data: &[u8]) -> Hash {
+    hash(transaction_data)
+}
+
+/// Build an approval request for a transaction
+///
+/// # Arguments
+/// * `cluster` - Cluster the multisig lives on
+/// * `multisig` - Multisig account
+/// * `transaction_index` - Index of the transaction to approve
+/// * `transaction_data` - Raw data of the vault or config transaction account
+/// * `program_id` - Optional custom program ID (uses canonical ID if None)
+pub fn approval_request(
+    cluster: Cluster,
+    multisig: &Pubkey,
+    transaction_index: u64,
+    transaction_data: &[u8],
+    program_id: Option<&Pubkey>,
+) -> ApprovalRequest {
+    let program_id = program_id.copied().unwrap_or_else(crate::program_id);
+    let (proposal, _) = pda::get_proposal_pda(multisig, transaction_index, Some(&program_id));
+
+    ApprovalRequest {
+        cluster,
+        program_id,
+        multisig: *multisig,
+        transaction_index,
+        proposal,
+        content_hash: content_hash(transaction_data),
+    }
+}
+
+/// Link to a transaction in the Squads web app
+///
+/// Mainnet links carry no cluster; other clusters are selected with a `cluster` query
+/// parameter.
+pub fn to_app_url(cluster: &Cluster, multisig: &Pubkey, transaction_index: u64) -> String {
+    let url = format!(
+        "{}/squads/{}/transactions/{}",
+        SQUADS_APP_URL, multisig, transaction_index
+    );
+    match cluster {
+        Cluster::Mainnet => url,
+        other => format!("{}?cluster={}", url, percent_encode(&other.to_string())),
+    }
+}
+
+/// Extract the multisig and transaction index from a Squads web app URL
+///
+/// Best effort: accepts the hosts the app has used, with or without scheme, trailing
+/// slash, query or fragment, and `transactions` or `tx` as the path segment before the
+/// index. The cluster is not part of the result.
+pub fn parse_app_url(url: &str) -> SquadsResult<(Pubkey, u64)> {
+    let invalid = |reason: &str| SquadsError::InvalidLink(format!("{}: {}", reason, url));
+    let rest = url.trim();
+    let rest = rest
+        .strip_prefix("https://")
+        .or_else(|| rest.strip_prefix("http://"))
+        .unwrap_or(rest);
+    let rest = rest.split(['?', '#']).next().unwrap_or_default();
+    let mut segments = rest.split('/').filter(|segment| !segment.is_empty());
+
+    let host = segments.next().unwrap_or_default();
+    if !SQUADS_APP_HOSTS.contains(&host) {
+        return Err(invalid("not a Squads app URL"));
+    }
+    let segments: Vec<&str> = segments.collect();
+    match segments.as_slice() {
+        ["squads", multisig, "transactions" | "tx", index, ..] => Ok((
+            multisig
+                .parse()
+                .map_err(|_| invalid("invalid multisig address"))?,
+            index
+                .parse()
+                .map_err(|_| invalid("invalid transaction index"))?,
+        )),
+        _ => Err(invalid("no transaction in URL")),
+    }
+}
+
+/// A Solana Pay transfer request funding a vault
+///
+/// Wallets that support Solana Pay open the URI as a prefilled transfer to the vault. For
+/// an SPL token the wallet sends to the vault's associated token account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepositRequest {
+    /// Vault PDA receiving the deposit
+    pub vault: Pubkey,
+    /// Token mint (None for SOL)
+    pub mint: Option<Pubkey>,
+    /// Amount in base units and the mint's decimals (None to let the payer choose)
+    pub amount: Option<(u64, u8)>,
+    /// Who the payment is for, e.g. the squad's name
+    pub label: Option<String>,
+    /// What the payment is for, e.g. an invoice number
+    pub message: Option<String>,
+}
+
+impl DepositRequest {
+    /// Request a deposit of any amount of SOL, or of the token `mint`
+    pub fn new(vault: Pubkey, mint: Option<Pubkey>) -> Self {
+        Self {
+            vault,
+            mint,
+            amount: None,
+            label: None,
+            message: None,
+        }
+    }
+
+    /// Request `amount` base units of a token with `decimals` decimals (9 for SOL)
+    pub fn amount(mut self, amount: u64, decimals: u8) -> Self {
+        self.amount = Some((amount, decimals));
+        self
+    }
+
+    /// Set the label shown by the wallet
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Set the message shown by the wallet
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Encode the request as a `solana:` URI
+    pub fn to_uri(&self) -> String {
+        let mut params = Vec::new();
+        if let Some((amount, decimals)) = self.amount {
+            params.push(format!("amount={}", decimal_amount(amount, decimals)));
+        }
+        if let Some(mint) = &self.mint {
+            params.push(format!("spl-token={}", mint));
+        }
+        if let Some(label) = &self.label {
+            params.push(format!("label={}", percent_encode(label)));
+        }
+        if let Some(message) = &self.message {
+            params.push(format!("message={}", percent_encode(message)));
+        }
+
+        let mut uri = format!("{}{}", SOLANA_PAY_SCHEME, self.vault);
+        if !params.is_empty() {
+            uri.push('?');
+            uri.push_str(&params.join("&"));
+        }
+        uri
+    }
+
+    /// Render the URI as an SVG QR code
+    #[cfg(feature = "qr")]
+    pub fn to_qr_svg(&self) -> SquadsResult<String> {
+        use qrcode::{render::svg, QrCode};
+
+        let code = QrCode::new(self.to_uri().as_bytes())
+            .map_err(|e| SquadsError::InvalidLink(e.to_string()))?;
+        Ok(code.render::<svg::Color>().min_dimensions(256, 256).build())
+    }
+}
+
+/// Solana Pay URI for depositing any amount of SOL, or of the token `mint`, into a vault
+///
+/// # Arguments
+/// * `vault` - Vault PDA, e.g. from [`pda::get_vault_pda`]
+/// * `mint` - Token mint (None for SOL)
+pub fn deposit_uri(vault: &Pubkey, mint: Option<&Pubkey>) -> String {
+    DepositRequest::new(*vault, mint.copied()).to_uri()
+}
+
+/// Format base units as a decimal amount without trailing zeros
+fn decimal_amount(amount: u64, decimals: u8) -> String {
+    let digits = format!("{:0>width$}", amount, width = usize::from(decimals) + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - usize::from(decimals));
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+/// Percent-encode a URI query value, keeping only unreserved characters
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Decode a value encoded with [`percent_encode`], None if it is malformed
+fn percent_decode(value: &str) -> Option<String> {
+    let mut decoded = Vec::with_capacity(value.len());
+    let mut bytes = value.bytes();
+    while let Some(byte) = bytes.next() {
+        if byte == b'%' {
+            let hex = [bytes.next()?, bytes.next()?];
+            decoded.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            decoded.push(byte);
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uri_round_trip() {
+        let multisig = Pubkey::new_unique();
+        let data = b"transaction account data";
+        let request = approval_request(Cluster::Devnet, &multisig, 42, data, None);
+
+        let uri = request.to_uri();
+        assert!(uri.starts_with("squads://approve?cluster=devnet&"));
+
+        let parsed = ApprovalRequest::from_uri(&uri).unwrap();
+        assert_eq!(parsed, request);
+        assert!(parsed.verify_content(data));
+        assert!(!parsed.verify_content(b"tampered"));
+
+        // Custom cluster URLs cannot break out of their query parameter
+        let custom = Cluster::Custom("http://localhost:8899/?a=b&c#d".to_string());
+        let request = approval_request(custom.clone(), &multisig, 42, data, None);
+        let uri = request.to_uri();
+        assert!(uri.starts_with(
+            "squads://approve?cluster=http%3A%2F%2Flocalhost%3A8899%2F%3Fa%3Db%26c%23d&"
+        ));
+        assert_eq!(ApprovalRequest::from_uri(&uri).unwrap(), request);
+        assert!(to_app_url(&custom, &multisig, 1)
+            .ends_with("?cluster=http%3A%2F%2Flocalhost%3A8899%2F%3Fa%3Db%26c%23d"));
+    }
+
+    #[test]
+    fn test_rejects_mismatched_proposal() {
+        let request = approval_request(Cluster::Mainnet, &Pubkey::new_unique(), 1, &[], None);
+        let uri = request.to_uri().replace(
+            &request.proposal.to_string(),
+            &Pubkey::new_unique().to_string(),
+        );
+        assert!(matches!(
+            ApprovalRequest::from_uri(&uri),
+            Err(SquadsError::InvalidLink(_))
+        ));
+    }
+
+    #[test]
+    fn test_app_url_round_trip() {
+        let multisig = Pubkey::new_unique();
+        let mainnet = to_app_url(&Cluster::Mainnet, &multisig, 7);
+        assert_eq!(
+            mainnet,
+            format!("https://app.squads.so/squads/{}/transactions/7", multisig)
+        );
+        assert_eq!(parse_app_url(&mainnet).unwrap(), (multisig, 7));
+
+        let devnet = to_app_url(&Cluster::Devnet, &multisig, 8);
+        assert!(devnet.ends_with("/transactions/8?cluster=devnet"));
+        assert_eq!(parse_app_url(&devnet).unwrap(), (multisig, 8));
+
+        let variant = format!("v4.squads.so/squads/{}/tx/9/#approve", multisig);
+        assert_eq!(parse_app_url(&variant).unwrap(), (multisig, 9));
+
+        for bad in [
+            format!("https://example.com/squads/{}/transactions/1", multisig),
+            format!("https://app.squads.so/squads/{}/home", multisig),
+            "https://app.squads.so/squads/not-a-key/transactions/1".to_string(),
+        ] {
+            assert!(matches!(
+                parse_app_url(&bad),
+                Err(SquadsError::InvalidLink(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_deposit_uri() {
+        let vault = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        assert_eq!(deposit_uri(&vault, None), format!("solana:{}", vault));
+        assert_eq!(
+            deposit_uri(&vault, Some(&mint)),
+            format!("solana:{}?spl-token={}", vault, mint)
+        );
+
+        let invoice = DepositRequest::new(vault, Some(mint))
+            .amount(1_250_000, 6)
+            .label("Treasury")
+            .message("Invoice #42 & fees");
+        assert_eq!(
+            invoice.to_uri(),
+            format!(
+                "solana:{}?amount=1.25&spl-token={}&label=Treasury&message=Invoice%20%2342%20%26%20fees",
+                vault, mint
+            )
+        );
+
+        assert_eq!(decimal_amount(5, 9), "0.000000005");
+        assert_eq!(decimal_amount(2_000_000_000, 9), "2");
+        assert_eq!(decimal_amount(7, 0), "7");
+    }
+}
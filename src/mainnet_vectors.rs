@@ -0,0 +1,402 @@
+//! Builder coverage against live mainnet transactions
+//!
+//! Enabled with the `mainnet-vectors` feature. The harness downloads Squads transactions
+//! from mainnet, decodes each top-level Squads instruction with the [`crate::parser`],
+//! rebuilds it with the matching builder from [`crate::instructions`] and asserts that
+//! the account keys and instruction data are byte-for-byte identical.
+//!
+//! | Variable                        | Meaning                                          | Default                         |
+//! |---------------------------------|--------------------------------------------------|---------------------------------|
+//! | `SQUADS_RPC_URL`                | RPC endpoint                                     | mainnet-beta public RPC         |
+//! | `SQUADS_MAINNET_VECTORS`        | Comma-separated transaction signatures           | none                            |
+//! | `SQUADS_MAINNET_VECTORS_LIMIT`  | Recent program transactions checked without a list | 50                            |
+//!
+//! The test needs network access, so it is ignored by default and runs with
+//! `cargo test --features mainnet-vectors mainnet_vectors -- --ignored`.
+//!
+//! Account flags are not compared: a compiled message marks an account writable or
+//! signer for the whole transaction, not per instruction.
+
+use std::str::FromStr;
+
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status_client_types::UiTransactionEncoding;
+
+use crate::error::{SquadsError, SquadsResult};
+use crate::instructions::{self, metadata, InstructionKind};
+use crate::parser::{parse_program_instruction_data, SquadsInstruction};
+use crate::report::decode_confirmed;
+
+/// Public mainnet-beta RPC endpoint
+const MAINNET_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
+
+/// Recent program transactions checked when no signatures are configured
+const DEFAULT_LIMIT: usize = 50;
+
+/// Rebuild a Squads instruction with its builder
+///
+/// Returns None for instructions without a builder.
+fn rebuild(program_id: &Pubkey, keys: &[Pubkey], data: &[u8]) -> SquadsResult<Option<Instruction>> {
+    let Some(kind) = InstructionKind::ALL.into_iter().find(|kind| {
+        data.get(..8)
+            == Some(&crate::dialect::instruction_discriminator(program_id, kind.name())[..])
+    }) else {
+        return Ok(None);
+    };
+    let parsed = parse_program_instruction_data(program_id, keys, data)?;
+    let specs = metadata(kind).accounts;
+    let key = |name: &str| {
+        specs
+            .iter()
+            .position(|spec| spec.name == name)
+            .and_then(|index| keys.get(index).copied())
+            .unwrap_or_default()
+    };
+    let optional = |name: &str| Some(key(name)).filter(|key| key != program_id);
+    let remaining = keys.get(specs.len()..).unwrap_or_default();
+    let p = Some(*program_id);
+
+    let ix = match parsed {
+        SquadsInstruction::MultisigCreateV2 { args, .. } => instructions::multisig_create_v2(
+            key("program_config"),
+            key("treasury"),
+            key("multisig"),
+            key("create_key"),
+            key("creator"),
+            args,
+            p,
+        ),
+        #[allow(deprecated)]
+        SquadsInstruction::MultisigCreate { args, .. } => instructions::multisig_create(
+            key("multisig"),
+            key("create_key"),
+            key("creator"),
+            args,
+            p,
+        ),
+        SquadsInstruction::MultisigAddMember { args, .. } => instructions::multisig_add_member(
+            key("multisig"),
+            key("config_authority"),
+            key("rent_payer"),
+            args,
+            p,
+        ),
+        SquadsInstruction::MultisigRemoveMember { args, .. } => {
+            instructions::multisig_remove_member(key("multisig"), key("config_authority"), args, p)
+        }
+        SquadsInstruction::MultisigSetTimeLock { args, .. } => {
+            instructions::multisig_set_time_lock(key("multisig"), key("config_authority"), args, p)
+        }
+        SquadsInstruction::MultisigSetRentCollector { args, .. } => {
+            instructions::multisig_set_rent_collector(
+                key("multisig"),
+                key("config_authority"),
+                optional("rent_payer"),
+                args,
+                p,
+            )
+        }
+        SquadsInstruction::MultisigAddSpendingLimit { args, .. } => {
+            instructions::multisig_add_spending_limit(
+                key("multisig"),
+                key("config_authority"),
+                key("spending_limit"),
+                key("rent_payer"),
+                args,
+                p,
+            )
+        }
+        SquadsInstruction::MultisigRemoveSpendingLimit { args, .. } => {
+            instructions::multisig_remove_spending_limit(
+                key("multisig"),
+                key("config_authority"),
+                key("spending_limit"),
+                key("rent_collector"),
+                args,
+                p,
+            )
+        }
+        SquadsInstruction::ConfigTransactionCreate { args, .. } => {
+            instructions::config_transaction_create(
+                key("multisig"),
+                key("transaction"),
+                key("creator"),
+                key("rent_payer"),
+                args,
+                p,
+            )
+        }
+        SquadsInstruction::ConfigTransactionExecute { .. } => {
+            instructions::config_transaction_execute(
+                key("multisig"),
+                key("proposal"),
+                key("transaction"),
+                key("member"),
+                optional("rent_payer"),
+                remaining.to_vec(),
+                p,
+            )
+        }
+        SquadsInstruction::VaultTransactionCreate { args, .. } => {
+            instructions::vault_transaction_create(
+                key("multisig"),
+                key("transaction"),
+                key("creator"),
+                key("rent_payer"),
+                args,
+                p,
+            )
+        }
+        SquadsInstruction::BatchCreate { args, .. } => instructions::batch_create(
+            key("multisig"),
+            key("creator"),
+            key("rent_payer"),
+            key("batch"),
+            args,
+            p,
+        ),
+        SquadsInstruction::BatchAddTransaction { args, .. } => instructions::batch_add_transaction(
+            key("multisig"),
+            key("proposal"),
+            key("batch"),
+            key("transaction"),
+            key("member"),
+            key("rent_payer"),
+            args,
+            p,
+        ),
+        SquadsInstruction::TransactionBufferCreate { args, .. } => {
+            instructions::transaction_buffer_create(
+                key("multisig"),
+                key("transaction_buffer"),
+                key("creator"),
+                key("rent_payer"),
+                args,
+                p,
+            )
+        }
+        SquadsInstruction::TransactionBufferExtend { args, .. } => {
+            instructions::transaction_buffer_extend(
+                key("multisig"),
+                key("transaction_buffer"),
+                key("creator"),
+                args,
+                p,
+            )
+        }
+        SquadsInstruction::TransactionBufferClose { .. } => instructions::transaction_buffer_close(
+            key("multisig"),
+            key("transaction_buffer"),
+            key("creator"),
+            p,
+        ),
+        SquadsInstruction::VaultTransactionCreateFromBuffer { args, .. } => {
+            instructions::vault_transaction_create_from_buffer(
+                key("multisig"),
+                key("transaction"),
+                key("creator"),
+                key("rent_payer"),
+                key("transaction_buffer"),
+                args,
+                p,
+            )
+        }
+        SquadsInstruction::ProgramConfigInit { args, .. } => {
+            instructions::program_config_init(key("program_config"), key("initializer"), args, p)
+        }
+        SquadsInstruction::ProgramConfigSetAuthority { args, .. } => {
+            instructions::program_config_set_authority(
+                key("program_config"),
+                key("authority"),
+                args,
+                p,
+            )
+        }
+        SquadsInstruction::ProgramConfigSetMultisigCreationFee { args, .. } => {
+            instructions::program_config_set_multisig_creation_fee(
+                key("program_config"),
+                key("authority"),
+                args,
+                p,
+            )
+        }
+        SquadsInstruction::ProgramConfigSetTreasury { args, .. } => {
+            instructions::program_config_set_treasury(
+                key("program_config"),
+                key("authority"),
+                args,
+                p,
+            )
+        }
+        SquadsInstruction::VaultTransactionExecute { .. } => {
+            let remaining = remaining
+                .iter()
+                .map(|key| solana_sdk::instruction::AccountMeta::new_readonly(*key, false))
+                .collect();
+            instructions::vault_transaction_execute(
+                key("multisig"),
+                key("proposal"),
+                key("transaction"),
+                key("member"),
+                remaining,
+                p,
+            )
+        }
+        SquadsInstruction::ProposalCreate { args, .. } => instructions::proposal_create(
+            key("multisig"),
+            key("proposal"),
+            key("creator"),
+            key("rent_payer"),
+            args,
+            p,
+        ),
+        SquadsInstruction::ProposalActivate { .. } => {
+            instructions::proposal_activate(key("multisig"), key("proposal"), key("member"), p)
+        }
+        SquadsInstruction::ProposalApprove { args, .. } => {
+            instructions::proposal_approve(key("multisig"), key("proposal"), key("member"), args, p)
+        }
+        SquadsInstruction::ProposalReject { args, .. } => {
+            instructions::proposal_reject(key("multisig"), key("proposal"), key("member"), args, p)
+        }
+        SquadsInstruction::ProposalCancel { args, .. } => {
+            let build = match kind {
+                InstructionKind::ProposalCancelV2 => instructions::proposal_cancel_v2,
+                _ => instructions::proposal_cancel,
+            };
+            build(key("multisig"), key("proposal"), key("member"), args, p)
+        }
+        SquadsInstruction::SpendingLimitUse { args, .. } => instructions::spending_limit_use(
+            key("multisig"),
+            key("member"),
+            key("spending_limit"),
+            key("vault"),
+            key("destination"),
+            optional("mint"),
+            optional("vault_token_account"),
+            optional("destination_token_account"),
+            optional("token_program"),
+            args,
+            p,
+        ),
+        SquadsInstruction::Unknown { .. } => match kind {
+            InstructionKind::ConfigTransactionAccountsClose => {
+                instructions::config_transaction_accounts_close(
+                    key("multisig"),
+                    key("proposal"),
+                    key("transaction"),
+                    key("rent_collector"),
+                    p,
+                )
+            }
+            InstructionKind::VaultTransactionAccountsClose => {
+                instructions::vault_transaction_accounts_close(
+                    key("multisig"),
+                    key("proposal"),
+                    key("transaction"),
+                    key("rent_collector"),
+                    p,
+                )
+            }
+            InstructionKind::BatchAccountsClose => instructions::batch_accounts_close(
+                key("multisig"),
+                key("proposal"),
+                key("batch"),
+                key("rent_collector"),
+                p,
+            ),
+            InstructionKind::VaultBatchTransactionAccountClose => {
+                instructions::vault_batch_transaction_account_close(
+                    key("multisig"),
+                    key("proposal"),
+                    key("batch"),
+                    key("transaction"),
+                    key("rent_collector"),
+                    p,
+                )
+            }
+            other => {
+                return Err(SquadsError::InvalidAccountData(format!(
+                    "parser did not decode {}",
+                    other.name()
+                )))
+            }
+        },
+    };
+    Ok(Some(ix))
+}
+
+/// Signatures to check: the configured list, or the program's latest transactions
+fn vector_signatures(rpc: &RpcClient, program_id: &Pubkey) -> Vec<Signature> {
+    if let Ok(list) = std::env::var("SQUADS_MAINNET_VECTORS") {
+        return list
+            .split(',')
+            .map(str::trim)
+            .filter(|signature| !signature.is_empty())
+            .map(|signature| Signature::from_str(signature).expect("valid signature"))
+            .collect();
+    }
+    let limit = std::env::var("SQUADS_MAINNET_VECTORS_LIMIT")
+        .ok()
+        .map(|limit| limit.parse().expect("numeric limit"))
+        .unwrap_or(DEFAULT_LIMIT);
+    rpc.get_signatures_for_address(program_id)
+        .expect("program signatures")
+        .into_iter()
+        .filter(|status| status.err.is_none())
+        .take(limit)
+        .map(|status| Signature::from_str(&status.signature).expect("valid signature"))
+        .collect()
+}
+
+#[test]
+#[ignore = "needs network access; run with --ignored"]
+fn test_builders_match_mainnet_transactions() {
+    let rpc = RpcClient::new(
+        std::env::var("SQUADS_RPC_URL").unwrap_or_else(|_| MAINNET_RPC_URL.to_string()),
+    );
+    let program_id = crate::program_id();
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+
+    let mut checked = 0;
+    for signature in vector_signatures(&rpc, &program_id) {
+        let confirmed = rpc
+            .get_transaction_with_config(&signature, config)
+            .unwrap_or_else(|err| panic!("fetching {}: {}", signature, err));
+        let (transaction, account_keys) = decode_confirmed(&confirmed).unwrap();
+
+        for (position, compiled) in transaction.message.instructions().iter().enumerate() {
+            if account_keys.get(usize::from(compiled.program_id_index)) != Some(&program_id) {
+                continue;
+            }
+            let keys: Vec<Pubkey> = compiled
+                .accounts
+                .iter()
+                .map(|index| account_keys[usize::from(*index)])
+                .collect();
+            let context = format!("{} instruction {}", signature, position);
+            let Some(rebuilt) = rebuild(&program_id, &keys, &compiled.data)
+                .unwrap_or_else(|err| panic!("{}: {}", context, err))
+            else {
+                continue;
+            };
+
+            let rebuilt_keys: Vec<Pubkey> =
+                rebuilt.accounts.iter().map(|meta| meta.pubkey).collect();
+            assert_eq!(rebuilt_keys, keys, "{}: account keys differ", context);
+            assert_eq!(rebuilt.data, compiled.data, "{}: data differs", context);
+            checked += 1;
+        }
+    }
+    assert!(
+        checked > 0,
+        "no Squads instructions with a builder were found"
+    );
+}
@@ -0,0 +1,132 @@
+//! Memo validation and SPL Memo instructions
+//!
+//! Squads instructions accept an optional memo that is only used for indexing. Memos are
+//! carried in the instruction data, so oversized memos surface as opaque "transaction too
+//! large" failures when the transaction is sent. The program itself enforces no memo
+//! limit, so the args constructors in [`crate::instructions`] only reject memos longer
+//! than [`MAX_MEMO_LENGTH`], which cannot fit in any transaction. Whether a shorter memo
+//! fits depends on the rest of the transaction; check it with
+//! [`crate::sizing::legacy_transaction_size`] or [`crate::sizing::v0_transaction_size`].
+
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+use crate::error::{SquadsError, SquadsResult};
+use crate::programs::MEMO_PROGRAM_ID;
+use crate::sizing::MAX_TRANSACTION_SIZE;
+
+/// Bytes besides the memo in the smallest transaction carrying a Squads memo: a proposal
+/// vote paid for by the voting member
+const MIN_MEMO_TRANSACTION_OVERHEAD: usize = 250;
+
+/// Maximum memo length in bytes accepted by the args constructors
+///
+/// The room left for a memo in a [`MAX_TRANSACTION_SIZE`] transaction by the smallest
+/// instruction that takes one; longer memos can never be sent.
+pub const MAX_MEMO_LENGTH: usize = MAX_TRANSACTION_SIZE - MIN_MEMO_TRANSACTION_OVERHEAD;
+
+/// How to handle memos longer than [`MAX_MEMO_LENGTH`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MemoPolicy {
+    /// Reject the memo with [`SquadsError::MemoTooLong`]
+    #[default]
+    Error,
+    /// Truncate the memo at the last character boundary that fits
+    Truncate,
+}
+
+/// Validate a memo according to `policy`
+///
+/// Returns the memo unchanged if it fits, truncated if the policy allows it,
+/// or an error otherwise.
+pub fn check_memo(memo: Option<String>, policy: MemoPolicy) -> SquadsResult<Option<String>> {
+    let Some(mut memo) = memo else {
+        return Ok(None);
+    };
+    if memo.len() <= MAX_MEMO_LENGTH {
+        return Ok(Some(memo));
+    }
+
+    match policy {
+        MemoPolicy::Error => Err(SquadsError::MemoTooLong {
+            len: memo.len(),
+            max: MAX_MEMO_LENGTH,
+        }),
+        MemoPolicy::Truncate => {
+            let mut end = MAX_MEMO_LENGTH;
+            while !memo.is_char_boundary(end) {
+                end -= 1;
+            }
+            memo.truncate(end);
+            Ok(Some(memo))
+        }
+    }
+}
+
+/// Build an SPL Memo program instruction
+///
+/// # Arguments
+/// * `memo` - UTF-8 memo text
+/// * `signers` - Accounts that must sign the memo (may be empty)
+pub fn memo_instruction(memo: &str, signers: &[Pubkey]) -> Instruction {
+    Instruction {
+        program_id: MEMO_PROGRAM_ID,
+        accounts: signers
+            .iter()
+            .map(|signer| AccountMeta::new_readonly(*signer, true))
+            .collect(),
+        data: memo.as_bytes().to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_memo_policies() {
+        let short = Some("payroll".to_string());
+        assert_eq!(check_memo(short.clone(), MemoPolicy::Error).unwrap(), short);
+        assert_eq!(check_memo(None, MemoPolicy::Error).unwrap(), None);
+
+        // Multi-byte characters must not be split when truncating
+        let long = "é".repeat(MAX_MEMO_LENGTH);
+        assert!(matches!(
+            check_memo(Some(long.clone()), MemoPolicy::Error),
+            Err(SquadsError::MemoTooLong { .. })
+        ));
+        let truncated = check_memo(Some(long), MemoPolicy::Truncate)
+            .unwrap()
+            .unwrap();
+        assert_eq!(truncated.len(), MAX_MEMO_LENGTH);
+        assert_eq!(truncated.chars().count(), MAX_MEMO_LENGTH / 2);
+    }
+
+    #[test]
+    fn test_max_memo_length_fills_a_vote_transaction() {
+        let member = Pubkey::new_unique();
+        let memo = "x".repeat(MAX_MEMO_LENGTH);
+        let ix = crate::instructions::proposal_approve(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            member,
+            crate::instructions::ProposalVoteArgs::new(Some(memo)).unwrap(),
+            None,
+        );
+        assert_eq!(
+            crate::sizing::legacy_transaction_size(&[ix], &member),
+            MAX_TRANSACTION_SIZE
+        );
+    }
+
+    #[test]
+    fn test_memo_instruction() {
+        let signer = Pubkey::new_unique();
+        let ix = memo_instruction("hello", &[signer]);
+        assert_eq!(ix.program_id, MEMO_PROGRAM_ID);
+        assert_eq!(ix.data, b"hello");
+        assert!(ix.accounts[0].is_signer);
+    }
+}
@@ -3,6 +3,8 @@
 //! This module provides the custom TransactionMessage format required by the Squads program.
 
 use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::error::{SquadsError, SquadsResult};
 use solana_sdk::{
     hash::Hash,
     instruction::Instruction,
@@ -11,9 +13,33 @@ use solana_sdk::{
 };
 
 /// SmallVec with u8 length prefix for Borsh serialization
+///
+/// Serializing more than `u8::MAX` items fails instead of truncating the length.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SmallVecU8<T>(Vec<T>);
 
+impl<T> SmallVecU8<T> {
+    /// Number of items
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether there are no items
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The items as a slice
+    pub fn as_slice(&self) -> &[T] {
+        &self.0
+    }
+
+    /// Unwrap into the inner vector
+    pub fn into_vec(self) -> Vec<T> {
+        self.0
+    }
+}
+
 impl<T> From<Vec<T>> for SmallVecU8<T> {
     fn from(vec: Vec<T>) -> Self {
         SmallVecU8(vec)
@@ -22,7 +48,12 @@ impl<T> From<Vec<T>> for SmallVecU8<T> {
 
 impl<T: BorshSerialize> BorshSerialize for SmallVecU8<T> {
     fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
-        let len = self.0.len() as u8;
+        let len = u8::try_from(self.0.len()).map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("too many items for a u8 length prefix: {}", self.0.len()),
+            )
+        })?;
         len.serialize(writer)?;
         for item in &self.0 {
             item.serialize(writer)?;
@@ -43,9 +74,33 @@ impl<T: BorshDeserialize> BorshDeserialize for SmallVecU8<T> {
 }
 
 /// SmallVec with u16 length prefix for Borsh serialization
+///
+/// Serializing more than `u16::MAX` items fails instead of truncating the length.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SmallVecU16<T>(Vec<T>);
 
+impl<T> SmallVecU16<T> {
+    /// Number of items
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether there are no items
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The items as a slice
+    pub fn as_slice(&self) -> &[T] {
+        &self.0
+    }
+
+    /// Unwrap into the inner vector
+    pub fn into_vec(self) -> Vec<T> {
+        self.0
+    }
+}
+
 impl<T> From<Vec<T>> for SmallVecU16<T> {
     fn from(vec: Vec<T>) -> Self {
         SmallVecU16(vec)
@@ -54,7 +109,12 @@ impl<T> From<Vec<T>> for SmallVecU16<T> {
 
 impl<T: BorshSerialize> BorshSerialize for SmallVecU16<T> {
     fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
-        let len = self.0.len() as u16;
+        let len = u16::try_from(self.0.len()).map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("too many items for a u16 length prefix: {}", self.0.len()),
+            )
+        })?;
         len.serialize(writer)?;
         for item in &self.0 {
             item.serialize(writer)?;
@@ -75,7 +135,7 @@ impl<T: BorshDeserialize> BorshDeserialize for SmallVecU16<T> {
 }
 
 /// Transaction message format used by Squads v4
-#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
 pub struct TransactionMessage {
     /// The number of signer pubkeys in the account_keys vec
     pub num_signers: u8,
@@ -92,7 +152,7 @@ pub struct TransactionMessage {
 }
 
 /// Compiled instruction format for Squads messages
-#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
 pub struct CompiledInstruction {
     /// Index into the message's account_keys array indicating the program account
     pub program_id_index: u8,
@@ -103,7 +163,7 @@ pub struct CompiledInstruction {
 }
 
 /// Address table lookup (for versioned transactions with lookup tables)
-#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
 pub struct MessageAddressTableLookup {
     /// Address lookup table account key
     pub account_key: Pubkey,
@@ -170,6 +230,90 @@ impl TransactionMessage {
             address_table_lookups: SmallVecU8(Vec::new()),
         })
     }
+
+    /// Serialize the message as the `transaction_message` bytes of
+    /// `vault_transaction_create` and `batch_add_transaction`
+    pub fn to_bytes(&self) -> SquadsResult<Vec<u8>> {
+        borsh::to_vec(self).map_err(SquadsError::SerializationError)
+    }
+
+    /// Deserialize a message from `transaction_message` bytes, rejecting trailing data
+    pub fn from_bytes(bytes: &[u8]) -> SquadsResult<Self> {
+        Self::try_from_slice(bytes).map_err(|_| SquadsError::DeserializationError)
+    }
+
+    /// Check the message against the constraints the Squads program enforces
+    ///
+    /// Signer and writable counts must be consistent with the static account keys,
+    /// every program and account index must refer to a static or looked-up key, and
+    /// every address table lookup must load at least one address. Violations are
+    /// reported as [`SquadsError::InvalidTransactionMessage`].
+    pub fn validate(&self) -> SquadsResult<()> {
+        let num_static_keys = self.account_keys.0.len();
+        let num_signers = usize::from(self.num_signers);
+        if usize::from(self.num_writable_signers) > num_signers
+            || num_signers > num_static_keys
+            || usize::from(self.num_writable_non_signers) > num_static_keys - num_signers
+        {
+            return Err(SquadsError::InvalidTransactionMessage);
+        }
+
+        let mut num_all_keys = num_static_keys;
+        for lookup in &self.address_table_lookups.0 {
+            let loaded = lookup.writable_indexes.0.len() + lookup.readonly_indexes.0.len();
+            if loaded == 0 {
+                return Err(SquadsError::InvalidTransactionMessage);
+            }
+            num_all_keys += loaded;
+        }
+
+        for ix in &self.instructions.0 {
+            if usize::from(ix.program_id_index) >= num_all_keys {
+                return Err(SquadsError::InvalidTransactionMessage);
+            }
+            if ix
+                .account_indexes
+                .0
+                .iter()
+                .any(|index| usize::from(*index) >= num_all_keys)
+            {
+                return Err(SquadsError::InvalidTransactionMessage);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl From<&TransactionMessage> for crate::accounts::VaultTransactionMessage {
+    fn from(message: &TransactionMessage) -> Self {
+        Self {
+            num_signers: message.num_signers,
+            num_writable_signers: message.num_writable_signers,
+            num_writable_non_signers: message.num_writable_non_signers,
+            account_keys: message.account_keys.as_slice().to_vec(),
+            instructions: message
+                .instructions
+                .as_slice()
+                .iter()
+                .map(|ix| crate::accounts::CompiledInstruction {
+                    program_id_index: ix.program_id_index,
+                    account_indexes: ix.account_indexes.as_slice().to_vec(),
+                    data: ix.data.as_slice().to_vec(),
+                })
+                .collect(),
+            address_table_lookups: message
+                .address_table_lookups
+                .as_slice()
+                .iter()
+                .map(|lookup| crate::accounts::MessageAddressTableLookup {
+                    account_key: lookup.account_key,
+                    writable_indexes: lookup.writable_indexes.as_slice().to_vec(),
+                    readonly_indexes: lookup.readonly_indexes.as_slice().to_vec(),
+                })
+                .collect(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -181,7 +325,7 @@ mod tests {
         let vault = Pubkey::new_unique();
         let destination = Pubkey::new_unique();
         
-        let transfer_ix = solana_sdk::system_instruction::transfer(&vault, &destination, 1000);
+        let transfer_ix = solana_system_interface::instruction::transfer(&vault, &destination, 1000);
         
         let message = TransactionMessage::try_compile(&vault, &[transfer_ix]).unwrap();
         
@@ -189,4 +333,96 @@ mod tests {
         assert_eq!(message.num_writable_signers, 1);
         assert_eq!(message.instructions.0.len(), 1);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_message_bytes_round_trip() {
+        let vault = Pubkey::new_unique();
+        let transfer_ix =
+            solana_system_interface::instruction::transfer(&vault, &Pubkey::new_unique(), 1);
+        let message = TransactionMessage::try_compile(&vault, &[transfer_ix]).unwrap();
+
+        let mut bytes = message.to_bytes().unwrap();
+        assert_eq!(TransactionMessage::from_bytes(&bytes).unwrap(), message);
+        assert_eq!(message.account_keys.len(), 3);
+        assert_eq!(message.instructions.as_slice()[0].data.len(), 12);
+
+        bytes.push(0);
+        assert!(TransactionMessage::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_out_of_bounds_index() {
+        let vault = Pubkey::new_unique();
+        let transfer_ix =
+            solana_system_interface::instruction::transfer(&vault, &Pubkey::new_unique(), 1);
+        let mut message = TransactionMessage::try_compile(&vault, &[transfer_ix]).unwrap();
+        assert!(message.validate().is_ok());
+
+        message.instructions.0[0].program_id_index = 3;
+        assert!(message.validate().is_err());
+    }
+
+    #[test]
+    fn test_small_vec_rejects_oversized_length() {
+        let too_long = SmallVecU8(vec![0u8; 256]);
+        assert!(borsh::to_vec(&too_long).is_err());
+    }
+
+    mod proptests {
+        use super::*;
+        use proptest::prelude::*;
+        use solana_sdk::instruction::AccountMeta;
+
+        fn instruction(keys: Vec<Pubkey>) -> impl Strategy<Value = Instruction> {
+            let program = prop::sample::select(keys.clone());
+            let meta = (prop::sample::select(keys), any::<bool>(), any::<bool>()).prop_map(
+                |(pubkey, is_signer, is_writable)| AccountMeta {
+                    pubkey,
+                    is_signer,
+                    is_writable,
+                },
+            );
+            (
+                program,
+                prop::collection::vec(meta, 0..8),
+                prop::collection::vec(any::<u8>(), 0..600),
+            )
+                .prop_map(|(program_id, accounts, data)| Instruction {
+                    program_id,
+                    accounts,
+                    data,
+                })
+        }
+
+        fn instructions() -> impl Strategy<Value = (Pubkey, Vec<Instruction>)> {
+            prop::collection::vec(any::<[u8; 32]>().prop_map(Pubkey::new_from_array), 2..12)
+                .prop_flat_map(|keys| {
+                    let vault = keys[0];
+                    (Just(vault), prop::collection::vec(instruction(keys), 0..6))
+                })
+        }
+
+        proptest! {
+            #[test]
+            fn compiled_messages_validate_and_round_trip(
+                (vault, ixs) in instructions()
+            ) {
+                let message = TransactionMessage::try_compile(&vault, &ixs).unwrap();
+                prop_assert!(message.validate().is_ok());
+
+                let bytes = borsh::to_vec(&message).unwrap();
+                let decoded = TransactionMessage::try_from_slice(&bytes).unwrap();
+                prop_assert_eq!(&decoded, &message);
+                prop_assert_eq!(borsh::to_vec(&decoded).unwrap(), bytes);
+            }
+
+            #[test]
+            fn small_vec_u16_round_trips(data in prop::collection::vec(any::<u8>(), 0..2000)) {
+                let bytes = borsh::to_vec(&SmallVecU16(data.clone())).unwrap();
+                prop_assert_eq!(bytes.len(), 2 + data.len());
+                let decoded = SmallVecU16::<u8>::try_from_slice(&bytes).unwrap();
+                prop_assert_eq!(decoded.0, data);
+            }
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! Durable nonce accounts managed by a multisig vault
+//!
+//! Collecting signatures from many members can take longer than a recent blockhash is
+//! valid. Durable nonces avoid that expiry. This module builds vault transaction messages
+//! that create, re-authorize and withdraw from nonce accounts whose authority is the vault,
+//! plus helpers to build outer transactions that use a durable nonce.
+//!
+//! A nonce account created from a vault transaction uses one of the transaction's
+//! ephemeral signer PDAs as its address (see [`crate::pda::get_ephemeral_signer_pda`]),
+//! so no extra keypair has to be managed.
+
+use solana_sdk::{hash::Hash, instruction::Instruction, message::Message, pubkey::Pubkey};
+use solana_system_interface::instruction as system_instruction;
+
+use crate::error::{SquadsError, SquadsResult};
+use crate::message::TransactionMessage;
+
+/// Size in bytes of a nonce account
+pub const NONCE_ACCOUNT_SIZE: usize = 80;
+
+fn compile(vault: &Pubkey, instructions: &[Instruction]) -> SquadsResult<TransactionMessage> {
+    TransactionMessage::try_compile(vault, instructions)
+        .map_err(|_| SquadsError::InvalidTransactionMessage)
+}
+
+/// Vault transaction message that creates a nonce account funded by the vault
+///
+/// # Arguments
+/// * `vault` - The vault PDA paying for the account
+/// * `nonce_account` - Address of the new nonce account (typically an ephemeral signer PDA)
+/// * `authority` - Nonce authority (usually the vault itself)
+/// * `lamports` - Lamports to fund the account with (at least rent exemption for
+///   [`NONCE_ACCOUNT_SIZE`] bytes)
+pub fn create_nonce_account_message(
+    vault: &Pubkey,
+    nonce_account: &Pubkey,
+    authority: &Pubkey,
+    lamports: u64,
+) -> SquadsResult<TransactionMessage> {
+    let instructions =
+        system_instruction::create_nonce_account(vault, nonce_account, authority, lamports);
+    compile(vault, &instructions)
+}
+
+/// Vault transaction message that hands a vault-owned nonce account to a new authority
+///
+/// # Arguments
+/// * `vault` - The vault PDA that is the current nonce authority
+/// * `nonce_account` - The nonce account
+/// * `new_authority` - The new nonce authority
+pub fn authorize_nonce_account_message(
+    vault: &Pubkey,
+    nonce_account: &Pubkey,
+    new_authority: &Pubkey,
+) -> SquadsResult<TransactionMessage> {
+    let ix = system_instruction::authorize_nonce_account(nonce_account, vault, new_authority);
+    compile(vault, &[ix])
+}
+
+/// Vault transaction message that withdraws lamports from a vault-owned nonce account
+///
+/// # Arguments
+/// * `vault` - The vault PDA that is the nonce authority
+/// * `nonce_account` - The nonce account
+/// * `destination` - Recipient of the lamports
+/// * `lamports` - Amount to withdraw (the full balance closes the account)
+pub fn withdraw_nonce_account_message(
+    vault: &Pubkey,
+    nonce_account: &Pubkey,
+    destination: &Pubkey,
+    lamports: u64,
+) -> SquadsResult<TransactionMessage> {
+    let ix =
+        system_instruction::withdraw_nonce_account(nonce_account, vault, destination, lamports);
+    compile(vault, &[ix])
+}
+
+/// Build an outer transaction message that uses a durable nonce instead of a blockhash
+///
+/// An `advance_nonce_account` instruction is prepended, so `nonce_authority` must sign
+/// the outer transaction. For nonces whose authority is a vault, first move the
+/// authority to a member with [`authorize_nonce_account_message`].
+///
+/// # Arguments
+/// * `instructions` - Instructions to send (e.g. Squads approve or execute)
+/// * `payer` - Fee payer
+/// * `nonce_account` - The nonce account
+/// * `nonce_authority` - The nonce authority signing the outer transaction
+/// * `nonce_hash` - Current durable nonce value stored in the account
+pub fn durable_message(
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    nonce_account: &Pubkey,
+    nonce_authority: &Pubkey,
+    nonce_hash: Hash,
+) -> Message {
+    let mut message = Message::new_with_nonce(
+        instructions.to_vec(),
+        Some(payer),
+        nonce_account,
+        nonce_authority,
+    );
+    message.recent_blockhash = nonce_hash;
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_nonce_message_signers() {
+        let vault = Pubkey::new_unique();
+        let nonce_account = Pubkey::new_unique();
+        let message =
+            create_nonce_account_message(&vault, &nonce_account, &vault, 1_500_000).unwrap();
+
+        // The vault pays and the new nonce account signs its creation
+        assert_eq!(message.num_signers, 2);
+        assert_eq!(message.num_writable_signers, 2);
+    }
+
+    #[test]
+    fn test_durable_message() {
+        let payer = Pubkey::new_unique();
+        let nonce_account = Pubkey::new_unique();
+        let nonce_hash = Hash::new_unique();
+        let memo = Instruction::new_with_bytes(Pubkey::new_unique(), b"hi", vec![]);
+
+        let message = durable_message(&[memo], &payer, &nonce_account, &payer, nonce_hash);
+        assert_eq!(message.recent_blockhash, nonce_hash);
+        assert_eq!(message.instructions.len(), 2);
+        assert_eq!(
+            message.account_keys[usize::from(message.instructions[0].program_id_index)],
+            solana_sdk_ids::system_program::ID
+        );
+    }
+}
@@ -0,0 +1,731 @@
+//! Decoding of Squads program instructions
+//!
+//! [`parse_instruction`] is the inverse of the builders in [`crate::instructions`]: it
+//! matches the Anchor discriminator, decodes the arguments and picks out the accounts
+//! by the positions the builders use. Instructions without a builder in this crate are
+//! returned as [`SquadsInstruction::Unknown`].
+
+use borsh::BorshDeserialize;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+use crate::dialect;
+use crate::error::{SquadsError, SquadsResult};
+use crate::instructions::{
+    BatchAddTransactionArgs, BatchCreateArgs, ConfigTransactionCreateArgs, MultisigAddMemberArgs,
+    MultisigAddSpendingLimitArgs, MultisigCreateArgs, MultisigCreateArgsV2,
+    MultisigRemoveMemberArgs, MultisigRemoveSpendingLimitArgs, MultisigSetRentCollectorArgs,
+    MultisigSetTimeLockArgs, ProgramConfigInitArgs, ProgramConfigSetAuthorityArgs,
+    ProgramConfigSetMultisigCreationFeeArgs, ProgramConfigSetTreasuryArgs, ProposalCreateArgs,
+    ProposalVoteArgs, SpendingLimitUseArgs, TransactionBufferCreateArgs,
+    TransactionBufferExtendArgs, VaultTransactionCreateArgs,
+};
+
+/// A decoded Squads instruction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SquadsInstruction {
+    /// `multisig_create_v2`
+    MultisigCreateV2 {
+        /// Multisig account being created
+        multisig: Pubkey,
+        /// Create key the multisig PDA is derived from
+        create_key: Pubkey,
+        /// Creator and fee payer
+        creator: Pubkey,
+        /// Instruction arguments
+        args: MultisigCreateArgsV2,
+    },
+    /// `multisig_create` (legacy)
+    MultisigCreate {
+        /// Multisig account being created
+        multisig: Pubkey,
+        /// Create key the multisig PDA is derived from
+        create_key: Pubkey,
+        /// Creator and fee payer
+        creator: Pubkey,
+        /// Instruction arguments
+        args: MultisigCreateArgs,
+    },
+    /// `multisig_add_member`
+    MultisigAddMember {
+        /// Multisig account
+        multisig: Pubkey,
+        /// Config authority of the multisig
+        config_authority: Pubkey,
+        /// Instruction arguments
+        args: MultisigAddMemberArgs,
+    },
+    /// `multisig_remove_member`
+    MultisigRemoveMember {
+        /// Multisig account
+        multisig: Pubkey,
+        /// Config authority of the multisig
+        config_authority: Pubkey,
+        /// Instruction arguments
+        args: MultisigRemoveMemberArgs,
+    },
+    /// `multisig_set_time_lock`
+    MultisigSetTimeLock {
+        /// Multisig account
+        multisig: Pubkey,
+        /// Config authority of the multisig
+        config_authority: Pubkey,
+        /// Instruction arguments
+        args: MultisigSetTimeLockArgs,
+    },
+    /// `multisig_set_rent_collector`
+    MultisigSetRentCollector {
+        /// Multisig account
+        multisig: Pubkey,
+        /// Config authority of the multisig
+        config_authority: Pubkey,
+        /// Instruction arguments
+        args: MultisigSetRentCollectorArgs,
+    },
+    /// `multisig_add_spending_limit`
+    MultisigAddSpendingLimit {
+        /// Multisig account
+        multisig: Pubkey,
+        /// Config authority of the multisig
+        config_authority: Pubkey,
+        /// Spending limit account
+        spending_limit: Pubkey,
+        /// Instruction arguments
+        args: MultisigAddSpendingLimitArgs,
+    },
+    /// `multisig_remove_spending_limit`
+    MultisigRemoveSpendingLimit {
+        /// Multisig account
+        multisig: Pubkey,
+        /// Config authority of the multisig
+        config_authority: Pubkey,
+        /// Spending limit account
+        spending_limit: Pubkey,
+        /// Account receiving the spending limit's rent
+        rent_collector: Pubkey,
+        /// Instruction arguments
+        args: MultisigRemoveSpendingLimitArgs,
+    },
+    /// `config_transaction_create`
+    ConfigTransactionCreate {
+        /// Multisig account
+        multisig: Pubkey,
+        /// Config transaction account being created
+        transaction: Pubkey,
+        /// Transaction creator
+        creator: Pubkey,
+        /// Instruction arguments
+        args: ConfigTransactionCreateArgs,
+    },
+    /// `config_transaction_execute`
+    ConfigTransactionExecute {
+        /// Multisig account
+        multisig: Pubkey,
+        /// Executing member
+        member: Pubkey,
+        /// Proposal account
+        proposal: Pubkey,
+        /// Config transaction account
+        transaction: Pubkey,
+    },
+    /// `vault_transaction_create`
+    VaultTransactionCreate {
+        /// Multisig account
+        multisig: Pubkey,
+        /// Vault transaction account being created
+        transaction: Pubkey,
+        /// Transaction creator
+        creator: Pubkey,
+        /// Instruction arguments
+        args: VaultTransactionCreateArgs,
+    },
+    /// `batch_create`
+    BatchCreate {
+        /// Multisig account
+        multisig: Pubkey,
+        /// Batch creator
+        creator: Pubkey,
+        /// Batch account being created
+        batch: Pubkey,
+        /// Instruction arguments
+        args: BatchCreateArgs,
+    },
+    /// `batch_add_transaction`
+    BatchAddTransaction {
+        /// Multisig account
+        multisig: Pubkey,
+        /// Draft proposal of the batch
+        proposal: Pubkey,
+        /// Batch account
+        batch: Pubkey,
+        /// Batch transaction account being created
+        transaction: Pubkey,
+        /// Batch creator
+        member: Pubkey,
+        /// Instruction arguments
+        args: BatchAddTransactionArgs,
+    },
+    /// `transaction_buffer_create`
+    TransactionBufferCreate {
+        /// Multisig account
+        multisig: Pubkey,
+        /// Transaction buffer account being created
+        transaction_buffer: Pubkey,
+        /// Buffer creator
+        creator: Pubkey,
+        /// Instruction arguments
+        args: TransactionBufferCreateArgs,
+    },
+    /// `transaction_buffer_extend`
+    TransactionBufferExtend {
+        /// Multisig account
+        multisig: Pubkey,
+        /// Transaction buffer account
+        transaction_buffer: Pubkey,
+        /// Buffer creator
+        creator: Pubkey,
+        /// Instruction arguments
+        args: TransactionBufferExtendArgs,
+    },
+    /// `transaction_buffer_close`
+    TransactionBufferClose {
+        /// Multisig account
+        multisig: Pubkey,
+        /// Transaction buffer account being closed
+        transaction_buffer: Pubkey,
+        /// Buffer creator, receiving the rent
+        creator: Pubkey,
+    },
+    /// `vault_transaction_create_from_buffer`
+    VaultTransactionCreateFromBuffer {
+        /// Multisig account
+        multisig: Pubkey,
+        /// Vault transaction account being created
+        transaction: Pubkey,
+        /// Transaction and buffer creator
+        creator: Pubkey,
+        /// Transaction buffer holding the message, closed by the instruction
+        transaction_buffer: Pubkey,
+        /// Instruction arguments
+        args: VaultTransactionCreateArgs,
+    },
+    /// `program_config_init`
+    ProgramConfigInit {
+        /// Program config account being created
+        program_config: Pubkey,
+        /// Initializer paying for the account
+        initializer: Pubkey,
+        /// Instruction arguments
+        args: ProgramConfigInitArgs,
+    },
+    /// `program_config_set_authority`
+    ProgramConfigSetAuthority {
+        /// Program config account
+        program_config: Pubkey,
+        /// Current program config authority
+        authority: Pubkey,
+        /// Instruction arguments
+        args: ProgramConfigSetAuthorityArgs,
+    },
+    /// `program_config_set_multisig_creation_fee`
+    ProgramConfigSetMultisigCreationFee {
+        /// Program config account
+        program_config: Pubkey,
+        /// Program config authority
+        authority: Pubkey,
+        /// Instruction arguments
+        args: ProgramConfigSetMultisigCreationFeeArgs,
+    },
+    /// `program_config_set_treasury`
+    ProgramConfigSetTreasury {
+        /// Program config account
+        program_config: Pubkey,
+        /// Program config authority
+        authority: Pubkey,
+        /// Instruction arguments
+        args: ProgramConfigSetTreasuryArgs,
+    },
+    /// `vault_transaction_execute`
+    VaultTransactionExecute {
+        /// Multisig account
+        multisig: Pubkey,
+        /// Proposal account
+        proposal: Pubkey,
+        /// Vault transaction account
+        transaction: Pubkey,
+        /// Executing member
+        member: Pubkey,
+    },
+    /// `proposal_create`
+    ProposalCreate {
+        /// Multisig account
+        multisig: Pubkey,
+        /// Proposal account being created
+        proposal: Pubkey,
+        /// Proposal creator
+        creator: Pubkey,
+        /// Instruction arguments
+        args: ProposalCreateArgs,
+    },
+    /// `proposal_activate`
+    ProposalActivate {
+        /// Multisig account
+        multisig: Pubkey,
+        /// Proposal account
+        proposal: Pubkey,
+        /// Activating member
+        member: Pubkey,
+    },
+    /// `proposal_approve`
+    ProposalApprove {
+        /// Multisig account
+        multisig: Pubkey,
+        /// Voting member
+        member: Pubkey,
+        /// Proposal account
+        proposal: Pubkey,
+        /// Instruction arguments
+        args: ProposalVoteArgs,
+    },
+    /// `proposal_reject`
+    ProposalReject {
+        /// Multisig account
+        multisig: Pubkey,
+        /// Voting member
+        member: Pubkey,
+        /// Proposal account
+        proposal: Pubkey,
+        /// Instruction arguments
+        args: ProposalVoteArgs,
+    },
+    /// `proposal_cancel` or `proposal_cancel_v2`, which only adds the system program
+    ProposalCancel {
+        /// Multisig account
+        multisig: Pubkey,
+        /// Voting member
+        member: Pubkey,
+        /// Proposal account
+        proposal: Pubkey,
+        /// Instruction arguments
+        args: ProposalVoteArgs,
+    },
+    /// `spending_limit_use`
+    SpendingLimitUse {
+        /// Multisig account
+        multisig: Pubkey,
+        /// Member using the limit
+        member: Pubkey,
+        /// Spending limit account
+        spending_limit: Pubkey,
+        /// Account receiving the funds
+        destination: Pubkey,
+        /// Token mint (None for SOL)
+        mint: Option<Pubkey>,
+        /// Instruction arguments
+        args: SpendingLimitUseArgs,
+    },
+    /// An instruction of the Squads program this crate does not decode
+    Unknown {
+        /// First 8 bytes of the instruction data
+        discriminator: [u8; 8],
+    },
+}
+
+impl SquadsInstruction {
+    /// Multisig the instruction operates on, if decoded
+    pub fn multisig(&self) -> Option<&Pubkey> {
+        match self {
+            SquadsInstruction::MultisigCreateV2 { multisig, .. }
+            | SquadsInstruction::MultisigCreate { multisig, .. }
+            | SquadsInstruction::MultisigAddMember { multisig, .. }
+            | SquadsInstruction::MultisigRemoveMember { multisig, .. }
+            | SquadsInstruction::MultisigSetTimeLock { multisig, .. }
+            | SquadsInstruction::MultisigSetRentCollector { multisig, .. }
+            | SquadsInstruction::MultisigAddSpendingLimit { multisig, .. }
+            | SquadsInstruction::MultisigRemoveSpendingLimit { multisig, .. }
+            | SquadsInstruction::ConfigTransactionCreate { multisig, .. }
+            | SquadsInstruction::ConfigTransactionExecute { multisig, .. }
+            | SquadsInstruction::VaultTransactionCreate { multisig, .. }
+            | SquadsInstruction::BatchCreate { multisig, .. }
+            | SquadsInstruction::BatchAddTransaction { multisig, .. }
+            | SquadsInstruction::TransactionBufferCreate { multisig, .. }
+            | SquadsInstruction::TransactionBufferExtend { multisig, .. }
+            | SquadsInstruction::TransactionBufferClose { multisig, .. }
+            | SquadsInstruction::VaultTransactionCreateFromBuffer { multisig, .. }
+            | SquadsInstruction::VaultTransactionExecute { multisig, .. }
+            | SquadsInstruction::ProposalCreate { multisig, .. }
+            | SquadsInstruction::ProposalActivate { multisig, .. }
+            | SquadsInstruction::ProposalApprove { multisig, .. }
+            | SquadsInstruction::ProposalReject { multisig, .. }
+            | SquadsInstruction::ProposalCancel { multisig, .. }
+            | SquadsInstruction::SpendingLimitUse { multisig, .. } => Some(multisig),
+            SquadsInstruction::ProgramConfigInit { .. }
+            | SquadsInstruction::ProgramConfigSetAuthority { .. }
+            | SquadsInstruction::ProgramConfigSetMultisigCreationFee { .. }
+            | SquadsInstruction::ProgramConfigSetTreasury { .. }
+            | SquadsInstruction::Unknown { .. } => None,
+        }
+    }
+
+    /// Transaction or batch account created by the instruction, if any
+    pub fn created_transaction(&self) -> Option<&Pubkey> {
+        match self {
+            SquadsInstruction::ConfigTransactionCreate { transaction, .. }
+            | SquadsInstruction::VaultTransactionCreate { transaction, .. }
+            | SquadsInstruction::VaultTransactionCreateFromBuffer { transaction, .. } => {
+                Some(transaction)
+            }
+            SquadsInstruction::BatchCreate { batch, .. } => Some(batch),
+            _ => None,
+        }
+    }
+
+    /// Memo passed to a transaction or batch create instruction
+    ///
+    /// The program never stores memos, so the create instruction is the only place to read
+    /// them from.
+    pub fn memo(&self) -> Option<&str> {
+        let memo = match self {
+            SquadsInstruction::ConfigTransactionCreate { args, .. } => &args.memo,
+            SquadsInstruction::VaultTransactionCreate { args, .. }
+            | SquadsInstruction::VaultTransactionCreateFromBuffer { args, .. } => &args.memo,
+            SquadsInstruction::BatchCreate { args, .. } => &args.memo,
+            _ => return None,
+        };
+        memo.as_deref()
+    }
+}
+
+/// Decode an instruction, returning None if it is not for the Squads program
+///
+/// # Arguments
+/// * `instruction` - Instruction to decode
+/// * `program_id` - Optional custom program ID (uses canonical ID if None)
+pub fn parse_instruction(
+    instruction: &Instruction,
+    program_id: Option<&Pubkey>,
+) -> SquadsResult<Option<SquadsInstruction>> {
+    if instruction.program_id != *program_id.unwrap_or(&crate::program_id()) {
+        return Ok(None);
+    }
+    let accounts: Vec<Pubkey> = instruction
+        .accounts
+        .iter()
+        .map(|meta| meta.pubkey)
+        .collect();
+    parse_program_instruction_data(&instruction.program_id, &accounts, &instruction.data).map(Some)
+}
+
+/// Decode Squads instruction data given the instruction's account keys in order
+///
+/// Use this for compiled instructions, where the keys are resolved from the message's
+/// account key list.
+pub fn parse_instruction_data(accounts: &[Pubkey], data: &[u8]) -> SquadsResult<SquadsInstruction> {
+    parse_program_instruction_data(&crate::program_id(), accounts, data)
+}
+
+/// Decode instruction data of `program_id`, using its registered [`crate::dialect`]
+///
+/// # Arguments
+/// * `program_id` - Program the instruction was sent to
+/// * `accounts` - The instruction's account keys in order
+/// * `data` - Instruction data
+pub fn parse_program_instruction_data(
+    program_id: &Pubkey,
+    accounts: &[Pubkey],
+    data: &[u8],
+) -> SquadsResult<SquadsInstruction> {
+    if data.len() < 8 {
+        return Err(SquadsError::InvalidAccountData(
+            "instruction data shorter than a discriminator".to_string(),
+        ));
+    }
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&data[..8]);
+    let args = &data[8..];
+
+    let is = |name: &str| discriminator == dialect::instruction_discriminator(program_id, name);
+    let key = |index: usize| {
+        accounts.get(index).copied().ok_or_else(|| {
+            SquadsError::InvalidAccountData(format!(
+                "instruction expects at least {} accounts, got {}",
+                index + 1,
+                accounts.len()
+            ))
+        })
+    };
+
+    let instruction = if is("multisig_create_v2") {
+        SquadsInstruction::MultisigCreateV2 {
+            multisig: key(2)?,
+            create_key: key(3)?,
+            creator: key(4)?,
+            args: decode_args(args)?,
+        }
+    } else if is("multisig_create") {
+        SquadsInstruction::MultisigCreate {
+            multisig: key(0)?,
+            create_key: key(1)?,
+            creator: key(2)?,
+            args: decode_args(args)?,
+        }
+    } else if is("multisig_add_member") {
+        SquadsInstruction::MultisigAddMember {
+            multisig: key(0)?,
+            config_authority: key(1)?,
+            args: decode_args(args)?,
+        }
+    } else if is("multisig_remove_member") {
+        SquadsInstruction::MultisigRemoveMember {
+            multisig: key(0)?,
+            config_authority: key(1)?,
+            args: decode_args(args)?,
+        }
+    } else if is("multisig_set_time_lock") {
+        SquadsInstruction::MultisigSetTimeLock {
+            multisig: key(0)?,
+            config_authority: key(1)?,
+            args: decode_args(args)?,
+        }
+    } else if is("multisig_set_rent_collector") {
+        SquadsInstruction::MultisigSetRentCollector {
+            multisig: key(0)?,
+            config_authority: key(1)?,
+            args: decode_args(args)?,
+        }
+    } else if is("multisig_add_spending_limit") {
+        SquadsInstruction::MultisigAddSpendingLimit {
+            multisig: key(0)?,
+            config_authority: key(1)?,
+            spending_limit: key(2)?,
+            args: decode_args(args)?,
+        }
+    } else if is("multisig_remove_spending_limit") {
+        SquadsInstruction::MultisigRemoveSpendingLimit {
+            multisig: key(0)?,
+            config_authority: key(1)?,
+            spending_limit: key(2)?,
+            rent_collector: key(3)?,
+            args: decode_args(args)?,
+        }
+    } else if is("config_transaction_create") {
+        SquadsInstruction::ConfigTransactionCreate {
+            multisig: key(0)?,
+            creator: key(1)?,
+            transaction: key(3)?,
+            args: decode_args(args)?,
+        }
+    } else if is("config_transaction_execute") {
+        SquadsInstruction::ConfigTransactionExecute {
+            multisig: key(0)?,
+            member: key(1)?,
+            proposal: key(2)?,
+            transaction: key(3)?,
+        }
+    } else if is("vault_transaction_create") {
+        SquadsInstruction::VaultTransactionCreate {
+            multisig: key(0)?,
+            transaction: key(1)?,
+            creator: key(2)?,
+            args: decode_args(args)?,
+        }
+    } else if is("batch_create") {
+        SquadsInstruction::BatchCreate {
+            multisig: key(0)?,
+            creator: key(1)?,
+            batch: key(3)?,
+            args: decode_args(args)?,
+        }
+    } else if is("batch_add_transaction") {
+        SquadsInstruction::BatchAddTransaction {
+            multisig: key(0)?,
+            proposal: key(1)?,
+            batch: key(2)?,
+            transaction: key(3)?,
+            member: key(4)?,
+            args: decode_args(args)?,
+        }
+    } else if is("transaction_buffer_create") {
+        SquadsInstruction::TransactionBufferCreate {
+            multisig: key(0)?,
+            transaction_buffer: key(1)?,
+            creator: key(2)?,
+            args: decode_args(args)?,
+        }
+    } else if is("transaction_buffer_extend") {
+        SquadsInstruction::TransactionBufferExtend {
+            multisig: key(0)?,
+            transaction_buffer: key(1)?,
+            creator: key(2)?,
+            args: decode_args(args)?,
+        }
+    } else if is("transaction_buffer_close") {
+        SquadsInstruction::TransactionBufferClose {
+            multisig: key(0)?,
+            transaction_buffer: key(1)?,
+            creator: key(2)?,
+        }
+    } else if is("vault_transaction_create_from_buffer") {
+        SquadsInstruction::VaultTransactionCreateFromBuffer {
+            multisig: key(0)?,
+            transaction: key(1)?,
+            creator: key(2)?,
+            transaction_buffer: key(5)?,
+            args: decode_args(args)?,
+        }
+    } else if is("program_config_init") {
+        SquadsInstruction::ProgramConfigInit {
+            program_config: key(0)?,
+            initializer: key(1)?,
+            args: decode_args(args)?,
+        }
+    } else if is("program_config_set_authority") {
+        SquadsInstruction::ProgramConfigSetAuthority {
+            program_config: key(0)?,
+            authority: key(1)?,
+            args: decode_args(args)?,
+        }
+    } else if is("program_config_set_multisig_creation_fee") {
+        SquadsInstruction::ProgramConfigSetMultisigCreationFee {
+            program_config: key(0)?,
+            authority: key(1)?,
+            args: decode_args(args)?,
+        }
+    } else if is("program_config_set_treasury") {
+        SquadsInstruction::ProgramConfigSetTreasury {
+            program_config: key(0)?,
+            authority: key(1)?,
+            args: decode_args(args)?,
+        }
+    } else if is("vault_transaction_execute") {
+        SquadsInstruction::VaultTransactionExecute {
+            multisig: key(0)?,
+            proposal: key(1)?,
+            transaction: key(2)?,
+            member: key(3)?,
+        }
+    } else if is("proposal_create") {
+        SquadsInstruction::ProposalCreate {
+            multisig: key(0)?,
+            proposal: key(1)?,
+            creator: key(2)?,
+            args: decode_args(args)?,
+        }
+    } else if is("proposal_activate") {
+        SquadsInstruction::ProposalActivate {
+            multisig: key(0)?,
+            proposal: key(1)?,
+            member: key(2)?,
+        }
+    } else if is("proposal_approve") {
+        SquadsInstruction::ProposalApprove {
+            multisig: key(0)?,
+            member: key(1)?,
+            proposal: key(2)?,
+            args: decode_args(args)?,
+        }
+    } else if is("proposal_reject") {
+        SquadsInstruction::ProposalReject {
+            multisig: key(0)?,
+            member: key(1)?,
+            proposal: key(2)?,
+            args: decode_args(args)?,
+        }
+    } else if is("proposal_cancel") || is("proposal_cancel_v2") {
+        SquadsInstruction::ProposalCancel {
+            multisig: key(0)?,
+            member: key(1)?,
+            proposal: key(2)?,
+            args: decode_args(args)?,
+        }
+    } else if is("spending_limit_use") {
+        SquadsInstruction::SpendingLimitUse {
+            multisig: key(0)?,
+            member: key(1)?,
+            spending_limit: key(2)?,
+            destination: key(4)?,
+            // The program ID stands in for the optional mint of SOL transfers
+            mint: accounts.get(6).copied().filter(|mint| mint != program_id),
+            args: decode_args(args)?,
+        }
+    } else {
+        SquadsInstruction::Unknown { discriminator }
+    };
+    Ok(instruction)
+}
+
+fn decode_args<T: BorshDeserialize>(mut data: &[u8]) -> SquadsResult<T> {
+    T::deserialize(&mut data).map_err(|_| SquadsError::DeserializationError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions;
+
+    #[test]
+    fn test_parse_builder_output() {
+        let multisig = Pubkey::new_unique();
+        let proposal = Pubkey::new_unique();
+        let member = Pubkey::new_unique();
+
+        let ix = instructions::proposal_approve(
+            multisig,
+            proposal,
+            member,
+            ProposalVoteArgs {
+                memo: Some("lgtm".to_string()),
+            },
+            None,
+        );
+        assert_eq!(
+            parse_instruction(&ix, None).unwrap(),
+            Some(SquadsInstruction::ProposalApprove {
+                multisig,
+                member,
+                proposal,
+                args: ProposalVoteArgs {
+                    memo: Some("lgtm".to_string()),
+                },
+            })
+        );
+
+        let ix = instructions::vault_transaction_execute(
+            multisig,
+            proposal,
+            Pubkey::new_unique(),
+            member,
+            vec![],
+            None,
+        );
+        let parsed = parse_instruction(&ix, None).unwrap().unwrap();
+        assert_eq!(parsed.multisig(), Some(&multisig));
+
+        let close = instructions::vault_transaction_accounts_close(
+            multisig,
+            proposal,
+            Pubkey::new_unique(),
+            member,
+            None,
+        );
+        assert!(matches!(
+            parse_instruction(&close, None).unwrap(),
+            Some(SquadsInstruction::Unknown { .. })
+        ));
+
+        let authority = Pubkey::new_unique();
+        let args = MultisigAddMemberArgs::new(crate::types::Member::new(member), None).unwrap();
+        let ix =
+            instructions::multisig_add_member(multisig, authority, authority, args.clone(), None);
+        assert_eq!(
+            parse_instruction(&ix, None).unwrap(),
+            Some(SquadsInstruction::MultisigAddMember {
+                multisig,
+                config_authority: authority,
+                args,
+            })
+        );
+
+        let other = solana_system_interface::instruction::transfer(&member, &multisig, 1);
+        assert_eq!(parse_instruction(&other, None).unwrap(), None);
+    }
+}
@@ -3,7 +3,7 @@
 //! This module provides helper functions for deriving PDAs used by the Squads multisig program.
 //! PDAs are deterministic addresses derived from seeds and the program ID.
 
-use solana_sdk::pubkey::Pubkey;
+use solana_program::pubkey::Pubkey;
 
 use crate::seeds::*;
 
@@ -162,6 +162,83 @@ pub fn get_ephemeral_signer_pda(
     )
 }
 
+/// Get the batch PDA for a multisig batch
+///
+/// Batches share the transaction index space and seeds with vault and config
+/// transactions, so this is the transaction PDA at `batch_index`.
+///
+/// # Arguments
+/// * `multisig_pda` - The multisig account public key
+/// * `batch_index` - The transaction index of the batch
+/// * `program_id` - Optional custom program ID (uses canonical ID if None)
+///
+/// # Returns
+/// Tuple of (PDA pubkey, bump seed)
+pub fn get_batch_pda(
+    multisig_pda: &Pubkey,
+    batch_index: u64,
+    program_id: Option<&Pubkey>,
+) -> (Pubkey, u8) {
+    get_transaction_pda(multisig_pda, batch_index, program_id)
+}
+
+/// Get the PDA of a transaction inside a batch
+///
+/// # Arguments
+/// * `multisig_pda` - The multisig account public key
+/// * `batch_index` - The transaction index of the batch
+/// * `transaction_index` - The 1-based index of the transaction within the batch
+/// * `program_id` - Optional custom program ID (uses canonical ID if None)
+///
+/// # Returns
+/// Tuple of (PDA pubkey, bump seed)
+pub fn get_batch_transaction_pda(
+    multisig_pda: &Pubkey,
+    batch_index: u64,
+    transaction_index: u32,
+    program_id: Option<&Pubkey>,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            SEED_PREFIX,
+            multisig_pda.as_ref(),
+            SEED_TRANSACTION,
+            &batch_index.to_le_bytes(),
+            SEED_BATCH_TRANSACTION,
+            &transaction_index.to_le_bytes(),
+        ],
+        program_id.unwrap_or(&crate::program_id()),
+    )
+}
+
+/// Get the PDA of a transaction buffer
+///
+/// # Arguments
+/// * `multisig_pda` - The multisig account public key
+/// * `creator` - Member that creates the buffer
+/// * `buffer_index` - Index distinguishing the creator's buffers
+/// * `program_id` - Optional custom program ID (uses canonical ID if None)
+///
+/// # Returns
+/// Tuple of (PDA pubkey, bump seed)
+pub fn get_transaction_buffer_pda(
+    multisig_pda: &Pubkey,
+    creator: &Pubkey,
+    buffer_index: u8,
+    program_id: Option<&Pubkey>,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            SEED_PREFIX,
+            multisig_pda.as_ref(),
+            SEED_TRANSACTION_BUFFER,
+            creator.as_ref(),
+            &buffer_index.to_le_bytes(),
+        ],
+        program_id.unwrap_or(&crate::program_id()),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -193,4 +270,30 @@ mod tests {
         let (pda, _bump) = get_proposal_pda(&multisig_pda, 1, None);
         assert_ne!(pda, Pubkey::default());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_batch_transaction_pda_derivation() {
+        let multisig_pda = Pubkey::new_unique();
+        assert_eq!(
+            get_batch_pda(&multisig_pda, 3, None),
+            get_transaction_pda(&multisig_pda, 3, None)
+        );
+
+        let (first, _) = get_batch_transaction_pda(&multisig_pda, 3, 1, None);
+        let (second, _) = get_batch_transaction_pda(&multisig_pda, 3, 2, None);
+        let (other_batch, _) = get_batch_transaction_pda(&multisig_pda, 4, 1, None);
+        assert_ne!(first, second);
+        assert_ne!(first, other_batch);
+    }
+
+    #[test]
+    fn test_transaction_buffer_pda_derivation() {
+        let multisig_pda = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let (first, _) = get_transaction_buffer_pda(&multisig_pda, &creator, 0, None);
+        let (second, _) = get_transaction_buffer_pda(&multisig_pda, &creator, 1, None);
+        let (other, _) = get_transaction_buffer_pda(&multisig_pda, &Pubkey::new_unique(), 0, None);
+        assert_ne!(first, second);
+        assert_ne!(first, other);
+    }
+}
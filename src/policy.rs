@@ -0,0 +1,449 @@
+//! Instruction-level approval policies for automated approvers
+//!
+//! Bots that approve routine proposals describe what is routine as a [`Policy`]: an
+//! ordered list of [`PolicyRule`]s that allow or deny the instructions matched by an
+//! [`InstructionMatcher`]. [`Policy::evaluate`] runs every instruction of a vault
+//! transaction through the rules and approves the transaction only when each instruction
+//! is allowed; anything unmatched is left for human review. [`AuditLog`] keeps every
+//! decision together with the rules that produced it.
+//!
+//! ```rust
+//! use squads_v4_client_v3::policy::{Policy, PolicyRule, TransferMatcher, Verdict};
+//! use solana_sdk::pubkey::Pubkey;
+//!
+//! let usdc = Pubkey::new_unique();
+//! let payroll = Pubkey::new_unique();
+//! let policy = Policy::new().with_rule(PolicyRule::allow(
+//!     "small USDC payouts",
+//!     TransferMatcher::token(usdc).to(vec![payroll]).max_amount(100_000_000),
+//! ));
+//!
+//! // Anything the rules do not cover needs a human
+//! assert_eq!(policy.evaluate(&[]).verdict, Verdict::Review);
+//! ```
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::{instruction::Instruction, message::AddressLookupTableAccount, pubkey::Pubkey};
+use solana_sdk_ids::system_program;
+
+use crate::accounts::VaultTransactionMessage;
+use crate::diff::Transfer;
+use crate::programs::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+
+/// Decides whether a rule applies to an instruction
+pub trait InstructionMatcher: Send + Sync {
+    /// Whether the instruction is matched
+    fn matches(&self, instruction: &Instruction) -> bool;
+
+    /// Largest total the matched transfers of one transaction may move, per mint and
+    /// destination, None for no limit
+    ///
+    /// [`Policy::evaluate`] sums the transfers a rule allows and withdraws the allowance
+    /// when the total exceeds this cap.
+    fn transaction_cap(&self) -> Option<u64> {
+        None
+    }
+}
+
+impl<F> InstructionMatcher for F
+where
+    F: Fn(&Instruction) -> bool + Send + Sync,
+{
+    fn matches(&self, instruction: &Instruction) -> bool {
+        self(instruction)
+    }
+}
+
+/// Matches every instruction of a program, e.g. the Memo or Compute Budget program
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgramMatcher(pub Pubkey);
+
+impl InstructionMatcher for ProgramMatcher {
+    fn matches(&self, instruction: &Instruction) -> bool {
+        instruction.program_id == self.0
+    }
+}
+
+/// Matches SOL or token transfers decoded with [`Transfer::decode`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferMatcher {
+    /// Programs whose transfers are matched
+    pub program_ids: Vec<Pubkey>,
+    /// Required token mint; only `TransferChecked` instructions carry it, so plain token
+    /// `Transfer`s never match a matcher with a mint
+    pub mint: Option<Pubkey>,
+    /// Allowed destinations (token accounts for token transfers), None for any
+    pub destinations: Option<Vec<Pubkey>>,
+    /// Largest allowed amount in lamports or token base units, None for any
+    ///
+    /// The cap applies to the whole transaction: transfers of the same mint to the same
+    /// destination are added up by [`Policy::evaluate`].
+    pub max_amount: Option<u64>,
+}
+
+impl TransferMatcher {
+    /// Match System program SOL transfers
+    pub fn sol() -> Self {
+        Self {
+            program_ids: vec![system_program::ID],
+            mint: None,
+            destinations: None,
+            max_amount: None,
+        }
+    }
+
+    /// Match Token and Token-2022 `TransferChecked` instructions of `mint`
+    pub fn token(mint: Pubkey) -> Self {
+        Self {
+            program_ids: vec![TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID],
+            mint: Some(mint),
+            destinations: None,
+            max_amount: None,
+        }
+    }
+
+    /// Only match transfers to one of `destinations`
+    pub fn to(mut self, destinations: Vec<Pubkey>) -> Self {
+        self.destinations = Some(destinations);
+        self
+    }
+
+    /// Only match transfers of at most `amount`
+    pub fn max_amount(mut self, amount: u64) -> Self {
+        self.max_amount = Some(amount);
+        self
+    }
+}
+
+impl InstructionMatcher for TransferMatcher {
+    fn matches(&self, instruction: &Instruction) -> bool {
+        let Some(transfer) = Transfer::decode(instruction) else {
+            return false;
+        };
+        self.program_ids.contains(&transfer.program_id)
+            && self.mint.is_none_or(|mint| transfer.mint == Some(mint))
+            && self
+                .destinations
+                .as_ref()
+                .is_none_or(|allowed| allowed.contains(&transfer.destination))
+            && self.max_amount.is_none_or(|max| transfer.amount <= max)
+    }
+
+    fn transaction_cap(&self) -> Option<u64> {
+        self.max_amount
+    }
+}
+
+/// What a rule does with the instructions it matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Effect {
+    /// The instruction may be approved automatically
+    Allow,
+    /// The transaction must not be approved automatically
+    Deny,
+}
+
+/// A named rule of a [`Policy`]
+pub struct PolicyRule {
+    /// Name recorded in the audit log
+    pub name: String,
+    /// What the rule does with matched instructions
+    pub effect: Effect,
+    /// Instructions the rule applies to
+    pub matcher: Box<dyn InstructionMatcher>,
+}
+
+impl PolicyRule {
+    /// A rule allowing the instructions matched by `matcher`
+    pub fn allow(name: impl Into<String>, matcher: impl InstructionMatcher + 'static) -> Self {
+        Self {
+            name: name.into(),
+            effect: Effect::Allow,
+            matcher: Box::new(matcher),
+        }
+    }
+
+    /// A rule denying the instructions matched by `matcher`
+    pub fn deny(name: impl Into<String>, matcher: impl InstructionMatcher + 'static) -> Self {
+        Self {
+            name: name.into(),
+            effect: Effect::Deny,
+            matcher: Box::new(matcher),
+        }
+    }
+}
+
+impl std::fmt::Debug for PolicyRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PolicyRule")
+            .field("name", &self.name)
+            .field("effect", &self.effect)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Outcome of evaluating a transaction against a [`Policy`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Verdict {
+    /// Every instruction is allowed; the bot may approve
+    Approve,
+    /// Some instruction is not covered by any rule, or the instructions could not be
+    /// resolved; a human must review
+    Review,
+    /// Some instruction is denied
+    Deny,
+}
+
+/// How one instruction of a transaction was judged
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstructionDecision {
+    /// Position of the instruction in the transaction
+    pub index: usize,
+    /// Program the instruction calls
+    pub program_id: Pubkey,
+    /// Name of the deciding rule, None if no rule matched
+    pub rule: Option<String>,
+    /// Effect of the deciding rule
+    pub effect: Option<Effect>,
+}
+
+/// Result of [`Policy::evaluate`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Evaluation {
+    /// Overall verdict
+    pub verdict: Verdict,
+    /// Decision for each instruction, in transaction order
+    pub instructions: Vec<InstructionDecision>,
+}
+
+/// Ordered allow/deny rules for automated approval
+///
+/// For each instruction, a matching deny rule takes precedence over allow rules; among
+/// rules with the same effect the first one added decides.
+#[derive(Debug, Default)]
+pub struct Policy {
+    rules: Vec<PolicyRule>,
+}
+
+impl Policy {
+    /// An empty policy, which sends every transaction to review
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a rule
+    pub fn with_rule(mut self, rule: PolicyRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Judge the instructions of a transaction
+    ///
+    /// A transaction without instructions is sent to review. Transfers allowed by a
+    /// rule with a [`transaction_cap`](InstructionMatcher::transaction_cap) are added up
+    /// per mint and destination; when the total exceeds the cap, those transfers are
+    /// treated as unmatched, so splitting a payment cannot get around the cap.
+    pub fn evaluate(&self, instructions: &[Instruction]) -> Evaluation {
+        let rules: Vec<Option<&PolicyRule>> = instructions
+            .iter()
+            .map(|instruction| {
+                let matched = |effect| {
+                    self.rules
+                        .iter()
+                        .find(|rule| rule.effect == effect && rule.matcher.matches(instruction))
+                };
+                matched(Effect::Deny).or_else(|| matched(Effect::Allow))
+            })
+            .collect();
+
+        let mut totals: HashMap<(Option<Pubkey>, Pubkey), u64> = HashMap::new();
+        for (instruction, _) in instructions
+            .iter()
+            .zip(&rules)
+            .filter(|(_, rule)| rule.is_some_and(|rule| rule.effect == Effect::Allow))
+        {
+            if let Some(transfer) = Transfer::decode(instruction) {
+                let total = totals
+                    .entry((transfer.mint, transfer.destination))
+                    .or_default();
+                *total = total.saturating_add(transfer.amount);
+            }
+        }
+        let within_cap = |instruction: &Instruction, rule: &PolicyRule| {
+            let (Some(cap), Some(transfer)) = (
+                rule.matcher.transaction_cap(),
+                Transfer::decode(instruction),
+            ) else {
+                return true;
+            };
+            totals[&(transfer.mint, transfer.destination)] <= cap
+        };
+
+        let decisions: Vec<InstructionDecision> = instructions
+            .iter()
+            .zip(rules)
+            .enumerate()
+            .map(|(index, (instruction, rule))| {
+                let rule = rule
+                    .filter(|rule| rule.effect == Effect::Deny || within_cap(instruction, rule));
+                InstructionDecision {
+                    index,
+                    program_id: instruction.program_id,
+                    rule: rule.map(|rule| rule.name.clone()),
+                    effect: rule.map(|rule| rule.effect),
+                }
+            })
+            .collect();
+
+        let verdict = if decisions.iter().any(|d| d.effect == Some(Effect::Deny)) {
+            Verdict::Deny
+        } else if !decisions.is_empty() && decisions.iter().all(|d| d.effect == Some(Effect::Allow))
+        {
+            Verdict::Approve
+        } else {
+            Verdict::Review
+        };
+
+        Evaluation {
+            verdict,
+            instructions: decisions,
+        }
+    }
+
+    /// Judge the instructions of a vault transaction message, resolving lookups from
+    /// `tables`
+    ///
+    /// Messages whose instructions cannot be resolved are sent to review.
+    pub fn evaluate_message(
+        &self,
+        message: &VaultTransactionMessage,
+        tables: &[AddressLookupTableAccount],
+    ) -> Evaluation {
+        match message.to_instructions_with_tables(tables) {
+            Some(instructions) => self.evaluate(&instructions),
+            None => Evaluation {
+                verdict: Verdict::Review,
+                instructions: vec![],
+            },
+        }
+    }
+}
+
+/// A decision recorded in an [`AuditLog`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Transaction index of the judged proposal
+    pub transaction_index: u64,
+    /// Unix timestamp of the decision
+    pub timestamp: i64,
+    /// The evaluation
+    pub evaluation: Evaluation,
+}
+
+/// Record of the decisions of an automated approver
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditLog {
+    /// Recorded decisions, oldest first
+    pub entries: Vec<AuditEntry>,
+}
+
+impl AuditLog {
+    /// Record an evaluation and return its verdict
+    pub fn record(
+        &mut self,
+        transaction_index: u64,
+        timestamp: i64,
+        evaluation: Evaluation,
+    ) -> Verdict {
+        let verdict = evaluation.verdict;
+        self.entries.push(AuditEntry {
+            transaction_index,
+            timestamp,
+            evaluation,
+        });
+        verdict
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::programs::MEMO_PROGRAM_ID;
+    use solana_sdk::instruction::AccountMeta;
+
+    fn transfer_checked(
+        source: Pubkey,
+        mint: Pubkey,
+        destination: Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        let mut data = vec![12];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.push(6);
+        Instruction::new_with_bytes(
+            TOKEN_PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(source, false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new(destination, false),
+                AccountMeta::new_readonly(source, true),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_policy_evaluate() {
+        let vault = Pubkey::new_unique();
+        let usdc = Pubkey::new_unique();
+        let payroll = Pubkey::new_unique();
+        let blocked = Pubkey::new_unique();
+        let policy = Policy::new()
+            .with_rule(PolicyRule::allow(
+                "small USDC payouts",
+                TransferMatcher::token(usdc)
+                    .to(vec![payroll])
+                    .max_amount(100),
+            ))
+            .with_rule(PolicyRule::allow("memos", ProgramMatcher(MEMO_PROGRAM_ID)))
+            .with_rule(PolicyRule::deny(
+                "blocked destination",
+                move |ix: &Instruction| ix.accounts.iter().any(|meta| meta.pubkey == blocked),
+            ));
+        let memo = Instruction::new_with_bytes(MEMO_PROGRAM_ID, b"payroll", vec![]);
+        let mut log = AuditLog::default();
+
+        let routine = policy.evaluate(&[transfer_checked(vault, usdc, payroll, 100), memo.clone()]);
+        assert_eq!(log.record(1, 10, routine), Verdict::Approve);
+
+        let large = policy.evaluate(&[transfer_checked(vault, usdc, payroll, 101), memo]);
+        assert_eq!(large.instructions[0].rule, None);
+        assert_eq!(large.instructions[1].rule.as_deref(), Some("memos"));
+        assert_eq!(log.record(2, 20, large), Verdict::Review);
+
+        let denied = policy.evaluate(&[transfer_checked(vault, usdc, blocked, 1)]);
+        assert_eq!(
+            denied.instructions[0].rule.as_deref(),
+            Some("blocked destination")
+        );
+        assert_eq!(log.record(3, 30, denied), Verdict::Deny);
+
+        // The cap applies to the total sent to a destination, not to each transfer
+        let split = policy.evaluate(&[
+            transfer_checked(vault, usdc, payroll, 60),
+            transfer_checked(vault, usdc, payroll, 60),
+        ]);
+        assert!(split.instructions.iter().all(|d| d.rule.is_none()));
+        assert_eq!(log.record(4, 40, split), Verdict::Review);
+        let within = policy.evaluate(&[
+            transfer_checked(vault, usdc, payroll, 40),
+            transfer_checked(vault, usdc, payroll, 60),
+        ]);
+        assert_eq!(within.verdict, Verdict::Approve);
+
+        let json = serde_json::to_string(&log).unwrap();
+        assert_eq!(serde_json::from_str::<AuditLog>(&json).unwrap(), log);
+    }
+}
@@ -0,0 +1,474 @@
+//! Vault portfolio detection
+//!
+//! Treasury reporting needs more than a vault's SOL balance: funds also sit in token
+//! accounts, stake accounts and protocol accounts owned by the vault. Each
+//! [`PositionDetector`] describes how to find one kind of account with
+//! `getProgramAccounts` filters and how to turn a matching account into a [`Position`].
+//! Token, stake and OpenBook open orders detectors are built in; other protocols (LP
+//! positions, lending obligations, ...) can be added by implementing the trait.
+//!
+//! `vault_positions` (requires the `async` feature) runs the detectors against a vault
+//! and returns a [`PortfolioSummary`].
+
+use std::collections::BTreeMap;
+
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::{account::Account, pubkey, pubkey::Pubkey};
+
+use crate::programs::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+
+/// Native Stake program
+pub const STAKE_PROGRAM_ID: Pubkey = pubkey!("Stake11111111111111111111111111111111111111");
+
+/// OpenBook v1 (Serum v3 fork) DEX program
+pub const OPENBOOK_PROGRAM_ID: Pubkey = pubkey!("srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX");
+
+/// Size of an SPL Token account
+const TOKEN_ACCOUNT_SIZE: u64 = 165;
+
+/// Size of a Serum v3 / OpenBook v1 open orders account
+const OPEN_ORDERS_SIZE: u64 = 3228;
+
+/// A `getProgramAccounts` query used to find candidate accounts
+#[derive(Debug, Clone)]
+pub struct AccountQuery {
+    /// Program owning the accounts
+    pub program_id: Pubkey,
+    /// Filters selecting the vault's accounts
+    pub filters: Vec<RpcFilterType>,
+}
+
+/// What kind of position an account holds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PositionKind {
+    /// SPL Token or Token-2022 account
+    Token {
+        /// Token mint
+        mint: Pubkey,
+        /// Balance in base units
+        amount: u64,
+        /// Token program owning the account
+        token_program: Pubkey,
+    },
+    /// Stake account withdrawable by the vault
+    Stake {
+        /// Vote account the stake is delegated to, if delegated
+        voter: Option<Pubkey>,
+        /// Delegated lamports
+        delegated: u64,
+        /// Whether the stake is deactivating or deactivated
+        deactivating: bool,
+    },
+    /// DEX open orders account
+    OpenOrders {
+        /// Market the account trades on
+        market: Pubkey,
+        /// Total base token amount held, including amounts locked in orders
+        base_total: u64,
+        /// Total quote token amount held, including amounts locked in orders
+        quote_total: u64,
+    },
+    /// Position detected by a custom detector
+    Custom {
+        /// Protocol name
+        protocol: String,
+        /// Human-readable description of the position
+        description: String,
+    },
+}
+
+/// An account holding value on behalf of a vault
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Position {
+    /// Address of the account
+    pub address: Pubkey,
+    /// Lamports held by the account
+    pub lamports: u64,
+    /// What the account holds
+    pub kind: PositionKind,
+}
+
+/// Finds and parses one kind of vault-owned account
+pub trait PositionDetector: Send + Sync {
+    /// Short name of the detector, used in reports
+    fn name(&self) -> &str;
+
+    /// Queries returning candidate accounts for `vault`
+    fn queries(&self, vault: &Pubkey) -> Vec<AccountQuery>;
+
+    /// Parse a candidate account, returning `None` if it is not a position of `vault`
+    fn detect(&self, vault: &Pubkey, address: &Pubkey, account: &Account) -> Option<Position>;
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Option<Pubkey> {
+    let bytes: [u8; 32] = data.get(offset..offset + 32)?.try_into().ok()?;
+    Some(Pubkey::new_from_array(bytes))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    let bytes: [u8; 8] = data.get(offset..offset + 8)?.try_into().ok()?;
+    Some(u64::from_le_bytes(bytes))
+}
+
+fn owner_filter(offset: usize, owner: &Pubkey) -> RpcFilterType {
+    RpcFilterType::Memcmp(Memcmp::new_base58_encoded(offset, owner.as_ref()))
+}
+
+/// Detects SPL Token and Token-2022 accounts owned by the vault
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokenAccountDetector;
+
+impl PositionDetector for TokenAccountDetector {
+    fn name(&self) -> &str {
+        "token"
+    }
+
+    fn queries(&self, vault: &Pubkey) -> Vec<AccountQuery> {
+        vec![
+            AccountQuery {
+                program_id: TOKEN_PROGRAM_ID,
+                filters: vec![
+                    RpcFilterType::DataSize(TOKEN_ACCOUNT_SIZE),
+                    owner_filter(32, vault),
+                ],
+            },
+            // Token-2022 accounts with extensions are larger than the base layout
+            AccountQuery {
+                program_id: TOKEN_2022_PROGRAM_ID,
+                filters: vec![owner_filter(32, vault)],
+            },
+        ]
+    }
+
+    fn detect(&self, vault: &Pubkey, address: &Pubkey, account: &Account) -> Option<Position> {
+        if account.owner != TOKEN_PROGRAM_ID && account.owner != TOKEN_2022_PROGRAM_ID {
+            return None;
+        }
+        if read_pubkey(&account.data, 32)? != *vault {
+            return None;
+        }
+        Some(Position {
+            address: *address,
+            lamports: account.lamports,
+            kind: PositionKind::Token {
+                mint: read_pubkey(&account.data, 0)?,
+                amount: read_u64(&account.data, 64)?,
+                token_program: account.owner,
+            },
+        })
+    }
+}
+
+/// Detects stake accounts whose withdraw authority is the vault
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StakeAccountDetector;
+
+impl StakeAccountDetector {
+    /// Offset of the withdraw authority in a stake account
+    const WITHDRAWER_OFFSET: usize = 44;
+    /// Offset of the delegation in a delegated stake account
+    const DELEGATION_OFFSET: usize = 124;
+}
+
+impl PositionDetector for StakeAccountDetector {
+    fn name(&self) -> &str {
+        "stake"
+    }
+
+    fn queries(&self, vault: &Pubkey) -> Vec<AccountQuery> {
+        vec![AccountQuery {
+            program_id: STAKE_PROGRAM_ID,
+            filters: vec![owner_filter(Self::WITHDRAWER_OFFSET, vault)],
+        }]
+    }
+
+    fn detect(&self, vault: &Pubkey, address: &Pubkey, account: &Account) -> Option<Position> {
+        if account.owner != STAKE_PROGRAM_ID {
+            return None;
+        }
+        if read_pubkey(&account.data, Self::WITHDRAWER_OFFSET)? != *vault {
+            return None;
+        }
+
+        let state = u32::from_le_bytes(account.data.get(..4)?.try_into().ok()?);
+        let kind = match state {
+            // Initialized but not delegated
+            1 => PositionKind::Stake {
+                voter: None,
+                delegated: 0,
+                deactivating: false,
+            },
+            // Delegated: voter, stake, activation epoch, deactivation epoch
+            2 => PositionKind::Stake {
+                voter: Some(read_pubkey(&account.data, Self::DELEGATION_OFFSET)?),
+                delegated: read_u64(&account.data, Self::DELEGATION_OFFSET + 32)?,
+                deactivating: read_u64(&account.data, Self::DELEGATION_OFFSET + 48)? != u64::MAX,
+            },
+            _ => return None,
+        };
+
+        Some(Position {
+            address: *address,
+            lamports: account.lamports,
+            kind,
+        })
+    }
+}
+
+/// Detects Serum v3 style open orders accounts owned by the vault
+#[derive(Debug, Clone, Copy)]
+pub struct OpenOrdersDetector {
+    /// DEX program to scan
+    pub program_id: Pubkey,
+}
+
+impl Default for OpenOrdersDetector {
+    fn default() -> Self {
+        Self {
+            program_id: OPENBOOK_PROGRAM_ID,
+        }
+    }
+}
+
+impl OpenOrdersDetector {
+    /// Offset of the owner after the 5 byte padding, account flags and market
+    const OWNER_OFFSET: usize = 45;
+}
+
+impl PositionDetector for OpenOrdersDetector {
+    fn name(&self) -> &str {
+        "open_orders"
+    }
+
+    fn queries(&self, vault: &Pubkey) -> Vec<AccountQuery> {
+        vec![AccountQuery {
+            program_id: self.program_id,
+            filters: vec![
+                RpcFilterType::DataSize(OPEN_ORDERS_SIZE),
+                owner_filter(Self::OWNER_OFFSET, vault),
+            ],
+        }]
+    }
+
+    fn detect(&self, vault: &Pubkey, address: &Pubkey, account: &Account) -> Option<Position> {
+        if account.owner != self.program_id {
+            return None;
+        }
+        if read_pubkey(&account.data, Self::OWNER_OFFSET)? != *vault {
+            return None;
+        }
+        Some(Position {
+            address: *address,
+            lamports: account.lamports,
+            kind: PositionKind::OpenOrders {
+                market: read_pubkey(&account.data, 13)?,
+                base_total: read_u64(&account.data, 85)?,
+                quote_total: read_u64(&account.data, 101)?,
+            },
+        })
+    }
+}
+
+/// Token, stake and OpenBook open orders detectors
+pub fn default_detectors() -> Vec<Box<dyn PositionDetector>> {
+    vec![
+        Box::new(TokenAccountDetector),
+        Box::new(StakeAccountDetector),
+        Box::new(OpenOrdersDetector::default()),
+    ]
+}
+
+/// Positions held by a vault
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortfolioSummary {
+    /// Vault address
+    pub vault: Pubkey,
+    /// SOL balance of the vault itself
+    pub sol_lamports: u64,
+    /// Detected positions, in detector order and then by account address
+    pub positions: Vec<Position>,
+}
+
+impl PortfolioSummary {
+    /// Token balances summed per mint
+    pub fn token_balances(&self) -> BTreeMap<Pubkey, u64> {
+        let mut balances = BTreeMap::new();
+        for position in &self.positions {
+            if let PositionKind::Token { mint, amount, .. } = position.kind {
+                let total: &mut u64 = balances.entry(mint).or_default();
+                *total = total.saturating_add(amount);
+            }
+        }
+        balances
+    }
+
+    /// Lamports delegated from stake accounts that are not deactivating
+    pub fn active_stake(&self) -> u64 {
+        self.positions
+            .iter()
+            .filter_map(|position| match position.kind {
+                PositionKind::Stake {
+                    delegated,
+                    deactivating: false,
+                    ..
+                } => Some(delegated),
+                _ => None,
+            })
+            .sum()
+    }
+
+    /// SOL held by the vault and all position accounts (including rent reserves)
+    pub fn total_lamports(&self) -> u64 {
+        self.positions
+            .iter()
+            .fold(self.sol_lamports, |total, position| {
+                total.saturating_add(position.lamports)
+            })
+    }
+}
+
+#[cfg(feature = "async")]
+pub use fetch::vault_positions;
+
+#[cfg(feature = "async")]
+mod fetch {
+    use solana_account_decoder_client_types::UiAccountEncoding;
+    use solana_client::{
+        nonblocking::rpc_client::RpcClient,
+        rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    };
+
+    use super::*;
+    use crate::{error::SquadsResult, pda};
+
+    /// Detect the positions held by a multisig vault
+    ///
+    /// # Arguments
+    /// * `rpc` - RPC client (the endpoint must allow `getProgramAccounts`)
+    /// * `multisig` - Multisig account
+    /// * `vault_index` - Vault index
+    /// * `program_id` - Optional custom program ID
+    /// * `detectors` - Detectors to run, e.g. [`default_detectors`]
+    pub async fn vault_positions(
+        rpc: &RpcClient,
+        multisig: &Pubkey,
+        vault_index: u8,
+        program_id: Option<&Pubkey>,
+        detectors: &[Box<dyn PositionDetector>],
+    ) -> SquadsResult<PortfolioSummary> {
+        let (vault, _) = pda::get_vault_pda(multisig, vault_index, program_id);
+        let sol_lamports = rpc.get_balance(&vault).await?;
+
+        let mut positions = Vec::new();
+        for detector in detectors {
+            for query in detector.queries(&vault) {
+                let config = RpcProgramAccountsConfig {
+                    filters: Some(query.filters),
+                    account_config: RpcAccountInfoConfig {
+                        encoding: Some(UiAccountEncoding::Base64),
+                        ..RpcAccountInfoConfig::default()
+                    },
+                    ..RpcProgramAccountsConfig::default()
+                };
+                let accounts = rpc
+                    .get_program_accounts_with_config(&query.program_id, config)
+                    .await?;
+                let mut detected: Vec<Position> = accounts
+                    .iter()
+                    .filter_map(|(address, account)| detector.detect(&vault, address, account))
+                    .collect();
+                // getProgramAccounts returns accounts in no particular order
+                detected.sort_by_key(|position| position.address);
+                positions.extend(detected);
+            }
+        }
+
+        Ok(PortfolioSummary {
+            vault,
+            sol_lamports,
+            positions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(owner: Pubkey, data: Vec<u8>, lamports: u64) -> Account {
+        Account {
+            lamports,
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_detect_token_account() {
+        let vault = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let mut data = vec![0u8; TOKEN_ACCOUNT_SIZE as usize];
+        data[..32].copy_from_slice(mint.as_ref());
+        data[32..64].copy_from_slice(vault.as_ref());
+        data[64..72].copy_from_slice(&500u64.to_le_bytes());
+        let token = account(TOKEN_PROGRAM_ID, data, 2_039_280);
+
+        let address = Pubkey::new_unique();
+        let position = TokenAccountDetector
+            .detect(&vault, &address, &token)
+            .unwrap();
+        assert_eq!(
+            position.kind,
+            PositionKind::Token {
+                mint,
+                amount: 500,
+                token_program: TOKEN_PROGRAM_ID,
+            }
+        );
+        assert!(TokenAccountDetector
+            .detect(&Pubkey::new_unique(), &address, &token)
+            .is_none());
+
+        let summary = PortfolioSummary {
+            vault,
+            sol_lamports: 1_000,
+            positions: vec![position.clone(), position],
+        };
+        assert_eq!(summary.token_balances()[&mint], 1_000);
+        assert_eq!(summary.total_lamports(), 1_000 + 2 * 2_039_280);
+    }
+
+    #[test]
+    fn test_detect_stake_account() {
+        let vault = Pubkey::new_unique();
+        let voter = Pubkey::new_unique();
+        let mut data = vec![0u8; 200];
+        data[..4].copy_from_slice(&2u32.to_le_bytes());
+        data[44..76].copy_from_slice(vault.as_ref());
+        data[124..156].copy_from_slice(voter.as_ref());
+        data[156..164].copy_from_slice(&5_000u64.to_le_bytes());
+        data[172..180].copy_from_slice(&u64::MAX.to_le_bytes());
+        let stake = account(STAKE_PROGRAM_ID, data, 5_002_282);
+
+        let position = StakeAccountDetector
+            .detect(&vault, &Pubkey::new_unique(), &stake)
+            .unwrap();
+        assert_eq!(
+            position.kind,
+            PositionKind::Stake {
+                voter: Some(voter),
+                delegated: 5_000,
+                deactivating: false,
+            }
+        );
+
+        let summary = PortfolioSummary {
+            vault,
+            sol_lamports: 0,
+            positions: vec![position],
+        };
+        assert_eq!(summary.active_stake(), 5_000);
+    }
+}
@@ -0,0 +1,335 @@
+//! Single round trip account loading for vault transaction execution
+//!
+//! Executing a vault transaction needs the multisig, proposal and transaction accounts,
+//! plus the address lookup tables the transaction message uses. Fetching them one by one
+//! costs a round trip each. [`ExecutePrefetch`] plans every address up front so they can
+//! be loaded with one `getMultipleAccounts` call, and [`ExecutePrefetch::resolve`] decodes
+//! the result into a [`PrefetchedExecution`].
+//!
+//! Lookup tables are only known once the transaction is decoded, so pass them to
+//! [`ExecutePrefetch::with_lookup_tables`] when they are known in advance (for example
+//! from the message the proposer compiled). Tables missing from the plan are listed by
+//! [`PrefetchedExecution::missing_lookup_tables`] and need a second fetch.
+
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    message::AddressLookupTableAccount,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    accounts::{check_owner, Multisig, Proposal, VaultTransaction},
+    alt,
+    error::{SquadsError, SquadsResult},
+    instructions, pda,
+};
+
+/// Addresses to load before executing a vault transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutePrefetch {
+    /// Multisig account
+    pub multisig: Pubkey,
+    /// Proposal account of the transaction
+    pub proposal: Pubkey,
+    /// Vault transaction account
+    pub transaction: Pubkey,
+    /// Address lookup tables the transaction message is expected to use
+    pub lookup_tables: Vec<Pubkey>,
+    /// Squads program ID the accounts must be owned by
+    pub program_id: Pubkey,
+}
+
+impl ExecutePrefetch {
+    /// Plan the accounts of the vault transaction at `transaction_index`
+    ///
+    /// # Arguments
+    /// * `multisig` - Multisig account
+    /// * `transaction_index` - Index of the vault transaction
+    /// * `program_id` - Optional custom program ID (uses canonical ID if None)
+    pub fn new(multisig: Pubkey, transaction_index: u64, program_id: Option<&Pubkey>) -> Self {
+        let program_id = program_id.copied().unwrap_or_else(crate::program_id);
+        Self {
+            multisig,
+            proposal: pda::get_proposal_pda(&multisig, transaction_index, Some(&program_id)).0,
+            transaction: pda::get_transaction_pda(&multisig, transaction_index, Some(&program_id))
+                .0,
+            lookup_tables: Vec::new(),
+            program_id,
+        }
+    }
+
+    /// Also load these lookup tables, skipping duplicates
+    pub fn with_lookup_tables(mut self, tables: impl IntoIterator<Item = Pubkey>) -> Self {
+        for table in tables {
+            if !self.lookup_tables.contains(&table) {
+                self.lookup_tables.push(table);
+            }
+        }
+        self
+    }
+
+    /// Addresses to pass to `getMultipleAccounts`, in the order [`resolve`](Self::resolve)
+    /// expects the accounts
+    pub fn addresses(&self) -> Vec<Pubkey> {
+        [self.multisig, self.proposal, self.transaction]
+            .into_iter()
+            .chain(self.lookup_tables.iter().copied())
+            .collect()
+    }
+
+    /// Decode the accounts fetched for [`addresses`](Self::addresses)
+    ///
+    /// Planned lookup tables that do not exist are skipped; the transaction reports them
+    /// through [`PrefetchedExecution::missing_lookup_tables`] if it needs them.
+    pub fn resolve(&self, accounts: Vec<Option<Account>>) -> SquadsResult<PrefetchedExecution> {
+        let addresses = self.addresses();
+        let mut accounts = addresses
+            .iter()
+            .zip(accounts.into_iter().chain(std::iter::repeat(None)));
+        let mut next_squads_account = || {
+            let (address, account) = accounts.next().expect("planned address");
+            let account =
+                account.ok_or_else(|| SquadsError::AccountNotFound(address.to_string()))?;
+            check_owner(&account.owner, &self.program_id)?;
+            Ok::<_, SquadsError>(account.data)
+        };
+
+        let multisig = Multisig::try_from_slice(&next_squads_account()?)
+            .map_err(SquadsError::from_decode_error)?;
+        let proposal = Proposal::try_from_slice(&next_squads_account()?)
+            .map_err(SquadsError::from_decode_error)?;
+        let transaction = VaultTransaction::try_from_slice(&next_squads_account()?)
+            .map_err(SquadsError::from_decode_error)?;
+
+        let mut prefetched = PrefetchedExecution {
+            multisig_address: self.multisig,
+            proposal_address: self.proposal,
+            transaction_address: self.transaction,
+            multisig,
+            proposal,
+            transaction,
+            lookup_tables: Vec::new(),
+            program_id: self.program_id,
+        };
+        for (address, account) in accounts {
+            if let Some(account) = account {
+                prefetched.add_lookup_table(*address, &account)?;
+            }
+        }
+        Ok(prefetched)
+    }
+}
+
+/// Decoded accounts needed to execute a vault transaction
+#[derive(Debug, Clone)]
+pub struct PrefetchedExecution {
+    /// Multisig account address
+    pub multisig_address: Pubkey,
+    /// Proposal account address
+    pub proposal_address: Pubkey,
+    /// Vault transaction account address
+    pub transaction_address: Pubkey,
+    /// Decoded multisig
+    pub multisig: Multisig,
+    /// Decoded proposal
+    pub proposal: Proposal,
+    /// Decoded vault transaction
+    pub transaction: VaultTransaction,
+    /// Decoded lookup tables
+    pub lookup_tables: Vec<AddressLookupTableAccount>,
+    /// Squads program ID the accounts were loaded for
+    pub program_id: Pubkey,
+}
+
+impl PrefetchedExecution {
+    /// Decode and keep a lookup table account fetched separately
+    pub fn add_lookup_table(&mut self, key: Pubkey, account: &Account) -> SquadsResult<()> {
+        check_owner(
+            &account.owner,
+            &solana_address_lookup_table_interface::program::ID,
+        )?;
+        let table = alt::parse(&account.data)?.into_account(key);
+        self.lookup_tables.retain(|existing| existing.key != key);
+        self.lookup_tables.push(table);
+        Ok(())
+    }
+
+    /// Lookup tables the transaction message uses that have not been loaded
+    pub fn missing_lookup_tables(&self) -> Vec<Pubkey> {
+        self.transaction
+            .message
+            .address_table_lookups
+            .iter()
+            .map(|lookup| lookup.account_key)
+            .filter(|key| !self.lookup_tables.iter().any(|table| table.key == *key))
+            .collect()
+    }
+
+    /// Accounts to pass as `remaining_accounts` to `vault_transaction_execute`
+    ///
+    /// Returns `None` while lookup tables are missing or a lookup cannot be resolved.
+    pub fn remaining_accounts(&self) -> Option<Vec<AccountMeta>> {
+        self.transaction
+            .message
+            .execute_remaining_accounts_with_tables(&self.lookup_tables)
+    }
+
+    /// Build the `vault_transaction_execute` instruction for `member`
+    ///
+    /// Fails with [`SquadsError::InvalidTransactionMessage`] while lookup tables are
+    /// missing.
+    pub fn execute_instruction(&self, member: Pubkey) -> SquadsResult<Instruction> {
+        let remaining_accounts = self
+            .remaining_accounts()
+            .ok_or(SquadsError::InvalidTransactionMessage)?;
+        Ok(instructions::vault_transaction_execute(
+            self.multisig_address,
+            self.proposal_address,
+            self.transaction_address,
+            member,
+            remaining_accounts,
+            Some(self.program_id),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accounts::{
+        account_discriminator, MessageAddressTableLookup, VaultTransactionMessage,
+    };
+    use crate::types::{Member, ProposalStatus};
+    use borsh::BorshSerialize;
+    use solana_address_lookup_table_interface::state::{AddressLookupTable, LookupTableMeta};
+    use std::borrow::Cow;
+
+    fn account(owner: Pubkey, data: Vec<u8>) -> Option<Account> {
+        Some(Account {
+            lamports: 1,
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        })
+    }
+
+    fn anchor_data(name: &str, fields: impl BorshSerialize) -> Vec<u8> {
+        let mut data = account_discriminator(name).to_vec();
+        fields.serialize(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_resolve_single_round_trip() {
+        let squads = crate::program_id();
+        let multisig = Pubkey::new_unique();
+        let table_key = Pubkey::new_unique();
+        let other_table = Pubkey::new_unique();
+        let looked_up = Pubkey::new_unique();
+
+        let plan =
+            ExecutePrefetch::new(multisig, 4, None).with_lookup_tables([table_key, table_key]);
+        let addresses = plan.addresses();
+        assert_eq!(addresses.len(), 4);
+        assert_eq!(addresses[1], pda::get_proposal_pda(&multisig, 4, None).0);
+        assert_eq!(addresses[3], table_key);
+
+        let multisig_data = anchor_data(
+            "Multisig",
+            (
+                Pubkey::new_unique(),
+                Pubkey::default(),
+                1u16,
+                0u32,
+                4u64,
+                0u64,
+                None::<Pubkey>,
+                255u8,
+                vec![Member::new(Pubkey::new_unique())],
+            ),
+        );
+        let proposal_data = anchor_data(
+            "Proposal",
+            Proposal {
+                multisig,
+                transaction_index: 4,
+                status: ProposalStatus::Approved { timestamp: 0 },
+                bump: 255,
+                approved: vec![],
+                rejected: vec![],
+                cancelled: vec![],
+                unknown_tail: vec![],
+            },
+        );
+        let lookup = |key| MessageAddressTableLookup {
+            account_key: key,
+            writable_indexes: vec![0],
+            readonly_indexes: vec![],
+        };
+        let transaction_data = anchor_data(
+            "VaultTransaction",
+            VaultTransaction {
+                multisig,
+                creator: Pubkey::new_unique(),
+                index: 4,
+                bump: 255,
+                vault_index: 0,
+                vault_bump: 255,
+                ephemeral_signer_bumps: vec![],
+                message: VaultTransactionMessage {
+                    num_signers: 1,
+                    num_writable_signers: 1,
+                    num_writable_non_signers: 0,
+                    account_keys: vec![Pubkey::new_unique()],
+                    instructions: vec![],
+                    address_table_lookups: vec![lookup(table_key), lookup(other_table)],
+                },
+                unknown_tail: vec![],
+            },
+        );
+        let table = |addresses: &[Pubkey]| {
+            AddressLookupTable {
+                meta: LookupTableMeta::default(),
+                addresses: Cow::Borrowed(addresses),
+            }
+            .serialize_for_tests()
+            .unwrap()
+        };
+        let alt_program = solana_address_lookup_table_interface::program::ID;
+
+        let mut prefetched = plan
+            .resolve(vec![
+                account(squads, multisig_data),
+                account(squads, proposal_data),
+                account(squads, transaction_data.clone()),
+                account(alt_program, table(&[looked_up])),
+            ])
+            .unwrap();
+        assert_eq!(prefetched.multisig.transaction_index, 4);
+        assert_eq!(prefetched.missing_lookup_tables(), vec![other_table]);
+        assert!(prefetched.remaining_accounts().is_none());
+        assert!(prefetched
+            .execute_instruction(Pubkey::new_unique())
+            .is_err());
+
+        let other = account(alt_program, table(&[Pubkey::new_unique()])).unwrap();
+        prefetched.add_lookup_table(other_table, &other).unwrap();
+        assert!(prefetched.missing_lookup_tables().is_empty());
+        let remaining = prefetched.remaining_accounts().unwrap();
+        assert_eq!(remaining[0].pubkey, table_key);
+        assert_eq!(remaining[1].pubkey, other_table);
+        assert!(remaining.iter().any(|meta| meta.pubkey == looked_up));
+
+        // Missing and foreign-owned Squads accounts are rejected
+        assert!(matches!(
+            plan.resolve(vec![]),
+            Err(SquadsError::AccountNotFound(_))
+        ));
+        assert!(matches!(
+            plan.resolve(vec![account(Pubkey::new_unique(), vec![0; 8])]),
+            Err(SquadsError::WrongOwner { .. })
+        ));
+    }
+}
@@ -0,0 +1,57 @@
+//! Commonly used items, re-exported for glob import
+//!
+//! ```rust
+//! use squads_v4_client_v3::prelude::*;
+//!
+//! let (multisig, _) = get_multisig_pda(&Pubkey::new_unique(), None);
+//! let (vault, _) = get_vault_pda(&multisig, 0, None);
+//! ```
+//!
+//! The solana-sdk types used in this crate's public signatures are re-exported too, so
+//! consumers can use them without depending on a matching solana-sdk version directly.
+
+pub use crate::accounts::{
+    AnchorAccount, Batch, ConfigTransaction, Multisig, MultisigRef, MultisigSnapshot, ParseOptions,
+    ProgramConfig, Proposal, SpendingLimit, SquadsAccount, SquadsTransaction, TransactionBuffer,
+    VaultBatchTransaction, VaultTransaction,
+};
+pub use crate::error::{SquadsError, SquadsResult};
+pub use crate::events::{AnchorEvent, LogEvent};
+pub use crate::guard::{CpiGuard, GuardRule, Severity};
+pub use crate::history::TransactionRecord;
+pub use crate::indexer::{BackfillConfig, Checkpoint};
+pub use crate::instructions::{
+    self, BatchAddTransactionArgs, BatchCreateArgs, ConfigTransactionCreateArgs, InstructionKind,
+    InstructionMeta, MultisigAddMemberArgs, MultisigAddSpendingLimitArgs, MultisigCreateArgs,
+    MultisigCreateArgsV2, MultisigRemoveMemberArgs, MultisigRemoveSpendingLimitArgs,
+    MultisigSetRentCollectorArgs, MultisigSetTimeLockArgs, ProgramConfigInitArgs,
+    ProgramConfigSetAuthorityArgs, ProgramConfigSetMultisigCreationFeeArgs,
+    ProgramConfigSetTreasuryArgs, ProposalActivateArgs, ProposalCreateArgs, ProposalVoteArgs,
+    SpendingLimitUseArgs, TransactionBufferCreateArgs, TransactionBufferExtendArgs,
+    VaultTransactionCreateArgs, VoteOptions,
+};
+pub use crate::message::TransactionMessage;
+pub use crate::pda::{
+    self, get_batch_pda, get_batch_transaction_pda, get_ephemeral_signer_pda, get_multisig_pda,
+    get_program_config_pda, get_proposal_pda, get_spending_limit_pda, get_transaction_buffer_pda,
+    get_transaction_pda, get_vault_pda,
+};
+pub use crate::pricing::{PriceSource, PriceTable, TokenPrice};
+pub use crate::reader::SquadsReader;
+pub use crate::spec::MultisigSpec;
+pub use crate::types::{
+    ConfigAction, Member, Period, PeriodWindow, Permission, Permissions, ProposalStatus, SortOrder,
+    TransactionKind,
+};
+pub use crate::upgrade::UpgradePlan;
+pub use crate::voting::{MemberAction, Tally, VoteSet};
+pub use crate::watch::{EventStreamConfig, SquadsEvent};
+pub use crate::workspace::{Workspace, WorkspaceEntry};
+pub use crate::{program_id, SQUADS_PROGRAM_ID};
+
+#[cfg(feature = "async")]
+pub use crate::client::SquadsClient;
+#[cfg(feature = "async")]
+pub use crate::watch::EventStream;
+
+pub use crate::{AccountMeta, Instruction, Keypair, Pubkey, Signature, Signer};
@@ -0,0 +1,518 @@
+//! USD valuations for balance and history reports
+//!
+//! A [`PriceSource`] resolves mints to USD prices, returned as a [`PriceTable`]. The
+//! reports value themselves against a table: [`PortfolioSummary::usd_value`],
+//! [`WorkspaceBalance::usd_value`] and [`TransactionRecord::usd_value`]. Native SOL is
+//! priced under [`NATIVE_MINT`].
+//!
+//! A [`PriceTable`] is itself a source of fixed prices, for tests and static
+//! configuration. With the `pyth` feature, [`PythPriceSource`] reads prices from Pyth
+//! price update accounts; other providers plug in by implementing [`PriceSource`].
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
+use std::pin::Pin;
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::SquadsResult;
+use crate::history::TransactionRecord;
+use crate::parser::SquadsInstruction;
+use crate::portfolio::PortfolioSummary;
+use crate::programs::NATIVE_MINT;
+use crate::workspace::WorkspaceBalance;
+
+/// Decimals of native SOL
+pub const SOL_DECIMALS: u8 = 9;
+
+/// USD price of a token
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TokenPrice {
+    /// Price of one whole token in USD
+    pub usd: f64,
+    /// Decimals of the mint, to convert base units into whole tokens
+    pub decimals: u8,
+}
+
+impl TokenPrice {
+    /// Create a price of one whole token
+    pub fn new(usd: f64, decimals: u8) -> Self {
+        Self { usd, decimals }
+    }
+
+    /// USD value of `amount` base units
+    pub fn value_of(&self, amount: u64) -> f64 {
+        amount as f64 / 10f64.powi(i32::from(self.decimals)) * self.usd
+    }
+}
+
+/// USD prices by mint
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PriceTable {
+    prices: BTreeMap<Pubkey, TokenPrice>,
+}
+
+impl PriceTable {
+    /// Create an empty table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the price of a mint, [`NATIVE_MINT`] for SOL
+    pub fn with_price(mut self, mint: Pubkey, price: TokenPrice) -> Self {
+        self.insert(mint, price);
+        self
+    }
+
+    /// Set the price of a mint, replacing any previous price
+    pub fn insert(&mut self, mint: Pubkey, price: TokenPrice) {
+        self.prices.insert(mint, price);
+    }
+
+    /// Price of a mint
+    pub fn get(&self, mint: &Pubkey) -> Option<&TokenPrice> {
+        self.prices.get(mint)
+    }
+
+    /// USD value of `amount` base units of `mint`, if the mint is priced
+    pub fn value_of(&self, mint: &Pubkey, amount: u64) -> Option<f64> {
+        self.get(mint).map(|price| price.value_of(amount))
+    }
+
+    /// Value a list of (mint, amount) holdings
+    fn value_all(&self, holdings: impl IntoIterator<Item = (Pubkey, u64)>) -> Valuation {
+        let mut valuation = Valuation::default();
+        for (mint, amount) in holdings {
+            match self.value_of(&mint, amount) {
+                Some(usd) => valuation.usd += usd,
+                None if amount > 0 => {
+                    valuation.unpriced.insert(mint);
+                }
+                None => {}
+            }
+        }
+        valuation
+    }
+}
+
+/// USD value of a report
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Valuation {
+    /// Total value of the priced holdings
+    pub usd: f64,
+    /// Mints with a non-zero amount that had no price and are not included in `usd`
+    pub unpriced: BTreeSet<Pubkey>,
+}
+
+impl Valuation {
+    /// Whether every holding was priced
+    pub fn is_complete(&self) -> bool {
+        self.unpriced.is_empty()
+    }
+
+    fn add(&mut self, other: Valuation) {
+        self.usd += other.usd;
+        self.unpriced.extend(other.unpriced);
+    }
+}
+
+/// Future returned by [`PriceSource`] methods
+pub type PriceFuture<'a> = Pin<Box<dyn Future<Output = SquadsResult<PriceTable>> + Send + 'a>>;
+
+/// A source of USD token prices
+pub trait PriceSource: Send + Sync {
+    /// Latest prices of `mints`
+    ///
+    /// Mints the source has no price for are left out of the table.
+    fn latest_prices<'a>(&'a self, mints: &'a [Pubkey]) -> PriceFuture<'a>;
+
+    /// Prices of `mints` at a unix timestamp, for valuing history
+    ///
+    /// The default prices nothing, so history valuations list every mint as unpriced
+    /// for sources without historical data.
+    fn prices_at<'a>(&'a self, mints: &'a [Pubkey], timestamp: i64) -> PriceFuture<'a> {
+        let _ = (mints, timestamp);
+        Box::pin(std::future::ready(Ok(PriceTable::new())))
+    }
+}
+
+impl PriceSource for PriceTable {
+    fn latest_prices<'a>(&'a self, mints: &'a [Pubkey]) -> PriceFuture<'a> {
+        let prices = mints
+            .iter()
+            .filter_map(|mint| self.get(mint).map(|price| (*mint, *price)))
+            .collect();
+        Box::pin(std::future::ready(Ok(PriceTable { prices })))
+    }
+
+    fn prices_at<'a>(&'a self, mints: &'a [Pubkey], _timestamp: i64) -> PriceFuture<'a> {
+        self.latest_prices(mints)
+    }
+}
+
+impl PortfolioSummary {
+    /// Mints to price for [`usd_value`](Self::usd_value), including [`NATIVE_MINT`]
+    pub fn mints(&self) -> Vec<Pubkey> {
+        let mut mints: BTreeSet<Pubkey> = self.token_balances().into_keys().collect();
+        mints.insert(NATIVE_MINT);
+        mints.into_iter().collect()
+    }
+
+    /// USD value of the vault's SOL (including rent reserves and stake) and tokens
+    ///
+    /// Open orders and custom positions are valued by their lamports only, since their
+    /// token amounts are not tied to a mint here.
+    pub fn usd_value(&self, prices: &PriceTable) -> Valuation {
+        // Wrapped SOL is already counted in the lamports of its token account
+        let tokens = self
+            .token_balances()
+            .into_iter()
+            .filter(|(mint, _)| *mint != NATIVE_MINT);
+        prices.value_all(std::iter::once((NATIVE_MINT, self.total_lamports())).chain(tokens))
+    }
+}
+
+impl WorkspaceBalance {
+    /// Mints to price for [`usd_value`](Self::usd_value)
+    pub fn mints(&self) -> Vec<Pubkey> {
+        let mints: BTreeSet<Pubkey> = self
+            .vaults
+            .iter()
+            .flat_map(|vault| vault.summary.mints())
+            .collect();
+        mints.into_iter().collect()
+    }
+
+    /// Combined USD value of every vault in the workspace
+    pub fn usd_value(&self, prices: &PriceTable) -> Valuation {
+        let mut valuation = Valuation::default();
+        for vault in &self.vaults {
+            valuation.add(vault.summary.usd_value(prices));
+        }
+        valuation
+    }
+}
+
+impl TransactionRecord {
+    /// Amounts sent through spending limits, as (mint, amount) with [`NATIVE_MINT`] for
+    /// SOL
+    pub fn spending_limit_transfers(&self) -> Vec<(Pubkey, u64)> {
+        self.instructions
+            .iter()
+            .filter_map(|instruction| match instruction {
+                SquadsInstruction::SpendingLimitUse { mint, args, .. } => {
+                    Some((mint.unwrap_or(NATIVE_MINT), args.amount))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// USD value moved by the transaction's spending limit transfers
+    ///
+    /// Use prices from [`PriceSource::prices_at`] with the record's `block_time` to value
+    /// the transfers at the time they happened. Failed transactions moved nothing.
+    pub fn usd_value(&self, prices: &PriceTable) -> Valuation {
+        if !self.success {
+            return Valuation::default();
+        }
+        prices.value_all(self.spending_limit_transfers())
+    }
+}
+
+#[cfg(feature = "pyth")]
+pub use pyth::{PythPrice, PythPriceSource, PYTH_RECEIVER_PROGRAM_ID};
+
+#[cfg(feature = "pyth")]
+mod pyth {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use borsh::BorshDeserialize;
+    use solana_client::nonblocking::rpc_client::RpcClient;
+    use solana_sdk::pubkey;
+
+    use super::*;
+    use crate::accounts::{check_owner, has_discriminator};
+    use crate::error::SquadsError;
+
+    /// Pyth Solana receiver program, which owns price update accounts
+    pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey =
+        pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+    /// Verification level of a price update
+    #[derive(BorshDeserialize)]
+    enum VerificationLevel {
+        Partial { _num_signatures: u8 },
+        Full,
+    }
+
+    /// Layout of a `PriceUpdateV2` account after its discriminator
+    #[derive(BorshDeserialize)]
+    struct PriceUpdateV2 {
+        _write_authority: Pubkey,
+        verification_level: VerificationLevel,
+        _feed_id: [u8; 32],
+        price: i64,
+        conf: u64,
+        exponent: i32,
+        publish_time: i64,
+    }
+
+    /// A price read from a Pyth price update account
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct PythPrice {
+        /// Price in USD
+        pub usd: f64,
+        /// Confidence interval in USD
+        pub confidence: f64,
+        /// Unix timestamp the price was published at
+        pub publish_time: i64,
+    }
+
+    impl PythPrice {
+        /// Decode a fully verified `PriceUpdateV2` account
+        pub fn try_from_slice(data: &[u8]) -> SquadsResult<Self> {
+            if !has_discriminator(data, "PriceUpdateV2") {
+                return Err(SquadsError::InvalidAccountData(
+                    "not a Pyth price update account".to_string(),
+                ));
+            }
+            let update = PriceUpdateV2::deserialize(&mut &data[8..])
+                .map_err(|_| SquadsError::DeserializationError)?;
+            if !matches!(update.verification_level, VerificationLevel::Full) {
+                return Err(SquadsError::InvalidAccountData(
+                    "Pyth price update is only partially verified".to_string(),
+                ));
+            }
+            let scale = 10f64.powi(update.exponent);
+            Ok(Self {
+                usd: update.price as f64 * scale,
+                confidence: update.conf as f64 * scale,
+                publish_time: update.publish_time,
+            })
+        }
+
+        /// Whether the price was published at most `max_age` seconds before `now`
+        pub fn is_fresh(&self, now: i64, max_age: i64) -> bool {
+            now.saturating_sub(self.publish_time) <= max_age
+        }
+    }
+
+    /// Latest prices from Pyth price update accounts
+    ///
+    /// Each priced mint is mapped to the price update account of its USD feed, e.g. a
+    /// sponsored price feed account maintained by Pyth.
+    pub struct PythPriceSource {
+        rpc: RpcClient,
+        feeds: BTreeMap<Pubkey, (Pubkey, u8)>,
+        max_age: Option<i64>,
+    }
+
+    impl PythPriceSource {
+        /// Create a source reading price accounts through `rpc_url`
+        pub fn new(rpc_url: String) -> Self {
+            Self {
+                rpc: RpcClient::new(rpc_url),
+                feeds: BTreeMap::new(),
+                max_age: None,
+            }
+        }
+
+        /// Price `mint`, which has `decimals` decimals, with the given price update account
+        pub fn with_feed(mut self, mint: Pubkey, price_account: Pubkey, decimals: u8) -> Self {
+            self.feeds.insert(mint, (price_account, decimals));
+            self
+        }
+
+        /// Leave out prices published more than `seconds` ago, by the system clock
+        pub fn with_max_age(mut self, seconds: i64) -> Self {
+            self.max_age = Some(seconds);
+            self
+        }
+
+        async fn fetch(&self, mints: &[Pubkey]) -> SquadsResult<PriceTable> {
+            let feeds: Vec<(Pubkey, Pubkey, u8)> = mints
+                .iter()
+                .filter_map(|mint| {
+                    self.feeds
+                        .get(mint)
+                        .map(|(account, decimals)| (*mint, *account, *decimals))
+                })
+                .collect();
+            let addresses: Vec<Pubkey> = feeds.iter().map(|(_, account, _)| *account).collect();
+            let accounts = self.rpc.get_multiple_accounts(&addresses).await?;
+
+            let mut prices = Vec::new();
+            for ((mint, address, decimals), account) in feeds.into_iter().zip(accounts) {
+                let account =
+                    account.ok_or_else(|| SquadsError::AccountNotFound(address.to_string()))?;
+                check_owner(&account.owner, &PYTH_RECEIVER_PROGRAM_ID)?;
+                prices.push((mint, decimals, PythPrice::try_from_slice(&account.data)?));
+            }
+
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs() as i64;
+            let mut table = PriceTable::new();
+            for (mint, decimals, price) in prices {
+                if self
+                    .max_age
+                    .is_none_or(|max_age| price.is_fresh(now, max_age))
+                {
+                    table.insert(mint, TokenPrice::new(price.usd, decimals));
+                }
+            }
+            Ok(table)
+        }
+    }
+
+    impl PriceSource for PythPriceSource {
+        fn latest_prices<'a>(&'a self, mints: &'a [Pubkey]) -> PriceFuture<'a> {
+            Box::pin(self.fetch(mints))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::accounts::account_discriminator;
+        use borsh::BorshSerialize;
+
+        #[test]
+        fn test_decode_price_update() {
+            let mut data = account_discriminator("PriceUpdateV2").to_vec();
+            Pubkey::new_unique().serialize(&mut data).unwrap();
+            data.push(1); // VerificationLevel::Full
+            [7u8; 32].serialize(&mut data).unwrap();
+            (15_012_345_678i64, 1_000_000u64, -8i32, 1_700_000_000i64)
+                .serialize(&mut data)
+                .unwrap();
+            // prev_publish_time, ema_price, ema_conf, posted_slot
+            (0i64, 0i64, 0u64, 0u64).serialize(&mut data).unwrap();
+
+            let price = PythPrice::try_from_slice(&data).unwrap();
+            assert!((price.usd - 150.12345678).abs() < 1e-9);
+            assert!((price.confidence - 0.01).abs() < 1e-12);
+            assert_eq!(price.publish_time, 1_700_000_000);
+            assert!(price.is_fresh(1_700_000_060, 60));
+            assert!(!price.is_fresh(1_700_000_061, 60));
+
+            data[40] = 0; // VerificationLevel::Partial
+            data.insert(41, 3);
+            assert!(PythPrice::try_from_slice(&data).is_err());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::{spending_limit_use, SpendingLimitUseArgs};
+    use crate::portfolio::{Position, PositionKind};
+    use crate::workspace::VaultBalance;
+    use solana_sdk::message::{Message, VersionedMessage};
+    use solana_sdk::signature::Signature;
+
+    #[tokio::test]
+    async fn test_portfolio_valuation() {
+        let usdc = Pubkey::new_unique();
+        let bonk = Pubkey::new_unique();
+        let token = |mint, amount| Position {
+            address: Pubkey::new_unique(),
+            lamports: 1_000_000_000,
+            kind: PositionKind::Token {
+                mint,
+                amount,
+                token_program: crate::programs::TOKEN_PROGRAM_ID,
+            },
+        };
+        let summary = PortfolioSummary {
+            vault: Pubkey::new_unique(),
+            sol_lamports: 1_000_000_000,
+            positions: vec![token(usdc, 2_500_000), token(bonk, 10)],
+        };
+
+        let fixed = PriceTable::new()
+            .with_price(NATIVE_MINT, TokenPrice::new(100.0, SOL_DECIMALS))
+            .with_price(usdc, TokenPrice::new(1.0, 6));
+        let prices = fixed.latest_prices(&summary.mints()).await.unwrap();
+        assert_eq!(prices.get(&bonk), None);
+
+        let valuation = summary.usd_value(&prices);
+        assert!((valuation.usd - 302.5).abs() < 1e-9);
+        assert_eq!(valuation.unpriced, BTreeSet::from([bonk]));
+        assert!(!valuation.is_complete());
+
+        let workspace = WorkspaceBalance {
+            vaults: vec![
+                VaultBalance {
+                    label: "ops".to_string(),
+                    vault_index: 0,
+                    summary: summary.clone(),
+                },
+                VaultBalance {
+                    label: "grants".to_string(),
+                    vault_index: 0,
+                    summary,
+                },
+            ],
+        };
+        assert_eq!(workspace.mints().len(), 3);
+        assert!((workspace.usd_value(&prices).usd - 605.0).abs() < 1e-9);
+
+        // Sources without history price nothing in the past
+        struct LatestOnly;
+        impl PriceSource for LatestOnly {
+            fn latest_prices<'a>(&'a self, _mints: &'a [Pubkey]) -> PriceFuture<'a> {
+                Box::pin(std::future::ready(Ok(PriceTable::new())))
+            }
+        }
+        let past = LatestOnly.prices_at(&[usdc], 0).await.unwrap();
+        assert_eq!(past, PriceTable::new());
+    }
+
+    #[test]
+    fn test_history_valuation() {
+        let usdc = Pubkey::new_unique();
+        let member = Pubkey::new_unique();
+        let transfer = |mint: Option<Pubkey>, amount| {
+            spending_limit_use(
+                Pubkey::new_unique(),
+                member,
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                mint,
+                mint.map(|_| Pubkey::new_unique()),
+                mint.map(|_| Pubkey::new_unique()),
+                mint.map(|_| crate::programs::TOKEN_PROGRAM_ID),
+                SpendingLimitUseArgs::new(amount, 6, None).unwrap(),
+                None,
+            )
+        };
+        let message = Message::new(
+            &[transfer(Some(usdc), 3_000_000), transfer(None, 500_000_000)],
+            Some(&member),
+        );
+        let account_keys = message.account_keys.clone();
+        let record = TransactionRecord::from_parts(
+            Signature::default(),
+            1,
+            Some(1_700_000_000),
+            &VersionedMessage::Legacy(message),
+            &account_keys,
+            None,
+            None,
+        );
+        assert_eq!(
+            record.spending_limit_transfers(),
+            vec![(usdc, 3_000_000), (NATIVE_MINT, 500_000_000)]
+        );
+
+        let prices = PriceTable::new()
+            .with_price(NATIVE_MINT, TokenPrice::new(200.0, SOL_DECIMALS))
+            .with_price(usdc, TokenPrice::new(1.0, 6));
+        assert!((record.usd_value(&prices).usd - 103.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,460 @@
+//! Read-only access to Squads accounts
+//!
+//! [`SquadsReader`] exposes only fetch, list and PDA derivation methods, so analytics
+//! services and indexers can depend on a narrow interface that is easy to mock. It is
+//! implemented by `SquadsClient` and by the signing-free [`SquadsReadClient`] (both
+//! require the `async` feature).
+
+use std::future::Future;
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    accounts::{ConfigTransaction, Multisig, Proposal, SpendingLimit, VaultTransaction},
+    error::SquadsResult,
+    pda,
+    types::SortOrder,
+};
+
+/// Sort proposals by transaction index, then by status
+///
+/// Listing APIs apply this so results do not depend on the order an RPC provider
+/// returns accounts in.
+pub fn sort_proposals(proposals: &mut [(u64, Proposal)], order: SortOrder) {
+    order.sort_by_key(proposals, |(index, proposal)| {
+        (*index, proposal.status.ordinal())
+    });
+}
+
+/// Read-only operations against the Squads program
+pub trait SquadsReader {
+    /// Program ID the reader targets
+    fn program_id(&self) -> Pubkey;
+
+    /// Fetch and deserialize a Multisig account
+    fn get_multisig(
+        &self,
+        multisig: &Pubkey,
+    ) -> impl Future<Output = SquadsResult<Multisig>> + Send;
+
+    /// Fetch and deserialize a Proposal account
+    fn get_proposal(
+        &self,
+        proposal: &Pubkey,
+    ) -> impl Future<Output = SquadsResult<Proposal>> + Send;
+
+    /// Fetch and deserialize a VaultTransaction account
+    fn get_vault_transaction(
+        &self,
+        transaction: &Pubkey,
+    ) -> impl Future<Output = SquadsResult<VaultTransaction>> + Send;
+
+    /// Fetch and deserialize a ConfigTransaction account
+    fn get_config_transaction(
+        &self,
+        transaction: &Pubkey,
+    ) -> impl Future<Output = SquadsResult<ConfigTransaction>> + Send;
+
+    /// Fetch and deserialize a SpendingLimit account
+    fn get_spending_limit(
+        &self,
+        spending_limit: &Pubkey,
+    ) -> impl Future<Output = SquadsResult<SpendingLimit>> + Send;
+
+    /// List the proposals that exist for transaction indexes in `start_index..=end_index`
+    ///
+    /// Indexes without a proposal account are skipped. Results are in ascending index
+    /// order.
+    fn list_proposals(
+        &self,
+        multisig: &Pubkey,
+        start_index: u64,
+        end_index: u64,
+    ) -> impl Future<Output = SquadsResult<Vec<(u64, Proposal)>>> + Send;
+
+    /// List proposals like [`list_proposals`](Self::list_proposals), sorted with
+    /// [`sort_proposals`] in the given order
+    fn list_proposals_ordered(
+        &self,
+        multisig: &Pubkey,
+        start_index: u64,
+        end_index: u64,
+        order: SortOrder,
+    ) -> impl Future<Output = SquadsResult<Vec<(u64, Proposal)>>> + Send
+    where
+        Self: Sync,
+    {
+        async move {
+            let mut proposals = self
+                .list_proposals(multisig, start_index, end_index)
+                .await?;
+            sort_proposals(&mut proposals, order);
+            Ok(proposals)
+        }
+    }
+
+    /// Fetch the memo the transaction (or batch) at `transaction_index` was created with
+    ///
+    /// Memos are not stored on-chain, so this reads the create transaction from the
+    /// account's history. Readers without access to transaction history return None,
+    /// which is the default.
+    fn get_creation_memo(
+        &self,
+        multisig: &Pubkey,
+        transaction_index: u64,
+    ) -> impl Future<Output = SquadsResult<Option<String>>> + Send {
+        let _ = (multisig, transaction_index);
+        async { Ok(None) }
+    }
+
+    /// Get the vault PDA for a multisig
+    fn get_vault_pda(&self, multisig: &Pubkey, vault_index: u8) -> (Pubkey, u8) {
+        pda::get_vault_pda(multisig, vault_index, Some(&self.program_id()))
+    }
+
+    /// Get the proposal PDA for a transaction
+    fn get_proposal_pda(&self, multisig: &Pubkey, transaction_index: u64) -> (Pubkey, u8) {
+        pda::get_proposal_pda(multisig, transaction_index, Some(&self.program_id()))
+    }
+
+    /// Get the transaction PDA
+    fn get_transaction_pda(&self, multisig: &Pubkey, transaction_index: u64) -> (Pubkey, u8) {
+        pda::get_transaction_pda(multisig, transaction_index, Some(&self.program_id()))
+    }
+}
+
+#[cfg(feature = "async")]
+pub use read_client::SquadsReadClient;
+
+#[cfg(feature = "async")]
+pub(crate) use read_client::{
+    fetch_account, fetch_account_at_slot, fetch_creation_memo, fetch_proposals, index_chunks,
+};
+
+#[cfg(feature = "async")]
+mod read_client {
+    use std::ops::RangeInclusive;
+
+    use solana_account_decoder_client_types::UiAccountEncoding;
+    use solana_client::{
+        nonblocking::rpc_client::RpcClient,
+        rpc_client::GetConfirmedSignaturesForAddress2Config,
+        rpc_config::{RpcAccountInfoConfig, RpcTransactionConfig},
+    };
+    use solana_commitment_config::CommitmentConfig;
+    use solana_sdk::signature::Signature;
+    use solana_transaction_status_client_types::UiTransactionEncoding;
+
+    use super::*;
+    use crate::accounts::check_owner;
+    use crate::error::SquadsError;
+    use crate::history::TransactionRecord;
+    use crate::indexer::MAX_PAGE_SIZE;
+
+    /// Maximum number of accounts requested per `getMultipleAccounts` call
+    pub(crate) const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+    /// Split `range` into consecutive ranges of at most `size` indexes
+    ///
+    /// Callers pass arbitrary ranges, so they are never collected up front.
+    pub(crate) fn index_chunks(
+        range: RangeInclusive<u64>,
+        size: usize,
+    ) -> impl Iterator<Item = RangeInclusive<u64>> {
+        let (start, end) = range.into_inner();
+        let step = (size.max(1) - 1) as u64;
+        let mut next = (start <= end).then_some(start);
+        std::iter::from_fn(move || {
+            let chunk_start = next?;
+            let chunk_end = chunk_start.saturating_add(step).min(end);
+            next = chunk_end.checked_add(1).filter(|index| *index <= end);
+            Some(chunk_start..=chunk_end)
+        })
+    }
+
+    /// Fetch an account owned by `program_id` and parse it with the given deserializer
+    pub(crate) async fn fetch_account<T>(
+        rpc: &RpcClient,
+        program_id: &Pubkey,
+        address: &Pubkey,
+        parse: fn(&[u8]) -> Result<T, std::io::Error>,
+    ) -> SquadsResult<T> {
+        let account = rpc
+            .get_account(address)
+            .await
+            .map_err(SquadsError::ClientError)?;
+
+        check_owner(&account.owner, program_id)?;
+        parse(&account.data).map_err(SquadsError::from_decode_error)
+    }
+
+    /// Fetch an account like [`fetch_account`] from a node that has processed
+    /// `min_context_slot`
+    ///
+    /// A node behind that slot answers with an RPC error rather than stale data.
+    pub(crate) async fn fetch_account_at_slot<T>(
+        rpc: &RpcClient,
+        program_id: &Pubkey,
+        address: &Pubkey,
+        parse: fn(&[u8]) -> Result<T, std::io::Error>,
+        min_context_slot: u64,
+    ) -> SquadsResult<T> {
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            min_context_slot: Some(min_context_slot),
+            ..RpcAccountInfoConfig::default()
+        };
+        let account = rpc
+            .get_account_with_config(address, config)
+            .await?
+            .value
+            .ok_or_else(|| SquadsError::AccountNotFound(address.to_string()))?;
+
+        check_owner(&account.owner, program_id)?;
+        parse(&account.data).map_err(SquadsError::from_decode_error)
+    }
+
+    /// Fetch the proposals for a range of transaction indexes
+    pub(crate) async fn fetch_proposals(
+        rpc: &RpcClient,
+        program_id: &Pubkey,
+        multisig: &Pubkey,
+        start_index: u64,
+        end_index: u64,
+    ) -> SquadsResult<Vec<(u64, Proposal)>> {
+        let mut proposals = Vec::new();
+
+        for chunk in index_chunks(start_index..=end_index, MAX_MULTIPLE_ACCOUNTS) {
+            let chunk: Vec<u64> = chunk.collect();
+            let addresses: Vec<Pubkey> = chunk
+                .iter()
+                .map(|index| pda::get_proposal_pda(multisig, *index, Some(program_id)).0)
+                .collect();
+            let accounts = rpc.get_multiple_accounts(&addresses).await?;
+
+            for (index, account) in chunk.iter().zip(accounts) {
+                if let Some(account) = account {
+                    check_owner(&account.owner, program_id)?;
+                    let proposal = Proposal::try_from_slice(&account.data)
+                        .map_err(SquadsError::from_decode_error)?;
+                    proposals.push((*index, proposal));
+                }
+            }
+        }
+
+        sort_proposals(&mut proposals, SortOrder::Ascending);
+        Ok(proposals)
+    }
+
+    /// Fetch the memo a transaction or batch account was created with
+    ///
+    /// Pages through the account's whole signature history, then fetches its successful
+    /// transactions oldest first until one contains the instruction creating the
+    /// account. Transaction addresses are predictable, so transactions touching the
+    /// address before it was created are skipped rather than taken for the creation.
+    /// Closed accounts keep their history, so their memos can still be read.
+    pub(crate) async fn fetch_creation_memo(
+        rpc: &RpcClient,
+        program_id: &Pubkey,
+        transaction: &Pubkey,
+    ) -> SquadsResult<Option<String>> {
+        let mut signatures = Vec::new();
+        let mut before = None;
+        loop {
+            let config = GetConfirmedSignaturesForAddress2Config {
+                before,
+                until: None,
+                limit: Some(MAX_PAGE_SIZE),
+                commitment: Some(CommitmentConfig::confirmed()),
+            };
+            let statuses = rpc
+                .get_signatures_for_address_with_config(transaction, config)
+                .await?;
+            for status in &statuses {
+                let signature: Signature = status.signature.parse().map_err(|_| {
+                    SquadsError::EncodingError(format!("invalid signature {}", status.signature))
+                })?;
+                before = Some(signature);
+                if status.err.is_none() {
+                    signatures.push(signature);
+                }
+            }
+            if statuses.len() < MAX_PAGE_SIZE {
+                break;
+            }
+        }
+
+        // Signatures come newest first
+        for signature in signatures.into_iter().rev() {
+            let tx_config = RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: Some(0),
+            };
+            let confirmed = rpc
+                .get_transaction_with_config(&signature, tx_config)
+                .await?;
+            let record =
+                TransactionRecord::from_confirmed(signature, &confirmed, Some(program_id))?;
+            if let Some(create) = record
+                .instructions
+                .iter()
+                .find(|instruction| instruction.created_transaction() == Some(transaction))
+            {
+                return Ok(create.memo().map(str::to_string));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Read-only Squads client that never handles keypairs
+    pub struct SquadsReadClient {
+        /// RPC client for communicating with Solana
+        pub rpc: RpcClient,
+        /// Program ID to use (defaults to canonical Squads program ID)
+        pub program_id: Pubkey,
+    }
+
+    impl SquadsReadClient {
+        /// Create a new SquadsReadClient with the default program ID
+        pub fn new(rpc_url: String) -> Self {
+            Self {
+                rpc: RpcClient::new(rpc_url),
+                program_id: crate::program_id(),
+            }
+        }
+
+        /// Create a new SquadsReadClient with a custom program ID
+        pub fn new_with_program_id(rpc_url: String, program_id: Pubkey) -> Self {
+            Self {
+                rpc: RpcClient::new(rpc_url),
+                program_id,
+            }
+        }
+    }
+
+    impl SquadsReader for SquadsReadClient {
+        fn program_id(&self) -> Pubkey {
+            self.program_id
+        }
+
+        async fn get_multisig(&self, multisig: &Pubkey) -> SquadsResult<Multisig> {
+            fetch_account(
+                &self.rpc,
+                &self.program_id,
+                multisig,
+                Multisig::try_from_slice,
+            )
+            .await
+        }
+
+        async fn get_proposal(&self, proposal: &Pubkey) -> SquadsResult<Proposal> {
+            fetch_account(
+                &self.rpc,
+                &self.program_id,
+                proposal,
+                Proposal::try_from_slice,
+            )
+            .await
+        }
+
+        async fn get_vault_transaction(
+            &self,
+            transaction: &Pubkey,
+        ) -> SquadsResult<VaultTransaction> {
+            fetch_account(
+                &self.rpc,
+                &self.program_id,
+                transaction,
+                VaultTransaction::try_from_slice,
+            )
+            .await
+        }
+
+        async fn get_config_transaction(
+            &self,
+            transaction: &Pubkey,
+        ) -> SquadsResult<ConfigTransaction> {
+            fetch_account(
+                &self.rpc,
+                &self.program_id,
+                transaction,
+                ConfigTransaction::try_from_slice,
+            )
+            .await
+        }
+
+        async fn get_spending_limit(&self, spending_limit: &Pubkey) -> SquadsResult<SpendingLimit> {
+            fetch_account(
+                &self.rpc,
+                &self.program_id,
+                spending_limit,
+                SpendingLimit::try_from_slice,
+            )
+            .await
+        }
+
+        async fn list_proposals(
+            &self,
+            multisig: &Pubkey,
+            start_index: u64,
+            end_index: u64,
+        ) -> SquadsResult<Vec<(u64, Proposal)>> {
+            fetch_proposals(
+                &self.rpc,
+                &self.program_id,
+                multisig,
+                start_index,
+                end_index,
+            )
+            .await
+        }
+
+        async fn get_creation_memo(
+            &self,
+            multisig: &Pubkey,
+            transaction_index: u64,
+        ) -> SquadsResult<Option<String>> {
+            let transaction = self.get_transaction_pda(multisig, transaction_index).0;
+            fetch_creation_memo(&self.rpc, &self.program_id, &transaction).await
+        }
+    }
+}
+
+#[cfg(all(test, feature = "async"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_client_derivations() {
+        let program_id = Pubkey::new_unique();
+        let client = SquadsReadClient::new_with_program_id(
+            "https://api.mainnet-beta.solana.com".to_string(),
+            program_id,
+        );
+        let multisig = Pubkey::new_unique();
+
+        assert_eq!(
+            client.get_proposal_pda(&multisig, 3),
+            pda::get_proposal_pda(&multisig, 3, Some(&program_id))
+        );
+        assert_eq!(
+            client.get_vault_pda(&multisig, 0),
+            pda::get_vault_pda(&multisig, 0, Some(&program_id))
+        );
+    }
+
+    #[test]
+    fn test_index_chunks() {
+        let chunks: Vec<_> = index_chunks(1..=250, 100).collect();
+        assert_eq!(chunks, vec![1..=100, 101..=200, 201..=250]);
+        let (start, end) = (5, 4);
+        assert_eq!(index_chunks(start..=end, 100).count(), 0);
+
+        let mut huge = index_chunks(0..=u64::MAX, 100);
+        assert_eq!(huge.next(), Some(0..=99));
+        let tail: Vec<_> = index_chunks(u64::MAX - 1..=u64::MAX, 100).collect();
+        assert_eq!(tail, vec![u64::MAX - 1..=u64::MAX]);
+    }
+}
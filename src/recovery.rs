@@ -0,0 +1,248 @@
+//! Key-loss recovery planning
+//!
+//! When members lose their keys, the reachable members must pass config transactions
+//! that add replacements, remove the lost keys and restore the threshold.
+//! [`plan_recovery`] builds the smallest set of config transactions for that and checks
+//! every step against the voters that are reachable when the step is voted on, so a
+//! recovery never stalls halfway with a threshold nobody can meet.
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::accounts::Multisig;
+use crate::error::{SquadsError, SquadsResult};
+use crate::sizing::{self, ConfigExecutionPlan};
+use crate::types::{ConfigAction, Member};
+
+/// One config transaction of a recovery
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecoveryStep {
+    /// Config actions of the transaction
+    pub actions: Vec<ConfigAction>,
+    /// Approvals needed to pass the transaction (the threshold when it is voted on)
+    pub approvals_required: u16,
+    /// Voters whose keys are still available when the transaction is voted on
+    pub reachable_voters: usize,
+    /// Whether the execute transaction needs an address lookup table
+    pub requires_lookup_table: bool,
+}
+
+/// Config transactions that recover a multisig from lost member keys
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecoveryPlan {
+    /// Config transactions to create, approve and execute in order
+    pub steps: Vec<RecoveryStep>,
+    /// The multisig configuration after all steps
+    pub resulting: Multisig,
+}
+
+/// Plan the config transactions that replace lost member keys
+///
+/// Replacements are added before lost members are removed, so the voter count never
+/// drops below its final value between steps. If `original_threshold` is given and
+/// differs from the current threshold (for example because it was lowered while keys
+/// were unavailable), the plan also restores it. Lowering happens first and raising
+/// last, so every intermediate configuration satisfies the program's invariants.
+///
+/// # Arguments
+/// * `multisig_address` - Multisig account
+/// * `multisig` - Current multisig state
+/// * `lost` - Members whose keys are lost
+/// * `replacements` - Members to add in their place
+/// * `original_threshold` - Threshold to end with (defaults to the current threshold)
+/// * `initiator` - Reachable member creating and executing the transactions
+/// * `program_id` - Optional custom program ID
+pub fn plan_recovery(
+    multisig_address: &Pubkey,
+    multisig: &Multisig,
+    lost: &[Pubkey],
+    replacements: &[Member],
+    original_threshold: Option<u16>,
+    initiator: &Pubkey,
+    program_id: Option<&Pubkey>,
+) -> SquadsResult<RecoveryPlan> {
+    let infeasible = |reason: String| Err(SquadsError::RecoveryInfeasible(reason));
+
+    if let Some(key) = lost.iter().find(|key| !multisig.is_member(key)) {
+        return infeasible(format!("{} is not a member", key));
+    }
+    if let Some(member) = replacements.iter().find(|m| multisig.is_member(&m.key)) {
+        return infeasible(format!("{} is already a member", member.key));
+    }
+    if lost.contains(initiator) || !multisig.is_member(initiator) {
+        return infeasible(format!("{} is not a reachable member", initiator));
+    }
+
+    let target_threshold = original_threshold.unwrap_or(multisig.threshold);
+    let mut actions = Vec::new();
+    if target_threshold < multisig.threshold {
+        actions.push(ConfigAction::ChangeThreshold {
+            new_threshold: target_threshold,
+        });
+    }
+    actions.extend(replacements.iter().map(|member| ConfigAction::AddMember {
+        new_member: member.clone(),
+    }));
+    actions.extend(
+        lost.iter()
+            .map(|key| ConfigAction::RemoveMember { old_member: *key }),
+    );
+    if target_threshold > multisig.threshold {
+        actions.push(ConfigAction::ChangeThreshold {
+            new_threshold: target_threshold,
+        });
+    }
+    if actions.is_empty() {
+        return infeasible("nothing to recover".to_string());
+    }
+
+    let (chunks, requires_lookup_table) =
+        match sizing::plan_config_transaction(multisig_address, initiator, &actions, program_id)? {
+            ConfigExecutionPlan::Single => (vec![actions], false),
+            ConfigExecutionPlan::LookupTable { .. } => (vec![actions], true),
+            ConfigExecutionPlan::Split(chunks) => (chunks, false),
+        };
+
+    let mut state = multisig.clone();
+    let mut steps = Vec::with_capacity(chunks.len());
+    for (i, chunk) in chunks.into_iter().enumerate() {
+        let reachable_voters = state
+            .members
+            .iter()
+            .filter(|m| m.permissions.has_vote() && !lost.contains(&m.key))
+            .count();
+        if reachable_voters < usize::from(state.threshold) {
+            return infeasible(format!(
+                "step {} needs {} approvals but only {} voters are reachable",
+                i + 1,
+                state.threshold,
+                reachable_voters
+            ));
+        }
+
+        let approvals_required = state.threshold;
+        for action in &chunk {
+            state.apply_config_action(action);
+        }
+        state.check_invariants().map_err(|err| {
+            SquadsError::RecoveryInfeasible(format!(
+                "step {} leaves an invalid config: {}",
+                i + 1,
+                err
+            ))
+        })?;
+
+        steps.push(RecoveryStep {
+            actions: chunk,
+            approvals_required,
+            reachable_voters,
+            requires_lookup_table,
+        });
+    }
+
+    Ok(RecoveryPlan {
+        steps,
+        resulting: state,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn multisig(members: &[Pubkey], threshold: u16) -> Multisig {
+        Multisig {
+            create_key: Pubkey::new_unique(),
+            config_authority: Pubkey::default(),
+            threshold,
+            time_lock: 0,
+            transaction_index: 7,
+            stale_transaction_index: 0,
+            rent_collector: None,
+            bump: 255,
+            members: members.iter().map(|key| Member::new(*key)).collect(),
+            unknown_tail: vec![],
+        }
+    }
+
+    #[test]
+    fn test_plan_recovery_replaces_lost_member() {
+        let keys: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let ms = multisig(&keys, 3);
+        let replacement = Member::new(Pubkey::new_unique());
+
+        let plan = plan_recovery(
+            &Pubkey::new_unique(),
+            &ms,
+            &keys[..1],
+            std::slice::from_ref(&replacement),
+            None,
+            &keys[1],
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(plan.steps.len(), 1);
+        assert_eq!(plan.steps[0].approvals_required, 3);
+        assert_eq!(plan.steps[0].reachable_voters, 4);
+        assert!(plan.resulting.is_member(&replacement.key));
+        assert!(!plan.resulting.is_member(&keys[0]));
+        assert_eq!(plan.resulting.threshold, 3);
+        assert_eq!(plan.resulting.stale_transaction_index, 7);
+    }
+
+    #[test]
+    fn test_plan_recovery_restores_threshold() {
+        let keys: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        // Threshold was lowered to 2 while a key was unavailable
+        let ms = multisig(&keys, 2);
+        let replacement = Member::new(Pubkey::new_unique());
+
+        let plan = plan_recovery(
+            &Pubkey::new_unique(),
+            &ms,
+            &keys[3..],
+            std::slice::from_ref(&replacement),
+            Some(3),
+            &keys[0],
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            plan.steps[0].actions.last(),
+            Some(&ConfigAction::ChangeThreshold { new_threshold: 3 })
+        );
+        assert_eq!(plan.resulting.threshold, 3);
+    }
+
+    #[test]
+    fn test_plan_recovery_infeasible() {
+        let keys: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let ms = multisig(&keys, 3);
+
+        // Two lost keys leave only two voters for a threshold of three
+        let err = plan_recovery(
+            &Pubkey::new_unique(),
+            &ms,
+            &keys[..2],
+            &[],
+            None,
+            &keys[2],
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(err, SquadsError::RecoveryInfeasible(_)));
+
+        // Restoring a threshold above the remaining voter count is rejected
+        let err = plan_recovery(
+            &Pubkey::new_unique(),
+            &ms,
+            &keys[..1],
+            &[],
+            Some(4),
+            &keys[2],
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(err, SquadsError::RecoveryInfeasible(_)));
+    }
+}
@@ -0,0 +1,211 @@
+//! Reminder schedules for pending proposals
+//!
+//! The Squads program has no voting deadline, so coordination bots nudge members
+//! themselves. [`compute`] turns the pending proposals of a multisig into a schedule of
+//! [`Reminder`]s: members who have not voted on an active proposal, and executors of an
+//! approved proposal whose time lock has elapsed, are reminded at fixed offsets from
+//! the time the proposal reached its current status.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::accounts::{Multisig, Proposal};
+use crate::types::ProposalStatus;
+
+/// Seconds in a day
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// When to remind members about a pending proposal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReminderPolicy {
+    /// Seconds after the proposal reached its status before the first reminder
+    pub first_after: i64,
+    /// Seconds between reminders
+    pub interval: i64,
+    /// Maximum number of reminders per member and proposal
+    pub max_reminders: u32,
+    /// Seconds after activation by which votes are expected; no reminders are
+    /// scheduled after it (None for no deadline)
+    pub voting_deadline: Option<i64>,
+}
+
+impl Default for ReminderPolicy {
+    fn default() -> Self {
+        Self {
+            first_after: SECONDS_PER_DAY,
+            interval: SECONDS_PER_DAY,
+            max_reminders: 3,
+            voting_deadline: None,
+        }
+    }
+}
+
+/// What a member is being reminded to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ReminderKind {
+    /// Vote on an active proposal
+    Vote,
+    /// Execute an approved proposal
+    Execute,
+}
+
+/// A reminder to send to a member
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reminder {
+    /// Unix timestamp at which to send the reminder
+    pub at: i64,
+    /// Transaction index of the proposal
+    pub transaction_index: u64,
+    /// Member to remind
+    pub member: Pubkey,
+    /// What the member is reminded to do
+    pub kind: ReminderKind,
+    /// 1 for the first reminder about this proposal, 2 for the second, ...
+    pub sequence: u32,
+    /// Unix timestamp of the voting deadline, if the policy sets one
+    pub deadline: Option<i64>,
+}
+
+/// Compute the reminder schedule for the pending proposals of a multisig
+///
+/// Reminders are returned sorted by time, then transaction index and member. Stale
+/// proposals and proposals in other statuses are skipped. The schedule includes
+/// reminders in the past; callers send those with `at` in the window since their last
+/// run.
+///
+/// # Arguments
+/// * `multisig` - Multisig the proposals belong to
+/// * `pending` - Proposals with their transaction indices
+/// * `policy` - When to send reminders
+pub fn compute(
+    multisig: &Multisig,
+    pending: &[(u64, Proposal)],
+    policy: &ReminderPolicy,
+) -> Vec<Reminder> {
+    let mut reminders = Vec::new();
+
+    for (index, proposal) in pending {
+        let (kind, since, members, deadline) = match proposal.status {
+            ProposalStatus::Active { timestamp } if *index > multisig.stale_transaction_index => {
+                let deadline = policy.voting_deadline.map(|d| timestamp.saturating_add(d));
+                (
+                    ReminderKind::Vote,
+                    timestamp,
+                    proposal.outstanding_voters(multisig),
+                    deadline,
+                )
+            }
+            ProposalStatus::Approved { timestamp } => {
+                let executors = multisig
+                    .members
+                    .iter()
+                    .filter(|m| m.permissions.has_execute())
+                    .map(|m| m.key)
+                    .collect();
+                (
+                    ReminderKind::Execute,
+                    timestamp.saturating_add(i64::from(multisig.time_lock)),
+                    executors,
+                    None,
+                )
+            }
+            _ => continue,
+        };
+
+        for sequence in 1..=policy.max_reminders {
+            let offset = policy
+                .interval
+                .saturating_mul(i64::from(sequence - 1))
+                .saturating_add(policy.first_after);
+            let at = since.saturating_add(offset);
+            if deadline.is_some_and(|deadline| at > deadline) {
+                break;
+            }
+            reminders.extend(members.iter().map(|member| Reminder {
+                at,
+                transaction_index: *index,
+                member: *member,
+                kind,
+                sequence,
+                deadline,
+            }));
+        }
+    }
+
+    reminders.sort_by_key(|r| (r.at, r.transaction_index, r.member));
+    reminders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Member, Permission, Permissions};
+
+    #[test]
+    fn test_compute_reminders() {
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        let executor = Pubkey::new_unique();
+        let multisig = Multisig {
+            create_key: Pubkey::new_unique(),
+            config_authority: Pubkey::default(),
+            threshold: 2,
+            time_lock: 100,
+            transaction_index: 3,
+            stale_transaction_index: 1,
+            rent_collector: None,
+            bump: 255,
+            members: vec![
+                Member::with_permissions(a, Permissions::from_vec(&[Permission::Vote])),
+                Member::with_permissions(b, Permissions::from_vec(&[Permission::Vote])),
+                Member::with_permissions(executor, Permissions::from_vec(&[Permission::Execute])),
+            ],
+            unknown_tail: vec![],
+        };
+        let proposal = |status, approved| Proposal {
+            multisig: Pubkey::new_unique(),
+            transaction_index: 0,
+            status,
+            bump: 255,
+            approved,
+            rejected: vec![],
+            cancelled: vec![],
+            unknown_tail: vec![],
+        };
+        let pending = vec![
+            (1, proposal(ProposalStatus::Active { timestamp: 0 }, vec![])),
+            (
+                2,
+                proposal(ProposalStatus::Active { timestamp: 1_000 }, vec![a]),
+            ),
+            (
+                3,
+                proposal(ProposalStatus::Approved { timestamp: 500 }, vec![a, b]),
+            ),
+        ];
+        let policy = ReminderPolicy {
+            first_after: 50,
+            interval: 100,
+            max_reminders: 3,
+            voting_deadline: Some(200),
+        };
+
+        let reminders = compute(&multisig, &pending, &policy);
+
+        // The stale proposal is skipped and the deadline cuts off the third vote reminder
+        let votes: Vec<_> = reminders
+            .iter()
+            .filter(|r| r.kind == ReminderKind::Vote)
+            .map(|r| (r.at, r.member, r.sequence))
+            .collect();
+        assert_eq!(votes, vec![(1_050, b, 1), (1_150, b, 2)]);
+
+        let executes: Vec<_> = reminders
+            .iter()
+            .filter(|r| r.kind == ReminderKind::Execute)
+            .map(|r| r.at)
+            .collect();
+        assert_eq!(executes, vec![650, 750, 850]);
+        assert!(reminders.windows(2).all(|w| w[0].at <= w[1].at));
+    }
+}
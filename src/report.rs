@@ -0,0 +1,326 @@
+//! Balance changes of confirmed transactions
+//!
+//! [`ExecutionReport`] turns the status meta of a confirmed transaction into the SOL and
+//! token balance changes it caused, so an executed proposal can be checked against what
+//! actually moved rather than what the transaction message intended.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use solana_sdk::{pubkey::Pubkey, signature::Signature, transaction::VersionedTransaction};
+use solana_transaction_status_client_types::{
+    option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta,
+    UiTransactionStatusMeta, UiTransactionTokenBalance,
+};
+
+use crate::error::{SquadsError, SquadsResult};
+use crate::events::{decode_program_logs, LogEvent};
+use crate::failure::{locate_failure, InstructionFailure};
+
+/// Change of an account's lamport balance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolDelta {
+    /// Account whose balance changed
+    pub account: Pubkey,
+    /// Balance before the transaction
+    pub pre: u64,
+    /// Balance after the transaction
+    pub post: u64,
+}
+
+impl SolDelta {
+    /// Signed change in lamports
+    pub fn change(&self) -> i128 {
+        i128::from(self.post) - i128::from(self.pre)
+    }
+}
+
+/// Change of a token account's balance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenDelta {
+    /// Token account whose balance changed
+    pub account: Pubkey,
+    /// Token mint
+    pub mint: Pubkey,
+    /// Owner of the token account, when reported by the RPC node
+    pub owner: Option<Pubkey>,
+    /// Mint decimals
+    pub decimals: u8,
+    /// Raw amount before the transaction (0 if the account was created)
+    pub pre: u64,
+    /// Raw amount after the transaction (0 if the account was closed)
+    pub post: u64,
+}
+
+impl TokenDelta {
+    /// Signed change in raw token units
+    pub fn change(&self) -> i128 {
+        i128::from(self.post) - i128::from(self.pre)
+    }
+}
+
+/// What a confirmed transaction moved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionReport {
+    /// Transaction signature
+    pub signature: Signature,
+    /// Whether the transaction succeeded
+    pub success: bool,
+    /// Fee paid in lamports
+    pub fee: u64,
+    /// Accounts whose lamport balance changed, in account key order
+    pub sol_deltas: Vec<SolDelta>,
+    /// Token accounts whose balance changed, in account key order
+    pub token_deltas: Vec<TokenDelta>,
+    /// Program log messages
+    pub logs: Vec<String>,
+}
+
+impl ExecutionReport {
+    /// Build a report from a transaction fetched with a binary encoding
+    ///
+    /// Account keys loaded from lookup tables are resolved from the meta's loaded
+    /// addresses.
+    pub fn from_confirmed(
+        signature: Signature,
+        confirmed: &EncodedConfirmedTransactionWithStatusMeta,
+    ) -> SquadsResult<Self> {
+        let (_, account_keys) = decode_confirmed(confirmed)?;
+        let meta =
+            confirmed.transaction.meta.as_ref().ok_or_else(|| {
+                SquadsError::AccountNotFound(format!("status meta of {}", signature))
+            })?;
+
+        Self::from_meta(signature, &account_keys, meta)
+    }
+
+    /// Build a report from a status meta and the transaction's full account key list
+    pub fn from_meta(
+        signature: Signature,
+        account_keys: &[Pubkey],
+        meta: &UiTransactionStatusMeta,
+    ) -> SquadsResult<Self> {
+        let key = |index: usize| {
+            account_keys.get(index).copied().ok_or_else(|| {
+                SquadsError::InvalidAccountData(format!("account index {} out of range", index))
+            })
+        };
+
+        let mut sol_deltas = Vec::new();
+        for (index, (pre, post)) in meta
+            .pre_balances
+            .iter()
+            .zip(&meta.post_balances)
+            .enumerate()
+        {
+            if pre != post {
+                sol_deltas.push(SolDelta {
+                    account: key(index)?,
+                    pre: *pre,
+                    post: *post,
+                });
+            }
+        }
+
+        let pre_tokens = token_balances(&meta.pre_token_balances)?;
+        let post_tokens = token_balances(&meta.post_token_balances)?;
+        let mut indexes: Vec<u8> = pre_tokens
+            .keys()
+            .chain(post_tokens.keys())
+            .copied()
+            .collect();
+        indexes.sort_unstable();
+        indexes.dedup();
+
+        let mut token_deltas = Vec::new();
+        for index in indexes {
+            let pre = pre_tokens.get(&index);
+            let post = post_tokens.get(&index);
+            let Some(balance) = post.or(pre) else {
+                continue;
+            };
+            let pre_amount = pre.map_or(0, |b| b.amount);
+            let post_amount = post.map_or(0, |b| b.amount);
+            if pre_amount != post_amount {
+                token_deltas.push(TokenDelta {
+                    account: key(usize::from(index))?,
+                    mint: balance.mint,
+                    owner: balance.owner,
+                    decimals: balance.decimals,
+                    pre: pre_amount,
+                    post: post_amount,
+                });
+            }
+        }
+
+        Ok(Self {
+            signature,
+            success: meta.err.is_none(),
+            fee: meta.fee,
+            sol_deltas,
+            token_deltas,
+            logs: match &meta.log_messages {
+                OptionSerializer::Some(logs) => logs.clone(),
+                _ => Vec::new(),
+            },
+        })
+    }
+
+    /// Lamport change of an account (0 if it did not change)
+    pub fn sol_change(&self, account: &Pubkey) -> i128 {
+        self.sol_deltas
+            .iter()
+            .find(|delta| &delta.account == account)
+            .map_or(0, SolDelta::change)
+    }
+
+    /// Log entries attributed to the Squads program (see [`crate::events`])
+    ///
+    /// # Arguments
+    /// * `program_id` - Optional custom program ID (uses canonical ID if None)
+    pub fn events(&self, program_id: Option<&Pubkey>) -> Vec<LogEvent> {
+        decode_program_logs(&self.logs, program_id)
+    }
+
+    /// Vault transaction instruction that made a failed execution fail (see
+    /// [`crate::failure`])
+    ///
+    /// # Arguments
+    /// * `program_id` - Optional custom program ID (uses canonical ID if None)
+    pub fn failure(&self, program_id: Option<&Pubkey>) -> Option<InstructionFailure> {
+        if self.success {
+            return None;
+        }
+        locate_failure(&self.logs, program_id)
+    }
+
+    /// Token deltas of accounts owned by `owner`
+    pub fn token_changes_of<'a>(
+        &'a self,
+        owner: &'a Pubkey,
+    ) -> impl Iterator<Item = &'a TokenDelta> {
+        self.token_deltas
+            .iter()
+            .filter(move |delta| delta.owner.as_ref() == Some(owner))
+    }
+}
+
+/// Decode a transaction fetched with a binary encoding and resolve its full account key
+/// list (static keys followed by writable and readonly lookup table keys)
+pub(crate) fn decode_confirmed(
+    confirmed: &EncodedConfirmedTransactionWithStatusMeta,
+) -> SquadsResult<(VersionedTransaction, Vec<Pubkey>)> {
+    let transaction = confirmed.transaction.transaction.decode().ok_or_else(|| {
+        SquadsError::EncodingError("transaction must use a binary encoding".to_string())
+    })?;
+
+    let mut account_keys = transaction.message.static_account_keys().to_vec();
+    if let Some(meta) = &confirmed.transaction.meta {
+        if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+            for key in loaded.writable.iter().chain(&loaded.readonly) {
+                account_keys.push(parse_pubkey(key)?);
+            }
+        }
+    }
+    Ok((transaction, account_keys))
+}
+
+struct TokenBalance {
+    mint: Pubkey,
+    owner: Option<Pubkey>,
+    decimals: u8,
+    amount: u64,
+}
+
+fn token_balances(
+    balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>,
+) -> SquadsResult<BTreeMap<u8, TokenBalance>> {
+    let mut parsed = BTreeMap::new();
+    let OptionSerializer::Some(balances) = balances else {
+        return Ok(parsed);
+    };
+    for balance in balances {
+        let owner = match &balance.owner {
+            OptionSerializer::Some(owner) => Some(parse_pubkey(owner)?),
+            _ => None,
+        };
+        let amount = balance.ui_token_amount.amount.parse().map_err(|_| {
+            SquadsError::InvalidAccountData(format!(
+                "invalid token amount {}",
+                balance.ui_token_amount.amount
+            ))
+        })?;
+        parsed.insert(
+            balance.account_index,
+            TokenBalance {
+                mint: parse_pubkey(&balance.mint)?,
+                owner,
+                decimals: balance.ui_token_amount.decimals,
+                amount,
+            },
+        );
+    }
+    Ok(parsed)
+}
+
+fn parse_pubkey(key: &str) -> SquadsResult<Pubkey> {
+    Pubkey::from_str(key)
+        .map_err(|_| SquadsError::InvalidAccountData(format!("invalid pubkey {}", key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_from_meta() {
+        let payer = Pubkey::new_unique();
+        let vault = Pubkey::new_unique();
+        let source = Pubkey::new_unique();
+        let destination = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+
+        let meta: UiTransactionStatusMeta = serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [1_000_000, 50_000, 2_039_280, 0, 1],
+            "postBalances": [995_000, 50_000, 2_039_280, 2_039_280, 1],
+            "preTokenBalances": [{
+                "accountIndex": 2,
+                "mint": mint.to_string(),
+                "owner": vault.to_string(),
+                "uiTokenAmount": { "uiAmount": 1.0, "decimals": 6, "amount": "1000000", "uiAmountString": "1" }
+            }],
+            "postTokenBalances": [{
+                "accountIndex": 2,
+                "mint": mint.to_string(),
+                "owner": vault.to_string(),
+                "uiTokenAmount": { "uiAmount": 0.75, "decimals": 6, "amount": "750000", "uiAmountString": "0.75" }
+            }, {
+                "accountIndex": 3,
+                "mint": mint.to_string(),
+                "uiTokenAmount": { "uiAmount": 0.25, "decimals": 6, "amount": "250000", "uiAmountString": "0.25" }
+            }],
+            "logMessages": ["Program log: Instruction: VaultTransactionExecute"]
+        }))
+        .unwrap();
+
+        let keys = [payer, vault, source, destination, program];
+        let report = ExecutionReport::from_meta(Signature::default(), &keys, &meta).unwrap();
+
+        assert!(report.success);
+        assert_eq!(report.sol_change(&payer), -5000);
+        assert_eq!(report.sol_deltas.len(), 2);
+        assert_eq!(report.sol_deltas[1].account, destination);
+
+        assert_eq!(report.token_deltas.len(), 2);
+        assert_eq!(report.token_deltas[0].change(), -250_000);
+        assert_eq!(report.token_deltas[1].pre, 0);
+        assert_eq!(report.token_changes_of(&vault).count(), 1);
+        assert_eq!(report.logs.len(), 1);
+
+        assert!(ExecutionReport::from_meta(Signature::default(), &keys[..2], &meta).is_err());
+    }
+}
@@ -0,0 +1,302 @@
+//! Runtime-independent timers
+//!
+//! The crate's own waiting (lookup table activation, airdrop polling, [`retry`]) goes
+//! through [`sleep`] and [`timeout`] instead of calling tokio directly. With the `tokio`
+//! feature, which is on by default, they use tokio's timer; without it they use
+//! [`ThreadTimer`], which needs no reactor and works under async-std, smol or any other
+//! executor.
+//!
+//! RPC calls still go through solana-client, whose HTTP transport expects to run inside
+//! a tokio context. async-std and smol users should wrap those futures with a
+//! compatibility layer such as `async-compat`.
+//!
+//! This module is only available with the `async` feature enabled.
+
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+use crate::error::{SquadsError, SquadsResult};
+
+/// A source of sleep futures
+pub trait Timer {
+    /// Complete after `duration` has elapsed
+    fn sleep(duration: Duration) -> impl Future<Output = ()> + Send;
+}
+
+/// Timer that parks a helper thread per sleep and wakes the task when it finishes
+///
+/// Works with any executor. Intended for the occasional polling delays of this crate,
+/// not for high-frequency timers.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThreadTimer;
+
+impl Timer for ThreadTimer {
+    fn sleep(duration: Duration) -> impl Future<Output = ()> + Send {
+        ThreadSleep::new(duration)
+    }
+}
+
+/// Timer backed by `tokio::time`
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioTimer;
+
+#[cfg(feature = "tokio")]
+impl Timer for TokioTimer {
+    fn sleep(duration: Duration) -> impl Future<Output = ()> + Send {
+        tokio::time::sleep(duration)
+    }
+}
+
+/// Timer used by the crate: [`TokioTimer`] with the `tokio` feature, else [`ThreadTimer`]
+#[cfg(feature = "tokio")]
+pub type DefaultTimer = TokioTimer;
+
+/// Timer used by the crate: [`TokioTimer`] with the `tokio` feature, else [`ThreadTimer`]
+#[cfg(not(feature = "tokio"))]
+pub type DefaultTimer = ThreadTimer;
+
+/// Sleep for `duration` using the [`DefaultTimer`]
+pub async fn sleep(duration: Duration) {
+    DefaultTimer::sleep(duration).await
+}
+
+/// Run `future` to completion unless `duration` elapses first, returning None on timeout
+pub async fn timeout<F: Future>(duration: Duration, future: F) -> Option<F::Output> {
+    Timeout {
+        future: Box::pin(future),
+        sleep: Box::pin(DefaultTimer::sleep(duration)),
+    }
+    .await
+}
+
+/// Exponential backoff with jitter for [`retry`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts in total, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for each further retry
+    pub base_delay: Duration,
+    /// Upper bound of the delay before jitter
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 8,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(2),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `attempt` (starting at 1)
+    ///
+    /// Half of the backoff is fixed and the other half scaled by `sample`, a number in
+    /// `[0, 1)`, so concurrent clients spread their retries without retrying at once.
+    pub fn delay(&self, attempt: u32, sample: f64) -> Duration {
+        let factor = 1u32
+            .checked_shl(attempt.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        let backoff = self.base_delay.saturating_mul(factor).min(self.max_delay);
+        let half = backoff / 2;
+        half + half.mul_f64(sample.clamp(0.0, 1.0))
+    }
+}
+
+/// A number in `[0, 1)` from the randomly keyed std hasher, good enough for jitter
+pub(crate) fn jitter_sample() -> f64 {
+    // Every RandomState gets fresh keys, so hashing nothing yields a new number each time
+    let bits = RandomState::new().build_hasher().finish();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Run `operation` until it succeeds, `should_retry` rejects its error or the attempts
+/// of `policy` are used up, sleeping with jittered backoff between attempts
+///
+/// Returns the last error if every attempt failed.
+pub async fn retry<T, F, Fut>(
+    policy: &RetryPolicy,
+    should_retry: impl Fn(&SquadsError) -> bool,
+    mut operation: F,
+) -> SquadsResult<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = SquadsResult<T>>,
+{
+    let mut attempt = 1;
+    loop {
+        match operation().await {
+            Err(err) if attempt < policy.max_attempts && should_retry(&err) => {
+                sleep(policy.delay(attempt, jitter_sample())).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+struct Timeout<F: Future, S> {
+    future: Pin<Box<F>>,
+    sleep: Pin<Box<S>>,
+}
+
+impl<F: Future, S: Future<Output = ()>> Future for Timeout<F, S> {
+    type Output = Option<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Poll::Ready(output) = self.future.as_mut().poll(cx) {
+            return Poll::Ready(Some(output));
+        }
+        self.sleep.as_mut().poll(cx).map(|()| None)
+    }
+}
+
+#[derive(Default)]
+struct SleepState {
+    done: bool,
+    waker: Option<Waker>,
+}
+
+struct ThreadSleep {
+    deadline: Instant,
+    state: Option<Arc<Mutex<SleepState>>>,
+}
+
+impl ThreadSleep {
+    fn new(duration: Duration) -> Self {
+        Self {
+            deadline: Instant::now() + duration,
+            state: None,
+        }
+    }
+}
+
+impl Future for ThreadSleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if Instant::now() >= self.deadline {
+            return Poll::Ready(());
+        }
+
+        let deadline = self.deadline;
+        let state = self.state.get_or_insert_with(|| {
+            let state = Arc::new(Mutex::new(SleepState::default()));
+            let shared = Arc::clone(&state);
+            std::thread::spawn(move || {
+                std::thread::sleep(deadline.saturating_duration_since(Instant::now()));
+                let mut state = shared.lock().unwrap_or_else(|e| e.into_inner());
+                state.done = true;
+                if let Some(waker) = state.waker.take() {
+                    waker.wake();
+                }
+            });
+            state
+        });
+
+        let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.done {
+            Poll::Ready(())
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal executor without any reactor, standing in for async-std or smol
+    fn block_on<F: Future>(future: F) -> F::Output {
+        struct ThreadWaker(std::thread::Thread);
+
+        impl std::task::Wake for ThreadWaker {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+
+        let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+            std::thread::park();
+        }
+    }
+
+    #[test]
+    fn test_retry_policy_delay() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(300),
+        };
+        assert_eq!(policy.delay(1, 0.0), Duration::from_millis(50));
+        assert_eq!(policy.delay(2, 0.5), Duration::from_millis(150));
+        assert_eq!(policy.delay(3, 1.0), Duration::from_millis(300));
+        assert_eq!(policy.delay(40, 0.0), Duration::from_millis(150));
+        assert!((0..100)
+            .map(|_| jitter_sample())
+            .all(|x| (0.0..1.0).contains(&x)));
+    }
+
+    #[tokio::test]
+    async fn test_retry_until_success() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(10),
+        };
+        let mut calls = 0;
+        let result: SquadsResult<u32> = retry(
+            &policy,
+            |err| matches!(err, SquadsError::AccountNotFound(_)),
+            || {
+                calls += 1;
+                let calls = calls;
+                async move {
+                    if calls < 3 {
+                        Err(SquadsError::AccountNotFound("multisig".to_string()))
+                    } else {
+                        Ok(calls)
+                    }
+                }
+            },
+        )
+        .await;
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[test]
+    fn test_thread_timer_without_reactor() {
+        let start = Instant::now();
+        block_on(ThreadTimer::sleep(Duration::from_millis(20)));
+        assert!(start.elapsed() >= Duration::from_millis(20));
+
+        let slow = ThreadTimer::sleep(Duration::from_secs(5));
+        let result = block_on(Timeout {
+            future: Box::pin(slow),
+            sleep: Box::pin(ThreadTimer::sleep(Duration::from_millis(10))),
+        });
+        assert_eq!(result, None);
+
+        let result = block_on(Timeout {
+            future: Box::pin(async { 7 }),
+            sleep: Box::pin(ThreadTimer::sleep(Duration::from_secs(5))),
+        });
+        assert_eq!(result, Some(7));
+    }
+}
@@ -0,0 +1,176 @@
+//! Recurring proposal scheduling primitives
+//!
+//! This module builds on [`crate::templates`] to describe vault transactions that should be
+//! proposed on a fixed cadence (e.g. monthly payroll). A [`RecurringProposal`] tracks when it
+//! is next due and which transaction index it last proposed; bots call [`RecurringProposal::tick`]
+//! (or `SquadsClient::tick_recurring` with the `async` feature) periodically to drive it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::SquadsResult;
+use crate::message::TransactionMessage;
+use crate::templates::{Template, TemplateParams};
+
+/// Seconds in a day
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// How often a recurring proposal is due
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecurringPeriod {
+    /// A fixed interval in seconds
+    Interval {
+        /// Length of the interval in seconds
+        seconds: i64,
+    },
+    /// Every 24 hours
+    Daily,
+    /// Every 7 days
+    Weekly,
+    /// Every 30 days (matches the Squads program's notion of a month)
+    Monthly,
+}
+
+impl RecurringPeriod {
+    /// Length of the period in seconds
+    pub fn seconds(&self) -> i64 {
+        match self {
+            RecurringPeriod::Interval { seconds } => *seconds,
+            RecurringPeriod::Daily => SECONDS_PER_DAY,
+            RecurringPeriod::Weekly => 7 * SECONDS_PER_DAY,
+            RecurringPeriod::Monthly => 30 * SECONDS_PER_DAY,
+        }
+    }
+}
+
+/// Result of a scheduler tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TickOutcome {
+    /// Nothing was proposed; the proposal is next due at the given unix timestamp
+    NotDue {
+        /// Unix timestamp at which the proposal becomes due
+        next_due: i64,
+    },
+    /// A transaction was proposed at the given index
+    Proposed {
+        /// Index of the newly created transaction
+        transaction_index: u64,
+    },
+}
+
+/// A template that should be proposed on a recurring schedule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecurringProposal {
+    /// Template that is instantiated each period
+    pub template: Template,
+    /// Parameters used to instantiate the template
+    pub params: TemplateParams,
+    /// How often the proposal is due
+    pub period: RecurringPeriod,
+    /// Unix timestamp at which the proposal is next due
+    pub next_due: i64,
+    /// Transaction index of the most recent proposal, if any
+    pub last_proposed_index: Option<u64>,
+}
+
+impl RecurringProposal {
+    /// Create a recurring proposal that first becomes due at `start`
+    pub fn new(
+        template: Template,
+        params: TemplateParams,
+        period: RecurringPeriod,
+        start: i64,
+    ) -> Self {
+        Self {
+            template,
+            params,
+            period,
+            next_due: start,
+            last_proposed_index: None,
+        }
+    }
+
+    /// Check whether the proposal is due at `now`
+    pub fn is_due(&self, now: i64) -> bool {
+        now >= self.next_due
+    }
+
+    /// Instantiate the template with the stored parameters
+    pub fn message(&self) -> SquadsResult<TransactionMessage> {
+        self.template.instantiate(&self.params)
+    }
+
+    /// Record that a proposal was created at `now` with the given transaction index
+    ///
+    /// The next due time is advanced by whole periods until it lies in the future, so a
+    /// bot that was offline for several periods proposes once rather than catching up.
+    pub fn record_proposed(&mut self, now: i64, transaction_index: u64) {
+        let period = self.period.seconds().max(1);
+        if now >= self.next_due {
+            let missed = (now - self.next_due) / period + 1;
+            self.next_due += missed * period;
+        }
+        self.last_proposed_index = Some(transaction_index);
+    }
+
+    /// Drive the schedule at `now`
+    ///
+    /// When due, the template is instantiated and passed to `propose`, which must create
+    /// the vault transaction and proposal and return the new transaction index.
+    pub fn tick<F>(&mut self, now: i64, propose: F) -> SquadsResult<TickOutcome>
+    where
+        F: FnOnce(TransactionMessage) -> SquadsResult<u64>,
+    {
+        if !self.is_due(now) {
+            return Ok(TickOutcome::NotDue {
+                next_due: self.next_due,
+            });
+        }
+
+        let transaction_index = propose(self.message()?)?;
+        self.record_proposed(now, transaction_index);
+        Ok(TickOutcome::Proposed { transaction_index })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    fn payroll(start: i64) -> RecurringProposal {
+        let params = TemplateParams::new()
+            .with_pubkey("destination", Pubkey::new_unique())
+            .with_amount("amount", 500);
+        RecurringProposal::new(
+            Template::sol_transfer(Pubkey::new_unique()),
+            params,
+            RecurringPeriod::Daily,
+            start,
+        )
+    }
+
+    #[test]
+    fn test_tick_proposes_when_due() {
+        let mut recurring = payroll(1_000);
+
+        let outcome = recurring.tick(999, |_| unreachable!()).unwrap();
+        assert_eq!(outcome, TickOutcome::NotDue { next_due: 1_000 });
+
+        let outcome = recurring.tick(1_000, |_| Ok(7)).unwrap();
+        assert_eq!(
+            outcome,
+            TickOutcome::Proposed {
+                transaction_index: 7
+            }
+        );
+        assert_eq!(recurring.last_proposed_index, Some(7));
+        assert_eq!(recurring.next_due, 1_000 + SECONDS_PER_DAY);
+    }
+
+    #[test]
+    fn test_missed_periods_are_skipped() {
+        let mut recurring = payroll(0);
+        recurring.tick(3 * SECONDS_PER_DAY + 10, |_| Ok(1)).unwrap();
+        assert_eq!(recurring.next_due, 4 * SECONDS_PER_DAY);
+    }
+}
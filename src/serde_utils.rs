@@ -0,0 +1,170 @@
+//! Serde helpers for Solana types
+//!
+//! `Pubkey`'s own serde implementation uses a 32-byte array, which is awkward in JSON
+//! request bodies. The deserializers here accept either a base58 string or a byte array,
+//! so they stay compatible with data written by the default implementation. Binary
+//! formats keep the default representation. Use them with
+//! `#[serde(deserialize_with = "...")]`; the `serialize_*` counterparts write base58
+//! strings for human-readable formats. The [`base58`], [`option_base58`] and
+//! [`vec_base58`] modules pair them up for `#[serde(with = "...")]`.
+
+use std::{fmt, str::FromStr};
+
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use solana_program::pubkey::Pubkey;
+
+struct PubkeyVisitor;
+
+impl<'de> Visitor<'de> for PubkeyVisitor {
+    type Value = Pubkey;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a base58 encoded public key or an array of 32 bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Pubkey, E> {
+        Pubkey::from_str(value).map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Pubkey, E> {
+        Pubkey::try_from(value).map_err(|_| E::invalid_length(value.len(), &self))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Pubkey, A::Error> {
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(de::Error::invalid_length(33, &self));
+        }
+        Ok(Pubkey::new_from_array(bytes))
+    }
+}
+
+/// Deserializer wrapper used by the collection helpers
+struct PubkeyDe(Pubkey);
+
+impl<'de> Deserialize<'de> for PubkeyDe {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        pubkey(deserializer).map(PubkeyDe)
+    }
+}
+
+/// Deserialize a `Pubkey` from a base58 string or a byte array
+pub fn pubkey<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(PubkeyVisitor)
+    } else {
+        Pubkey::deserialize(deserializer)
+    }
+}
+
+/// Deserialize an `Option<Pubkey>` (null or a [`pubkey`] value)
+pub fn option_pubkey<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Pubkey>, D::Error> {
+    Ok(Option::<PubkeyDe>::deserialize(deserializer)?.map(|key| key.0))
+}
+
+/// Deserialize a `Vec<Pubkey>` whose elements are [`pubkey`] values
+pub fn vec_pubkey<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Pubkey>, D::Error> {
+    Ok(Vec::<PubkeyDe>::deserialize(deserializer)?
+        .into_iter()
+        .map(|key| key.0)
+        .collect())
+}
+
+/// Serialize a `Pubkey` as a base58 string in human-readable formats
+pub fn serialize_pubkey<S: Serializer>(key: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.collect_str(key)
+    } else {
+        key.serialize(serializer)
+    }
+}
+
+/// Serialize an `Option<Pubkey>` as null or a [`serialize_pubkey`] value
+pub fn serialize_option_pubkey<S: Serializer>(
+    key: &Option<Pubkey>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match key {
+        Some(key) => serializer.serialize_some(&PubkeySer(key)),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Serialize a `Vec<Pubkey>` whose elements are [`serialize_pubkey`] values
+pub fn serialize_vec_pubkey<S: Serializer>(
+    keys: &[Pubkey],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(keys.iter().map(PubkeySer))
+}
+
+/// Serializer wrapper used by the collection helpers
+struct PubkeySer<'a>(&'a Pubkey);
+
+impl Serialize for PubkeySer<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_pubkey(self.0, serializer)
+    }
+}
+
+/// [`serialize_pubkey`] and [`pubkey`], for `#[serde(with = "...")]`
+pub mod base58 {
+    pub use super::pubkey as deserialize;
+    pub use super::serialize_pubkey as serialize;
+}
+
+/// [`serialize_option_pubkey`] and [`option_pubkey`], for `#[serde(with = "...")]`
+pub mod option_base58 {
+    pub use super::option_pubkey as deserialize;
+    pub use super::serialize_option_pubkey as serialize;
+}
+
+/// [`serialize_vec_pubkey`] and [`vec_pubkey`], for `#[serde(with = "...")]`
+pub mod vec_base58 {
+    pub use super::serialize_vec_pubkey as serialize;
+    pub use super::vec_pubkey as deserialize;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Wrapper {
+        #[serde(deserialize_with = "pubkey")]
+        key: Pubkey,
+        #[serde(deserialize_with = "option_pubkey")]
+        maybe: Option<Pubkey>,
+    }
+
+    #[test]
+    fn test_pubkey_from_string_or_bytes() {
+        let key = Pubkey::new_unique();
+
+        let json = format!(r#"{{"key":"{}","maybe":null}}"#, key);
+        let parsed: Wrapper = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.key, key);
+        assert_eq!(parsed.maybe, None);
+
+        let json = format!(
+            r#"{{"key":{},"maybe":"{}"}}"#,
+            serde_json::to_string(&key.to_bytes()).unwrap(),
+            key
+        );
+        let parsed: Wrapper = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.key, key);
+        assert_eq!(parsed.maybe, Some(key));
+
+        assert!(serde_json::from_str::<Wrapper>(r#"{"key":"not-a-key","maybe":null}"#).is_err());
+    }
+}
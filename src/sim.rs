@@ -0,0 +1,872 @@
+//! Local replay of Squads instructions
+//!
+//! [`SimState`] is a small state machine that applies decoded Squads instructions to
+//! multisig and proposal accounts the way the program does. [`replay`] feeds it the
+//! top-level Squads instructions of confirmed transactions and compares the derived
+//! accounts with the ones on chain, which answers "how did we get into this state" one
+//! instruction at a time.
+//!
+//! The simulation covers member management, voting and execution. Spending limits,
+//! vault balances and Squads instructions invoked through CPI are not modelled.
+
+use std::collections::BTreeMap;
+
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::accounts::{ConfigTransaction, Multisig, Proposal};
+use crate::error::{SquadsError, SquadsResult};
+use crate::parser::SquadsInstruction;
+use crate::pda;
+use crate::types::{ConfigAction, ProposalStatus};
+
+/// A field whose derived value differs from the on-chain account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateMismatch {
+    /// Account that differs
+    pub account: Pubkey,
+    /// Name of the differing field
+    pub field: &'static str,
+    /// Value derived by the simulation
+    pub derived: String,
+    /// Value found on chain
+    pub on_chain: String,
+}
+
+/// Multisig and proposal accounts derived from replayed instructions
+#[derive(Debug, Clone)]
+pub struct SimState {
+    program_id: Pubkey,
+    multisigs: BTreeMap<Pubkey, Multisig>,
+    proposals: BTreeMap<Pubkey, Proposal>,
+    config_transactions: BTreeMap<Pubkey, ConfigTransaction>,
+}
+
+impl SimState {
+    /// Create an empty state
+    ///
+    /// # Arguments
+    /// * `program_id` - Optional custom program ID (uses canonical ID if None)
+    pub fn new(program_id: Option<&Pubkey>) -> Self {
+        Self {
+            program_id: program_id.copied().unwrap_or_else(crate::program_id),
+            multisigs: BTreeMap::new(),
+            proposals: BTreeMap::new(),
+            config_transactions: BTreeMap::new(),
+        }
+    }
+
+    /// Seed the state with a multisig snapshot, for replays that do not start at the
+    /// multisig's creation
+    pub fn with_multisig(mut self, address: Pubkey, multisig: Multisig) -> Self {
+        self.multisigs.insert(address, multisig);
+        self
+    }
+
+    /// Program ID the state simulates
+    pub fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    /// Derived state of a multisig
+    pub fn multisig(&self, address: &Pubkey) -> Option<&Multisig> {
+        self.multisigs.get(address)
+    }
+
+    /// Derived state of a proposal
+    pub fn proposal(&self, address: &Pubkey) -> Option<&Proposal> {
+        self.proposals.get(address)
+    }
+
+    /// Addresses of all multisig and proposal accounts in the state
+    pub fn accounts(&self) -> Vec<Pubkey> {
+        self.multisigs
+            .keys()
+            .chain(self.proposals.keys())
+            .copied()
+            .collect()
+    }
+
+    /// Apply one instruction at `timestamp`, checking the program's preconditions
+    ///
+    /// Instructions the simulation does not model leave the state unchanged.
+    pub fn apply(&mut self, instruction: &SquadsInstruction, timestamp: i64) -> SquadsResult<()> {
+        match instruction {
+            SquadsInstruction::MultisigCreateV2 {
+                multisig,
+                create_key,
+                args,
+                ..
+            } => {
+                if self.multisigs.contains_key(multisig) {
+                    return Err(SquadsError::InvalidConfig(format!(
+                        "multisig {} already exists",
+                        multisig
+                    )));
+                }
+                let mut members = args.members.clone();
+                members.sort_by_key(|m| m.key);
+                let created = Multisig {
+                    create_key: *create_key,
+                    config_authority: args.config_authority.unwrap_or_default(),
+                    threshold: args.threshold,
+                    time_lock: args.time_lock,
+                    transaction_index: 0,
+                    stale_transaction_index: 0,
+                    rent_collector: args.rent_collector,
+                    bump: pda::get_multisig_pda(create_key, Some(&self.program_id)).1,
+                    members,
+                    unknown_tail: vec![],
+                };
+                created.check_invariants()?;
+                self.multisigs.insert(*multisig, created);
+            }
+            SquadsInstruction::MultisigCreate {
+                multisig,
+                create_key,
+                creator,
+                args,
+            } => {
+                // Older deployments create the same account, without a rent collector
+                self.apply(
+                    &SquadsInstruction::MultisigCreateV2 {
+                        multisig: *multisig,
+                        create_key: *create_key,
+                        creator: *creator,
+                        args: args.clone().into(),
+                    },
+                    timestamp,
+                )?;
+            }
+            SquadsInstruction::MultisigAddMember {
+                multisig,
+                config_authority,
+                args,
+            } => {
+                let action = ConfigAction::AddMember {
+                    new_member: args.new_member.clone(),
+                };
+                self.apply_controlled(multisig, config_authority, &action)?;
+            }
+            SquadsInstruction::MultisigRemoveMember {
+                multisig,
+                config_authority,
+                args,
+            } => {
+                let action = ConfigAction::RemoveMember {
+                    old_member: args.old_member,
+                };
+                self.apply_controlled(multisig, config_authority, &action)?;
+            }
+            SquadsInstruction::MultisigSetTimeLock {
+                multisig,
+                config_authority,
+                args,
+            } => {
+                let action = ConfigAction::SetTimeLock {
+                    new_time_lock: args.time_lock,
+                };
+                self.apply_controlled(multisig, config_authority, &action)?;
+            }
+            SquadsInstruction::MultisigSetRentCollector {
+                multisig,
+                config_authority,
+                args,
+            } => {
+                let action = ConfigAction::SetRentCollector {
+                    new_rent_collector: args.rent_collector,
+                };
+                self.apply_controlled(multisig, config_authority, &action)?;
+            }
+            SquadsInstruction::MultisigAddSpendingLimit {
+                multisig,
+                config_authority,
+                args,
+                ..
+            } => {
+                let action = ConfigAction::AddSpendingLimit {
+                    create_key: args.create_key,
+                    vault_index: args.vault_index,
+                    mint: args.mint,
+                    amount: args.amount,
+                    period: args.period,
+                    members: args.members.clone(),
+                    destinations: args.destinations.clone(),
+                };
+                self.apply_controlled(multisig, config_authority, &action)?;
+            }
+            SquadsInstruction::MultisigRemoveSpendingLimit {
+                multisig,
+                config_authority,
+                spending_limit,
+                ..
+            } => {
+                let action = ConfigAction::RemoveSpendingLimit {
+                    spending_limit: *spending_limit,
+                };
+                self.apply_controlled(multisig, config_authority, &action)?;
+            }
+            SquadsInstruction::ConfigTransactionCreate {
+                multisig,
+                transaction,
+                creator,
+                args,
+            } => {
+                let index = self.next_transaction_index(multisig, creator)?;
+                let bump = pda::get_transaction_pda(multisig, index, Some(&self.program_id)).1;
+                self.config_transactions.insert(
+                    *transaction,
+                    ConfigTransaction {
+                        multisig: *multisig,
+                        creator: *creator,
+                        index,
+                        bump,
+                        actions: args.actions.clone(),
+                        unknown_tail: vec![],
+                    },
+                );
+            }
+            SquadsInstruction::VaultTransactionCreate {
+                multisig, creator, ..
+            }
+            | SquadsInstruction::VaultTransactionCreateFromBuffer {
+                multisig, creator, ..
+            }
+            | SquadsInstruction::BatchCreate {
+                multisig, creator, ..
+            } => {
+                self.next_transaction_index(multisig, creator)?;
+            }
+            SquadsInstruction::ProposalCreate {
+                multisig,
+                proposal,
+                args,
+                ..
+            } => {
+                let state = self.get_multisig(multisig)?;
+                if args.transaction_index > state.transaction_index
+                    || args.transaction_index <= state.stale_transaction_index
+                {
+                    return Err(SquadsError::InvalidProposalTransition(format!(
+                        "transaction {} cannot be proposed",
+                        args.transaction_index
+                    )));
+                }
+                if self.proposals.contains_key(proposal) {
+                    return Err(SquadsError::InvalidProposalTransition(format!(
+                        "proposal {} already exists",
+                        proposal
+                    )));
+                }
+                let bump =
+                    pda::get_proposal_pda(multisig, args.transaction_index, Some(&self.program_id))
+                        .1;
+                let status = if args.draft {
+                    ProposalStatus::Draft { timestamp }
+                } else {
+                    ProposalStatus::Active { timestamp }
+                };
+                self.proposals.insert(
+                    *proposal,
+                    Proposal {
+                        multisig: *multisig,
+                        transaction_index: args.transaction_index,
+                        status,
+                        bump,
+                        approved: vec![],
+                        rejected: vec![],
+                        cancelled: vec![],
+                        unknown_tail: vec![],
+                    },
+                );
+            }
+            SquadsInstruction::BatchAddTransaction {
+                multisig,
+                proposal,
+                member,
+                ..
+            } => {
+                self.check_member(multisig, member, |m| m.permissions.has_initiate())?;
+                let status = &self.get_proposal(proposal)?.status;
+                if !matches!(status, ProposalStatus::Draft { .. }) {
+                    return Err(SquadsError::InvalidProposalTransition(format!(
+                        "cannot add transactions to a batch whose proposal is {}",
+                        status_name(status)
+                    )));
+                }
+            }
+            SquadsInstruction::TransactionBufferCreate {
+                multisig, creator, ..
+            }
+            | SquadsInstruction::TransactionBufferExtend {
+                multisig, creator, ..
+            } => {
+                self.check_member(multisig, creator, |m| m.permissions.has_initiate())?;
+            }
+            SquadsInstruction::ProposalActivate {
+                multisig, proposal, ..
+            } => {
+                self.transition(multisig, proposal, ProposalStatus::Active { timestamp })?;
+            }
+            SquadsInstruction::ProposalApprove {
+                multisig,
+                member,
+                proposal,
+                ..
+            } => {
+                self.vote(multisig, member, proposal)?;
+                let threshold = usize::from(self.get_multisig(multisig)?.threshold);
+                let p = self.get_proposal_mut(proposal)?;
+                insert_vote(&mut p.approved, member)?;
+                p.rejected.retain(|key| key != member);
+                if p.approved.len() >= threshold {
+                    self.transition(multisig, proposal, ProposalStatus::Approved { timestamp })?;
+                }
+            }
+            SquadsInstruction::ProposalReject {
+                multisig,
+                member,
+                proposal,
+                ..
+            } => {
+                self.vote(multisig, member, proposal)?;
+                let cutoff = self.get_multisig(multisig)?.cutoff();
+                let p = self.get_proposal_mut(proposal)?;
+                insert_vote(&mut p.rejected, member)?;
+                p.approved.retain(|key| key != member);
+                if p.rejected.len() >= cutoff {
+                    self.transition(multisig, proposal, ProposalStatus::Rejected { timestamp })?;
+                }
+            }
+            SquadsInstruction::ProposalCancel {
+                multisig,
+                member,
+                proposal,
+                ..
+            } => {
+                self.check_member(multisig, member, |m| m.permissions.has_vote())?;
+                let threshold = usize::from(self.get_multisig(multisig)?.threshold);
+                let p = self.get_proposal_mut(proposal)?;
+                if !matches!(p.status, ProposalStatus::Approved { .. }) {
+                    return Err(SquadsError::InvalidProposalTransition(format!(
+                        "cannot cancel a {:?} proposal",
+                        p.status
+                    )));
+                }
+                insert_vote(&mut p.cancelled, member)?;
+                if p.cancelled.len() >= threshold {
+                    self.transition(multisig, proposal, ProposalStatus::Cancelled { timestamp })?;
+                }
+            }
+            SquadsInstruction::ConfigTransactionExecute {
+                multisig,
+                member,
+                proposal,
+                transaction,
+            } => {
+                self.check_member(multisig, member, |m| m.permissions.has_execute())?;
+                let actions = self
+                    .config_transactions
+                    .get(transaction)
+                    .map(|tx| tx.actions.clone())
+                    .ok_or_else(|| SquadsError::AccountNotFound(transaction.to_string()))?;
+                self.transition(multisig, proposal, ProposalStatus::Executed { timestamp })?;
+
+                let state = self.get_multisig_mut(multisig)?;
+                for action in &actions {
+                    state.apply_config_action(action);
+                }
+                state.check_invariants()?;
+            }
+            SquadsInstruction::VaultTransactionExecute {
+                multisig,
+                proposal,
+                member,
+                ..
+            } => {
+                self.check_member(multisig, member, |m| m.permissions.has_execute())?;
+                self.transition(multisig, proposal, ProposalStatus::Executed { timestamp })?;
+            }
+            SquadsInstruction::SpendingLimitUse { .. }
+            | SquadsInstruction::TransactionBufferClose { .. }
+            | SquadsInstruction::ProgramConfigInit { .. }
+            | SquadsInstruction::ProgramConfigSetAuthority { .. }
+            | SquadsInstruction::ProgramConfigSetMultisigCreationFee { .. }
+            | SquadsInstruction::ProgramConfigSetTreasury { .. }
+            | SquadsInstruction::Unknown { .. } => {}
+        }
+        Ok(())
+    }
+
+    /// Compare the derived state of `address` with its on-chain account data
+    ///
+    /// Status timestamps are not compared, since replays use block times rather than
+    /// the program's clock. Accounts missing on either side are reported as a mismatch
+    /// of the `account` field.
+    pub fn compare(&self, address: &Pubkey, data: Option<&[u8]>) -> Vec<StateMismatch> {
+        let mut mismatches = Vec::new();
+        let mut check = |field: &'static str, derived: String, on_chain: String| {
+            if derived != on_chain {
+                mismatches.push(StateMismatch {
+                    account: *address,
+                    field,
+                    derived,
+                    on_chain,
+                });
+            }
+        };
+
+        if let Some(derived) = self.multisigs.get(address) {
+            let Some(actual) = data.and_then(|data| Multisig::try_from_slice(data).ok()) else {
+                check("account", "Multisig".to_string(), "missing".to_string());
+                return mismatches;
+            };
+            check(
+                "threshold",
+                derived.threshold.to_string(),
+                actual.threshold.to_string(),
+            );
+            check(
+                "time_lock",
+                derived.time_lock.to_string(),
+                actual.time_lock.to_string(),
+            );
+            check(
+                "transaction_index",
+                derived.transaction_index.to_string(),
+                actual.transaction_index.to_string(),
+            );
+            check(
+                "stale_transaction_index",
+                derived.stale_transaction_index.to_string(),
+                actual.stale_transaction_index.to_string(),
+            );
+            check(
+                "config_authority",
+                derived.config_authority.to_string(),
+                actual.config_authority.to_string(),
+            );
+            check(
+                "rent_collector",
+                format!("{:?}", derived.rent_collector),
+                format!("{:?}", actual.rent_collector),
+            );
+            check(
+                "members",
+                format!("{:?}", derived.members),
+                format!("{:?}", actual.members),
+            );
+        } else if let Some(derived) = self.proposals.get(address) {
+            let Some(actual) = data.and_then(|data| Proposal::try_from_slice(data).ok()) else {
+                check("account", "Proposal".to_string(), "missing".to_string());
+                return mismatches;
+            };
+            check(
+                "status",
+                status_name(&derived.status).to_string(),
+                status_name(&actual.status).to_string(),
+            );
+            check(
+                "approved",
+                format!("{:?}", derived.approved_set()),
+                format!("{:?}", actual.approved_set()),
+            );
+            check(
+                "rejected",
+                format!("{:?}", derived.rejected_set()),
+                format!("{:?}", actual.rejected_set()),
+            );
+            check(
+                "cancelled",
+                format!("{:?}", derived.cancelled_set()),
+                format!("{:?}", actual.cancelled_set()),
+            );
+        }
+        mismatches
+    }
+
+    fn get_multisig(&self, address: &Pubkey) -> SquadsResult<&Multisig> {
+        self.multisigs
+            .get(address)
+            .ok_or_else(|| SquadsError::AccountNotFound(address.to_string()))
+    }
+
+    fn get_multisig_mut(&mut self, address: &Pubkey) -> SquadsResult<&mut Multisig> {
+        self.multisigs
+            .get_mut(address)
+            .ok_or_else(|| SquadsError::AccountNotFound(address.to_string()))
+    }
+
+    fn get_proposal(&self, address: &Pubkey) -> SquadsResult<&Proposal> {
+        self.proposals
+            .get(address)
+            .ok_or_else(|| SquadsError::AccountNotFound(address.to_string()))
+    }
+
+    fn get_proposal_mut(&mut self, address: &Pubkey) -> SquadsResult<&mut Proposal> {
+        self.proposals
+            .get_mut(address)
+            .ok_or_else(|| SquadsError::AccountNotFound(address.to_string()))
+    }
+
+    /// Apply a config change made directly by the config authority of a controlled
+    /// multisig
+    fn apply_controlled(
+        &mut self,
+        multisig: &Pubkey,
+        config_authority: &Pubkey,
+        action: &ConfigAction,
+    ) -> SquadsResult<()> {
+        let state = self.get_multisig_mut(multisig)?;
+        if state.config_authority == Pubkey::default()
+            || state.config_authority != *config_authority
+        {
+            return Err(SquadsError::InvalidPermissions(format!(
+                "{} is not the config authority",
+                config_authority
+            )));
+        }
+        state.apply_config_action(action);
+        state.check_invariants()
+    }
+
+    fn check_member(
+        &self,
+        multisig: &Pubkey,
+        member: &Pubkey,
+        allowed: impl Fn(&crate::types::Member) -> bool,
+    ) -> SquadsResult<()> {
+        let state = self.get_multisig(multisig)?;
+        match state.members.iter().find(|m| &m.key == member) {
+            Some(m) if allowed(m) => Ok(()),
+            _ => Err(SquadsError::InvalidPermissions(format!(
+                "{} lacks the required permission",
+                member
+            ))),
+        }
+    }
+
+    fn next_transaction_index(&mut self, multisig: &Pubkey, creator: &Pubkey) -> SquadsResult<u64> {
+        self.check_member(multisig, creator, |m| m.permissions.has_initiate())?;
+        let state = self.get_multisig_mut(multisig)?;
+        state.transaction_index += 1;
+        Ok(state.transaction_index)
+    }
+
+    fn vote(&self, multisig: &Pubkey, member: &Pubkey, proposal: &Pubkey) -> SquadsResult<()> {
+        self.check_member(multisig, member, |m| m.permissions.has_vote())?;
+        let state = self.get_multisig(multisig)?;
+        let p = self
+            .proposals
+            .get(proposal)
+            .ok_or_else(|| SquadsError::AccountNotFound(proposal.to_string()))?;
+        if !matches!(p.status, ProposalStatus::Active { .. })
+            || p.transaction_index <= state.stale_transaction_index
+        {
+            return Err(SquadsError::InvalidProposalTransition(format!(
+                "cannot vote on a {:?} proposal",
+                p.status
+            )));
+        }
+        Ok(())
+    }
+
+    fn transition(
+        &mut self,
+        multisig: &Pubkey,
+        proposal: &Pubkey,
+        next: ProposalStatus,
+    ) -> SquadsResult<()> {
+        let ctx = {
+            let state = self.get_multisig(multisig)?;
+            let p = self
+                .proposals
+                .get(proposal)
+                .ok_or_else(|| SquadsError::AccountNotFound(proposal.to_string()))?;
+            p.transition_context(state)
+        };
+        let p = self.get_proposal_mut(proposal)?;
+        p.status.check_transition(&next, &ctx)?;
+        p.status = next;
+        Ok(())
+    }
+}
+
+/// Insert a voter keeping the list sorted, as the program does
+fn insert_vote(voters: &mut Vec<Pubkey>, member: &Pubkey) -> SquadsResult<()> {
+    match voters.binary_search(member) {
+        Ok(_) => Err(SquadsError::InvalidProposalTransition(format!(
+            "{} already voted",
+            member
+        ))),
+        Err(position) => {
+            voters.insert(position, *member);
+            Ok(())
+        }
+    }
+}
+
+fn status_name(status: &ProposalStatus) -> &'static str {
+    match status {
+        ProposalStatus::Draft { .. } => "Draft",
+        ProposalStatus::Active { .. } => "Active",
+        ProposalStatus::Rejected { .. } => "Rejected",
+        ProposalStatus::Approved { .. } => "Approved",
+        ProposalStatus::Executed { .. } => "Executed",
+        ProposalStatus::Cancelled { .. } => "Cancelled",
+    }
+}
+
+/// Outcome of [`replay`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplayReport {
+    /// Number of Squads instructions applied
+    pub applied: usize,
+    /// Transactions that were not replayed, with the reason
+    pub skipped: Vec<(Signature, String)>,
+    /// Instructions the simulation rejected although they succeeded on chain
+    pub rejected: Vec<(Signature, String)>,
+    /// Differences between the derived and the on-chain accounts
+    pub mismatches: Vec<StateMismatch>,
+}
+
+impl ReplayReport {
+    /// Whether every instruction replayed and the derived state matches the chain
+    pub fn is_consistent(&self) -> bool {
+        self.rejected.is_empty() && self.mismatches.is_empty()
+    }
+
+    /// Fail with [`SquadsError::StateMismatch`] unless the replay is consistent
+    pub fn ensure_consistent(&self) -> SquadsResult<()> {
+        if let Some((signature, reason)) = self.rejected.first() {
+            return Err(SquadsError::StateMismatch(format!(
+                "{} was rejected by the simulation: {}",
+                signature, reason
+            )));
+        }
+        if let Some(mismatch) = self.mismatches.first() {
+            return Err(SquadsError::StateMismatch(format!(
+                "{} {}: derived {}, on chain {} ({} mismatches)",
+                mismatch.account,
+                mismatch.field,
+                mismatch.derived,
+                mismatch.on_chain,
+                self.mismatches.len()
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+pub use fetch::replay;
+
+#[cfg(feature = "async")]
+mod fetch {
+    use std::collections::BTreeSet;
+
+    use solana_client::{nonblocking::rpc_client::RpcClient, rpc_config::RpcTransactionConfig};
+    use solana_commitment_config::CommitmentConfig;
+    use solana_transaction_status_client_types::UiTransactionEncoding;
+
+    use super::*;
+    use crate::parser::parse_program_instruction_data;
+    use crate::report::decode_confirmed;
+
+    /// Replay transactions through `state` and compare the result with the chain
+    ///
+    /// Transactions are replayed in the given order, which should be chronological
+    /// (oldest first) and should cover every Squads transaction of the multisig since
+    /// `state` was seeded; compare against the chain only once it is up to date. Failed
+    /// transactions are skipped. Only top-level Squads instructions are replayed.
+    ///
+    /// # Arguments
+    /// * `rpc` - RPC client
+    /// * `signatures` - Transactions to replay, oldest first
+    /// * `state` - State to replay into (see [`SimState::with_multisig`])
+    pub async fn replay(
+        rpc: &RpcClient,
+        signatures: &[Signature],
+        state: &mut SimState,
+    ) -> SquadsResult<ReplayReport> {
+        let mut report = ReplayReport::default();
+        let mut accounts = BTreeSet::new();
+
+        for signature in signatures {
+            let config = RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: Some(0),
+            };
+            let confirmed = rpc.get_transaction_with_config(signature, config).await?;
+            if confirmed
+                .transaction
+                .meta
+                .as_ref()
+                .is_some_and(|meta| meta.err.is_some())
+            {
+                report
+                    .skipped
+                    .push((*signature, "transaction failed".to_string()));
+                continue;
+            }
+            let (transaction, keys) = decode_confirmed(&confirmed)?;
+            let timestamp = confirmed.block_time.unwrap_or_default();
+
+            for compiled in transaction.message.instructions() {
+                let program_id = keys.get(usize::from(compiled.program_id_index));
+                if program_id != Some(&state.program_id()) {
+                    continue;
+                }
+                let ix_accounts: Vec<Pubkey> = compiled
+                    .accounts
+                    .iter()
+                    .filter_map(|index| keys.get(usize::from(*index)).copied())
+                    .collect();
+                let instruction = match parse_program_instruction_data(
+                    &state.program_id(),
+                    &ix_accounts,
+                    &compiled.data,
+                ) {
+                    Ok(instruction) => instruction,
+                    Err(err) => {
+                        report.rejected.push((*signature, err.to_string()));
+                        continue;
+                    }
+                };
+                match state.apply(&instruction, timestamp) {
+                    Ok(()) => {
+                        report.applied += 1;
+                        accounts.extend(touched(&instruction));
+                    }
+                    Err(err) => report
+                        .rejected
+                        .push((*signature, format!("{:?}: {}", instruction, err))),
+                }
+            }
+        }
+
+        let accounts: Vec<Pubkey> = accounts.into_iter().collect();
+        for chunk in accounts.chunks(100) {
+            let fetched = rpc.get_multiple_accounts(chunk).await?;
+            for (address, account) in chunk.iter().zip(fetched) {
+                report
+                    .mismatches
+                    .extend(state.compare(address, account.as_ref().map(|a| a.data.as_slice())));
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Accounts changed by an instruction, for comparison with the chain
+    fn touched(instruction: &SquadsInstruction) -> BTreeSet<Pubkey> {
+        let mut accounts = BTreeSet::new();
+        if let Some(multisig) = instruction.multisig() {
+            accounts.insert(*multisig);
+        }
+        match instruction {
+            SquadsInstruction::ProposalCreate { proposal, .. }
+            | SquadsInstruction::ProposalActivate { proposal, .. }
+            | SquadsInstruction::ProposalApprove { proposal, .. }
+            | SquadsInstruction::ProposalReject { proposal, .. }
+            | SquadsInstruction::ProposalCancel { proposal, .. }
+            | SquadsInstruction::ConfigTransactionExecute { proposal, .. }
+            | SquadsInstruction::VaultTransactionExecute { proposal, .. } => {
+                accounts.insert(*proposal);
+            }
+            _ => {}
+        }
+        accounts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::{
+        ConfigTransactionCreateArgs, MultisigCreateArgsV2, ProposalCreateArgs, ProposalVoteArgs,
+    };
+    use crate::types::{ConfigAction, Member};
+
+    #[test]
+    fn test_config_change_lifecycle() {
+        let keys: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let create_key = Pubkey::new_unique();
+        let multisig = pda::get_multisig_pda(&create_key, None).0;
+        let transaction = pda::get_transaction_pda(&multisig, 1, None).0;
+        let proposal = pda::get_proposal_pda(&multisig, 1, None).0;
+        let newcomer = Pubkey::new_unique();
+        let vote = |member: Pubkey| SquadsInstruction::ProposalApprove {
+            multisig,
+            member,
+            proposal,
+            args: ProposalVoteArgs { memo: None },
+        };
+
+        let mut state = SimState::new(None);
+        let steps = [
+            SquadsInstruction::MultisigCreateV2 {
+                multisig,
+                create_key,
+                creator: keys[0],
+                args: MultisigCreateArgsV2 {
+                    config_authority: None,
+                    threshold: 2,
+                    members: keys.iter().map(|key| Member::new(*key)).collect(),
+                    time_lock: 0,
+                    rent_collector: None,
+                    memo: None,
+                },
+            },
+            SquadsInstruction::ConfigTransactionCreate {
+                multisig,
+                transaction,
+                creator: keys[0],
+                args: ConfigTransactionCreateArgs {
+                    actions: vec![ConfigAction::AddMember {
+                        new_member: Member::new(newcomer),
+                    }],
+                    memo: None,
+                },
+            },
+            SquadsInstruction::ProposalCreate {
+                multisig,
+                proposal,
+                creator: keys[0],
+                args: ProposalCreateArgs {
+                    transaction_index: 1,
+                    draft: false,
+                },
+            },
+            vote(keys[0]),
+            vote(keys[1]),
+            SquadsInstruction::ConfigTransactionExecute {
+                multisig,
+                member: keys[2],
+                proposal,
+                transaction,
+            },
+        ];
+        for (i, step) in steps.iter().enumerate() {
+            state.apply(step, i as i64).unwrap();
+        }
+
+        let derived = state.multisig(&multisig).unwrap();
+        assert!(derived.is_member(&newcomer));
+        assert_eq!(derived.transaction_index, 1);
+        assert_eq!(derived.stale_transaction_index, 1);
+        assert!(matches!(
+            state.proposal(&proposal).unwrap().status,
+            ProposalStatus::Executed { .. }
+        ));
+
+        // A duplicate vote and a vote on an executed proposal are rejected
+        assert!(state.apply(&vote(keys[2]), 10).is_err());
+
+        // Comparing against an identical account finds nothing; a changed one is reported
+        let mut data = crate::accounts::account_discriminator("Proposal").to_vec();
+        borsh::to_writer(&mut data, state.proposal(&proposal).unwrap()).unwrap();
+        assert!(state.compare(&proposal, Some(&data)).is_empty());
+        assert_eq!(state.compare(&proposal, None)[0].field, "account");
+    }
+}
@@ -0,0 +1,352 @@
+//! Transaction size estimation and config transaction planning
+//!
+//! Config transactions that add or remove many spending limits can exceed the 1232 byte
+//! transaction limit: the create instruction carries every action in its data and the
+//! execute instruction takes one account per spending limit. [`plan_config_transaction`]
+//! uses the estimators in this module to choose between a single config transaction, an
+//! execute routed through an address lookup table, or splitting the actions across
+//! several config transactions.
+
+use solana_sdk::{
+    hash::Hash,
+    instruction::Instruction,
+    message::{v0, AddressLookupTableAccount, Message},
+    pubkey::Pubkey,
+};
+
+use crate::error::{SquadsError, SquadsResult};
+use crate::instructions::{self, ConfigTransactionCreateArgs};
+use crate::pda;
+use crate::types::ConfigAction;
+
+/// Maximum size in bytes of a serialized transaction
+pub const MAX_TRANSACTION_SIZE: usize = 1232;
+
+/// Bytes used by the compact-u16 length prefix of a list
+fn short_vec_len(len: usize) -> usize {
+    match len {
+        0..=0x7f => 1,
+        0x80..=0x3fff => 2,
+        _ => 3,
+    }
+}
+
+fn signatures_size(num_signers: u8) -> usize {
+    short_vec_len(usize::from(num_signers)) + 64 * usize::from(num_signers)
+}
+
+fn compiled_instructions_size(
+    instructions: &[solana_sdk::message::compiled_instruction::CompiledInstruction],
+) -> usize {
+    short_vec_len(instructions.len())
+        + instructions
+            .iter()
+            .map(|ix| {
+                1 + short_vec_len(ix.accounts.len())
+                    + ix.accounts.len()
+                    + short_vec_len(ix.data.len())
+                    + ix.data.len()
+            })
+            .sum::<usize>()
+}
+
+/// Serialized size of a signed legacy transaction containing `instructions`
+pub fn legacy_transaction_size(instructions: &[Instruction], payer: &Pubkey) -> usize {
+    let message = Message::new(instructions, Some(payer));
+
+    signatures_size(message.header.num_required_signatures)
+        + 3
+        + short_vec_len(message.account_keys.len())
+        + 32 * message.account_keys.len()
+        + 32
+        + compiled_instructions_size(&message.instructions)
+}
+
+/// Serialized size of a signed v0 transaction using the given address lookup tables
+///
+/// Returns `None` if the message cannot be compiled.
+pub fn v0_transaction_size(
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    lookup_tables: &[AddressLookupTableAccount],
+) -> Option<usize> {
+    let message =
+        v0::Message::try_compile(payer, instructions, lookup_tables, Hash::default()).ok()?;
+
+    let lookups_size = short_vec_len(message.address_table_lookups.len())
+        + message
+            .address_table_lookups
+            .iter()
+            .map(|lookup| {
+                32 + short_vec_len(lookup.writable_indexes.len())
+                    + lookup.writable_indexes.len()
+                    + short_vec_len(lookup.readonly_indexes.len())
+                    + lookup.readonly_indexes.len()
+            })
+            .sum::<usize>();
+
+    Some(
+        signatures_size(message.header.num_required_signatures)
+            + 1
+            + 3
+            + short_vec_len(message.account_keys.len())
+            + 32 * message.account_keys.len()
+            + 32
+            + compiled_instructions_size(&message.instructions)
+            + lookups_size,
+    )
+}
+
+/// Spending limit accounts that `config_transaction_execute` needs for `actions`
+///
+/// # Arguments
+/// * `multisig` - Multisig account
+/// * `actions` - Config actions of the transaction
+/// * `program_id` - Optional custom program ID
+pub fn config_spending_limit_accounts(
+    multisig: &Pubkey,
+    actions: &[ConfigAction],
+    program_id: Option<&Pubkey>,
+) -> Vec<Pubkey> {
+    actions
+        .iter()
+        .filter_map(|action| match action {
+            ConfigAction::AddSpendingLimit { create_key, .. } => {
+                Some(pda::get_spending_limit_pda(multisig, create_key, program_id).0)
+            }
+            ConfigAction::RemoveSpendingLimit { spending_limit } => Some(*spending_limit),
+            _ => None,
+        })
+        .collect()
+}
+
+/// How a set of config actions should be proposed and executed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigExecutionPlan {
+    /// One config transaction, executed with a legacy transaction
+    Single,
+    /// One config transaction, executed with a v0 transaction whose address lookup table
+    /// holds the spending limit accounts
+    LookupTable {
+        /// Addresses to put in the lookup table
+        addresses: Vec<Pubkey>,
+    },
+    /// Several config transactions, each created and executed with legacy transactions
+    Split(Vec<Vec<ConfigAction>>),
+}
+
+/// Estimated sizes of the create and execute transactions for a set of actions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfigTransactionSizes {
+    /// Size of the `config_transaction_create` transaction
+    pub create: usize,
+    /// Size of the `config_transaction_execute` legacy transaction
+    pub execute: usize,
+}
+
+impl ConfigTransactionSizes {
+    /// Whether both transactions fit without a lookup table
+    pub fn fits(&self) -> bool {
+        self.create <= MAX_TRANSACTION_SIZE && self.execute <= MAX_TRANSACTION_SIZE
+    }
+}
+
+/// Estimate the create and execute transaction sizes for `actions`
+///
+/// # Arguments
+/// * `multisig` - Multisig account
+/// * `member` - Member creating and executing the transaction (also the fee payer)
+/// * `actions` - Config actions
+/// * `program_id` - Optional custom program ID
+pub fn estimate_config_transaction_sizes(
+    multisig: &Pubkey,
+    member: &Pubkey,
+    actions: &[ConfigAction],
+    program_id: Option<&Pubkey>,
+) -> ConfigTransactionSizes {
+    let (create, execute) = config_instructions(multisig, member, actions, program_id);
+    ConfigTransactionSizes {
+        create: legacy_transaction_size(&[create], member),
+        execute: legacy_transaction_size(&[execute], member),
+    }
+}
+
+/// Choose how to propose and execute `actions`
+///
+/// Prefers a single legacy execution, then a lookup table for the execute transaction,
+/// and splits the actions only if the create transaction itself is too large or the
+/// execute transaction does not fit even with a lookup table.
+///
+/// # Arguments
+/// * `multisig` - Multisig account
+/// * `member` - Member creating and executing the transaction (also the fee payer)
+/// * `actions` - Config actions
+/// * `program_id` - Optional custom program ID
+pub fn plan_config_transaction(
+    multisig: &Pubkey,
+    member: &Pubkey,
+    actions: &[ConfigAction],
+    program_id: Option<&Pubkey>,
+) -> SquadsResult<ConfigExecutionPlan> {
+    let sizes = estimate_config_transaction_sizes(multisig, member, actions, program_id);
+    if sizes.fits() {
+        return Ok(ConfigExecutionPlan::Single);
+    }
+
+    if sizes.create <= MAX_TRANSACTION_SIZE {
+        let addresses = config_spending_limit_accounts(multisig, actions, program_id);
+        let (_, execute) = config_instructions(multisig, member, actions, program_id);
+        let lookup_table = AddressLookupTableAccount {
+            key: Pubkey::default(),
+            addresses: addresses.clone(),
+        };
+        if v0_transaction_size(&[execute], member, &[lookup_table])
+            .is_some_and(|size| size <= MAX_TRANSACTION_SIZE)
+        {
+            return Ok(ConfigExecutionPlan::LookupTable { addresses });
+        }
+    }
+
+    split_config_actions(multisig, member, actions, program_id).map(ConfigExecutionPlan::Split)
+}
+
+/// Greedily split `actions` into chunks whose create and execute transactions fit
+fn split_config_actions(
+    multisig: &Pubkey,
+    member: &Pubkey,
+    actions: &[ConfigAction],
+    program_id: Option<&Pubkey>,
+) -> SquadsResult<Vec<Vec<ConfigAction>>> {
+    let mut chunks: Vec<Vec<ConfigAction>> = Vec::new();
+    let mut current: Vec<ConfigAction> = Vec::new();
+
+    for action in actions {
+        current.push(action.clone());
+        if estimate_config_transaction_sizes(multisig, member, &current, program_id).fits() {
+            continue;
+        }
+
+        let action = current.pop().expect("just pushed");
+        let alone = estimate_config_transaction_sizes(
+            multisig,
+            member,
+            std::slice::from_ref(&action),
+            program_id,
+        );
+        if !alone.fits() {
+            return Err(SquadsError::TransactionTooLarge {
+                size: alone.create.max(alone.execute),
+                max: MAX_TRANSACTION_SIZE,
+            });
+        }
+        if !current.is_empty() {
+            chunks.push(std::mem::take(&mut current));
+        }
+        current.push(action);
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    Ok(chunks)
+}
+
+/// Representative create and execute instructions for size estimation
+fn config_instructions(
+    multisig: &Pubkey,
+    member: &Pubkey,
+    actions: &[ConfigAction],
+    program_id: Option<&Pubkey>,
+) -> (Instruction, Instruction) {
+    let (transaction, _) = pda::get_transaction_pda(multisig, 1, program_id);
+    let (proposal, _) = pda::get_proposal_pda(multisig, 1, program_id);
+
+    let create = instructions::config_transaction_create(
+        *multisig,
+        transaction,
+        *member,
+        *member,
+        ConfigTransactionCreateArgs {
+            actions: actions.to_vec(),
+            memo: None,
+        },
+        program_id.copied(),
+    );
+    let execute = instructions::config_transaction_execute(
+        *multisig,
+        proposal,
+        transaction,
+        *member,
+        Some(*member),
+        config_spending_limit_accounts(multisig, actions, program_id),
+        program_id.copied(),
+    );
+
+    (create, execute)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Period;
+
+    fn add_spending_limit(members: usize) -> ConfigAction {
+        ConfigAction::AddSpendingLimit {
+            create_key: Pubkey::new_unique(),
+            vault_index: 0,
+            mint: Pubkey::default(),
+            amount: 1_000,
+            period: Period::Day,
+            members: (0..members).map(|_| Pubkey::new_unique()).collect(),
+            destinations: vec![],
+        }
+    }
+
+    #[test]
+    fn test_legacy_transaction_size_matches_transfer() {
+        let payer = Pubkey::new_unique();
+        let ix = solana_system_interface::instruction::transfer(&payer, &Pubkey::new_unique(), 1);
+        // 1 signature + header + 3 keys + blockhash + one 12-byte transfer instruction
+        assert_eq!(
+            legacy_transaction_size(&[ix], &payer),
+            1 + 64 + 3 + 1 + 96 + 32 + 1 + 1 + 1 + 2 + 1 + 12
+        );
+    }
+
+    #[test]
+    fn test_plan_config_transaction() {
+        let multisig = Pubkey::new_unique();
+        let member = Pubkey::new_unique();
+
+        let few = vec![add_spending_limit(1)];
+        assert_eq!(
+            plan_config_transaction(&multisig, &member, &few, None).unwrap(),
+            ConfigExecutionPlan::Single
+        );
+
+        // The execute transaction also references the proposal, so with 28 removals
+        // only the execute transaction is too large
+        let removals: Vec<ConfigAction> = (0..28)
+            .map(|_| ConfigAction::RemoveSpendingLimit {
+                spending_limit: Pubkey::new_unique(),
+            })
+            .collect();
+        assert!(matches!(
+            plan_config_transaction(&multisig, &member, &removals, None).unwrap(),
+            ConfigExecutionPlan::LookupTable { addresses } if addresses.len() == 28
+        ));
+
+        // Large actions: the create instruction data alone is too large
+        let many: Vec<ConfigAction> = (0..8).map(|_| add_spending_limit(4)).collect();
+        let ConfigExecutionPlan::Split(chunks) =
+            plan_config_transaction(&multisig, &member, &many, None).unwrap()
+        else {
+            panic!("expected split");
+        };
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks.iter().map(Vec::len).sum::<usize>(), many.len());
+        for chunk in &chunks {
+            assert!(estimate_config_transaction_sizes(&multisig, &member, chunk, None).fits());
+        }
+    }
+}
@@ -0,0 +1,249 @@
+//! Multisig specs for recreating a squad from an exported configuration
+//!
+//! [`MultisigSpec`] reads the JSON settings export of the Squads app: camelCase field
+//! names, base58 member keys, and permissions given as a bitmask, a `{ "mask": n }`
+//! object or a list of permission names. snake_case names and 32-byte key arrays are
+//! accepted too. A spec can also be taken from an on-chain multisig with
+//! [`MultisigSpec::from_multisig`], so an existing squad can be cloned.
+
+use serde::{de, Deserialize, Deserializer, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::accounts::Multisig;
+use crate::codec::{Format, Json};
+use crate::error::{SquadsError, SquadsResult};
+use crate::instructions::MultisigCreateArgsV2;
+use crate::types::{Member, Permission, Permissions};
+
+/// A member entry of a [`MultisigSpec`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpecMember {
+    /// Public key of the member
+    #[serde(
+        alias = "address",
+        alias = "publicKey",
+        alias = "public_key",
+        deserialize_with = "crate::serde_utils::pubkey",
+        serialize_with = "crate::serde_utils::serialize_pubkey"
+    )]
+    pub key: Pubkey,
+    /// Permissions granted to the member
+    #[serde(deserialize_with = "permissions")]
+    pub permissions: Permissions,
+}
+
+impl From<SpecMember> for Member {
+    fn from(member: SpecMember) -> Self {
+        Member::with_permissions(member.key, member.permissions)
+    }
+}
+
+impl From<&Member> for SpecMember {
+    fn from(member: &Member) -> Self {
+        Self {
+            key: member.key,
+            permissions: member.permissions,
+        }
+    }
+}
+
+/// Configuration needed to create a multisig
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultisigSpec {
+    /// Display name of the squad (used as the creation memo)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Approval threshold
+    pub threshold: u16,
+    /// Time lock in seconds
+    #[serde(default, alias = "time_lock")]
+    pub time_lock: u32,
+    /// Config authority (None for an autonomous multisig)
+    #[serde(
+        default,
+        alias = "config_authority",
+        deserialize_with = "crate::serde_utils::option_pubkey",
+        serialize_with = "crate::serde_utils::serialize_option_pubkey"
+    )]
+    pub config_authority: Option<Pubkey>,
+    /// Rent collector (None to disable rent reclamation)
+    #[serde(
+        default,
+        alias = "rent_collector",
+        deserialize_with = "crate::serde_utils::option_pubkey",
+        serialize_with = "crate::serde_utils::serialize_option_pubkey"
+    )]
+    pub rent_collector: Option<Pubkey>,
+    /// Members and their permissions
+    pub members: Vec<SpecMember>,
+}
+
+impl MultisigSpec {
+    /// Parse a spec from JSON
+    pub fn from_json(json: &str) -> SquadsResult<Self> {
+        Json::decode(json.as_bytes())
+    }
+
+    /// Serialize the spec as JSON
+    pub fn to_json(&self) -> SquadsResult<String> {
+        serde_json::to_string_pretty(self).map_err(|e| SquadsError::EncodingError(e.to_string()))
+    }
+
+    /// Take the configuration of an existing multisig
+    ///
+    /// A config authority of `Pubkey::default()` (autonomous multisig) maps to `None`.
+    pub fn from_multisig(multisig: &Multisig) -> Self {
+        Self {
+            name: None,
+            threshold: multisig.threshold,
+            time_lock: multisig.time_lock,
+            config_authority: Some(multisig.config_authority)
+                .filter(|authority| *authority != Pubkey::default()),
+            rent_collector: multisig.rent_collector,
+            members: multisig.members.iter().map(SpecMember::from).collect(),
+        }
+    }
+
+    /// Members of the spec
+    pub fn members(&self) -> Vec<Member> {
+        self.members.iter().cloned().map(Member::from).collect()
+    }
+
+    /// Check the spec against the program's multisig invariants
+    pub fn validate(&self) -> SquadsResult<()> {
+        let mut keys: Vec<&Pubkey> = self.members.iter().map(|m| &m.key).collect();
+        keys.sort();
+        if keys.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(SquadsError::InvalidPermissions(
+                "duplicate member".to_string(),
+            ));
+        }
+
+        let voters = self
+            .members
+            .iter()
+            .filter(|m| m.permissions.has_vote())
+            .count();
+        if voters == 0 {
+            return Err(SquadsError::NoVotingMembers);
+        }
+        if self.threshold == 0 || usize::from(self.threshold) > voters {
+            return Err(SquadsError::InvalidThreshold);
+        }
+        Ok(())
+    }
+
+    /// Arguments for `multisig_create_v2`, with the spec name as memo
+    pub fn create_args(&self) -> SquadsResult<MultisigCreateArgsV2> {
+        self.validate()?;
+        MultisigCreateArgsV2::new(
+            self.config_authority,
+            self.threshold,
+            self.members(),
+            self.time_lock,
+            self.rent_collector,
+            self.name.clone(),
+        )
+    }
+}
+
+/// Permissions as a bitmask, a `{ "mask": n }` object or a list of names
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PermissionsRepr {
+    Mask(u8),
+    Object { mask: u8 },
+    Names(Vec<String>),
+}
+
+fn permission_from_name(name: &str) -> Option<Permission> {
+    match name.to_ascii_lowercase().as_str() {
+        "initiate" | "proposer" | "propose" => Some(Permission::Initiate),
+        "vote" | "voter" => Some(Permission::Vote),
+        "execute" | "executor" => Some(Permission::Execute),
+        _ => None,
+    }
+}
+
+fn permissions<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Permissions, D::Error> {
+    match PermissionsRepr::deserialize(deserializer)? {
+        PermissionsRepr::Mask(mask) | PermissionsRepr::Object { mask } => {
+            Ok(Permissions::from_mask(mask))
+        }
+        PermissionsRepr::Names(names) => names
+            .iter()
+            .map(|name| {
+                permission_from_name(name).ok_or_else(|| {
+                    de::Error::unknown_variant(name, &["Initiate", "Vote", "Execute"])
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(|permissions| Permissions::from_vec(&permissions)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_from_app_export() {
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        let c = Pubkey::new_unique();
+        let json = format!(
+            r#"{{
+                "name": "Treasury",
+                "threshold": 2,
+                "timeLock": 3600,
+                "configAuthority": null,
+                "members": [
+                    {{ "key": "{a}", "permissions": {{ "mask": 7 }} }},
+                    {{ "address": "{b}", "permissions": ["Vote", "Execute"] }},
+                    {{ "publicKey": "{c}", "permissions": 1 }}
+                ]
+            }}"#
+        );
+
+        let spec = MultisigSpec::from_json(&json).unwrap();
+        assert_eq!(spec.time_lock, 3600);
+        assert_eq!(spec.members[1].permissions.mask, 6);
+        assert!(!spec.members[2].permissions.has_vote());
+
+        let args = spec.create_args().unwrap();
+        assert_eq!(args.memo.as_deref(), Some("Treasury"));
+        assert_eq!(args.members.len(), 3);
+
+        let round_trip = MultisigSpec::from_json(&spec.to_json().unwrap()).unwrap();
+        assert_eq!(round_trip, spec);
+    }
+
+    #[test]
+    fn test_spec_validation() {
+        let key = Pubkey::new_unique();
+        let mut spec = MultisigSpec {
+            name: None,
+            threshold: 2,
+            time_lock: 0,
+            config_authority: None,
+            rent_collector: None,
+            members: vec![SpecMember::from(&Member::new(key))],
+        };
+        assert!(matches!(
+            spec.validate(),
+            Err(SquadsError::InvalidThreshold)
+        ));
+
+        spec.members.push(SpecMember::from(&Member::new(key)));
+        assert!(matches!(
+            spec.validate(),
+            Err(SquadsError::InvalidPermissions(_))
+        ));
+
+        assert!(MultisigSpec::from_json(
+            r#"{"threshold": 1, "members": [{"key": "11111111111111111111111111111111", "permissions": ["Admin"]}]}"#
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,277 @@
+//! Vault transaction templates with placeholders
+//!
+//! This module lets a vault transaction be described once with named placeholders
+//! (for example a destination and an amount) and instantiated later with concrete
+//! values. This is useful for recurring operations such as payroll, where the same
+//! instruction list is proposed repeatedly with different parameters.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use solana_sdk_ids::system_program;
+
+use crate::error::{SquadsError, SquadsResult};
+use crate::message::TransactionMessage;
+
+/// A value that is either fixed in the template or filled in at instantiation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TemplateValue<T> {
+    /// A concrete value stored in the template
+    Fixed(T),
+    /// A named placeholder resolved from [`TemplateParams`]
+    Placeholder(String),
+}
+
+impl<T> TemplateValue<T> {
+    /// Create a placeholder with the given name
+    pub fn placeholder(name: &str) -> Self {
+        TemplateValue::Placeholder(name.to_string())
+    }
+}
+
+/// An account reference inside a template instruction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateAccount {
+    /// The account public key
+    pub pubkey: TemplateValue<Pubkey>,
+    /// Whether the account signs the instruction
+    pub is_signer: bool,
+    /// Whether the account is writable
+    pub is_writable: bool,
+}
+
+/// A segment of instruction data inside a template instruction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TemplateData {
+    /// Raw bytes copied verbatim
+    Bytes(Vec<u8>),
+    /// A named amount encoded as a little-endian u64
+    Amount(String),
+    /// A named public key encoded as its 32 bytes
+    Pubkey(String),
+}
+
+/// An instruction inside a template
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateInstruction {
+    /// The program to invoke
+    pub program_id: TemplateValue<Pubkey>,
+    /// Accounts passed to the instruction
+    pub accounts: Vec<TemplateAccount>,
+    /// Instruction data, concatenated from its segments
+    pub data: Vec<TemplateData>,
+}
+
+/// Concrete values used to fill the placeholders of a [`Template`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateParams {
+    /// Values for public key placeholders
+    pub pubkeys: HashMap<String, Pubkey>,
+    /// Values for amount placeholders
+    pub amounts: HashMap<String, u64>,
+}
+
+impl TemplateParams {
+    /// Create an empty set of parameters
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a public key parameter
+    pub fn with_pubkey(mut self, name: &str, value: Pubkey) -> Self {
+        self.pubkeys.insert(name.to_string(), value);
+        self
+    }
+
+    /// Set an amount parameter
+    pub fn with_amount(mut self, name: &str, value: u64) -> Self {
+        self.amounts.insert(name.to_string(), value);
+        self
+    }
+
+    fn pubkey(&self, name: &str) -> SquadsResult<Pubkey> {
+        self.pubkeys
+            .get(name)
+            .copied()
+            .ok_or_else(|| SquadsError::MissingTemplateParam(name.to_string()))
+    }
+
+    fn amount(&self, name: &str) -> SquadsResult<u64> {
+        self.amounts
+            .get(name)
+            .copied()
+            .ok_or_else(|| SquadsError::MissingTemplateParam(name.to_string()))
+    }
+}
+
+/// A reusable vault transaction with placeholder fields
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Template {
+    /// Human readable name of the template
+    pub name: String,
+    /// The vault PDA that executes the transaction
+    pub vault: Pubkey,
+    /// Instructions with placeholders
+    pub instructions: Vec<TemplateInstruction>,
+}
+
+impl Template {
+    /// Create an empty template for a vault
+    pub fn new(name: &str, vault: Pubkey) -> Self {
+        Self {
+            name: name.to_string(),
+            vault,
+            instructions: Vec::new(),
+        }
+    }
+
+    /// Append an instruction to the template
+    pub fn with_instruction(mut self, instruction: TemplateInstruction) -> Self {
+        self.instructions.push(instruction);
+        self
+    }
+
+    /// Template for a SOL transfer out of the vault
+    ///
+    /// Uses the `destination` pubkey placeholder and the `amount` amount placeholder.
+    pub fn sol_transfer(vault: Pubkey) -> Self {
+        // System program Transfer instruction: u32 variant index 2 followed by u64 lamports
+        Self::new("sol_transfer", vault).with_instruction(TemplateInstruction {
+            program_id: TemplateValue::Fixed(system_program::ID),
+            accounts: vec![
+                TemplateAccount {
+                    pubkey: TemplateValue::Fixed(vault),
+                    is_signer: true,
+                    is_writable: true,
+                },
+                TemplateAccount {
+                    pubkey: TemplateValue::placeholder("destination"),
+                    is_signer: false,
+                    is_writable: true,
+                },
+            ],
+            data: vec![
+                TemplateData::Bytes(2u32.to_le_bytes().to_vec()),
+                TemplateData::Amount("amount".to_string()),
+            ],
+        })
+    }
+
+    /// Names of all placeholders referenced by this template
+    pub fn placeholders(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut push = |name: &String| {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        };
+
+        for ix in &self.instructions {
+            if let TemplateValue::Placeholder(name) = &ix.program_id {
+                push(name);
+            }
+            for account in &ix.accounts {
+                if let TemplateValue::Placeholder(name) = &account.pubkey {
+                    push(name);
+                }
+            }
+            for segment in &ix.data {
+                match segment {
+                    TemplateData::Amount(name) | TemplateData::Pubkey(name) => push(name),
+                    TemplateData::Bytes(_) => {}
+                }
+            }
+        }
+
+        names
+    }
+
+    /// Resolve all placeholders into concrete instructions
+    pub fn instructions(&self, params: &TemplateParams) -> SquadsResult<Vec<Instruction>> {
+        let resolve = |value: &TemplateValue<Pubkey>| match value {
+            TemplateValue::Fixed(pubkey) => Ok(*pubkey),
+            TemplateValue::Placeholder(name) => params.pubkey(name),
+        };
+
+        self.instructions
+            .iter()
+            .map(|ix| {
+                let accounts = ix
+                    .accounts
+                    .iter()
+                    .map(|account| {
+                        Ok(AccountMeta {
+                            pubkey: resolve(&account.pubkey)?,
+                            is_signer: account.is_signer,
+                            is_writable: account.is_writable,
+                        })
+                    })
+                    .collect::<SquadsResult<Vec<_>>>()?;
+
+                let mut data = Vec::new();
+                for segment in &ix.data {
+                    match segment {
+                        TemplateData::Bytes(bytes) => data.extend_from_slice(bytes),
+                        TemplateData::Amount(name) => {
+                            data.extend_from_slice(&params.amount(name)?.to_le_bytes())
+                        }
+                        TemplateData::Pubkey(name) => {
+                            data.extend_from_slice(params.pubkey(name)?.as_ref())
+                        }
+                    }
+                }
+
+                Ok(Instruction {
+                    program_id: resolve(&ix.program_id)?,
+                    accounts,
+                    data,
+                })
+            })
+            .collect()
+    }
+
+    /// Instantiate the template into a compiled vault TransactionMessage
+    pub fn instantiate(&self, params: &TemplateParams) -> SquadsResult<TransactionMessage> {
+        let instructions = self.instructions(params)?;
+        TransactionMessage::try_compile(&self.vault, &instructions)
+            .map_err(|_| SquadsError::InvalidTransactionMessage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sol_transfer_template() {
+        let vault = Pubkey::new_unique();
+        let destination = Pubkey::new_unique();
+        let template = Template::sol_transfer(vault);
+
+        assert_eq!(template.placeholders(), vec!["destination", "amount"]);
+
+        let params = TemplateParams::new()
+            .with_pubkey("destination", destination)
+            .with_amount("amount", 1_000);
+        let instructions = template.instructions(&params).unwrap();
+        let expected = solana_system_interface::instruction::transfer(&vault, &destination, 1_000);
+        assert_eq!(instructions, vec![expected]);
+
+        let message = template.instantiate(&params).unwrap();
+        assert_eq!(message.num_signers, 1);
+    }
+
+    #[test]
+    fn test_missing_param() {
+        let template = Template::sol_transfer(Pubkey::new_unique());
+        let params = TemplateParams::new().with_amount("amount", 1);
+        assert!(matches!(
+            template.instantiate(&params),
+            Err(SquadsError::MissingTemplateParam(name)) if name == "destination"
+        ));
+    }
+}
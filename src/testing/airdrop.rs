@@ -0,0 +1,152 @@
+//! Airdrops for devnet and local validator workflows
+//!
+//! Helpers shared by examples and integration tests so each one doesn't reimplement
+//! faucet polling.
+
+use std::time::{Duration, Instant};
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Signature};
+
+use crate::error::{SquadsError, SquadsResult};
+use crate::runtime;
+
+/// Retry and confirmation settings for [`airdrop_and_wait_with_config`]
+#[derive(Debug, Clone)]
+pub struct AirdropConfig {
+    /// Number of airdrop requests to attempt before giving up
+    pub max_attempts: u32,
+    /// Delay between failed airdrop requests (doubled after each failure)
+    pub retry_delay: Duration,
+    /// Interval between confirmation polls
+    pub poll_interval: Duration,
+    /// Maximum time to wait for a single airdrop to confirm
+    pub confirm_timeout: Duration,
+}
+
+impl Default for AirdropConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            retry_delay: Duration::from_secs(2),
+            poll_interval: Duration::from_millis(500),
+            confirm_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Request an airdrop and wait until it is confirmed, using the default retry settings
+///
+/// # Arguments
+/// * `rpc` - RPC client connected to a cluster with a faucet (devnet, testnet, localnet)
+/// * `pubkey` - Account to fund
+/// * `sol` - Amount of SOL to request
+pub async fn airdrop_and_wait(
+    rpc: &RpcClient,
+    pubkey: &Pubkey,
+    sol: f64,
+) -> SquadsResult<Signature> {
+    airdrop_and_wait_with_config(rpc, pubkey, sol, &AirdropConfig::default()).await
+}
+
+/// Request an airdrop and wait until it is confirmed
+///
+/// Failed requests (faucet rate limits, dropped transactions, confirmation timeouts)
+/// are retried with exponential backoff up to `config.max_attempts` times.
+pub async fn airdrop_and_wait_with_config(
+    rpc: &RpcClient,
+    pubkey: &Pubkey,
+    sol: f64,
+    config: &AirdropConfig,
+) -> SquadsResult<Signature> {
+    let lamports = (sol * LAMPORTS_PER_SOL as f64) as u64;
+    let mut delay = config.retry_delay;
+    let mut last_error = None;
+    let attempts = config.max_attempts.max(1);
+
+    for attempt in 1..=attempts {
+        match rpc.request_airdrop(pubkey, lamports).await {
+            Ok(signature) => match wait_for_confirmation(rpc, &signature, config).await {
+                Ok(true) => return Ok(signature),
+                Ok(false) => last_error = Some(format!("airdrop {} was not confirmed", signature)),
+                Err(e) => last_error = Some(e.to_string()),
+            },
+            Err(e) => last_error = Some(e.to_string()),
+        }
+
+        if attempt < attempts {
+            runtime::sleep(delay).await;
+            delay *= 2;
+        }
+    }
+
+    Err(SquadsError::AirdropFailed(format!(
+        "{} after {} attempts: {}",
+        pubkey,
+        attempts,
+        last_error.unwrap_or_default()
+    )))
+}
+
+/// Poll until the signature is confirmed or the timeout elapses
+///
+/// RPC errors while polling count as not yet confirmed; the last one is returned if
+/// the final poll before the timeout failed.
+async fn wait_for_confirmation(
+    rpc: &RpcClient,
+    signature: &Signature,
+    config: &AirdropConfig,
+) -> SquadsResult<bool> {
+    let deadline = Instant::now() + config.confirm_timeout;
+    let mut last_error = None;
+
+    while Instant::now() < deadline {
+        match rpc
+            .confirm_transaction_with_commitment(signature, CommitmentConfig::confirmed())
+            .await
+        {
+            Ok(response) if response.value => return Ok(true),
+            Ok(_) => last_error = None,
+            Err(e) => last_error = Some(e),
+        }
+        runtime::sleep(config.poll_interval).await;
+    }
+
+    match last_error {
+        Some(e) => Err(e.into()),
+        None => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use solana_client::rpc_request::RpcRequest;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_confirmation_survives_rpc_errors() {
+        // The first status request fails to decode, the next one reports confirmation
+        let mocks = HashMap::from([(RpcRequest::GetSignatureStatuses, serde_json::Value::Null)]);
+        let rpc = RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks);
+        let config = AirdropConfig {
+            poll_interval: Duration::from_millis(1),
+            ..AirdropConfig::default()
+        };
+        assert!(wait_for_confirmation(&rpc, &Signature::default(), &config)
+            .await
+            .unwrap());
+
+        let rpc = RpcClient::new_mock("fails".to_string());
+        let config = AirdropConfig {
+            confirm_timeout: Duration::from_millis(20),
+            ..config
+        };
+        assert!(wait_for_confirmation(&rpc, &Signature::default(), &config)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,119 @@
+//! Deterministic account fixtures
+//!
+//! Fixtures derive their keys with [`keys`] and encode accounts in the
+//! layout the program stores them in, so the bytes are identical on every run.
+
+use borsh::BorshSerialize;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+
+use super::keys;
+use crate::accounts::{AnchorAccount, Multisig};
+use crate::pda;
+use crate::types::Member;
+
+/// A multisig with deterministic keys, and its account data
+#[derive(Debug)]
+pub struct MultisigFixture {
+    /// Key the multisig PDA is derived from
+    pub create_key: Keypair,
+    /// Members in account order (sorted by key), each with full permissions
+    pub members: Vec<Keypair>,
+    /// Multisig PDA under the canonical program ID
+    pub address: Pubkey,
+    /// The decoded account
+    pub account: Multisig,
+    /// Account data, including the discriminator and the reserved rent collector space
+    pub data: Vec<u8>,
+}
+
+/// An autonomous multisig of `members` deterministic members
+///
+/// Keypair 0 of `seed` is the create key and keypairs `1..=members` are the members.
+/// The multisig has no time lock, no rent collector and no transactions.
+pub fn multisig(seed: u64, members: usize, threshold: u16) -> MultisigFixture {
+    let mut keypairs = keys::deterministic(seed, members + 1);
+    let create_key = keypairs.remove(0);
+    keypairs.sort_by_key(|keypair| keypair.pubkey());
+
+    let (address, bump) = pda::get_multisig_pda(&create_key.pubkey(), None);
+    let account = Multisig {
+        create_key: create_key.pubkey(),
+        config_authority: Pubkey::default(),
+        threshold,
+        time_lock: 0,
+        transaction_index: 0,
+        stale_transaction_index: 0,
+        rent_collector: None,
+        bump,
+        members: keypairs
+            .iter()
+            .map(|key| Member::new(key.pubkey()))
+            .collect(),
+        unknown_tail: vec![],
+    };
+    let data = multisig_account_data(&account);
+    let account = Multisig::try_from_slice(&data).expect("fixture encodes a valid multisig");
+
+    MultisigFixture {
+        create_key,
+        members: keypairs,
+        address,
+        account,
+        data,
+    }
+}
+
+/// Encode a multisig the way the program stores it
+///
+/// The program sizes the account for a rent collector and serializes `None` as a single
+/// byte, so an account without one ends with 32 zero bytes.
+pub fn multisig_account_data(multisig: &Multisig) -> Vec<u8> {
+    let mut data = Multisig::DISCRIMINATOR.to_vec();
+    let fields = (
+        multisig.create_key,
+        multisig.config_authority,
+        multisig.threshold,
+        multisig.time_lock,
+        multisig.transaction_index,
+        multisig.stale_transaction_index,
+        multisig.rent_collector,
+        multisig.bump,
+        &multisig.members,
+    );
+    fields
+        .serialize(&mut data)
+        .expect("writing to a Vec cannot fail");
+    if multisig.rent_collector.is_none() {
+        data.extend_from_slice(&[0u8; 32]);
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accounts::ParseOptions;
+
+    #[test]
+    fn test_multisig_fixture() {
+        let fixture = multisig(1, 3, 2);
+        assert_eq!(fixture.account.members.len(), 3);
+        assert_eq!(fixture.account.threshold, 2);
+        assert_eq!(
+            fixture.data.len(),
+            8 + 32 + 32 + 2 + 4 + 8 + 8 + 1 + 32 + 1 + 4 + 3 * 33
+        );
+        for (keypair, member) in fixture.members.iter().zip(&fixture.account.members) {
+            assert_eq!(keypair.pubkey(), member.key);
+        }
+
+        // The same seed gives the same bytes, and strict parsing accepts them
+        assert_eq!(multisig(1, 3, 2).data, fixture.data);
+        assert_ne!(multisig(2, 3, 2).data, fixture.data);
+        let strict = ParseOptions::new().strict(true);
+        assert_eq!(
+            Multisig::try_from_slice_with(&fixture.data, &strict).unwrap(),
+            fixture.account
+        );
+    }
+}
@@ -0,0 +1,42 @@
+//! Deterministic keypairs
+//!
+//! Keypairs are derived from a seed by hashing it with the key's index, so keypair `i`
+//! of a seed never changes, whatever the number of keys requested.
+
+use solana_sdk::{hash::hashv, signature::Keypair};
+
+/// Domain separator of the key derivation
+const DOMAIN: &[u8] = b"squads-v4-client:test-keypair";
+
+/// The first `n` keypairs of `seed`
+pub fn deterministic(seed: u64, n: usize) -> Vec<Keypair> {
+    (0..n as u64).map(|index| keypair(seed, index)).collect()
+}
+
+/// Keypair number `index` of `seed`
+pub fn keypair(seed: u64, index: u64) -> Keypair {
+    let secret = hashv(&[DOMAIN, &seed.to_le_bytes(), &index.to_le_bytes()]);
+    Keypair::new_from_array(secret.to_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signer::Signer;
+
+    #[test]
+    fn test_deterministic_keys() {
+        let keys = deterministic(7, 3);
+        assert_eq!(keys.len(), 3);
+        assert_eq!(keys[2].pubkey(), keypair(7, 2).pubkey());
+        assert_eq!(deterministic(7, 1)[0].pubkey(), keys[0].pubkey());
+        assert_ne!(keys[0].pubkey(), keys[1].pubkey());
+        assert_ne!(keypair(8, 0).pubkey(), keys[0].pubkey());
+
+        // Pinned so a change to the derivation is noticed
+        assert_eq!(
+            keypair(0, 0).pubkey().to_string(),
+            "HwYwaiNd2Z7eLziK26x53d492UnHXxWZiHB24GCUsYRi"
+        );
+    }
+}
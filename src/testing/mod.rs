@@ -0,0 +1,14 @@
+//! Test utilities
+//!
+//! [`keys`] and [`fixtures`] produce the same keypairs and account bytes on every run
+//! and machine, so snapshot tests in downstream crates are reproducible. With the
+//! `async` feature, `airdrop_and_wait` funds accounts on devnet and local validators.
+
+pub mod fixtures;
+pub mod keys;
+
+#[cfg(feature = "async")]
+mod airdrop;
+
+#[cfg(feature = "async")]
+pub use airdrop::{airdrop_and_wait, airdrop_and_wait_with_config, AirdropConfig};
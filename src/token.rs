@@ -0,0 +1,163 @@
+//! SPL token helpers for vault transactions
+//!
+//! Minimal builders for the SPL Token and Associated Token Account instructions that vault
+//! proposals need most often, together with wrap/unwrap SOL message builders. They are
+//! implemented directly so the crate does not depend on the SPL program crates.
+
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use solana_sdk_ids::system_program;
+use solana_system_interface::instruction as system_instruction;
+
+use crate::error::{SquadsError, SquadsResult};
+use crate::message::TransactionMessage;
+use crate::programs::{ASSOCIATED_TOKEN_PROGRAM_ID, NATIVE_MINT, TOKEN_PROGRAM_ID};
+
+/// SPL Token `CloseAccount` instruction index
+const TOKEN_IX_CLOSE_ACCOUNT: u8 = 9;
+/// SPL Token `SyncNative` instruction index
+const TOKEN_IX_SYNC_NATIVE: u8 = 17;
+/// Associated Token Account `CreateIdempotent` instruction index
+const ATA_IX_CREATE_IDEMPOTENT: u8 = 1;
+
+/// Derive the associated token account address for a wallet and mint
+///
+/// # Arguments
+/// * `wallet` - Owner of the token account (e.g. a vault PDA)
+/// * `mint` - Token mint
+/// * `token_program` - Token program owning the mint (Token or Token-2022)
+pub fn get_associated_token_address(
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[wallet.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Create an associated token account if it does not already exist
+///
+/// # Arguments
+/// * `payer` - Funding account
+/// * `wallet` - Owner of the new token account
+/// * `mint` - Token mint
+/// * `token_program` - Token program owning the mint
+pub fn create_associated_token_account_idempotent(
+    payer: &Pubkey,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    let associated_account = get_associated_token_address(wallet, mint, token_program);
+
+    Instruction {
+        program_id: ASSOCIATED_TOKEN_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(associated_account, false),
+            AccountMeta::new_readonly(*wallet, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(*token_program, false),
+        ],
+        data: vec![ATA_IX_CREATE_IDEMPOTENT],
+    }
+}
+
+/// Sync a native (wSOL) token account's amount with its lamport balance
+pub fn sync_native(token_program: &Pubkey, account: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *token_program,
+        accounts: vec![AccountMeta::new(*account, false)],
+        data: vec![TOKEN_IX_SYNC_NATIVE],
+    }
+}
+
+/// Close a token account, sending its lamports to `destination`
+pub fn close_account(
+    token_program: &Pubkey,
+    account: &Pubkey,
+    destination: &Pubkey,
+    owner: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *token_program,
+        accounts: vec![
+            AccountMeta::new(*account, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+        data: vec![TOKEN_IX_CLOSE_ACCOUNT],
+    }
+}
+
+/// Instructions that wrap vault SOL into the vault's wSOL associated token account
+///
+/// Creates the account if needed, transfers `lamports` into it and syncs its balance.
+pub fn wrap_sol_instructions(vault: &Pubkey, lamports: u64) -> Vec<Instruction> {
+    let wsol_account = get_associated_token_address(vault, &NATIVE_MINT, &TOKEN_PROGRAM_ID);
+
+    vec![
+        create_associated_token_account_idempotent(vault, vault, &NATIVE_MINT, &TOKEN_PROGRAM_ID),
+        system_instruction::transfer(vault, &wsol_account, lamports),
+        sync_native(&TOKEN_PROGRAM_ID, &wsol_account),
+    ]
+}
+
+/// Instructions that unwrap all of the vault's wSOL by closing its associated token account
+pub fn unwrap_sol_instructions(vault: &Pubkey) -> Vec<Instruction> {
+    let wsol_account = get_associated_token_address(vault, &NATIVE_MINT, &TOKEN_PROGRAM_ID);
+    vec![close_account(
+        &TOKEN_PROGRAM_ID,
+        &wsol_account,
+        vault,
+        vault,
+    )]
+}
+
+/// Vault transaction message that wraps `lamports` of vault SOL into wSOL
+pub fn wrap_sol_message(vault: &Pubkey, lamports: u64) -> SquadsResult<TransactionMessage> {
+    TransactionMessage::try_compile(vault, &wrap_sol_instructions(vault, lamports))
+        .map_err(|_| SquadsError::InvalidTransactionMessage)
+}
+
+/// Vault transaction message that unwraps all of the vault's wSOL back to SOL
+pub fn unwrap_sol_message(vault: &Pubkey) -> SquadsResult<TransactionMessage> {
+    TransactionMessage::try_compile(vault, &unwrap_sol_instructions(vault))
+        .map_err(|_| SquadsError::InvalidTransactionMessage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_sol_instructions() {
+        let vault = Pubkey::new_unique();
+        let wsol = get_associated_token_address(&vault, &NATIVE_MINT, &TOKEN_PROGRAM_ID);
+        let ixs = wrap_sol_instructions(&vault, 1_000);
+
+        assert_eq!(ixs.len(), 3);
+        assert_eq!(ixs[0].accounts[1].pubkey, wsol);
+        assert_eq!(ixs[1].accounts[1].pubkey, wsol);
+        assert_eq!(ixs[2].accounts[0].pubkey, wsol);
+
+        let message = wrap_sol_message(&vault, 1_000).unwrap();
+        assert_eq!(message.num_signers, 1);
+    }
+
+    #[test]
+    fn test_unwrap_sol_returns_to_vault() {
+        let vault = Pubkey::new_unique();
+        let ixs = unwrap_sol_instructions(&vault);
+
+        assert_eq!(ixs[0].accounts[1].pubkey, vault);
+        assert_eq!(ixs[0].accounts[2].pubkey, vault);
+        assert!(ixs[0].accounts[2].is_signer);
+    }
+}
@@ -5,7 +5,9 @@
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
-use solana_sdk::pubkey::Pubkey;
+use solana_program::pubkey::Pubkey;
+
+use crate::error::SquadsError;
 
 /// Permission flags for multisig members
 ///
@@ -24,7 +26,9 @@ pub enum Permission {
 }
 
 /// Permissions bitmask for a member
-#[derive(Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
+)]
 pub struct Permissions {
     /// Bitmask of permissions
     pub mask: u8,
@@ -79,6 +83,11 @@ impl Permissions {
 #[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
 pub struct Member {
     /// Public key of the member
+    #[serde(deserialize_with = "crate::serde_utils::pubkey")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serde_utils::serialize_pubkey")
+    )]
     pub key: Pubkey,
     /// Permissions granted to this member
     pub permissions: Permissions,
@@ -117,9 +126,176 @@ pub enum ProposalStatus {
     Cancelled { timestamp: i64 },
 }
 
+impl ProposalStatus {
+    /// Unix timestamp at which the status was set
+    pub fn timestamp(&self) -> i64 {
+        match self {
+            ProposalStatus::Draft { timestamp }
+            | ProposalStatus::Active { timestamp }
+            | ProposalStatus::Rejected { timestamp }
+            | ProposalStatus::Approved { timestamp }
+            | ProposalStatus::Executed { timestamp }
+            | ProposalStatus::Cancelled { timestamp } => *timestamp,
+        }
+    }
+
+    /// Whether no further transitions are possible from this status
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            ProposalStatus::Rejected { .. }
+                | ProposalStatus::Executed { .. }
+                | ProposalStatus::Cancelled { .. }
+        )
+    }
+
+    /// Position of the variant in the program's encoding, used as a sort key
+    pub fn ordinal(&self) -> u8 {
+        match self {
+            ProposalStatus::Draft { .. } => 0,
+            ProposalStatus::Active { .. } => 1,
+            ProposalStatus::Rejected { .. } => 2,
+            ProposalStatus::Approved { .. } => 3,
+            ProposalStatus::Executed { .. } => 4,
+            ProposalStatus::Cancelled { .. } => 5,
+        }
+    }
+
+    /// Whether the program's state machine allows moving from this status to `next`
+    ///
+    /// This only checks the shape of the transition; see [`ProposalStatus::check_transition`]
+    /// for the vote count and time lock conditions.
+    pub fn can_transition_to(&self, next: &ProposalStatus) -> bool {
+        matches!(
+            (self, next),
+            (ProposalStatus::Draft { .. }, ProposalStatus::Active { .. })
+                | (
+                    ProposalStatus::Active { .. },
+                    ProposalStatus::Approved { .. }
+                )
+                | (
+                    ProposalStatus::Active { .. },
+                    ProposalStatus::Rejected { .. }
+                )
+                | (
+                    ProposalStatus::Approved { .. },
+                    ProposalStatus::Executed { .. }
+                )
+                | (
+                    ProposalStatus::Approved { .. },
+                    ProposalStatus::Cancelled { .. }
+                )
+        )
+    }
+
+    /// Check that moving from this status to `next` is allowed under the given conditions
+    ///
+    /// # Arguments
+    /// * `next` - The status to move to (its timestamp is used as the transition time)
+    /// * `ctx` - Vote counts and multisig settings at the time of the transition
+    pub fn check_transition(
+        &self,
+        next: &ProposalStatus,
+        ctx: &TransitionContext,
+    ) -> Result<(), SquadsError> {
+        let invalid = |reason: String| {
+            Err(SquadsError::InvalidProposalTransition(format!(
+                "{:?} -> {:?}: {}",
+                self, next, reason
+            )))
+        };
+
+        if !self.can_transition_to(next) {
+            return invalid("not allowed by the proposal state machine".to_string());
+        }
+
+        match next {
+            ProposalStatus::Approved { .. } if ctx.approvals < ctx.threshold => invalid(format!(
+                "{} approvals, threshold is {}",
+                ctx.approvals, ctx.threshold
+            )),
+            ProposalStatus::Rejected { .. } if ctx.rejections < ctx.cutoff => invalid(format!(
+                "{} rejections, cutoff is {}",
+                ctx.rejections, ctx.cutoff
+            )),
+            ProposalStatus::Cancelled { .. } if ctx.cancellations < ctx.threshold => {
+                invalid(format!(
+                    "{} cancellations, threshold is {}",
+                    ctx.cancellations, ctx.threshold
+                ))
+            }
+            ProposalStatus::Executed { timestamp } => {
+                let unlocks_at = self.timestamp().saturating_add(i64::from(ctx.time_lock));
+                if *timestamp < unlocks_at {
+                    invalid(format!("time lock active until {}", unlocks_at))
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Conditions that gate proposal status transitions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransitionContext {
+    /// Number of approvals cast
+    pub approvals: usize,
+    /// Number of rejections cast
+    pub rejections: usize,
+    /// Number of cancellations cast
+    pub cancellations: usize,
+    /// Approval threshold of the multisig
+    pub threshold: usize,
+    /// Rejection cutoff of the multisig
+    pub cutoff: usize,
+    /// Time lock in seconds between approval and execution
+    pub time_lock: u32,
+}
+
+/// Kind of transaction a proposal is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransactionKind {
+    /// Vault transaction
+    Vault,
+    /// Config transaction
+    Config,
+    /// Batch
+    Batch,
+}
+
+/// Order of the results of listing and scanning APIs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Smallest key first
+    #[default]
+    Ascending,
+    /// Largest key first
+    Descending,
+}
+
+impl SortOrder {
+    /// Sort `items` by `key` in this order
+    ///
+    /// The sort is stable, so items with equal keys keep their relative order.
+    pub fn sort_by_key<T, K: Ord>(self, items: &mut [T], mut key: impl FnMut(&T) -> K) {
+        match self {
+            SortOrder::Ascending => items.sort_by_key(key),
+            SortOrder::Descending => items.sort_by_key(|item| std::cmp::Reverse(key(item))),
+        }
+    }
+}
+
 /// Period type for time-based limits
+///
+/// Variant order matches the program's Borsh encoding.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub enum Period {
+    /// The limit never resets
+    OneTime,
     /// Daily period
     Day,
     /// Weekly period  
@@ -128,8 +304,64 @@ pub enum Period {
     Month,
 }
 
+/// A reset window of a spending limit, from `start` (inclusive) to `end` (inclusive)
+///
+/// The program only resets a limit once strictly more than one period has passed since
+/// the last reset, so a use at exactly `end` still counts against this window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeriodWindow {
+    /// Unix timestamp the window starts at (the limit's `last_reset` after any reset)
+    pub start: i64,
+    /// Unix timestamp of the last second in the window
+    pub end: i64,
+}
+
+impl PeriodWindow {
+    /// Whether `timestamp` falls inside the window
+    pub fn contains(&self, timestamp: i64) -> bool {
+        (self.start..=self.end).contains(&timestamp)
+    }
+}
+
+impl Period {
+    /// Length of the period in seconds, or None for [`Period::OneTime`]
+    ///
+    /// Months are a fixed 30 days, as on-chain. Periods are not aligned to UTC calendar
+    /// days, weeks or months; they run from the limit's last reset.
+    pub fn to_seconds(&self) -> Option<i64> {
+        const DAY: i64 = 24 * 60 * 60;
+        match self {
+            Period::OneTime => None,
+            Period::Day => Some(DAY),
+            Period::Week => Some(7 * DAY),
+            Period::Month => Some(30 * DAY),
+        }
+    }
+
+    /// Window a use at `timestamp` is counted in, given the limit's `last_reset`
+    ///
+    /// Mirrors `spending_limit_use`: if more than one period has passed since
+    /// `last_reset`, the reset advances by whole periods. Timestamps before `last_reset`
+    /// fall in the current window. Returns None for [`Period::OneTime`].
+    pub fn window_containing(&self, last_reset: i64, timestamp: i64) -> Option<PeriodWindow> {
+        let period = self.to_seconds()?;
+        let elapsed = timestamp.saturating_sub(last_reset);
+        let start = if elapsed > period {
+            last_reset.saturating_add(elapsed / period * period)
+        } else {
+            last_reset
+        };
+        Some(PeriodWindow {
+            start,
+            end: start.saturating_add(period),
+        })
+    }
+}
+
 /// Actions that can be performed in a config transaction
 #[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde-args", derive(Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub enum ConfigAction {
     /// Add a new member to the multisig
     AddMember {
@@ -139,6 +371,14 @@ pub enum ConfigAction {
     /// Remove a member from the multisig
     RemoveMember {
         /// Public key of the member to remove
+        #[cfg_attr(
+            feature = "serde-args",
+            serde(deserialize_with = "crate::serde_utils::pubkey")
+        )]
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "crate::serde_utils::serialize_pubkey")
+        )]
         old_member: Pubkey,
     },
     /// Change the approval threshold
@@ -154,37 +394,106 @@ pub enum ConfigAction {
     /// Add a spending limit
     AddSpendingLimit {
         /// Unique key for this spending limit
+        #[cfg_attr(
+            feature = "serde-args",
+            serde(deserialize_with = "crate::serde_utils::pubkey")
+        )]
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "crate::serde_utils::serialize_pubkey")
+        )]
         create_key: Pubkey,
         /// Vault index this limit applies to
         vault_index: u8,
         /// Token mint (None for SOL)
+        #[cfg_attr(
+            feature = "serde-args",
+            serde(deserialize_with = "crate::serde_utils::pubkey")
+        )]
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "crate::serde_utils::serialize_pubkey")
+        )]
         mint: Pubkey,
         /// Amount limit
         amount: u64,
         /// Time period for the limit
         period: Period,
         /// Members who can use this limit
+        #[cfg_attr(
+            feature = "serde-args",
+            serde(deserialize_with = "crate::serde_utils::vec_pubkey")
+        )]
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "crate::serde_utils::serialize_vec_pubkey")
+        )]
         members: Vec<Pubkey>,
         /// Destinations allowed
+        #[cfg_attr(
+            feature = "serde-args",
+            serde(deserialize_with = "crate::serde_utils::vec_pubkey")
+        )]
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "crate::serde_utils::serialize_vec_pubkey")
+        )]
         destinations: Vec<Pubkey>,
     },
     /// Remove a spending limit
     RemoveSpendingLimit {
         /// Key of the spending limit to remove
+        #[cfg_attr(
+            feature = "serde-args",
+            serde(deserialize_with = "crate::serde_utils::pubkey")
+        )]
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "crate::serde_utils::serialize_pubkey")
+        )]
         spending_limit: Pubkey,
     },
     /// Set the config authority
     SetConfigAuthority {
         /// New config authority (None to remove)
+        #[cfg_attr(
+            feature = "serde-args",
+            serde(default, deserialize_with = "crate::serde_utils::option_pubkey")
+        )]
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "crate::serde_utils::serialize_option_pubkey")
+        )]
         new_config_authority: Option<Pubkey>,
     },
     /// Set the rent collector
     SetRentCollector {
         /// New rent collector (None for default)
+        #[cfg_attr(
+            feature = "serde-args",
+            serde(default, deserialize_with = "crate::serde_utils::option_pubkey")
+        )]
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "crate::serde_utils::serialize_option_pubkey")
+        )]
         new_rent_collector: Option<Pubkey>,
     },
 }
 
+impl ConfigAction {
+    /// Whether executing the action makes all pending transactions stale
+    ///
+    /// Spending limit changes do not touch the multisig's configuration and leave
+    /// pending transactions valid.
+    pub fn invalidates_pending_transactions(&self) -> bool {
+        !matches!(
+            self,
+            ConfigAction::AddSpendingLimit { .. } | ConfigAction::RemoveSpendingLimit { .. }
+        )
+    }
+}
+
 /// Small vector type for efficient storage
 /// This matches the SmallVec used in the original program
 pub type SmallVec<T> = Vec<T>;
@@ -231,4 +540,126 @@ mod tests {
         assert!(member.permissions.has_vote());
         assert!(!member.permissions.has_execute());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_proposal_status_transitions() {
+        let draft = ProposalStatus::Draft { timestamp: 0 };
+        let active = ProposalStatus::Active { timestamp: 10 };
+        let approved = ProposalStatus::Approved { timestamp: 100 };
+        let executed = ProposalStatus::Executed { timestamp: 160 };
+
+        assert!(draft.can_transition_to(&active));
+        assert!(!draft.can_transition_to(&approved));
+        assert!(!executed.can_transition_to(&active));
+        assert!(executed.is_terminal());
+
+        let ctx = TransitionContext {
+            approvals: 1,
+            rejections: 0,
+            cancellations: 0,
+            threshold: 2,
+            cutoff: 2,
+            time_lock: 60,
+        };
+        assert!(active.check_transition(&approved, &ctx).is_err());
+        let ctx = TransitionContext {
+            approvals: 2,
+            ..ctx
+        };
+        assert!(active.check_transition(&approved, &ctx).is_ok());
+
+        // The time lock counts from the approval timestamp
+        assert!(approved.check_transition(&executed, &ctx).is_ok());
+        let early = ProposalStatus::Executed { timestamp: 159 };
+        assert!(approved.check_transition(&early, &ctx).is_err());
+
+        // Decoded timestamps near the limit must not overflow the unlock time
+        let corrupt = ProposalStatus::Approved {
+            timestamp: i64::MAX,
+        };
+        assert!(corrupt.check_transition(&executed, &ctx).is_err());
+    }
+
+    #[test]
+    fn test_period_borsh_matches_program() {
+        assert_eq!(borsh::to_vec(&Period::OneTime).unwrap(), vec![0]);
+        assert_eq!(borsh::to_vec(&Period::Day).unwrap(), vec![1]);
+        assert_eq!(borsh::to_vec(&Period::Month).unwrap(), vec![3]);
+    }
+
+    #[test]
+    fn test_period_window_boundaries() {
+        const DAY: i64 = 86_400;
+        let last_reset = 1_700_000_000;
+        let day = Period::Day;
+
+        // Exactly one period later is still the current window
+        let current = PeriodWindow {
+            start: last_reset,
+            end: last_reset + DAY,
+        };
+        assert_eq!(day.window_containing(last_reset, last_reset), Some(current));
+        assert_eq!(
+            day.window_containing(last_reset, last_reset + DAY),
+            Some(current)
+        );
+        assert!(current.contains(last_reset + DAY));
+        assert!(!current.contains(last_reset - 1));
+
+        // One second past resets by a whole period
+        let next = day
+            .window_containing(last_reset, last_reset + DAY + 1)
+            .unwrap();
+        assert_eq!(next.start, last_reset + DAY);
+        assert_eq!(next.end, last_reset + 2 * DAY);
+
+        // Several periods skipped land on a period boundary, including exact multiples
+        let later = day
+            .window_containing(last_reset, last_reset + 5 * DAY)
+            .unwrap();
+        assert_eq!(later.start, last_reset + 5 * DAY);
+        let later = day
+            .window_containing(last_reset, last_reset + 5 * DAY - 1)
+            .unwrap();
+        assert_eq!(later.start, last_reset + 4 * DAY);
+
+        // Clock skew before the last reset stays in the current window
+        assert_eq!(
+            day.window_containing(last_reset, last_reset - 10),
+            Some(current)
+        );
+
+        // Weeks and months are fixed lengths, not calendar-aligned
+        let week = Period::Week
+            .window_containing(last_reset, last_reset + 8 * DAY)
+            .unwrap();
+        assert_eq!(week.start, last_reset + 7 * DAY);
+
+        // 2024-01-31T00:00:00Z: a "month" later is 2024-03-01, not the end of February
+        let jan_31 = 1_706_659_200;
+        let month = Period::Month.window_containing(jan_31, jan_31).unwrap();
+        assert_eq!(month.end, 1_709_251_200);
+        let month = Period::Month
+            .window_containing(jan_31, 1_709_251_201)
+            .unwrap();
+        assert_eq!(month.start, 1_709_251_200);
+
+        assert_eq!(Period::OneTime.to_seconds(), None);
+        assert_eq!(
+            Period::OneTime.window_containing(last_reset, i64::MAX),
+            None
+        );
+        assert!(Period::Month
+            .window_containing(i64::MIN, i64::MAX)
+            .is_some());
+    }
+
+    #[test]
+    fn test_sort_order() {
+        let mut items = vec![(2, 'a'), (1, 'b'), (2, 'c'), (3, 'd')];
+        SortOrder::Descending.sort_by_key(&mut items, |(key, _)| *key);
+        assert_eq!(items, vec![(3, 'd'), (2, 'a'), (2, 'c'), (1, 'b')]);
+        SortOrder::default().sort_by_key(&mut items, |(key, _)| *key);
+        assert_eq!(items, vec![(1, 'b'), (2, 'a'), (2, 'c'), (3, 'd')]);
+    }
+}
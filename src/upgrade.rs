@@ -0,0 +1,272 @@
+//! Program upgrades governed by a squad
+//!
+//! Upgrading a program whose upgrade authority is a vault takes three stages:
+//!
+//! 1. Outside the squad, a member uploads the new program into a buffer account of the
+//!    upgradeable BPF loader, one write transaction per chunk ([`UpgradePlan::setup`]
+//!    and [`UpgradePlan::writes`]).
+//! 2. The uploader hands the buffer authority over to the vault
+//!    ([`UpgradePlan::handover`]), since the loader only upgrades from buffers owned by
+//!    the program's upgrade authority.
+//! 3. The squad votes on a vault transaction running [`UpgradePlan::upgrade`].
+//!
+//! [`UpgradePlan`] builds every instruction of the flow up front; with the `async`
+//! feature, `SquadsClient::propose_program_upgrade` sends them and creates the proposal.
+//! The loader instructions are built here so the crate does not need to depend on the
+//! loader interface crate.
+
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    sysvar,
+};
+
+use crate::error::{SquadsError, SquadsResult};
+use crate::programs::BPF_LOADER_UPGRADEABLE_PROGRAM_ID;
+
+/// Size of the loader state that precedes the program bytes in a buffer account
+pub const BUFFER_METADATA_SIZE: usize = 37;
+
+/// Bytes written per write instruction by default
+///
+/// Leaves room in a legacy transaction for a fee payer separate from the buffer
+/// authority.
+pub const DEFAULT_WRITE_CHUNK_SIZE: usize = 900;
+
+/// Size of a buffer account holding a program of `program_len` bytes
+pub fn buffer_size(program_len: usize) -> usize {
+    BUFFER_METADATA_SIZE + program_len
+}
+
+/// Program data account of an upgradeable program
+pub fn get_program_data_address(program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[program.as_ref()], &BPF_LOADER_UPGRADEABLE_PROGRAM_ID).0
+}
+
+/// Serialize a loader instruction: a u32 variant index followed by its fields
+fn loader_data(variant: u32, fields: &[u8]) -> Vec<u8> {
+    let mut data = variant.to_le_bytes().to_vec();
+    data.extend_from_slice(fields);
+    data
+}
+
+/// Initialize a buffer account created with [`buffer_size`] bytes, owned by the loader
+///
+/// # Arguments
+/// * `buffer` - Buffer account
+/// * `authority` - Authority allowed to write to the buffer
+pub fn initialize_buffer(buffer: Pubkey, authority: Pubkey) -> Instruction {
+    Instruction {
+        program_id: BPF_LOADER_UPGRADEABLE_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(buffer, false),
+            AccountMeta::new_readonly(authority, false),
+        ],
+        data: loader_data(0, &[]),
+    }
+}
+
+/// Write `bytes` into a buffer at `offset`
+///
+/// # Arguments
+/// * `buffer` - Buffer account
+/// * `authority` - Buffer authority (must be signer)
+/// * `offset` - Offset into the program bytes
+/// * `bytes` - Program bytes to write
+pub fn write(buffer: Pubkey, authority: Pubkey, offset: u32, bytes: &[u8]) -> Instruction {
+    let mut fields = offset.to_le_bytes().to_vec();
+    fields.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+    fields.extend_from_slice(bytes);
+    Instruction {
+        program_id: BPF_LOADER_UPGRADEABLE_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(buffer, false),
+            AccountMeta::new_readonly(authority, true),
+        ],
+        data: loader_data(1, &fields),
+    }
+}
+
+/// Upgrade a program from a buffer
+///
+/// # Arguments
+/// * `program` - Program to upgrade
+/// * `buffer` - Buffer holding the new program
+/// * `authority` - Upgrade authority of the program, and authority of the buffer (must be signer)
+/// * `spill` - Account receiving the buffer's lamports
+pub fn upgrade(program: Pubkey, buffer: Pubkey, authority: Pubkey, spill: Pubkey) -> Instruction {
+    Instruction {
+        program_id: BPF_LOADER_UPGRADEABLE_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(get_program_data_address(&program), false),
+            AccountMeta::new(program, false),
+            AccountMeta::new(buffer, false),
+            AccountMeta::new(spill, false),
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+            AccountMeta::new_readonly(sysvar::clock::ID, false),
+            AccountMeta::new_readonly(authority, true),
+        ],
+        data: loader_data(3, &[]),
+    }
+}
+
+/// Change the authority of a buffer
+///
+/// # Arguments
+/// * `buffer` - Buffer account
+/// * `current_authority` - Current buffer authority (must be signer)
+/// * `new_authority` - New buffer authority
+pub fn set_buffer_authority(
+    buffer: Pubkey,
+    current_authority: Pubkey,
+    new_authority: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: BPF_LOADER_UPGRADEABLE_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(buffer, false),
+            AccountMeta::new_readonly(current_authority, true),
+            AccountMeta::new_readonly(new_authority, false),
+        ],
+        data: loader_data(4, &[]),
+    }
+}
+
+/// Every instruction of a squad-governed program upgrade
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpgradePlan {
+    /// Buffer account the program is uploaded into
+    pub buffer: Pubkey,
+    /// Create and initialize the buffer (signed by the payer and the buffer keypair)
+    pub setup: Vec<Instruction>,
+    /// One write instruction per chunk (signed by the uploader), each sent in its own
+    /// transaction
+    pub writes: Vec<Instruction>,
+    /// Hand the buffer authority over to the vault (signed by the uploader)
+    pub handover: Instruction,
+    /// Upgrade instruction for the vault transaction the squad votes on
+    pub upgrade: Instruction,
+}
+
+impl UpgradePlan {
+    /// Plan the upload of `program_bytes` and the upgrade of `program` by `vault`
+    ///
+    /// # Arguments
+    /// * `payer` - Pays for the buffer account
+    /// * `uploader` - Buffer authority during the upload
+    /// * `buffer` - Address of the buffer account to create (a fresh keypair)
+    /// * `buffer_lamports` - Rent-exempt balance for [`buffer_size`] bytes
+    /// * `program` - Program to upgrade, whose upgrade authority is `vault`
+    /// * `vault` - Vault PDA executing the upgrade
+    /// * `spill` - Account receiving the buffer's lamports after the upgrade
+    /// * `program_bytes` - The new program (ELF)
+    /// * `chunk_size` - Bytes per write, e.g. [`DEFAULT_WRITE_CHUNK_SIZE`]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        payer: Pubkey,
+        uploader: Pubkey,
+        buffer: Pubkey,
+        buffer_lamports: u64,
+        program: Pubkey,
+        vault: Pubkey,
+        spill: Pubkey,
+        program_bytes: &[u8],
+        chunk_size: usize,
+    ) -> SquadsResult<Self> {
+        if program_bytes.is_empty() || chunk_size == 0 {
+            return Err(SquadsError::InvalidConfig(
+                "program and chunk size must not be empty".to_string(),
+            ));
+        }
+        if u32::try_from(program_bytes.len()).is_err() {
+            return Err(SquadsError::InvalidConfig(format!(
+                "program of {} bytes is too large for the loader",
+                program_bytes.len()
+            )));
+        }
+
+        let setup = vec![
+            solana_system_interface::instruction::create_account(
+                &payer,
+                &buffer,
+                buffer_lamports,
+                buffer_size(program_bytes.len()) as u64,
+                &BPF_LOADER_UPGRADEABLE_PROGRAM_ID,
+            ),
+            initialize_buffer(buffer, uploader),
+        ];
+        let writes = program_bytes
+            .chunks(chunk_size)
+            .enumerate()
+            .map(|(i, chunk)| write(buffer, uploader, (i * chunk_size) as u32, chunk))
+            .collect();
+
+        Ok(Self {
+            buffer,
+            setup,
+            writes,
+            handover: set_buffer_authority(buffer, uploader, vault),
+            upgrade: upgrade(program, buffer, vault, spill),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upgrade_plan() {
+        let payer = Pubkey::new_unique();
+        let buffer = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        let vault = Pubkey::new_unique();
+        let program_bytes: Vec<u8> = (0..2000u32).map(|i| i as u8).collect();
+
+        let plan = UpgradePlan::new(
+            payer,
+            payer,
+            buffer,
+            1_000,
+            program,
+            vault,
+            payer,
+            &program_bytes,
+            DEFAULT_WRITE_CHUNK_SIZE,
+        )
+        .unwrap();
+
+        assert_eq!(plan.setup.len(), 2);
+        assert_eq!(plan.setup[1].data, vec![0, 0, 0, 0]);
+        assert_eq!(plan.writes.len(), 3);
+        let last = &plan.writes[2];
+        assert_eq!(&last.data[..4], &1u32.to_le_bytes());
+        assert_eq!(&last.data[4..8], &1800u32.to_le_bytes());
+        assert_eq!(&last.data[8..16], &200u64.to_le_bytes());
+        assert_eq!(&last.data[16..], &program_bytes[1800..]);
+        assert!(last.accounts[1].is_signer);
+
+        assert_eq!(plan.handover.accounts[2].pubkey, vault);
+        assert_eq!(plan.upgrade.data, 3u32.to_le_bytes().to_vec());
+        assert_eq!(
+            plan.upgrade.accounts[0].pubkey,
+            get_program_data_address(&program)
+        );
+        assert_eq!(plan.upgrade.accounts[6].pubkey, vault);
+        assert!(plan.upgrade.accounts[6].is_signer);
+
+        // Every write fits a legacy transaction with a separate fee payer
+        let uploader = Pubkey::new_unique();
+        let message = solana_sdk::message::Message::new(&plan.writes[..1], Some(&uploader));
+        let tx = solana_sdk::transaction::Transaction::new_unsigned(message);
+        assert!(serialized_size(&tx) <= 1232);
+
+        assert!(UpgradePlan::new(payer, payer, buffer, 0, program, vault, payer, &[], 10).is_err());
+    }
+
+    /// Serialized size of a transaction with two signatures
+    fn serialized_size(tx: &solana_sdk::transaction::Transaction) -> usize {
+        let message = tx.message.serialize().len();
+        1 + 2 * 64 + message
+    }
+}
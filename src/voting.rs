@@ -0,0 +1,490 @@
+//! Vote tallying for Squads proposals
+//!
+//! The Squads v4 program counts one vote per member with Vote permission and compares
+//! the count to `Multisig::threshold`. Forks that introduce per-member weights can
+//! implement [`VotingModel`] and reuse the tally helpers in this module unchanged.
+//!
+//! [`VoteSet`] wraps the voter lists stored on a proposal with deduplicated, sorted
+//! storage for fast lookups and deterministic display.
+
+use std::collections::BTreeSet;
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::accounts::{Multisig, Proposal};
+use crate::types::ProposalStatus;
+
+/// A deduplicated set of voters with sorted, stable iteration order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VoteSet(BTreeSet<Pubkey>);
+
+impl VoteSet {
+    /// Create an empty set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether `voter` is in the set
+    pub fn contains(&self, voter: &Pubkey) -> bool {
+        self.0.contains(voter)
+    }
+
+    /// Add a voter, returning false if it was already present
+    pub fn insert(&mut self, voter: Pubkey) -> bool {
+        self.0.insert(voter)
+    }
+
+    /// Number of distinct voters
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether the set is empty
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterate over the voters in ascending key order
+    pub fn iter(&self) -> impl Iterator<Item = &Pubkey> {
+        self.0.iter()
+    }
+
+    /// Voters in either set
+    pub fn union(&self, other: &VoteSet) -> VoteSet {
+        VoteSet(self.0.union(&other.0).copied().collect())
+    }
+
+    /// Voters in both sets
+    pub fn intersection(&self, other: &VoteSet) -> VoteSet {
+        VoteSet(self.0.intersection(&other.0).copied().collect())
+    }
+
+    /// Voters in this set but not in `other`
+    pub fn difference(&self, other: &VoteSet) -> VoteSet {
+        VoteSet(self.0.difference(&other.0).copied().collect())
+    }
+
+    /// Keys from `candidates` that are not in the set, in ascending key order
+    ///
+    /// With a multisig's member keys this lists the members that have not voted yet.
+    pub fn missing<'a>(&self, candidates: impl IntoIterator<Item = &'a Pubkey>) -> VoteSet {
+        VoteSet(
+            candidates
+                .into_iter()
+                .filter(|key| !self.contains(key))
+                .copied()
+                .collect(),
+        )
+    }
+
+    /// The voters as a sorted vector
+    pub fn to_vec(&self) -> Vec<Pubkey> {
+        self.0.iter().copied().collect()
+    }
+}
+
+impl FromIterator<Pubkey> for VoteSet {
+    fn from_iter<I: IntoIterator<Item = Pubkey>>(iter: I) -> Self {
+        VoteSet(iter.into_iter().collect())
+    }
+}
+
+impl From<&[Pubkey]> for VoteSet {
+    fn from(voters: &[Pubkey]) -> Self {
+        voters.iter().copied().collect()
+    }
+}
+
+impl IntoIterator for VoteSet {
+    type Item = Pubkey;
+    type IntoIter = std::collections::btree_set::IntoIter<Pubkey>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a VoteSet {
+    type Item = &'a Pubkey;
+    type IntoIter = std::collections::btree_set::Iter<'a, Pubkey>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// Strategy used to weigh votes and derive approval/rejection thresholds
+pub trait VotingModel {
+    /// Weight of a single member's vote (0 for members that cannot vote)
+    fn weight(&self, multisig: &Multisig, member: &Pubkey) -> u64;
+
+    /// Total weight required to approve a proposal
+    fn approval_threshold(&self, multisig: &Multisig) -> u64;
+
+    /// Total weight required to reject a proposal
+    fn rejection_cutoff(&self, multisig: &Multisig) -> u64;
+
+    /// Combined weight of all members able to vote
+    fn total_weight(&self, multisig: &Multisig) -> u64 {
+        multisig
+            .members
+            .iter()
+            .map(|m| self.weight(multisig, &m.key))
+            .sum()
+    }
+}
+
+/// The Squads v4 voting model: one vote per member with Vote permission
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OneMemberOneVote;
+
+impl VotingModel for OneMemberOneVote {
+    fn weight(&self, multisig: &Multisig, member: &Pubkey) -> u64 {
+        let can_vote = multisig
+            .members
+            .iter()
+            .any(|m| &m.key == member && m.permissions.has_vote());
+        u64::from(can_vote)
+    }
+
+    fn approval_threshold(&self, multisig: &Multisig) -> u64 {
+        u64::from(multisig.threshold)
+    }
+
+    fn rejection_cutoff(&self, multisig: &Multisig) -> u64 {
+        multisig.cutoff() as u64
+    }
+}
+
+/// Result of tallying the votes on a proposal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tally {
+    /// Combined weight of approvals
+    pub approvals: u64,
+    /// Combined weight of rejections
+    pub rejections: u64,
+    /// Weight required to approve
+    pub threshold: u64,
+    /// Weight required to reject
+    pub cutoff: u64,
+}
+
+impl Tally {
+    /// Whether the approvals reach the threshold
+    pub fn is_approved(&self) -> bool {
+        self.approvals >= self.threshold
+    }
+
+    /// Whether the rejections reach the cutoff
+    pub fn is_rejected(&self) -> bool {
+        self.rejections >= self.cutoff
+    }
+
+    /// Additional approval weight needed to reach the threshold
+    pub fn approvals_needed(&self) -> u64 {
+        self.threshold.saturating_sub(self.approvals)
+    }
+}
+
+/// Tally the votes on a proposal using the given voting model
+pub fn tally<M: VotingModel + ?Sized>(
+    proposal: &Proposal,
+    multisig: &Multisig,
+    model: &M,
+) -> Tally {
+    let sum = |voters: &[Pubkey]| -> u64 { voters.iter().map(|v| model.weight(multisig, v)).sum() };
+
+    Tally {
+        approvals: sum(&proposal.approved),
+        rejections: sum(&proposal.rejected),
+        threshold: model.approval_threshold(multisig),
+        cutoff: model.rejection_cutoff(multisig),
+    }
+}
+
+impl Proposal {
+    /// Tally the votes on this proposal with the default one-member-one-vote model
+    pub fn tally(&self, multisig: &Multisig) -> Tally {
+        tally(self, multisig, &OneMemberOneVote)
+    }
+
+    /// Members who approved, as a [`VoteSet`]
+    pub fn approved_set(&self) -> VoteSet {
+        VoteSet::from(self.approved.as_slice())
+    }
+
+    /// Members who rejected, as a [`VoteSet`]
+    pub fn rejected_set(&self) -> VoteSet {
+        VoteSet::from(self.rejected.as_slice())
+    }
+
+    /// Members who cancelled, as a [`VoteSet`]
+    pub fn cancelled_set(&self) -> VoteSet {
+        VoteSet::from(self.cancelled.as_slice())
+    }
+
+    /// Members who approved or rejected
+    pub fn voters(&self) -> VoteSet {
+        self.approved_set().union(&self.rejected_set())
+    }
+
+    /// Members with the Vote permission who have neither approved nor rejected
+    ///
+    /// Keys are returned in the multisig's member order.
+    pub fn outstanding_voters(&self, multisig: &Multisig) -> Vec<Pubkey> {
+        let voters = self.voters();
+        multisig
+            .members
+            .iter()
+            .filter(|member| member.permissions.has_vote() && !voters.contains(&member.key))
+            .map(|member| member.key)
+            .collect()
+    }
+}
+
+/// An action a member can take on a proposal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MemberAction {
+    /// Approve an active proposal
+    Approve,
+    /// Reject an active proposal
+    Reject,
+    /// Cancel an approved proposal
+    Cancel,
+    /// Execute an approved proposal whose time lock has elapsed
+    Execute,
+}
+
+impl Proposal {
+    /// Actions `member` can currently take on this proposal
+    ///
+    /// Mirrors the program's checks: voting needs the Vote permission and a non-stale
+    /// active proposal, and a member cannot repeat the vote they already cast. Executing
+    /// needs the Execute permission and an approved proposal past the time lock. Stale
+    /// approved config transactions cannot be executed, which this does not check since
+    /// the proposal does not record its transaction type.
+    ///
+    /// # Arguments
+    /// * `multisig` - Multisig the proposal belongs to
+    /// * `member` - Member to list actions for
+    /// * `now` - Current unix timestamp
+    pub fn actions_for(&self, multisig: &Multisig, member: &Pubkey, now: i64) -> Vec<MemberAction> {
+        let Some(permissions) = multisig
+            .members
+            .iter()
+            .find(|m| &m.key == member)
+            .map(|m| m.permissions)
+        else {
+            return Vec::new();
+        };
+
+        let mut actions = Vec::new();
+        match self.status {
+            ProposalStatus::Active { .. }
+                if permissions.has_vote()
+                    && self.transaction_index > multisig.stale_transaction_index =>
+            {
+                if !self.has_approved(member) {
+                    actions.push(MemberAction::Approve);
+                }
+                if !self.has_rejected(member) {
+                    actions.push(MemberAction::Reject);
+                }
+            }
+            ProposalStatus::Approved { timestamp } => {
+                if permissions.has_vote() && !self.has_cancelled(member) {
+                    actions.push(MemberAction::Cancel);
+                }
+                if permissions.has_execute()
+                    && now >= timestamp.saturating_add(i64::from(multisig.time_lock))
+                {
+                    actions.push(MemberAction::Execute);
+                }
+            }
+            _ => {}
+        }
+        actions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Member, Permission, Permissions};
+
+    fn multisig(members: Vec<Member>, threshold: u16) -> Multisig {
+        Multisig {
+            create_key: Pubkey::new_unique(),
+            config_authority: Pubkey::default(),
+            threshold,
+            time_lock: 0,
+            transaction_index: 1,
+            stale_transaction_index: 0,
+            rent_collector: None,
+            bump: 255,
+            members,
+            unknown_tail: vec![],
+        }
+    }
+
+    fn proposal(approved: Vec<Pubkey>, rejected: Vec<Pubkey>) -> Proposal {
+        Proposal {
+            multisig: Pubkey::new_unique(),
+            transaction_index: 1,
+            status: ProposalStatus::Active { timestamp: 0 },
+            bump: 255,
+            approved,
+            rejected,
+            cancelled: vec![],
+            unknown_tail: vec![],
+        }
+    }
+
+    #[test]
+    fn test_one_member_one_vote() {
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        let c = Pubkey::new_unique();
+        let ms = multisig(
+            vec![
+                Member::new(a),
+                Member::new(b),
+                Member::with_permissions(c, Permissions::from_vec(&[Permission::Initiate])),
+            ],
+            2,
+        );
+
+        // A non-voter's approval carries no weight
+        let t = proposal(vec![a, c], vec![]).tally(&ms);
+        assert_eq!(t.approvals, 1);
+        assert!(!t.is_approved());
+        assert_eq!(t.approvals_needed(), 1);
+
+        let t = proposal(vec![a, b], vec![]).tally(&ms);
+        assert!(t.is_approved());
+
+        let t = proposal(vec![], vec![a]).tally(&ms);
+        assert!(t.is_rejected());
+    }
+
+    #[test]
+    fn test_vote_set() {
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        let c = Pubkey::new_unique();
+
+        // Duplicates collapse and iteration is sorted regardless of insertion order
+        let approved = VoteSet::from([b, a, b].as_slice());
+        assert_eq!(approved.len(), 2);
+        let mut sorted = vec![a, b];
+        sorted.sort();
+        assert_eq!(approved.to_vec(), sorted);
+
+        let p = proposal(vec![a], vec![c]);
+        assert_eq!(p.voters().len(), 2);
+        assert_eq!(p.voters().missing(&[a, b, c]).to_vec(), vec![b]);
+        assert!(approved.intersection(&p.rejected_set()).is_empty());
+        assert_eq!(approved.difference(&p.approved_set()).to_vec(), vec![b]);
+    }
+
+    #[test]
+    fn test_outstanding_voters() {
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        let c = Pubkey::new_unique();
+        let d = Pubkey::new_unique();
+        let ms = multisig(
+            vec![
+                Member::new(a),
+                Member::new(b),
+                Member::with_permissions(c, Permissions::from_vec(&[Permission::Initiate])),
+                Member::new(d),
+            ],
+            2,
+        );
+
+        assert_eq!(
+            proposal(vec![], vec![]).outstanding_voters(&ms),
+            vec![a, b, d]
+        );
+        assert_eq!(proposal(vec![a], vec![d]).outstanding_voters(&ms), vec![b]);
+        assert!(proposal(vec![a, b], vec![d])
+            .outstanding_voters(&ms)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_actions_for() {
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        let mut ms = multisig(
+            vec![
+                Member::new(a),
+                Member::with_permissions(b, Permissions::from_vec(&[Permission::Execute])),
+            ],
+            1,
+        );
+        ms.time_lock = 60;
+
+        let mut p = proposal(vec![a], vec![]);
+        assert_eq!(p.actions_for(&ms, &a, 0), vec![MemberAction::Reject]);
+        assert!(p.actions_for(&ms, &b, 0).is_empty());
+        assert!(p.actions_for(&ms, &Pubkey::new_unique(), 0).is_empty());
+
+        ms.stale_transaction_index = 1;
+        assert!(p.actions_for(&ms, &a, 0).is_empty());
+
+        p.status = ProposalStatus::Approved { timestamp: 100 };
+        assert_eq!(p.actions_for(&ms, &a, 120), vec![MemberAction::Cancel]);
+        assert!(p.actions_for(&ms, &b, 120).is_empty());
+        assert_eq!(p.actions_for(&ms, &b, 160), vec![MemberAction::Execute]);
+
+        // A corrupt timestamp must not wrap the time lock around to unlocked
+        p.status = ProposalStatus::Approved {
+            timestamp: i64::MAX,
+        };
+        assert!(p.actions_for(&ms, &b, 160).is_empty());
+        assert!(p.actions_for(&ms, &b, i64::MAX - 1).is_empty());
+    }
+
+    #[test]
+    fn test_custom_model() {
+        struct Weighted(Pubkey);
+
+        impl VotingModel for Weighted {
+            fn weight(&self, _multisig: &Multisig, member: &Pubkey) -> u64 {
+                if *member == self.0 {
+                    3
+                } else {
+                    1
+                }
+            }
+
+            fn approval_threshold(&self, _multisig: &Multisig) -> u64 {
+                3
+            }
+
+            fn rejection_cutoff(&self, multisig: &Multisig) -> u64 {
+                self.total_weight(multisig) - 2
+            }
+        }
+
+        let whale = Pubkey::new_unique();
+        let ms = multisig(
+            vec![Member::new(whale), Member::new(Pubkey::new_unique())],
+            2,
+        );
+        let t = tally(&proposal(vec![whale], vec![]), &ms, &Weighted(whale));
+        assert!(t.is_approved());
+        assert_eq!(t.cutoff, 2);
+    }
+
+    #[test]
+    fn test_threshold_above_voters() {
+        let a = Pubkey::new_unique();
+        let ms = multisig(vec![Member::new(a)], 3);
+        let t = proposal(vec![], vec![a]).tally(&ms);
+        assert_eq!(t.cutoff, 1);
+        assert!(t.is_rejected() && !t.is_approved());
+    }
+}
@@ -0,0 +1,842 @@
+//! A single ordered stream of what happens to a multisig
+//!
+//! Three sources describe a multisig's activity: its account state (the multisig and
+//! its proposals), the signature history of the multisig account, and websocket
+//! notifications. Each alone is incomplete: polls miss the transactions behind a state
+//! change, history lacks the resulting state, and notifications carry neither.
+//!
+//! [`EventTracker`] merges polled state and decoded transactions into typed
+//! [`SquadsEvent`]s, emitting each change once and ordering them by slot. With the
+//! `async` feature, `SquadsClient::events` returns an `EventStream` that drives the
+//! tracker: it polls on an interval and polls early whenever a websocket subscription
+//! reports a change to the multisig or one of its accounts. Notifications only trigger
+//! polls, so events are never duplicated by the source that observed them.
+//!
+//! Streams start at the current state and the newest transaction; earlier history is
+//! available through [`crate::indexer`].
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::accounts::{Multisig, Proposal};
+use crate::history::TransactionRecord;
+use crate::types::ProposalStatus;
+use crate::voting::MemberAction;
+
+/// Something that happened to a multisig
+#[derive(Debug, Clone, PartialEq)]
+pub enum SquadsEvent {
+    /// A transaction involving the multisig was confirmed
+    Transaction(TransactionRecord),
+    /// A vault, config or batch transaction was created
+    TransactionCreated {
+        /// Slot the change was observed at
+        slot: u64,
+        /// Index of the new transaction
+        transaction_index: u64,
+    },
+    /// A proposal was created
+    ProposalCreated {
+        /// Slot the change was observed at
+        slot: u64,
+        /// Transaction index of the proposal
+        transaction_index: u64,
+        /// Status of the new proposal
+        status: ProposalStatus,
+    },
+    /// A proposal changed status
+    ProposalStatusChanged {
+        /// Slot the change was observed at
+        slot: u64,
+        /// Transaction index of the proposal
+        transaction_index: u64,
+        /// Previous status
+        from: ProposalStatus,
+        /// New status
+        to: ProposalStatus,
+    },
+    /// A member voted on a proposal
+    Voted {
+        /// Slot the change was observed at
+        slot: u64,
+        /// Transaction index of the proposal
+        transaction_index: u64,
+        /// Member who voted
+        member: Pubkey,
+        /// Approve, reject or cancel
+        vote: MemberAction,
+    },
+    /// Members, threshold, time lock, config authority or rent collector changed
+    ConfigChanged {
+        /// Slot the change was observed at
+        slot: u64,
+        /// The multisig after the change
+        multisig: Box<Multisig>,
+    },
+}
+
+impl SquadsEvent {
+    /// Slot the transaction was confirmed in, or the state change was observed at
+    pub fn slot(&self) -> u64 {
+        match self {
+            SquadsEvent::Transaction(record) => record.slot,
+            SquadsEvent::TransactionCreated { slot, .. }
+            | SquadsEvent::ProposalCreated { slot, .. }
+            | SquadsEvent::ProposalStatusChanged { slot, .. }
+            | SquadsEvent::Voted { slot, .. }
+            | SquadsEvent::ConfigChanged { slot, .. } => *slot,
+        }
+    }
+}
+
+/// Sort events by slot, keeping the relative order of events in the same slot
+pub fn order_events(events: &mut [SquadsEvent]) {
+    events.sort_by_key(SquadsEvent::slot);
+}
+
+/// How an event stream polls and subscribes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventStreamConfig {
+    /// Time between polls when no notification arrives
+    pub poll_interval: Duration,
+    /// Subscribe to websocket notifications to poll as soon as something changes
+    pub subscribe: bool,
+    /// Websocket endpoint (None to derive it from the RPC URL with [`websocket_url`])
+    pub websocket_url: Option<String>,
+    /// Fetch and decode the transactions of the multisig's signature history
+    pub include_transactions: bool,
+    /// Most recent transaction indexes whose proposals are tracked
+    pub lookback: u64,
+}
+
+impl Default for EventStreamConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(10),
+            subscribe: true,
+            websocket_url: None,
+            include_transactions: true,
+            lookback: 50,
+        }
+    }
+}
+
+/// Websocket endpoint of an RPC URL, following the validator convention of serving
+/// websockets on the RPC port plus one (e.g. `http://localhost:8899` becomes
+/// `ws://localhost:8900`)
+pub fn websocket_url(rpc_url: &str) -> String {
+    let (scheme, rest) = match rpc_url.split_once("://") {
+        Some(("https", rest)) => ("wss", rest),
+        Some((_, rest)) => ("ws", rest),
+        None => ("ws", rpc_url),
+    };
+    let (host, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let host = match host.rsplit_once(':') {
+        Some((name, port)) => match port.parse::<u16>() {
+            Ok(port) => format!("{}:{}", name, port.saturating_add(1)),
+            Err(_) => host.to_string(),
+        },
+        None => host.to_string(),
+    };
+    format!("{}://{}{}", scheme, host, path)
+}
+
+/// Turns polled state and decoded transactions into deduplicated events
+///
+/// The first [`observe_state`](Self::observe_state) records a baseline without
+/// emitting events; later calls emit the differences.
+#[derive(Debug, Clone, Default)]
+pub struct EventTracker {
+    multisig: Option<Multisig>,
+    proposals: BTreeMap<u64, Proposal>,
+    /// Polled transaction indexes that had no proposal yet
+    awaiting_proposal: BTreeSet<u64>,
+    last_signature: Option<Signature>,
+    /// Signatures at or after the newest processed slot, with their slots
+    seen: HashMap<Signature, u64>,
+}
+
+impl EventTracker {
+    /// A tracker without a baseline
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Newest observed signature, the cursor for the next signature history request
+    pub fn last_signature(&self) -> Option<Signature> {
+        self.last_signature
+    }
+
+    /// Continue the transaction history after `signature`, e.g. a persisted cursor
+    pub fn resume_after(&mut self, signature: Signature) {
+        self.last_signature = Some(signature);
+        // The slot is unknown; the history starts after this signature anyway
+        self.seen.insert(signature, 0);
+    }
+
+    /// Transaction indexes whose proposals to poll next
+    ///
+    /// Covers proposals that can still change and new transaction indexes, limited to
+    /// the `lookback` most recent indexes. None if there is nothing to poll.
+    pub fn proposal_range(
+        &self,
+        multisig: &Multisig,
+        lookback: u64,
+    ) -> Option<RangeInclusive<u64>> {
+        let end = multisig.transaction_index;
+        let floor = end.saturating_sub(lookback.max(1) - 1).max(1);
+        let start = match &self.multisig {
+            None => floor,
+            Some(previous) => {
+                let pending = self
+                    .proposals
+                    .iter()
+                    .find(|(_, proposal)| !proposal.status.is_terminal())
+                    .map(|(index, _)| *index);
+                let awaiting = self.awaiting_proposal.first().copied();
+                [pending, awaiting, Some(previous.transaction_index + 1)]
+                    .into_iter()
+                    .flatten()
+                    .min()
+                    .unwrap_or(floor)
+                    .max(floor)
+            }
+        };
+        (start <= end).then_some(start..=end)
+    }
+
+    /// Record the state polled at `slot` and return what changed since the last poll
+    ///
+    /// # Arguments
+    /// * `slot` - Slot the state was read at
+    /// * `multisig` - Multisig account
+    /// * `range` - Transaction indexes that were polled, from [`proposal_range`](Self::proposal_range)
+    /// * `proposals` - Proposals found in `range`
+    pub fn observe_state(
+        &mut self,
+        slot: u64,
+        multisig: &Multisig,
+        range: Option<RangeInclusive<u64>>,
+        proposals: &[(u64, Proposal)],
+    ) -> Vec<SquadsEvent> {
+        let mut events = Vec::new();
+        let baseline = self.multisig.is_none();
+
+        if let Some(previous) = &self.multisig {
+            if config_changed(previous, multisig) {
+                events.push(SquadsEvent::ConfigChanged {
+                    slot,
+                    multisig: Box::new(multisig.clone()),
+                });
+            }
+            for transaction_index in previous.transaction_index + 1..=multisig.transaction_index {
+                events.push(SquadsEvent::TransactionCreated {
+                    slot,
+                    transaction_index,
+                });
+            }
+        }
+
+        for (transaction_index, proposal) in proposals {
+            let transaction_index = *transaction_index;
+            let previous = self.proposals.get(&transaction_index);
+            match previous {
+                None if baseline => {}
+                None => events.push(SquadsEvent::ProposalCreated {
+                    slot,
+                    transaction_index,
+                    status: proposal.status.clone(),
+                }),
+                Some(previous) if previous.status != proposal.status => {
+                    events.push(SquadsEvent::ProposalStatusChanged {
+                        slot,
+                        transaction_index,
+                        from: previous.status.clone(),
+                        to: proposal.status.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+            if !baseline {
+                let votes = [
+                    (
+                        MemberAction::Approve,
+                        &proposal.approved,
+                        previous.map(|p| &p.approved),
+                    ),
+                    (
+                        MemberAction::Reject,
+                        &proposal.rejected,
+                        previous.map(|p| &p.rejected),
+                    ),
+                    (
+                        MemberAction::Cancel,
+                        &proposal.cancelled,
+                        previous.map(|p| &p.cancelled),
+                    ),
+                ];
+                for (vote, voters, previous_voters) in votes {
+                    for member in voters {
+                        if !previous_voters.is_some_and(|previous| previous.contains(member)) {
+                            events.push(SquadsEvent::Voted {
+                                slot,
+                                transaction_index,
+                                member: *member,
+                                vote,
+                            });
+                        }
+                    }
+                }
+            }
+            self.proposals.insert(transaction_index, proposal.clone());
+            self.awaiting_proposal.remove(&transaction_index);
+        }
+
+        if let Some(range) = range {
+            // Indexes below the polled range are settled and never polled again
+            let start = *range.start();
+            self.proposals.retain(|index, _| *index >= start);
+            self.awaiting_proposal.retain(|index| *index >= start);
+            for index in range {
+                if !proposals.iter().any(|(found, _)| *found == index) {
+                    // A tracked proposal that disappeared was closed
+                    if self.proposals.remove(&index).is_none() {
+                        self.awaiting_proposal.insert(index);
+                    }
+                }
+            }
+        }
+
+        self.multisig = Some(multisig.clone());
+        events
+    }
+
+    /// Record decoded transactions and return those not seen before, ordered by slot
+    ///
+    /// Only signatures of the newest processed slot are kept for deduplication, as the
+    /// history cursor never returns transactions of older slots again.
+    pub fn observe_transactions(&mut self, records: Vec<TransactionRecord>) -> Vec<SquadsEvent> {
+        let mut events: Vec<SquadsEvent> = records
+            .into_iter()
+            .filter(|record| self.seen.insert(record.signature, record.slot).is_none())
+            .map(SquadsEvent::Transaction)
+            .collect();
+        order_events(&mut events);
+        if let Some(SquadsEvent::Transaction(newest)) = events.last() {
+            self.last_signature = Some(newest.signature);
+            let newest_slot = newest.slot;
+            self.seen.retain(|_, slot| *slot >= newest_slot);
+        }
+        events
+    }
+}
+
+/// Whether the configuration of a multisig changed, ignoring transaction creation
+fn config_changed(previous: &Multisig, current: &Multisig) -> bool {
+    previous.config_authority != current.config_authority
+        || previous.threshold != current.threshold
+        || previous.time_lock != current.time_lock
+        || previous.rent_collector != current.rent_collector
+        || previous.members != current.members
+}
+
+#[cfg(feature = "async")]
+pub use stream::EventStream;
+
+#[cfg(feature = "async")]
+mod stream {
+    use std::collections::VecDeque;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::task::Poll;
+    use std::time::Instant;
+
+    use futures_util::StreamExt;
+    use solana_account_decoder_client_types::UiAccountEncoding;
+    use solana_client::{
+        nonblocking::pubsub_client::{PubsubClient, PubsubClientError},
+        rpc_client::GetConfirmedSignaturesForAddress2Config,
+        rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcTransactionConfig},
+        rpc_filter::{Memcmp, RpcFilterType},
+    };
+    use solana_commitment_config::CommitmentConfig;
+    use solana_transaction_status_client_types::UiTransactionEncoding;
+
+    use super::*;
+    use crate::accounts::check_owner;
+    use crate::client::SquadsClient;
+    use crate::error::{SquadsError, SquadsResult};
+    use crate::indexer::MAX_PAGE_SIZE;
+    use crate::reader::fetch_proposals;
+    use crate::runtime::{self, RetryPolicy};
+
+    /// Upper bound of the backoff before subscribing again after a disconnect
+    const MAX_RESUBSCRIBE_DELAY: Duration = Duration::from_secs(300);
+
+    type Subscription = Pin<Box<dyn Future<Output = SquadsResult<()>> + Send>>;
+
+    /// Ordered events of a multisig, from [`SquadsClient::events`]
+    pub struct EventStream<'a> {
+        client: &'a SquadsClient,
+        multisig: Pubkey,
+        config: EventStreamConfig,
+        tracker: EventTracker,
+        pending: VecDeque<SquadsEvent>,
+        polled: bool,
+        history_started: bool,
+        subscription: Option<Subscription>,
+        /// Subscriptions that ended since the last notification
+        subscription_failures: u32,
+        /// When to subscribe again after the subscription ended
+        resubscribe_at: Option<Instant>,
+        notified: Arc<AtomicBool>,
+    }
+
+    impl std::fmt::Debug for EventStream<'_> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("EventStream")
+                .field("multisig", &self.multisig)
+                .field("config", &self.config)
+                .field("pending", &self.pending.len())
+                .finish_non_exhaustive()
+        }
+    }
+
+    impl EventStream<'_> {
+        /// Wait for the next event
+        ///
+        /// The first call records the current state; events are the changes after it.
+        /// Failed polls are returned as errors and retried on the next call. If the
+        /// websocket subscription fails, its error is returned once and the stream
+        /// keeps polling on the interval, subscribing again with backoff.
+        pub async fn next(&mut self) -> SquadsResult<SquadsEvent> {
+            loop {
+                if let Some(event) = self.pending.pop_front() {
+                    return Ok(event);
+                }
+                if self.polled {
+                    self.wait().await?;
+                }
+                self.polled = true;
+                self.poll().await?;
+            }
+        }
+
+        /// The tracker, e.g. to persist [`EventTracker::last_signature`]
+        pub fn tracker(&self) -> &EventTracker {
+            &self.tracker
+        }
+
+        async fn poll(&mut self) -> SquadsResult<()> {
+            let mut events = Vec::new();
+            if self.config.include_transactions {
+                events.extend(self.poll_transactions().await?);
+            }
+            events.extend(self.poll_state().await?);
+            order_events(&mut events);
+            self.pending.extend(events);
+            Ok(())
+        }
+
+        async fn poll_transactions(&mut self) -> SquadsResult<Vec<SquadsEvent>> {
+            let rpc = &self.client.rpc;
+            let history_config = |until, limit| GetConfirmedSignaturesForAddress2Config {
+                before: None,
+                until,
+                limit: Some(limit),
+                commitment: Some(CommitmentConfig::confirmed()),
+            };
+            let parse = |signature: &str| {
+                signature.parse::<Signature>().map_err(|_| {
+                    SquadsError::EncodingError(format!("invalid signature {}", signature))
+                })
+            };
+
+            if !self.history_started && self.tracker.last_signature().is_none() {
+                // Start at the newest transaction
+                let newest = rpc
+                    .get_signatures_for_address_with_config(&self.multisig, history_config(None, 1))
+                    .await?;
+                if let Some(status) = newest.first() {
+                    self.tracker.resume_after(parse(&status.signature)?);
+                }
+                self.history_started = true;
+                return Ok(vec![]);
+            }
+            self.history_started = true;
+
+            let statuses = rpc
+                .get_signatures_for_address_with_config(
+                    &self.multisig,
+                    history_config(self.tracker.last_signature(), MAX_PAGE_SIZE),
+                )
+                .await?;
+            let mut records = Vec::with_capacity(statuses.len());
+            for status in statuses.iter().rev() {
+                let signature = parse(&status.signature)?;
+                let tx_config = RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Base64),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    max_supported_transaction_version: Some(0),
+                };
+                let confirmed = rpc
+                    .get_transaction_with_config(&signature, tx_config)
+                    .await?;
+                records.push(TransactionRecord::from_confirmed(
+                    signature,
+                    &confirmed,
+                    Some(&self.client.program_id),
+                )?);
+            }
+            Ok(self.tracker.observe_transactions(records))
+        }
+
+        async fn poll_state(&mut self) -> SquadsResult<Vec<SquadsEvent>> {
+            let response = self
+                .client
+                .rpc
+                .get_account_with_commitment(&self.multisig, CommitmentConfig::confirmed())
+                .await?;
+            let account = response
+                .value
+                .ok_or_else(|| SquadsError::AccountNotFound(self.multisig.to_string()))?;
+            check_owner(&account.owner, &self.client.program_id)?;
+            let multisig =
+                Multisig::try_from_slice(&account.data).map_err(SquadsError::from_decode_error)?;
+
+            let range = self.tracker.proposal_range(&multisig, self.config.lookback);
+            let proposals = match &range {
+                Some(range) => {
+                    fetch_proposals(
+                        &self.client.rpc,
+                        &self.client.program_id,
+                        &self.multisig,
+                        *range.start(),
+                        *range.end(),
+                    )
+                    .await?
+                }
+                None => vec![],
+            };
+            Ok(self
+                .tracker
+                .observe_state(response.context.slot, &multisig, range, &proposals))
+        }
+
+        /// Wait for the poll interval or a websocket notification
+        async fn wait(&mut self) -> SquadsResult<()> {
+            let due = self.resubscribe_at.is_none_or(|at| Instant::now() >= at);
+            if self.config.subscribe && self.subscription.is_none() && due {
+                self.resubscribe_at = None;
+                self.subscription = Some(self.subscribe());
+            }
+
+            let mut sleep = Box::pin(runtime::sleep(self.config.poll_interval));
+            let subscription = &mut self.subscription;
+            let failures = &mut self.subscription_failures;
+            let resubscribe_at = &mut self.resubscribe_at;
+            let backoff = RetryPolicy {
+                max_attempts: u32::MAX,
+                base_delay: self.config.poll_interval,
+                max_delay: MAX_RESUBSCRIBE_DELAY,
+            };
+            let notified = &self.notified;
+            std::future::poll_fn(|cx| {
+                if let Some(driver) = subscription.as_mut() {
+                    if let Poll::Ready(result) = driver.as_mut().poll(cx) {
+                        *subscription = None;
+                        *failures = failures.saturating_add(1);
+                        *resubscribe_at = Some(
+                            Instant::now() + backoff.delay(*failures, runtime::jitter_sample()),
+                        );
+                        result?;
+                    }
+                }
+                if notified.swap(false, Ordering::AcqRel) {
+                    *failures = 0;
+                    return Poll::Ready(Ok(()));
+                }
+                sleep.as_mut().poll(cx).map(Ok)
+            })
+            .await
+        }
+
+        /// Subscribe to the multisig account and the program accounts that reference it
+        ///
+        /// Proposals and transactions store their multisig right after the
+        /// discriminator, so a memcmp filter at offset 8 selects them.
+        fn subscribe(&self) -> Subscription {
+            let url = self
+                .config
+                .websocket_url
+                .clone()
+                .unwrap_or_else(|| websocket_url(&self.client.rpc.url()));
+            let multisig = self.multisig;
+            let program_id = self.client.program_id;
+            let notified = self.notified.clone();
+
+            Box::pin(async move {
+                let subscription_error =
+                    |err: PubsubClientError| SquadsError::SubscriptionError(err.to_string());
+                let account_config = RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    ..RpcAccountInfoConfig::default()
+                };
+                let pubsub = PubsubClient::new(&url).await.map_err(subscription_error)?;
+                let (multisig_updates, _) = pubsub
+                    .account_subscribe(&multisig, Some(account_config.clone()))
+                    .await
+                    .map_err(subscription_error)?;
+                let (account_updates, _) = pubsub
+                    .program_subscribe(
+                        &program_id,
+                        Some(RpcProgramAccountsConfig {
+                            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                                8,
+                                multisig.as_ref(),
+                            ))]),
+                            account_config,
+                            ..RpcProgramAccountsConfig::default()
+                        }),
+                    )
+                    .await
+                    .map_err(subscription_error)?;
+
+                let mut updates = futures_util::stream::select(
+                    multisig_updates.map(|_| ()),
+                    account_updates.map(|_| ()),
+                );
+                while updates.next().await.is_some() {
+                    notified.store(true, Ordering::Release);
+                }
+                Err(SquadsError::SubscriptionError(
+                    "websocket subscription closed".to_string(),
+                ))
+            })
+        }
+    }
+
+    impl SquadsClient {
+        /// Stream the events of a multisig with the default [`EventStreamConfig`]
+        pub fn events(&self, multisig: &Pubkey) -> EventStream<'_> {
+            self.events_with_config(multisig, EventStreamConfig::default())
+        }
+
+        /// Stream the events of a multisig
+        ///
+        /// # Arguments
+        /// * `multisig` - Multisig account
+        /// * `config` - Polling and subscription settings
+        pub fn events_with_config(
+            &self,
+            multisig: &Pubkey,
+            config: EventStreamConfig,
+        ) -> EventStream<'_> {
+            EventStream {
+                client: self,
+                multisig: *multisig,
+                config,
+                tracker: EventTracker::new(),
+                pending: VecDeque::new(),
+                polled: false,
+                history_started: false,
+                subscription: None,
+                subscription_failures: 0,
+                resubscribe_at: None,
+                notified: Arc::new(AtomicBool::new(false)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Member, Permissions};
+
+    fn multisig(transaction_index: u64, threshold: u16) -> Multisig {
+        Multisig {
+            create_key: Pubkey::default(),
+            config_authority: Pubkey::default(),
+            threshold,
+            time_lock: 0,
+            transaction_index,
+            stale_transaction_index: 0,
+            rent_collector: None,
+            bump: 255,
+            members: vec![Member {
+                key: Pubkey::default(),
+                permissions: Permissions::from_mask(7),
+            }],
+            unknown_tail: vec![],
+        }
+    }
+
+    fn proposal(transaction_index: u64, status: ProposalStatus, approved: Vec<Pubkey>) -> Proposal {
+        Proposal {
+            multisig: Pubkey::default(),
+            transaction_index,
+            status,
+            bump: 255,
+            approved,
+            rejected: vec![],
+            cancelled: vec![],
+            unknown_tail: vec![],
+        }
+    }
+
+    fn record(signature: Signature, slot: u64) -> TransactionRecord {
+        TransactionRecord {
+            signature,
+            slot,
+            block_time: None,
+            success: true,
+            instructions: vec![],
+            events: vec![],
+        }
+    }
+
+    #[test]
+    fn test_tracker_emits_state_changes_once() {
+        let alice = Pubkey::new_unique();
+        let bob = Pubkey::new_unique();
+        let active = ProposalStatus::Active { timestamp: 1 };
+        let mut tracker = EventTracker::new();
+
+        // The baseline emits nothing and tracks the open proposal
+        let state = multisig(2, 2);
+        let range = tracker.proposal_range(&state, 50);
+        assert_eq!(range, Some(1..=2));
+        let proposals = [(2, proposal(2, active.clone(), vec![alice]))];
+        assert!(tracker
+            .observe_state(10, &state, range, &proposals)
+            .is_empty());
+
+        // Transaction 3 is created before its proposal; transaction 1 has no proposal
+        // either, so both stay polled
+        let state = multisig(3, 2);
+        let range = tracker.proposal_range(&state, 50);
+        assert_eq!(range, Some(1..=3));
+        let events = tracker.observe_state(20, &state, range, &proposals);
+        assert_eq!(
+            events,
+            vec![SquadsEvent::TransactionCreated {
+                slot: 20,
+                transaction_index: 3
+            }]
+        );
+
+        // Proposal 3 appears, bob approves 2 and the threshold changes
+        let state = multisig(3, 1);
+        let range = tracker.proposal_range(&state, 50);
+        assert_eq!(range, Some(1..=3));
+        assert_eq!(tracker.proposal_range(&state, 2), Some(2..=3));
+        let approved = ProposalStatus::Approved { timestamp: 2 };
+        let proposals = [
+            (2, proposal(2, approved.clone(), vec![alice, bob])),
+            (3, proposal(3, active.clone(), vec![])),
+        ];
+        let events = tracker.observe_state(30, &state, range.clone(), &proposals);
+        assert_eq!(events.len(), 4);
+        assert!(matches!(
+            events[0],
+            SquadsEvent::ConfigChanged { slot: 30, .. }
+        ));
+        assert_eq!(
+            events[1],
+            SquadsEvent::ProposalStatusChanged {
+                slot: 30,
+                transaction_index: 2,
+                from: active.clone(),
+                to: approved,
+            }
+        );
+        assert_eq!(
+            events[2],
+            SquadsEvent::Voted {
+                slot: 30,
+                transaction_index: 2,
+                member: bob,
+                vote: MemberAction::Approve,
+            }
+        );
+        assert!(matches!(
+            events[3],
+            SquadsEvent::ProposalCreated {
+                transaction_index: 3,
+                ..
+            }
+        ));
+
+        // Observing the same state again emits nothing
+        assert!(tracker
+            .observe_state(40, &state, range, &proposals)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_tracker_deduplicates_transactions() {
+        let first = Signature::from([1u8; 64]);
+        let second = Signature::from([2u8; 64]);
+        let mut tracker = EventTracker::new();
+        tracker.resume_after(first);
+
+        let events = tracker.observe_transactions(vec![record(second, 12), record(first, 11)]);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].slot(), 12);
+        assert_eq!(tracker.last_signature(), Some(second));
+        assert!(tracker
+            .observe_transactions(vec![record(second, 12)])
+            .is_empty());
+
+        let mut mixed = vec![
+            SquadsEvent::TransactionCreated {
+                slot: 13,
+                transaction_index: 1,
+            },
+            SquadsEvent::Transaction(record(Signature::from([3u8; 64]), 12)),
+        ];
+        order_events(&mut mixed);
+        assert_eq!(mixed[0].slot(), 12);
+    }
+
+    #[test]
+    fn test_tracker_prunes_signatures_before_newest_slot() {
+        let mut tracker = EventTracker::new();
+        let old = (0..100u8)
+            .map(|i| record(Signature::from([i; 64]), 10 + i as u64))
+            .collect();
+        assert_eq!(tracker.observe_transactions(old).len(), 100);
+        assert_eq!(tracker.seen.len(), 1);
+
+        let same_slot = Signature::from([200u8; 64]);
+        let events = tracker.observe_transactions(vec![record(same_slot, 109)]);
+        assert_eq!(events.len(), 1);
+        assert_eq!(tracker.seen.len(), 2);
+        assert!(tracker
+            .observe_transactions(vec![record(same_slot, 109)])
+            .is_empty());
+    }
+
+    #[test]
+    fn test_websocket_url() {
+        assert_eq!(
+            websocket_url("https://api.mainnet-beta.solana.com"),
+            "wss://api.mainnet-beta.solana.com"
+        );
+        assert_eq!(
+            websocket_url("http://localhost:8899"),
+            "ws://localhost:8900"
+        );
+        assert_eq!(
+            websocket_url("https://rpc.example.com/key/abc"),
+            "wss://rpc.example.com/key/abc"
+        );
+    }
+}
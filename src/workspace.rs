@@ -0,0 +1,483 @@
+//! Managing several multisigs together
+//!
+//! Organizations often operate several squads, e.g. one each for operations, grants and
+//! payroll. A [`Workspace`] holds them under labels and aggregates across them: the
+//! pending proposals of every squad ([`pending`]), the proposals a signer still has to
+//! approve ([`awaiting_approval`]) and the combined vault balances
+//! ([`WorkspaceBalance`]).
+//!
+//! Fetching state and approving across all squads needs the `async` feature; the
+//! aggregation itself works on already fetched [`SquadStatus`] values.
+
+use std::collections::BTreeMap;
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::accounts::{Multisig, Proposal};
+use crate::error::{SquadsError, SquadsResult};
+use crate::portfolio::PortfolioSummary;
+use crate::types::ProposalStatus;
+use crate::voting::MemberAction;
+
+/// A multisig in a workspace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceEntry {
+    /// Label, unique within the workspace (e.g. "payroll")
+    pub label: String,
+    /// Multisig account
+    pub multisig: Pubkey,
+    /// Vault indexes included in balance reports
+    pub vault_indexes: Vec<u8>,
+}
+
+impl WorkspaceEntry {
+    /// Entry for `multisig` reporting the balance of its default vault
+    pub fn new(label: impl Into<String>, multisig: Pubkey) -> Self {
+        Self {
+            label: label.into(),
+            multisig,
+            vault_indexes: vec![0],
+        }
+    }
+
+    /// Report the balances of these vaults instead
+    pub fn with_vault_indexes(mut self, vault_indexes: impl IntoIterator<Item = u8>) -> Self {
+        self.vault_indexes = vault_indexes.into_iter().collect();
+        self
+    }
+}
+
+/// A labelled set of multisigs operated together
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Workspace {
+    entries: Vec<WorkspaceEntry>,
+}
+
+impl Workspace {
+    /// Create an empty workspace
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a multisig, keeping entries in insertion order
+    ///
+    /// Fails with [`SquadsError::InvalidConfig`] if the label or the multisig is already
+    /// in the workspace.
+    pub fn add(&mut self, entry: WorkspaceEntry) -> SquadsResult<()> {
+        if let Some(existing) = self
+            .entries
+            .iter()
+            .find(|existing| existing.label == entry.label || existing.multisig == entry.multisig)
+        {
+            return Err(SquadsError::InvalidConfig(format!(
+                "workspace already contains {} ({})",
+                existing.label, existing.multisig
+            )));
+        }
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Remove the multisig with the given label
+    pub fn remove(&mut self, label: &str) -> Option<WorkspaceEntry> {
+        let position = self.entries.iter().position(|entry| entry.label == label)?;
+        Some(self.entries.remove(position))
+    }
+
+    /// Entry with the given label
+    pub fn get(&self, label: &str) -> Option<&WorkspaceEntry> {
+        self.entries.iter().find(|entry| entry.label == label)
+    }
+
+    /// All entries, in insertion order
+    pub fn entries(&self) -> &[WorkspaceEntry] {
+        &self.entries
+    }
+}
+
+/// Fetched state of one squad in a workspace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SquadStatus {
+    /// Label of the squad
+    pub label: String,
+    /// Multisig account address
+    pub address: Pubkey,
+    /// Decoded multisig
+    pub multisig: Multisig,
+    /// Active and approved proposals with their transaction indexes, ascending
+    pub pending: Vec<(u64, Proposal)>,
+    /// Memos the pending transactions were created with, by transaction index
+    pub memos: BTreeMap<u64, String>,
+}
+
+/// A pending proposal of one of the squads in a workspace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingProposal {
+    /// Label of the squad
+    pub label: String,
+    /// Multisig account address
+    pub multisig: Pubkey,
+    /// Transaction index of the proposal
+    pub transaction_index: u64,
+    /// Decoded proposal
+    pub proposal: Proposal,
+    /// Memo the transaction was created with, e.g. "Add Alice as member"
+    pub memo: Option<String>,
+}
+
+/// Pending proposals of all squads, in workspace order and then by transaction index
+pub fn pending(statuses: &[SquadStatus]) -> Vec<PendingProposal> {
+    statuses
+        .iter()
+        .flat_map(|status| {
+            status
+                .pending
+                .iter()
+                .filter(|(_, proposal)| {
+                    matches!(
+                        proposal.status,
+                        ProposalStatus::Active { .. } | ProposalStatus::Approved { .. }
+                    )
+                })
+                .map(|(index, proposal)| PendingProposal {
+                    label: status.label.clone(),
+                    multisig: status.address,
+                    transaction_index: *index,
+                    proposal: proposal.clone(),
+                    memo: status.memos.get(index).cloned(),
+                })
+        })
+        .collect()
+}
+
+/// Pending proposals across all squads that `signer` can still approve
+///
+/// Uses [`Proposal::actions_for`], so squads where `signer` is not a voter and stale
+/// proposals are left out.
+pub fn awaiting_approval(statuses: &[SquadStatus], signer: &Pubkey) -> Vec<PendingProposal> {
+    let multisigs: BTreeMap<Pubkey, &Multisig> = statuses
+        .iter()
+        .map(|status| (status.address, &status.multisig))
+        .collect();
+    pending(statuses)
+        .into_iter()
+        .filter(|pending| {
+            // Approving does not depend on the time, only executing does
+            pending
+                .proposal
+                .actions_for(multisigs[&pending.multisig], signer, 0)
+                .contains(&MemberAction::Approve)
+        })
+        .collect()
+}
+
+/// Positions of one vault of a squad
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultBalance {
+    /// Label of the squad
+    pub label: String,
+    /// Vault index
+    pub vault_index: u8,
+    /// Positions held by the vault
+    pub summary: PortfolioSummary,
+}
+
+/// Balances of the vaults of every squad in a workspace
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkspaceBalance {
+    /// Per-vault balances, in workspace order and then by vault index
+    pub vaults: Vec<VaultBalance>,
+}
+
+impl WorkspaceBalance {
+    /// SOL held by all vaults and their position accounts
+    pub fn total_lamports(&self) -> u64 {
+        self.vaults.iter().fold(0, |total, vault| {
+            total.saturating_add(vault.summary.total_lamports())
+        })
+    }
+
+    /// Token balances summed per mint across all vaults
+    pub fn token_balances(&self) -> BTreeMap<Pubkey, u64> {
+        let mut balances = BTreeMap::new();
+        for vault in &self.vaults {
+            for (mint, amount) in vault.summary.token_balances() {
+                let total: &mut u64 = balances.entry(mint).or_default();
+                *total = total.saturating_add(amount);
+            }
+        }
+        balances
+    }
+
+    /// SOL held by the vaults of one squad and their position accounts
+    pub fn lamports_of(&self, label: &str) -> u64 {
+        self.vaults
+            .iter()
+            .filter(|vault| vault.label == label)
+            .fold(0, |total, vault| {
+                total.saturating_add(vault.summary.total_lamports())
+            })
+    }
+}
+
+/// Result of approving one proposal in [`Workspace::approve_all`]
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct ApprovalOutcome {
+    /// Label of the squad
+    pub label: String,
+    /// Transaction index of the proposal
+    pub transaction_index: u64,
+    /// Signature of the approval, or why it failed
+    pub result: SquadsResult<solana_sdk::signature::Signature>,
+}
+
+#[cfg(feature = "async")]
+mod fetch {
+    use solana_sdk::{signature::Keypair, signer::Signer};
+
+    use super::*;
+    use crate::client::SquadsClient;
+    use crate::reader::SquadsReader;
+
+    impl Workspace {
+        /// Fetch every multisig, its pending proposals and the memos their transactions
+        /// were created with
+        ///
+        /// Proposals at or below a multisig's stale transaction index are not fetched,
+        /// since they can no longer be voted on. Memos come from
+        /// [`SquadsReader::get_creation_memo`], which reads transaction history.
+        pub async fn status<R: SquadsReader + Sync>(
+            &self,
+            reader: &R,
+        ) -> SquadsResult<Vec<SquadStatus>> {
+            let mut statuses = Vec::with_capacity(self.entries.len());
+            for entry in &self.entries {
+                let multisig = reader.get_multisig(&entry.multisig).await?;
+                let mut pending = reader
+                    .list_proposals(
+                        &entry.multisig,
+                        multisig.stale_transaction_index + 1,
+                        multisig.transaction_index,
+                    )
+                    .await?;
+                pending.retain(|(_, proposal)| {
+                    matches!(
+                        proposal.status,
+                        ProposalStatus::Active { .. } | ProposalStatus::Approved { .. }
+                    )
+                });
+                let mut memos = BTreeMap::new();
+                for (index, _) in &pending {
+                    if let Some(memo) = reader.get_creation_memo(&entry.multisig, *index).await? {
+                        memos.insert(*index, memo);
+                    }
+                }
+                statuses.push(SquadStatus {
+                    label: entry.label.clone(),
+                    address: entry.multisig,
+                    multisig,
+                    pending,
+                    memos,
+                });
+            }
+            Ok(statuses)
+        }
+
+        /// Fetch the positions of the configured vaults of every squad
+        pub async fn balances(&self, client: &SquadsClient) -> SquadsResult<WorkspaceBalance> {
+            let mut vaults = Vec::new();
+            for entry in &self.entries {
+                for vault_index in &entry.vault_indexes {
+                    let summary = client
+                        .get_vault_positions(&entry.multisig, *vault_index)
+                        .await?;
+                    vaults.push(VaultBalance {
+                        label: entry.label.clone(),
+                        vault_index: *vault_index,
+                        summary,
+                    });
+                }
+            }
+            Ok(WorkspaceBalance { vaults })
+        }
+
+        /// Approve every proposal `signer` can still approve, across all squads
+        ///
+        /// One transaction is sent per proposal. A failed approval does not stop the
+        /// others; check each [`ApprovalOutcome::result`].
+        pub async fn approve_all(
+            &self,
+            client: &SquadsClient,
+            statuses: &[SquadStatus],
+            signer: &Keypair,
+        ) -> Vec<ApprovalOutcome> {
+            let mut outcomes = Vec::new();
+            for pending in awaiting_approval(statuses, &signer.pubkey()) {
+                let (proposal, _) =
+                    client.get_proposal_pda(&pending.multisig, pending.transaction_index);
+                let result = client
+                    .approve_proposal(&pending.multisig, &proposal, signer)
+                    .await;
+                outcomes.push(ApprovalOutcome {
+                    label: pending.label,
+                    transaction_index: pending.transaction_index,
+                    result,
+                });
+            }
+            outcomes
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::portfolio::{Position, PositionKind};
+    use crate::types::{Member, Permission, Permissions};
+
+    fn multisig(members: Vec<Member>, stale_transaction_index: u64) -> Multisig {
+        Multisig {
+            create_key: Pubkey::new_unique(),
+            config_authority: Pubkey::default(),
+            threshold: 2,
+            time_lock: 0,
+            transaction_index: 5,
+            stale_transaction_index,
+            rent_collector: None,
+            bump: 255,
+            members,
+            unknown_tail: vec![],
+        }
+    }
+
+    fn proposal(index: u64, status: ProposalStatus, approved: Vec<Pubkey>) -> (u64, Proposal) {
+        (
+            index,
+            Proposal {
+                multisig: Pubkey::new_unique(),
+                transaction_index: index,
+                status,
+                bump: 255,
+                approved,
+                rejected: vec![],
+                cancelled: vec![],
+                unknown_tail: vec![],
+            },
+        )
+    }
+
+    #[test]
+    fn test_workspace_entries() {
+        let mut workspace = Workspace::new();
+        let ops = Pubkey::new_unique();
+        workspace.add(WorkspaceEntry::new("ops", ops)).unwrap();
+        workspace
+            .add(WorkspaceEntry::new("payroll", Pubkey::new_unique()).with_vault_indexes([0, 1]))
+            .unwrap();
+        assert!(matches!(
+            workspace.add(WorkspaceEntry::new("ops", Pubkey::new_unique())),
+            Err(SquadsError::InvalidConfig(_))
+        ));
+        assert!(workspace.add(WorkspaceEntry::new("other", ops)).is_err());
+
+        assert_eq!(workspace.get("payroll").unwrap().vault_indexes, vec![0, 1]);
+        assert_eq!(workspace.remove("ops").unwrap().multisig, ops);
+        assert_eq!(workspace.entries().len(), 1);
+    }
+
+    #[test]
+    fn test_pending_and_awaiting_approval() {
+        let signer = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let voters = vec![Member::new(signer), Member::new(other)];
+        let active = ProposalStatus::Active { timestamp: 0 };
+
+        let ops = SquadStatus {
+            label: "ops".to_string(),
+            address: Pubkey::new_unique(),
+            multisig: multisig(voters.clone(), 1),
+            pending: vec![
+                proposal(2, active.clone(), vec![]),
+                proposal(3, active.clone(), vec![signer]),
+                proposal(
+                    4,
+                    ProposalStatus::Approved { timestamp: 0 },
+                    vec![signer, other],
+                ),
+                proposal(5, ProposalStatus::Executed { timestamp: 0 }, vec![]),
+            ],
+            memos: BTreeMap::from([(2, "Add Alice as member".to_string())]),
+        };
+        let executor_only = Member::with_permissions(
+            signer,
+            Permissions::from_vec(&[Permission::Initiate, Permission::Execute]),
+        );
+        let payroll = SquadStatus {
+            label: "payroll".to_string(),
+            address: Pubkey::new_unique(),
+            multisig: multisig(vec![executor_only, Member::new(other)], 0),
+            pending: vec![proposal(1, active, vec![])],
+            memos: BTreeMap::new(),
+        };
+        let statuses = [ops, payroll];
+
+        let all = pending(&statuses);
+        let listed: Vec<(&str, u64)> = all
+            .iter()
+            .map(|pending| (pending.label.as_str(), pending.transaction_index))
+            .collect();
+        assert_eq!(
+            listed,
+            vec![("ops", 2), ("ops", 3), ("ops", 4), ("payroll", 1)]
+        );
+        assert_eq!(all[0].memo.as_deref(), Some("Add Alice as member"));
+        assert_eq!(all[1].memo, None);
+
+        let to_approve = awaiting_approval(&statuses, &signer);
+        assert_eq!(to_approve.len(), 1);
+        assert_eq!(to_approve[0].label, "ops");
+        assert_eq!(to_approve[0].transaction_index, 2);
+        assert_eq!(awaiting_approval(&statuses, &other).len(), 3);
+    }
+
+    #[test]
+    fn test_workspace_balance() {
+        let mint = Pubkey::new_unique();
+        let summary = |sol_lamports, amount| PortfolioSummary {
+            vault: Pubkey::new_unique(),
+            sol_lamports,
+            positions: vec![Position {
+                address: Pubkey::new_unique(),
+                lamports: 10,
+                kind: PositionKind::Token {
+                    mint,
+                    amount,
+                    token_program: Pubkey::new_unique(),
+                },
+            }],
+        };
+        let balance = WorkspaceBalance {
+            vaults: vec![
+                VaultBalance {
+                    label: "ops".to_string(),
+                    vault_index: 0,
+                    summary: summary(100, 5),
+                },
+                VaultBalance {
+                    label: "payroll".to_string(),
+                    vault_index: 0,
+                    summary: summary(200, 7),
+                },
+                VaultBalance {
+                    label: "payroll".to_string(),
+                    vault_index: 1,
+                    summary: summary(300, 1),
+                },
+            ],
+        };
+        assert_eq!(balance.total_lamports(), 630);
+        assert_eq!(balance.lamports_of("payroll"), 520);
+        assert_eq!(balance.token_balances()[&mint], 13);
+    }
+}